@@ -0,0 +1,151 @@
+//! Heap allocations made while tokenizing the bundled corpus
+//!
+//! A counting global allocator wraps the system allocator and a custom
+//! criterion measurement reports allocations instead of wall-clock time, so
+//! the numbers cover everything the tokenizer does: lattice nodes, character
+//! category lookups and the returned tokens. Interned strings (`*`,
+//! part-of-speech and inflection values, character categories) don't show up
+//! here. Throughput is reported per token, so the per-element figure is
+//! allocations per token.
+
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use runome::{TokenizeResult, Tokenizer};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Public-domain corpus bundled with the repository (Kajii Motojiro "Lemon", Aozora Bunko)
+const CORPUS_PATH: &str = "tests/text_lemon.txt";
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Criterion measurement counting heap allocations
+struct Allocations;
+
+impl Measurement for Allocations {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> usize {
+        ALLOCATIONS.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: usize) -> usize {
+        ALLOCATIONS.load(Ordering::Relaxed) - start
+    }
+
+    fn add(&self, v1: &usize, v2: &usize) -> usize {
+        v1 + v2
+    }
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &AllocationFormatter
+    }
+}
+
+struct AllocationFormatter;
+
+impl ValueFormatter for AllocationFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        match throughput {
+            Throughput::Elements(elements) => {
+                for value in values {
+                    *value /= *elements as f64;
+                }
+                "allocs/token"
+            }
+            Throughput::Bytes(bytes) | Throughput::BytesDecimal(bytes) => {
+                for value in values {
+                    *value /= *bytes as f64;
+                }
+                "allocs/byte"
+            }
+        }
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+}
+
+fn tokenize_all(tokenizer: &Tokenizer, lines: &[&str]) -> usize {
+    let mut tokens = 0;
+    for line in lines {
+        for result in tokenizer.tokenize(black_box(line), None, None) {
+            if let Ok(TokenizeResult::Token(token)) = result {
+                black_box(token);
+                tokens += 1;
+            }
+        }
+    }
+    tokens
+}
+
+fn alloc_benchmarks(c: &mut Criterion<Allocations>) {
+    let tokenizer = match Tokenizer::new(None, None) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Skipping benchmark: failed to initialize tokenizer: {}", e);
+            return;
+        }
+    };
+    let text = fs::read_to_string(CORPUS_PATH).expect("failed to read corpus");
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+
+    // Warm up, so one-time interning and lazy initialization aren't counted
+    let tokens = tokenize_all(&tokenizer, &lines);
+
+    let mut group = c.benchmark_group("alloc");
+    group.throughput(Throughput::Elements(tokens as u64));
+    group.bench_function("tokenize/lemon", |b| {
+        b.iter(|| tokenize_all(&tokenizer, &lines))
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    // Allocation counts are deterministic, so a few samples are enough
+    config = Criterion::default().with_measurement(Allocations).sample_size(10);
+    targets = alloc_benchmarks
+}
+criterion_main!(benches);
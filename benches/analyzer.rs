@@ -0,0 +1,123 @@
+//! Analyzer pipeline benchmarks: each stage on its own and end to end
+//!
+//! The stages are run over the bundled corpus in the order the analyzer
+//! applies them, each on the output of the previous one, so the sum of the
+//! stages can be compared with the end-to-end number. Throughput is reported
+//! in characters per second.
+
+use criterion::{BatchSize, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use runome::{
+    Analyzer, CharFilter, POSKeepFilter, Token, TokenCountFilter, TokenFilter, TokenizeResult,
+    Tokenizer, UnicodeNormalizeCharFilter,
+};
+use std::fs;
+
+/// Public-domain corpus bundled with the repository (Kajii Motojiro "Lemon", Aozora Bunko)
+const CORPUS_PATH: &str = "tests/text_lemon.txt";
+
+/// Part-of-speech prefixes kept by the POS filter stage
+const KEEP_POS: [&str; 2] = ["名詞", "動詞"];
+
+fn tokenize_line(tokenizer: &Tokenizer, line: &str) -> Vec<Token> {
+    tokenizer
+        .tokenize(line, None, None)
+        .filter_map(|result| match result {
+            Ok(TokenizeResult::Token(token)) => Some(token),
+            _ => None,
+        })
+        .collect()
+}
+
+fn analyzer_benchmarks(c: &mut Criterion) {
+    let tokenizer = match Tokenizer::new(None, None) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Skipping benchmark: failed to initialize tokenizer: {}", e);
+            return;
+        }
+    };
+    let text = fs::read_to_string(CORPUS_PATH).expect("failed to read corpus");
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let chars: usize = lines.iter().map(|l| l.chars().count()).sum();
+
+    let char_filter = UnicodeNormalizeCharFilter::with_default_form();
+    let keep_pos: Vec<String> = KEEP_POS.iter().map(|pos| pos.to_string()).collect();
+    let pos_filter = POSKeepFilter::new(keep_pos.clone());
+    let count_filter = TokenCountFilter::new("base_form".to_string(), true).unwrap();
+
+    // Inputs of each stage, computed once from the output of the previous stage
+    let normalized: Vec<String> = lines
+        .iter()
+        .map(|line| char_filter.apply(line).unwrap())
+        .collect();
+    let tokens: Vec<Vec<Token>> = normalized
+        .iter()
+        .map(|line| tokenize_line(&tokenizer, line))
+        .collect();
+    let kept: Vec<Vec<Token>> = tokens
+        .iter()
+        .map(|line| pos_filter.apply(line.clone().into_iter()).collect())
+        .collect();
+
+    let mut group = c.benchmark_group("analyzer");
+    group.throughput(Throughput::Elements(chars as u64));
+    group.bench_function("stage/normalize", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(char_filter.apply(black_box(line)).unwrap());
+            }
+        })
+    });
+    group.bench_function("stage/tokenize", |b| {
+        b.iter(|| {
+            for line in &normalized {
+                black_box(tokenize_line(&tokenizer, black_box(line)));
+            }
+        })
+    });
+    // Filters consume their input, so cloning the tokens is kept out of the measurement
+    group.bench_function("stage/pos_keep", |b| {
+        b.iter_batched(
+            || tokens.clone(),
+            |tokens| {
+                for line in tokens {
+                    black_box(pos_filter.apply(line.into_iter()).count());
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("stage/count", |b| {
+        b.iter_batched(
+            || kept.clone(),
+            |kept| {
+                for line in kept {
+                    black_box(count_filter.apply(line.into_iter()).count());
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    let analyzer = Analyzer::builder()
+        .add_char_filter(UnicodeNormalizeCharFilter::with_default_form())
+        .add_pos_keep_filter(keep_pos)
+        .build()
+        .unwrap();
+    group.bench_function("end_to_end", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(analyzer.analyze(black_box(line)).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    // Each corpus iteration takes long enough that fewer samples suffice
+    config = Criterion::default().sample_size(20);
+    targets = analyzer_benchmarks
+}
+criterion_main!(benches);
@@ -0,0 +1,84 @@
+//! Prefix lookups on the indexed FST against the packed single-entry layout
+//!
+//! Common prefix lookups are run from every character position of the
+//! bundled corpus and the matched values resolved to morpheme IDs, so the
+//! numbers include the indirection through the morpheme index that packing
+//! avoids for surfaces with a single entry.
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use runome::dictionary::{Matcher, loader, pack_single_entry_values};
+use std::fs;
+use std::path::PathBuf;
+
+/// Public-domain corpus bundled with the repository (Kajii Motojiro "Lemon", Aozora Bunko)
+const CORPUS_PATH: &str = "tests/text_lemon.txt";
+
+/// Longest lookup per position in characters, like the tokenizer
+const MAX_LOOKUP_CHARS: usize = 16;
+
+/// Run common prefix lookups from every character position and resolve morpheme IDs
+fn lookup_all(matcher: &Matcher, morpheme_index: &[Vec<u32>], positions: &[&str]) -> usize {
+    let mut resolved = 0;
+    for text in positions {
+        let (_, index_ids) = matcher.run(black_box(text), true).unwrap();
+        for index_id in index_ids {
+            resolved += matcher.lookup_morpheme_ids(index_id, morpheme_index).len();
+        }
+    }
+    resolved
+}
+
+fn fst_packing_benchmarks(c: &mut Criterion) {
+    let sysdic_path = PathBuf::from("sysdic");
+    if !sysdic_path.exists() {
+        eprintln!(
+            "Skipping benchmark: sysdic directory not found at {:?}",
+            sysdic_path
+        );
+        return;
+    }
+
+    let text = fs::read_to_string(CORPUS_PATH).expect("failed to read corpus");
+    let positions: Vec<&str> = text
+        .char_indices()
+        .map(|(i, _)| {
+            let rest = &text[i..];
+            let end = rest
+                .char_indices()
+                .nth(MAX_LOOKUP_CHARS)
+                .map_or(rest.len(), |(j, _)| j);
+            &rest[..end]
+        })
+        .collect();
+
+    let fst_bytes = loader::load_fst_bytes(&sysdic_path).expect("failed to load FST");
+    let morpheme_index =
+        loader::load_morpheme_index(&sysdic_path).expect("failed to load morpheme index");
+    let packed_bytes =
+        pack_single_entry_values(&fst_bytes, &morpheme_index).expect("failed to pack FST");
+    let indexed = Matcher::new(fst_bytes).expect("failed to build matcher");
+    let packed = Matcher::new(packed_bytes).expect("failed to build matcher");
+
+    // Both layouts must resolve the same morphemes for the comparison to mean anything
+    assert_eq!(
+        lookup_all(&indexed, &morpheme_index, &positions),
+        lookup_all(&packed, &morpheme_index, &positions),
+        "Packed FST resolved differently"
+    );
+
+    let mut group = c.benchmark_group("fst_packing");
+    group.throughput(Throughput::Elements(positions.len() as u64));
+    for (name, matcher) in [("indexed", &indexed), ("packed", &packed)] {
+        group.bench_function(name, |b| {
+            b.iter(|| lookup_all(matcher, &morpheme_index, &positions))
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = fst_packing_benchmarks
+}
+criterion_main!(benches);
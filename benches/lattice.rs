@@ -0,0 +1,94 @@
+//! Lattice benchmarks: Viterbi forward pass and best path backtracking
+//!
+//! Dictionary lookups are done once up front, so the numbers cover only
+//! adding nodes, advancing and tracing back the best path.
+
+use criterion::{BatchSize, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use runome::dictionary::{DictEntry, Dictionary, SystemDictionary};
+use runome::{DictNode, Lattice, NodeType};
+use std::sync::Arc;
+
+/// Longest word looked up per position, in characters
+const MAX_WORD_LENGTH: usize = 16;
+
+/// Sentences made of characters that are all in the system dictionary
+const SENTENCES: &[(&str, &str)] = &[
+    ("short", "すもももももももものうち"),
+    (
+        "long",
+        "えたいの知れない不吉な塊が私の心を始終押さえつけていた。不安と言おうか、嫌悪と言おうか",
+    ),
+];
+
+/// Dictionary entries starting at each character position of a sentence
+fn candidates<'d>(dic: &'d SystemDictionary, text: &str) -> Vec<Vec<&'d DictEntry>> {
+    let starts: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    starts
+        .iter()
+        .map(|&start| {
+            let ends = text[start..]
+                .char_indices()
+                .skip(1)
+                .map(|(i, _)| start + i)
+                .chain(std::iter::once(text.len()))
+                .take(MAX_WORD_LENGTH);
+            ends.flat_map(|end| dic.lookup(&text[start..end]).unwrap_or_default())
+                .collect()
+        })
+        .collect()
+}
+
+/// Build a lattice from the candidates the way the tokenizer does
+fn build<'d>(dic: &Arc<SystemDictionary>, candidates: &[Vec<&'d DictEntry>]) -> Lattice<'d> {
+    let mut lattice = Lattice::new(candidates.len() + 1, dic.clone() as Arc<dyn Dictionary>);
+    let mut pos = 0;
+    while pos < candidates.len() {
+        for &entry in &candidates[pos] {
+            lattice
+                .add(DictNode::new(entry, NodeType::SysDict))
+                .expect("failed to add node");
+        }
+        pos += lattice.forward();
+    }
+    lattice.end().expect("failed to end lattice");
+    lattice
+}
+
+fn lattice_benchmarks(c: &mut Criterion) {
+    let dic = match SystemDictionary::instance() {
+        Ok(dic) => dic,
+        Err(e) => {
+            eprintln!(
+                "Skipping benchmark: failed to load system dictionary: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    let mut group = c.benchmark_group("lattice");
+    for (name, text) in SENTENCES {
+        let candidates = candidates(&dic, text);
+        let path_len = build(&dic, &candidates)
+            .backward()
+            .expect("no path through the lattice")
+            .len();
+        // BOS and EOS aren't tokens
+        group.throughput(Throughput::Elements(path_len.saturating_sub(2) as u64));
+
+        group.bench_function(format!("{}/forward", name), |b| {
+            b.iter(|| build(&dic, black_box(&candidates)))
+        });
+        group.bench_function(format!("{}/backward", name), |b| {
+            b.iter_batched_ref(
+                || build(&dic, &candidates),
+                |lattice| lattice.backward().map(|path| path.len()),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lattice_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+//! Matcher lookup benchmarks: exact and prefix matches over the system FST
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use runome::{Matcher, loader};
+use std::path::PathBuf;
+
+/// Words of varying lengths and scripts
+const WORDS: &[(&str, &str)] = &[
+    ("single", "東"),
+    ("two_chars", "東京"),
+    ("three_chars", "東京都"),
+    ("long_hiragana", "すもももももももものうち"),
+    ("six_chars", "関西国際空港"),
+    ("mixed", "メロスは激怒した"),
+];
+
+/// Long text with many prefix matches
+const LONG_TEXT: &str =
+    "これは非常に長い日本語の文章でマッチャーのパフォーマンスをテストするためのものです";
+
+fn matcher_benchmarks(c: &mut Criterion) {
+    let sysdic_path = PathBuf::from("sysdic");
+    if !sysdic_path.exists() {
+        eprintln!(
+            "Skipping benchmark: sysdic directory not found at {:?}",
+            sysdic_path
+        );
+        return;
+    }
+    let fst_bytes = loader::load_fst_bytes(&sysdic_path).expect("failed to load FST");
+    let matcher = Matcher::new(fst_bytes).expect("failed to build matcher");
+
+    let mut group = c.benchmark_group("matcher/exact");
+    group.throughput(Throughput::Elements(1));
+    for (name, word) in WORDS {
+        group.bench_with_input(BenchmarkId::from_parameter(name), word, |b, word| {
+            b.iter(|| matcher.run(black_box(word), false))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("matcher/prefix");
+    group.throughput(Throughput::Elements(1));
+    for (name, word) in WORDS.iter().chain(&[("long_text", LONG_TEXT)]) {
+        group.bench_with_input(BenchmarkId::from_parameter(name), word, |b, word| {
+            b.iter(|| matcher.run(black_box(word), true))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, matcher_benchmarks);
+criterion_main!(benches);
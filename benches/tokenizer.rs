@@ -0,0 +1,65 @@
+//! End-to-end tokenization benchmarks on the bundled corpus
+//!
+//! Throughput is reported in tokens per second, counted once per input
+//! before measuring.
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use runome::Tokenizer;
+use std::fs;
+
+/// Public-domain corpus bundled with the repository (Kajii Motojiro "Lemon", Aozora Bunko)
+const CORPUS_PATH: &str = "tests/text_lemon.txt";
+
+/// Short sentence exercising dictionary and unknown words
+const SENTENCE: &str = "すもももももももものうち。東京スカイツリーへのお越しはＪＲ線が便利です。";
+
+fn count_tokens(tokenizer: &Tokenizer, lines: &[&str]) -> u64 {
+    lines
+        .iter()
+        .map(|line| tokenizer.tokenize(line, None, None).count() as u64)
+        .sum()
+}
+
+fn tokenize_all(tokenizer: &Tokenizer, lines: &[&str], wakati: bool) -> usize {
+    let mut tokens = 0;
+    for line in lines {
+        for result in tokenizer.tokenize(black_box(line), Some(wakati), None) {
+            black_box(result.expect("tokenization failed"));
+            tokens += 1;
+        }
+    }
+    tokens
+}
+
+fn tokenizer_benchmarks(c: &mut Criterion) {
+    let tokenizer = match Tokenizer::new(None, None) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Skipping benchmark: failed to initialize tokenizer: {}", e);
+            return;
+        }
+    };
+    let text = fs::read_to_string(CORPUS_PATH).expect("failed to read corpus");
+    let corpus: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let sentence = [SENTENCE];
+
+    let mut group = c.benchmark_group("tokenizer");
+    for (name, lines) in [("sentence", &sentence[..]), ("lemon", &corpus[..])] {
+        group.throughput(Throughput::Elements(count_tokens(&tokenizer, lines)));
+        group.bench_function(format!("{}/tokens", name), |b| {
+            b.iter(|| tokenize_all(&tokenizer, lines, false))
+        });
+        group.bench_function(format!("{}/wakati", name), |b| {
+            b.iter(|| tokenize_all(&tokenizer, lines, true))
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    // Each corpus iteration takes long enough that fewer samples suffice
+    config = Criterion::default().sample_size(20);
+    targets = tokenizer_benchmarks
+}
+criterion_main!(benches);
@@ -0,0 +1,334 @@
+//! Compare runome against MeCab and Janome on the same corpus
+//!
+//! Each line of the corpus is tokenized by runome and, when installed, by the
+//! `mecab` command and the `janome` Python package. Segmentations are compared
+//! with runome as the reference, ignoring whitespace tokens since MeCab drops
+//! them. MeCab should use IPADIC, the dictionary runome and Janome are built
+//! from, or agreement will be low for reasons unrelated to the analyzers.
+//!
+//! Usage:
+//!   cargo run --release --example compare_bench -- \
+//!       [--corpus PATH] [--mecab CMD] [--python CMD] [--iterations N]
+//!
+//! Agreement columns are the share of sentences with identical tokens and
+//! POS, the F1 score of token spans, the share of tokens with matching spans
+//! that also have the same 4-level POS, and the token count relative to
+//! runome.
+//!
+//! Throughput of MeCab is measured around the whole process, so it includes
+//! dictionary loading; Janome reports its own tokenization time.
+
+use runome::{TokenizeResult, Tokenizer};
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Public-domain corpus bundled with the repository (Kajii Motojiro "Lemon", Aozora Bunko)
+const DEFAULT_CORPUS_PATH: &str = "tests/text_lemon.txt";
+
+/// Tokenizes stdin line by line with Janome, printing MeCab-style output
+/// followed by the tokenization time on stderr
+const JANOME_SCRIPT: &str = r#"
+import sys, time
+from janome.tokenizer import Tokenizer
+t = Tokenizer()
+lines = sys.stdin.read().splitlines()
+start = time.perf_counter()
+out = []
+for line in lines:
+    for token in t.tokenize(line):
+        out.append(token.surface + "\t" + token.part_of_speech)
+    out.append("EOS")
+elapsed = time.perf_counter() - start
+sys.stdout.write("\n".join(out) + "\n")
+sys.stderr.write("elapsed %f\n" % elapsed)
+"#;
+
+/// A token of one analyzer: character span in the line and 4-level POS
+#[derive(Debug, Clone, PartialEq)]
+struct Morpheme {
+    start: usize,
+    end: usize,
+    pos: String,
+}
+
+/// Output of one analyzer over the whole corpus
+struct Run {
+    name: &'static str,
+    sentences: Vec<Vec<Morpheme>>,
+    elapsed: Duration,
+}
+
+/// Agreement of an analyzer with the reference
+#[derive(Default)]
+struct Agreement {
+    sentences: usize,
+    identical_sentences: usize,
+    reference_tokens: usize,
+    tokens: usize,
+    matching_tokens: usize,
+    matching_pos: usize,
+}
+
+impl Agreement {
+    fn compare(reference: &[Vec<Morpheme>], other: &[Vec<Morpheme>]) -> Self {
+        let mut agreement = Agreement::default();
+        for (expected, actual) in reference.iter().zip(other) {
+            agreement.sentences += 1;
+            agreement.reference_tokens += expected.len();
+            agreement.tokens += actual.len();
+            if expected == actual {
+                agreement.identical_sentences += 1;
+            }
+            // Both lists are sorted by span, so merge them
+            let (mut i, mut j) = (0, 0);
+            while i < expected.len() && j < actual.len() {
+                let (e, a) = (&expected[i], &actual[j]);
+                if (e.start, e.end) == (a.start, a.end) {
+                    agreement.matching_tokens += 1;
+                    if e.pos == a.pos {
+                        agreement.matching_pos += 1;
+                    }
+                    i += 1;
+                    j += 1;
+                } else if (e.start, e.end) < (a.start, a.end) {
+                    i += 1;
+                } else {
+                    j += 1;
+                }
+            }
+        }
+        agreement
+    }
+
+    fn report(&self, name: &str) {
+        let ratio = |n: usize, d: usize| if d > 0 { n as f64 / d as f64 } else { 0.0 };
+        let precision = ratio(self.matching_tokens, self.tokens);
+        let recall = ratio(self.matching_tokens, self.reference_tokens);
+        let f1 = if precision + recall > 0.0 {
+            2.0 * precision * recall / (precision + recall)
+        } else {
+            0.0
+        };
+        println!(
+            "{:<8} {:>9.2}% {:>9.2}% {:>9.2}% {:>9.2}%",
+            name,
+            ratio(self.identical_sentences, self.sentences) * 100.0,
+            f1 * 100.0,
+            ratio(self.matching_pos, self.matching_tokens) * 100.0,
+            ratio(self.tokens, self.reference_tokens) * 100.0,
+        );
+    }
+}
+
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Build a morpheme from a surface found at or after `cursor` in the line
+///
+/// Returns `None` for whitespace tokens and surfaces not found in the line.
+fn locate(line: &str, cursor: &mut usize, surface: &str, features: &str) -> Option<Morpheme> {
+    let offset = line[*cursor..].find(surface)?;
+    let start = *cursor + offset;
+    let end = start + surface.len();
+    *cursor = end;
+    if surface.trim().is_empty() {
+        return None;
+    }
+    let pos: Vec<&str> = features.split(',').take(4).collect();
+    Some(Morpheme {
+        start: line[..start].chars().count(),
+        end: line[..end].chars().count(),
+        pos: pos.join(","),
+    })
+}
+
+/// Parse MeCab-style `surface\tfeatures` lines with `EOS` after each sentence
+fn parse_sentences(lines: &[&str], output: &str) -> Vec<Vec<Morpheme>> {
+    let mut sentences = Vec::with_capacity(lines.len());
+    let mut current = Vec::new();
+    let mut cursor = 0;
+    for row in output.lines() {
+        if row == "EOS" {
+            sentences.push(std::mem::take(&mut current));
+            cursor = 0;
+            continue;
+        }
+        let Some(line) = lines.get(sentences.len()) else {
+            break;
+        };
+        if let Some((surface, features)) = row.split_once('\t') {
+            current.extend(locate(line, &mut cursor, surface, features));
+        }
+    }
+    sentences
+}
+
+fn run_runome(lines: &[&str], iterations: u32) -> Result<Run, String> {
+    let tokenizer = Tokenizer::new(None, None).map_err(|e| e.to_string())?;
+    let mut sentences = Vec::new();
+    let start = Instant::now();
+    for iteration in 0..iterations {
+        for line in lines {
+            let mut morphemes = Vec::new();
+            let mut cursor = 0;
+            for result in tokenizer.tokenize(line, None, None) {
+                if let TokenizeResult::Token(token) = result.map_err(|e| e.to_string())? {
+                    morphemes.extend(locate(
+                        line,
+                        &mut cursor,
+                        token.surface(),
+                        token.part_of_speech(),
+                    ));
+                }
+            }
+            if iteration == 0 {
+                sentences.push(morphemes);
+            }
+        }
+    }
+    Ok(Run {
+        name: "runome",
+        sentences,
+        elapsed: start.elapsed() / iterations,
+    })
+}
+
+/// Run a command with the corpus on stdin, returning stdout, stderr and wall time
+fn run_command(
+    program: &str,
+    args: &[&str],
+    input: &str,
+) -> Result<(String, String, Duration), String> {
+    let start = Instant::now();
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run {}: {}", program, e))?;
+    // Write from another thread so a child streaming its output can't block us
+    let mut stdin = child.stdin.take().ok_or("stdin not captured")?;
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    writer
+        .join()
+        .map_err(|_| "writer thread panicked".to_string())?
+        .map_err(|e| e.to_string())?;
+    let elapsed = start.elapsed();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with {}: {}",
+            program,
+            output.status,
+            stderr.trim()
+        ));
+    }
+    let stdout = String::from_utf8(output.stdout).map_err(|e| e.to_string())?;
+    Ok((stdout, stderr, elapsed))
+}
+
+fn run_mecab(program: &str, lines: &[&str]) -> Result<Run, String> {
+    let input = lines.join("\n") + "\n";
+    let (stdout, _, elapsed) = run_command(program, &[], &input)?;
+    Ok(Run {
+        name: "mecab",
+        sentences: parse_sentences(lines, &stdout),
+        elapsed,
+    })
+}
+
+fn run_janome(python: &str, lines: &[&str]) -> Result<Run, String> {
+    let input = lines.join("\n") + "\n";
+    let (stdout, stderr, wall) = run_command(python, &["-c", JANOME_SCRIPT], &input)?;
+    let elapsed = stderr
+        .lines()
+        .find_map(|l| l.strip_prefix("elapsed "))
+        .and_then(|secs| secs.trim().parse().ok())
+        .map_or(wall, Duration::from_secs_f64);
+    Ok(Run {
+        name: "janome",
+        sentences: parse_sentences(lines, &stdout),
+        elapsed,
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let corpus_path = arg_value(&args, "--corpus").unwrap_or(DEFAULT_CORPUS_PATH);
+    let mecab = arg_value(&args, "--mecab").unwrap_or("mecab");
+    let python = arg_value(&args, "--python").unwrap_or("python3");
+    let iterations: u32 = arg_value(&args, "--iterations")
+        .and_then(|n| n.parse().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(1);
+
+    let text = match fs::read_to_string(corpus_path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Failed to read corpus {}: {}", corpus_path, e);
+            std::process::exit(1);
+        }
+    };
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let total_chars: usize = lines.iter().map(|l| l.chars().count()).sum();
+    eprintln!(
+        "Comparing analyzers over {} lines ({} chars)...",
+        lines.len(),
+        total_chars
+    );
+
+    let reference = match run_runome(&lines, iterations) {
+        Ok(run) => run,
+        Err(e) => {
+            eprintln!("Failed to run runome: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut runs = vec![];
+    for run in [run_mecab(mecab, &lines), run_janome(python, &lines)] {
+        match run {
+            Ok(run) if run.sentences.len() == lines.len() => runs.push(run),
+            Ok(run) => eprintln!(
+                "Skipping {}: got {} sentences for {} lines",
+                run.name,
+                run.sentences.len(),
+                lines.len()
+            ),
+            Err(e) => eprintln!("Skipping: {}", e),
+        }
+    }
+
+    println!("=== Agreement with runome ===");
+    println!(
+        "{:<8} {:>10} {:>10} {:>10} {:>10}",
+        "analyzer", "sentences", "token F1", "POS", "tokens"
+    );
+    for run in &runs {
+        Agreement::compare(&reference.sentences, &run.sentences).report(run.name);
+    }
+
+    println!();
+    println!("=== Throughput ===");
+    let runome_secs = reference.elapsed.as_secs_f64();
+    for run in std::iter::once(&reference).chain(&runs) {
+        let secs = run.elapsed.as_secs_f64();
+        println!(
+            "{:<8} {:>10.2} ms {:>14.0} chars/s {:>8.2}x runome",
+            run.name,
+            secs * 1000.0,
+            total_chars as f64 / secs.max(f64::EPSILON),
+            runome_secs / secs.max(f64::EPSILON)
+        );
+    }
+}
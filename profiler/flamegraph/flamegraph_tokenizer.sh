@@ -20,11 +20,11 @@ if ! command -v cargo-flamegraph &> /dev/null; then
     cargo install flamegraph
 fi
 
-echo "Building and profiling tokenizer example..."
+echo "Building and profiling tokenizer benchmark..."
 
 # Generate flamegraph with cargo-flamegraph
 # This will build in release mode with debug symbols and profile the execution
-cargo flamegraph --example tokenizer_bench -o "$OUTPUT_DIR/tokenizer_flamegraph.svg" -- --bench
+cargo flamegraph --bench tokenizer -o "$OUTPUT_DIR/tokenizer_flamegraph.svg" -- --bench --profile-time 10
 
 echo "Flamegraph saved to: $OUTPUT_DIR/tokenizer_flamegraph.svg"
 
@@ -33,10 +33,11 @@ if command -v perf &> /dev/null; then
     echo "Generating detailed perf flamegraph..."
     
     # Build with full debug info
-    CARGO_PROFILE_RELEASE_DEBUG=true cargo build --release --example tokenizer_bench
+    CARGO_PROFILE_BENCH_DEBUG=true cargo bench --no-run --bench tokenizer
     
     # Record with perf
-    sudo perf record -F 999 -g --call-graph=dwarf target/release/examples/tokenizer_bench --bench
+    sudo perf record -F 999 -g --call-graph=dwarf -- \
+        env CARGO_PROFILE_BENCH_DEBUG=true cargo bench --bench tokenizer -- --profile-time 10
     
     # Generate flamegraph from perf data
     perf script | cargo flamegraph -- --perfdata /dev/stdin -o "$OUTPUT_DIR/tokenizer_perf_flamegraph.svg"
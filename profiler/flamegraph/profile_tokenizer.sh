@@ -14,15 +14,15 @@ mkdir -p "$OUTPUT_DIR"
 
 echo "Building release binary with debug symbols..."
 cd "$PROJECT_ROOT"
-cargo build --release --example tokenizer_bench
+cargo bench --no-run --bench tokenizer
 
 echo "Recording performance data..."
 # Run perf record on the tokenizer benchmark
-sudo perf record -F 99 -a -g -- cargo run --release --example tokenizer_bench
+sudo perf record -F 99 -a -g -- cargo bench --bench tokenizer -- --profile-time 10
 
 echo "Generating flamegraph..."
 # Generate flamegraph using cargo-flamegraph
-cargo flamegraph --bin tokenizer_bench --output "$OUTPUT_DIR/tokenizer_flamegraph.svg"
+cargo flamegraph --bench tokenizer --output "$OUTPUT_DIR/tokenizer_flamegraph.svg" -- --bench --profile-time 10
 
 echo "Flamegraph generated at: $OUTPUT_DIR/tokenizer_flamegraph.svg"
 echo "You can view it in a web browser."
\ No newline at end of file
@@ -5,6 +5,16 @@ This module provides character filters that transform input text
 before tokenization occurs.
 """
 
-from .runome import CharFilter, RegexReplaceCharFilter, UnicodeNormalizeCharFilter
+from .runome import (
+    CharFilter,
+    RegexReplaceCharFilter,
+    UnicodeNormalizeCharFilter,
+    WidthNormalizeCharFilter,
+)
 
-__all__ = ["CharFilter", "RegexReplaceCharFilter", "UnicodeNormalizeCharFilter"]
+__all__ = [
+    "CharFilter",
+    "RegexReplaceCharFilter",
+    "UnicodeNormalizeCharFilter",
+    "WidthNormalizeCharFilter",
+]
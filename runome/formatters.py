@@ -0,0 +1,20 @@
+"""
+Output formatters.
+
+This module provides formatters that write tokenized sentences in formats
+compatible with existing Janome/MeCab tooling.
+"""
+
+from .runome import (
+    ChasenFormatter,
+    MecabFormatter,
+    ConlluFormatter,
+    WakatiWriter,
+)
+
+__all__ = [
+    "ChasenFormatter",
+    "MecabFormatter",
+    "ConlluFormatter",
+    "WakatiWriter",
+]
@@ -2,7 +2,8 @@
 Type stubs for runome Rust module.
 """
 
-from typing import Optional, Union
+import os
+from typing import Any, Callable, Dict, Iterable, Iterator, List, Optional, Tuple, Union
 
 class Token:
     """Token with morphological information."""
@@ -37,6 +38,11 @@ class Token:
         """Reading of the token."""
         ...
 
+    @property
+    def reading_hiragana(self) -> str:
+        """Reading of the token in hiragana."""
+        ...
+
     @property
     def phonetic(self) -> str:
         """Phonetic transcription."""
@@ -47,6 +53,16 @@ class Token:
         """Type of the node (SysDict, UserDict, Unknown)."""
         ...
 
+    @property
+    def word_id(self) -> Optional[int]:
+        """ID of the dictionary entry, None for unknown words."""
+        ...
+
+    @property
+    def word_cost(self) -> Optional[int]:
+        """Word cost of the lattice node, without connection costs."""
+        ...
+
     def __str__(self) -> str:
         """String representation in Janome format."""
         ...
@@ -55,6 +71,22 @@ class Token:
         """Debug representation."""
         ...
 
+    def __eq__(self, other: object) -> bool:
+        """Compare all attributes, regardless of position in the text."""
+        ...
+
+    def __hash__(self) -> int:
+        """Hash consistent with __eq__."""
+        ...
+
+    def __len__(self) -> int:
+        """Length of the surface in characters."""
+        ...
+
+    def as_dict(self) -> Dict[str, Any]:
+        """All attributes as a dict, e.g. for json.dumps."""
+        ...
+
 class TokenIterator:
     """Iterator for tokenization results."""
 
@@ -71,35 +103,229 @@ class Tokenizer:
 
     def __init__(
         self,
-        udic: str = "",
+        udic: Union[str, "os.PathLike[str]"] = "",
         *,
         udic_enc: str = "utf8",
         udic_type: str = "ipadic",
         max_unknown_length: int = 1024,
         wakati: bool = False,
+        theta: float = 0.75,
+        cost_factor: float = 800.0,
     ) -> None:
         """Initialize tokenizer.
 
         Args:
             udic: User dictionary file path (CSV format) or directory path to compiled dictionary data (default: '')
-            udic_enc: Character encoding for user dictionary - 'utf8', 'euc-jp', or 'shift_jis' (default: 'utf8')
+            udic_enc: Character encoding of a CSV user dictionary - 'utf8', 'euc-jp', or 'shift_jis' (default: 'utf8')
             udic_type: User dictionary type - 'ipadic' or 'simpledic' (default: 'ipadic')
             max_unknown_length: Maximum unknown word length (default: 1024)
             wakati: Wakati mode flag (default: False)
+            theta: Scale of the costs in the probabilities of nbest candidates,
+                like MeCab's --theta (default: 0.75)
+            cost_factor: cost-factor of the dicrc the system dictionary was
+                compiled with, undone in the probabilities of nbest candidates
+                (default: 800, as in mecab-ipadic)
         """
         ...
 
     def tokenize(
-        self, text: str, wakati: Optional[bool] = None, baseform_unk: bool = True
+        self,
+        text: Union[str, bytes],
+        wakati: Optional[bool] = None,
+        baseform_unk: bool = True,
+        dotfile: str = "",
+        *,
+        encoding: Optional[str] = None,
     ) -> TokenIterator:
         """Tokenize text.
 
         Args:
-            text: Input text to tokenize
+            text: Input text to tokenize, str or encoded bytes
             wakati: Override wakati mode (default: None)
             baseform_unk: Set base form for unknown words (default: True)
+            dotfile: Write the lattice of inputs under 1024 characters as
+                Graphviz DOT to this file (default: "", no file)
+            encoding: Encoding of bytes input - 'utf8', 'euc-jp', or
+                'shift_jis' (default: None, meaning 'utf8')
 
         Returns:
             Iterator yielding Token objects (wakati=False) or strings (wakati=True)
         """
         ...
+
+    def tokenize_batch(self, texts: List[str]) -> List[List[Token]]:
+        """Tokenize many texts in parallel.
+
+        The GIL is released while tokenizing. Tokens are always returned in
+        full, ignoring wakati mode.
+
+        Args:
+            texts: Texts to tokenize
+
+        Returns:
+            Tokens of each text, in input order
+        """
+        ...
+
+    def nbest(self, text: str, n: int = 10) -> List[Tuple[List[Token], int, float]]:
+        """Find the N best segmentations of a text.
+
+        Args:
+            text: Text to analyze as a whole
+            n: Maximum number of candidates
+
+        Returns:
+            (tokens, cost, probability) of each candidate, in order of
+            increasing cost. Probabilities are proportional to
+            exp(-theta * cost / cost_factor) and sum to 1.
+        """
+        ...
+
+    def wakati(self, text: str) -> List[str]:
+        """Split text into a list of surface forms."""
+        ...
+
+    def __getstate__(self) -> Dict[str, Any]:
+        """Constructor arguments to rebuild the tokenizer from when unpickling."""
+        ...
+
+    def __setstate__(self, state: Dict[str, Any]) -> None:
+        """Rebuild the tokenizer from the state of __getstate__."""
+        ...
+
+def preload_dictionary() -> None:
+    """Load the system dictionary before forking worker processes.
+
+    Workers forked afterwards share the dictionary memory with the parent.
+    """
+    ...
+
+def compile_user_dictionary(
+    udic: Union[str, "os.PathLike[str]"],
+    output_dir: Union[str, "os.PathLike[str]"],
+    *,
+    udic_enc: str = "utf8",
+    udic_type: str = "ipadic",
+) -> None:
+    """Compile a CSV user dictionary into a directory loadable as `udic`."""
+    ...
+
+class ChasenFormatter:
+    """Formatter producing ChaSen (mecab -Ochasen) compatible output."""
+
+    def __init__(self) -> None: ...
+    def format(self, tokens: Iterable[Token]) -> str:
+        """Format one sentence, ending with an EOS line."""
+        ...
+
+class MecabFormatter:
+    """Formatter producing MeCab default format compatible output."""
+
+    def __init__(self) -> None: ...
+    def format(self, tokens: Iterable[Token]) -> str:
+        """Format one sentence, ending with an EOS line."""
+        ...
+
+class ConlluFormatter:
+    """Formatter producing CoNLL-U rows for Universal Dependencies tooling."""
+
+    def __init__(self) -> None: ...
+    def format(self, tokens: Iterable[Token]) -> str:
+        """Format one sentence, ending with a blank line."""
+        ...
+
+class WakatiWriter:
+    """Writer producing wakati (space-separated surface) output."""
+
+    def __init__(self, separator: str = " ") -> None: ...
+    def format(self, tokens: Iterable[Union[Token, str]]) -> str:
+        """Format one sentence of tokens or surfaces, ending with a newline."""
+        ...
+
+class CharFilter:
+    """Base class of character filters; subclasses override apply()."""
+
+    def __init__(self, *args: Any, **kwargs: Any) -> None: ...
+    def apply(self, text: str) -> str:
+        """Return the filtered text."""
+        ...
+
+    def __call__(self, text: str) -> str: ...
+
+class RegexReplaceCharFilter(CharFilter):
+    """Replace every match of a regular expression."""
+
+    def __init__(self, pat: str, repl: str) -> None: ...
+
+class UnicodeNormalizeCharFilter(CharFilter):
+    """Apply Unicode normalization ('NFC', 'NFKC', 'NFD' or 'NFKD')."""
+
+    def __init__(self, form: str = "NFKC") -> None: ...
+
+class WidthNormalizeCharFilter(CharFilter):
+    """Convert katakana and ASCII between half-width and full-width forms.
+
+    Only the width of these characters changes, unlike NFKC normalization.
+    """
+
+    def __init__(
+        self, katakana: Optional[str] = "full", ascii: Optional[str] = "half"
+    ) -> None: ...
+
+class TokenFilter:
+    """Base class of token filters; subclasses override apply()."""
+
+    def __init__(self, *args: Any, **kwargs: Any) -> None: ...
+    def apply(self, tokens: Iterable[Any]) -> Iterator[Any]:
+        """Return the filtered tokens."""
+        ...
+
+    def __call__(self, tokens: Iterable[Any]) -> Iterator[Any]: ...
+
+class LowerCaseFilter(TokenFilter):
+    def __init__(self) -> None: ...
+
+class UpperCaseFilter(TokenFilter):
+    def __init__(self) -> None: ...
+
+class RomajiFilter(TokenFilter):
+    def __init__(self) -> None: ...
+
+class POSStopFilter(TokenFilter):
+    def __init__(self, pos_list: List[str]) -> None: ...
+
+class POSKeepFilter(TokenFilter):
+    def __init__(self, pos_list: List[str]) -> None: ...
+
+class CompoundNounFilter(TokenFilter):
+    def __init__(self) -> None: ...
+
+class ExtractAttributeFilter(TokenFilter):
+    """Terminal filter yielding one attribute of each token as a string."""
+
+    def __init__(self, att: str = "surface") -> None: ...
+
+class TokenCountFilter(TokenFilter):
+    """Terminal filter yielding (attribute, count) tuples."""
+
+    def __init__(self, att: str = "surface", sorted: bool = False) -> None: ...
+    def __call__(self, tokens: Iterable[Any]) -> Iterator[Tuple[str, int]]: ...
+
+class Analyzer:
+    """Pipeline of character filters, a tokenizer and token filters."""
+
+    def __init__(
+        self,
+        *,
+        char_filters: List[Union[CharFilter, Callable[[str], str]]] = [],
+        tokenizer: Optional[Tokenizer] = None,
+        token_filters: List[
+            Union[TokenFilter, Callable[[Iterable[Any]], Iterable[Any]]]
+        ] = [],
+    ) -> None:
+        """Initialize analyzer; a tokenizer with wakati=True is rejected."""
+        ...
+
+    def analyze(self, text: str) -> Iterator[Any]:
+        """Run text through the filters and the tokenizer."""
+        ...
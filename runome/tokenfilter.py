@@ -9,9 +9,14 @@ from .runome import (
     TokenFilter,
     LowerCaseFilter,
     UpperCaseFilter,
+    RomajiFilter,
     POSStopFilter,
     POSKeepFilter,
     CompoundNounFilter,
+    DedupFilter,
+    WordStopFilter,
+    WordKeepFilter,
+    PiiMaskFilter,
     ExtractAttributeFilter,
     TokenCountFilter,
     TokenFilterIterator,
@@ -21,9 +26,14 @@ __all__ = [
     "TokenFilter",
     "LowerCaseFilter",
     "UpperCaseFilter",
+    "RomajiFilter",
     "POSStopFilter",
     "POSKeepFilter",
     "CompoundNounFilter",
+    "DedupFilter",
+    "WordStopFilter",
+    "WordKeepFilter",
+    "PiiMaskFilter",
     "ExtractAttributeFilter",
     "TokenCountFilter",
     "TokenFilterIterator",
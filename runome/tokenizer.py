@@ -5,6 +5,6 @@ This module provides the core tokenization functionality with the same API as
 the Janome library but with improved performance through Rust implementation.
 """
 
-from .runome import Token, Tokenizer
+from .runome import Token, Tokenizer, compile_user_dictionary, preload_dictionary
 
-__all__ = ["Token", "Tokenizer"]
+__all__ = ["Token", "Tokenizer", "compile_user_dictionary", "preload_dictionary"]
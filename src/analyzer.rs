@@ -1,8 +1,27 @@
-use crate::{CharFilter, RunomeError, Token, TokenFilter, TokenizeResult, Tokenizer};
+use crate::UnicodeNormalizeCharFilter;
 use crate::{
-    CompoundNounFilter, ExtractAttributeFilter, LowerCaseFilter, POSKeepFilter, POSStopFilter,
-    TokenCountFilter, UpperCaseFilter,
+    CharFilter, OffsetMapping, RunomeError, Token, TokenFilter, TokenizeResult, Tokenizer,
 };
+use crate::{
+    CompoundNounFilter, DedupFilter, ExtractAttributeFilter, LowerCaseFilter, POSKeepFilter,
+    POSStopFilter, PiiKind, PiiMaskFilter, TokenCountFilter, UpperCaseFilter, WordKeepFilter,
+    WordStopFilter,
+};
+use std::collections::HashSet;
+
+/// Part-of-speech prefixes kept by `index_terms`
+const INDEX_TERM_POS: [&str; 3] = ["名詞", "動詞", "形容詞"];
+
+/// Dependent words, pronouns and verb suffixes dropped by `index_terms`
+/// although their top-level part of speech is kept. Noun suffixes such as 駅
+/// are kept since they are meaningful search terms.
+const INDEX_TERM_STOP_POS: [&str; 5] = [
+    "名詞,非自立",
+    "名詞,代名詞",
+    "動詞,非自立",
+    "動詞,接尾",
+    "形容詞,非自立",
+];
 
 /// Enum wrapper for TokenFilter to enable dynamic dispatch
 /// This solves the trait object compatibility issue with generic methods
@@ -13,6 +32,10 @@ pub enum DynTokenFilter {
     POSStop(POSStopFilter),
     POSKeep(POSKeepFilter),
     CompoundNoun(CompoundNounFilter),
+    Dedup(DedupFilter),
+    WordStop(WordStopFilter),
+    WordKeep(WordKeepFilter),
+    PiiMask(PiiMaskFilter),
     ExtractAttribute(ExtractAttributeFilter),
     TokenCount(TokenCountFilter),
 }
@@ -28,6 +51,10 @@ impl DynTokenFilter {
             DynTokenFilter::POSStop(filter) => Ok(filter.apply(tokens_iter).collect()),
             DynTokenFilter::POSKeep(filter) => Ok(filter.apply(tokens_iter).collect()),
             DynTokenFilter::CompoundNoun(filter) => Ok(filter.apply(tokens_iter).collect()),
+            DynTokenFilter::Dedup(filter) => Ok(filter.apply(tokens_iter).collect()),
+            DynTokenFilter::WordStop(filter) => Ok(filter.apply(tokens_iter).collect()),
+            DynTokenFilter::WordKeep(filter) => Ok(filter.apply(tokens_iter).collect()),
+            DynTokenFilter::PiiMask(filter) => Ok(filter.apply(tokens_iter).collect()),
             DynTokenFilter::ExtractAttribute(_) => {
                 // ExtractAttributeFilter outputs strings, not tokens
                 Err(RunomeError::FilterChainError {
@@ -60,7 +87,8 @@ impl DynTokenFilter {
 ///     .add_char_filter(UnicodeNormalizeCharFilter::with_default_form())
 ///     .add_compound_noun_filter()
 ///     .add_lower_case_filter()
-///     .build();
+///     .build()
+///     .unwrap();
 ///
 /// let results = analyzer.analyze("テスト用のテキスト").unwrap();
 /// ```
@@ -78,6 +106,8 @@ impl Analyzer {
 
     /// Analyze text through the complete pipeline
     ///
+    /// Token spans refer to the input text before char filters were applied.
+    ///
     /// # Arguments
     /// * `text` - Input text to analyze
     ///
@@ -85,10 +115,13 @@ impl Analyzer {
     /// * `Ok(Vec<Token>)` - Vector of analysis results
     /// * `Err(RunomeError)` - Error if analysis fails
     pub fn analyze(&self, text: &str) -> Result<Vec<Token>, RunomeError> {
-        // Stage 1: Apply CharFilters sequentially
+        // Stage 1: Apply CharFilters sequentially, composing their offset mappings
         let mut processed_text = text.to_string();
+        let mut mapping = OffsetMapping::identity(text.len());
         for filter in &self.char_filters {
-            processed_text = filter.apply(&processed_text)?;
+            let (filtered, filter_mapping) = filter.apply_with_offsets(&processed_text)?;
+            mapping = mapping.compose(&filter_mapping);
+            processed_text = filtered;
         }
 
         // Stage 2: Tokenize the preprocessed text
@@ -101,8 +134,14 @@ impl Analyzer {
             .into_iter()
             .filter_map(|result| {
                 match result {
-                    TokenizeResult::Token(token) => Some(token),
+                    TokenizeResult::Token(token) => match token.span() {
+                        Some(span) => Some(token.with_span(mapping.to_original(span))),
+                        None => Some(token),
+                    },
                     TokenizeResult::Surface(_) => None, // Skip surface-only results
+                    TokenizeResult::Skipped { .. }
+                    | TokenizeResult::Bos { .. }
+                    | TokenizeResult::Eos { .. } => None,
                 }
             })
             .collect();
@@ -214,25 +253,151 @@ impl AnalyzerBuilder {
         self
     }
 
+    /// Add a DedupFilter to the post-processing chain
+    ///
+    /// # Arguments
+    /// * `attribute` - The token attribute to compare: surface or base_form
+    ///
+    /// # Returns
+    /// * `Ok(AnalyzerBuilder)` - Builder with the filter added
+    /// * `Err(RunomeError)` - Error if the attribute is invalid
+    pub fn add_dedup_filter(mut self, attribute: String) -> Result<Self, RunomeError> {
+        self.token_filters
+            .push(DynTokenFilter::Dedup(DedupFilter::new(attribute)?));
+        Ok(self)
+    }
+
+    /// Add a WordStopFilter to the post-processing chain
+    ///
+    /// # Arguments
+    /// * `words` - The words to remove
+    /// * `attribute` - The token attribute to match: surface or base_form
+    ///
+    /// # Returns
+    /// * `Ok(AnalyzerBuilder)` - Builder with the filter added
+    /// * `Err(RunomeError)` - Error if the attribute is invalid
+    pub fn add_word_stop_filter(
+        mut self,
+        words: HashSet<String>,
+        attribute: String,
+    ) -> Result<Self, RunomeError> {
+        self.token_filters
+            .push(DynTokenFilter::WordStop(WordStopFilter::new(
+                words, attribute,
+            )?));
+        Ok(self)
+    }
+
+    /// Add a PiiMaskFilter to the post-processing chain
+    ///
+    /// # Arguments
+    /// * `kinds` - The kinds of PII to mask
+    pub fn add_pii_mask_filter(mut self, kinds: Vec<PiiKind>) -> Self {
+        self.token_filters.push(DynTokenFilter::PiiMask(
+            PiiMaskFilter::new().with_kinds(kinds),
+        ));
+        self
+    }
+
+    /// Add a WordKeepFilter to the post-processing chain
+    ///
+    /// # Arguments
+    /// * `words` - The words to keep
+    /// * `attribute` - The token attribute to match: surface or base_form
+    ///
+    /// # Returns
+    /// * `Ok(AnalyzerBuilder)` - Builder with the filter added
+    /// * `Err(RunomeError)` - Error if the attribute is invalid
+    pub fn add_word_keep_filter(
+        mut self,
+        words: HashSet<String>,
+        attribute: String,
+    ) -> Result<Self, RunomeError> {
+        self.token_filters
+            .push(DynTokenFilter::WordKeep(WordKeepFilter::new(
+                words, attribute,
+            )?));
+        Ok(self)
+    }
+
+    /// Add the filters used by `index_terms` to the pipeline
+    ///
+    /// Adds NFKC normalization, a POS keep-list of nouns, verbs and adjectives
+    /// without dependent words, pronouns and verb suffixes, lower-casing and
+    /// removal of the bundled Japanese stopwords by base form. The stopwords
+    /// come with the `default-stopwords` feature, which is enabled by default.
+    /// Further filters can be chained afterwards.
+    ///
+    /// # Returns
+    /// * `Self` - Builder for chaining
+    pub fn add_index_terms_filters(self) -> Self {
+        let to_strings = |list: &[&str]| list.iter().map(|pos| pos.to_string()).collect();
+        let builder = self
+            .add_char_filter(UnicodeNormalizeCharFilter::with_default_form())
+            .add_pos_keep_filter(to_strings(&INDEX_TERM_POS))
+            .add_pos_stop_filter(to_strings(&INDEX_TERM_STOP_POS))
+            .add_lower_case_filter();
+        #[cfg(feature = "default-stopwords")]
+        let builder = builder.add_token_filter(DynTokenFilter::WordStop(
+            WordStopFilter::default_japanese_base_forms(),
+        ));
+        builder
+    }
+
     /// Build the final Analyzer
     ///
+    /// Without a tokenizer set, a default one is created with the system
+    /// dictionary.
+    ///
     /// # Returns
-    /// * `Analyzer` - Configured analyzer ready for use
-    pub fn build(self) -> Analyzer {
-        let tokenizer = self.tokenizer.unwrap_or_else(|| {
-            // Create default tokenizer - this might fail, but we'll handle it gracefully
-            Tokenizer::new(None, Some(false))
-                .unwrap_or_else(|_| panic!("Failed to create default tokenizer"))
-        });
-
-        Analyzer {
+    /// * `Ok(Analyzer)` - Configured analyzer ready for use
+    /// * `Err(RunomeError)` - Error if the default tokenizer cannot be created
+    pub fn build(self) -> Result<Analyzer, RunomeError> {
+        let tokenizer = match self.tokenizer {
+            Some(tokenizer) => tokenizer,
+            None => Tokenizer::new(None, Some(false))?,
+        };
+
+        Ok(Analyzer {
             char_filters: self.char_filters,
             tokenizer,
             token_filters: self.token_filters,
-        }
+        })
     }
 }
 
+/// Extract search index terms from text
+///
+/// One-call version of the usual search indexing pipeline: the text is
+/// analyzed with `AnalyzerBuilder::add_index_terms_filters` and the base forms
+/// of the remaining tokens are returned, so inflected forms of a word yield
+/// the same term. Build an Analyzer with those filters to customize the
+/// pipeline or to reuse it across calls.
+///
+/// # Arguments
+/// * `text` - Input text to extract terms from
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - Index terms in text order, including repeats
+/// * `Err(RunomeError)` - Error if the tokenizer cannot be created or analysis fails
+///
+/// # Example
+/// ```rust,no_run
+/// let terms = runome::index_terms("東京駅で美味しいラーメンを食べた").unwrap();
+/// assert_eq!(terms, vec!["東京", "駅", "美味しい", "ラーメン", "食べる"]);
+/// ```
+pub fn index_terms(text: &str) -> Result<Vec<String>, RunomeError> {
+    let analyzer = Analyzer::builder()
+        .tokenizer(Tokenizer::new(None, Some(false))?)?
+        .add_index_terms_filters()
+        .build()?;
+    Ok(analyzer
+        .analyze(text)?
+        .iter()
+        .map(|token| token.base_form().to_string())
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,7 +406,7 @@ mod tests {
     #[test]
     fn test_analyzer_default() {
         // Test default analyzer creation
-        let analyzer = Analyzer::builder().build();
+        let analyzer = Analyzer::builder().build().unwrap();
 
         // Should have no filters
         assert_eq!(analyzer.char_filters.len(), 0);
@@ -260,7 +425,8 @@ mod tests {
             .add_compound_noun_filter()
             .add_pos_stop_filter(vec!["記号".to_string(), "助詞".to_string()])
             .add_lower_case_filter()
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(analyzer.char_filters.len(), 2);
         assert_eq!(analyzer.token_filters.len(), 3);
@@ -285,7 +451,8 @@ mod tests {
         // Test basic text analysis
         let analyzer = Analyzer::builder()
             .add_char_filter(UnicodeNormalizeCharFilter::with_default_form())
-            .build();
+            .build()
+            .unwrap();
 
         let results = analyzer.analyze("テスト").unwrap();
         assert!(!results.is_empty());
@@ -294,6 +461,51 @@ mod tests {
         assert!(!results.is_empty());
     }
 
+    #[test]
+    fn test_index_terms() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let terms = index_terms("東京駅で美味しいラーメンを食べた").unwrap();
+        assert_eq!(terms, vec!["東京", "駅", "美味しい", "ラーメン", "食べる"]);
+
+        // Full-width letters are normalized and lower-cased; こと and いる are dropped
+        let terms = index_terms("ＰｙｔｈｏｎでWebアプリを作ることを考えている").unwrap();
+        assert_eq!(terms, vec!["python", "web", "アプリ", "作る", "考える"]);
+    }
+
+    #[cfg(feature = "default-stopwords")]
+    #[test]
+    fn test_index_terms_removes_default_stopwords() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        // ある is an independent verb kept by the POS filters; only the
+        // stopword list, part of the default features, removes it
+        let analyzer = Analyzer::builder()
+            .add_pos_keep_filter(vec!["動詞,自立".to_string()])
+            .build()
+            .unwrap();
+        let verbs = analyzer.analyze("本がある").unwrap();
+        assert_eq!(verbs.len(), 1);
+        assert_eq!(verbs[0].base_form(), "ある");
+
+        let terms = index_terms("本がある").unwrap();
+        assert_eq!(terms, vec!["本"]);
+    }
+
     #[test]
     fn test_analyzer_full_pipeline() {
         // Test complete analysis pipeline
@@ -302,7 +514,8 @@ mod tests {
             .add_char_filter(RegexReplaceCharFilter::new("蛇の目", "janome").unwrap())
             .add_compound_noun_filter()
             .add_lower_case_filter()
-            .build();
+            .build()
+            .unwrap();
 
         let results = analyzer.analyze("蛇の目はテスト用です").unwrap();
         assert!(!results.is_empty());
@@ -326,7 +539,8 @@ mod tests {
             .add_compound_noun_filter()
             .add_pos_stop_filter(vec!["記号".to_string(), "助詞".to_string()])
             .add_lower_case_filter()
-            .build();
+            .build()
+            .unwrap();
 
         let results = analyzer
             .analyze("蛇の目はPure Ｐｙｔｈｏｎな形態素解析器です。")
@@ -353,7 +567,8 @@ mod tests {
             .add_compound_noun_filter()
             .add_pos_stop_filter(vec!["記号".to_string()])
             .add_lower_case_filter()
-            .build();
+            .build()
+            .unwrap();
 
         let results = analyzer.analyze("東京  駅で  降りる").unwrap();
 
@@ -371,7 +586,7 @@ mod tests {
     #[test]
     fn test_analyzer_empty_filters() {
         // Test analyzer with no filters (should work with just tokenizer)
-        let analyzer = Analyzer::builder().build();
+        let analyzer = Analyzer::builder().build().unwrap();
         let results = analyzer.analyze("テスト").unwrap();
 
         assert!(!results.is_empty());
@@ -385,7 +600,8 @@ mod tests {
             .add_compound_noun_filter()
             .add_pos_keep_filter(vec!["名詞".to_string()])
             .add_upper_case_filter()
-            .build();
+            .build()
+            .unwrap();
 
         let results = analyzer.analyze("東京駅").unwrap();
         assert!(!results.is_empty());
@@ -398,4 +614,34 @@ mod tests {
         // This tests that the filter was applied without errors
         assert!(!text.is_empty());
     }
+
+    #[test]
+    fn test_analyzer_spans_refer_to_original_text() {
+        let analyzer = match Tokenizer::new(None, Some(false)) {
+            Ok(tokenizer) => Analyzer::builder()
+                .tokenizer(tokenizer)
+                .unwrap()
+                .add_char_filter(UnicodeNormalizeCharFilter::with_default_form())
+                .add_char_filter(RegexReplaceCharFilter::new("蛇の目", "janome").unwrap())
+                .build()
+                .unwrap(),
+            Err(_) => {
+                eprintln!("Skipping test: SystemDictionary not available");
+                return;
+            }
+        };
+
+        let text = "蛇の目はＰｙｔｈｏｎな形態素解析器です。";
+        let tokens = analyzer.analyze(text).unwrap();
+        let originals: Vec<&str> = tokens
+            .iter()
+            .map(|token| &text[token.span().unwrap()])
+            .collect();
+
+        assert_eq!(tokens[0].surface(), "janome");
+        assert_eq!(originals[0], "蛇の目");
+        assert_eq!(tokens[2].surface(), "Python");
+        assert_eq!(originals[2], "Ｐｙｔｈｏｎ");
+        assert_eq!(originals.concat(), text);
+    }
 }
@@ -0,0 +1,188 @@
+use runome::dict_builder::SourceDictType;
+use runome::dictionary::bundle;
+use runome::{BuildProgress, BuildStage, DictionaryBuilder, DictionaryExporter};
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const USAGE: &str =
+    "Usage: runome-build-dict build [--encoding ENCODING] [--dict-type ipadic|naist-jdic] [--pack-values | --streaming [--sort-chunk-size N]] [--compress [--compression-level N]] [--double-array] <mecab-dir> <output-dir>
+       runome-build-dict export [--encoding ENCODING] <sysdic-dir> <output-dir>
+       runome-build-dict bundle <sysdic-dir> <output-file>";
+
+/// zstd level of `--compress`; slow to compress, but decompression speed
+/// doesn't depend on the level
+const DEFAULT_COMPRESSION_LEVEL: i32 = 19;
+
+/// Width of the progress bar in characters
+const BAR_WIDTH: usize = 30;
+
+fn main() -> anyhow::Result<()> {
+    env_logger::init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("build") => build(&args[1..]),
+        Some("export") => export(&args[1..]),
+        Some("bundle") => pack(&args[1..]),
+        _ => anyhow::bail!("{}", USAGE),
+    }
+}
+
+fn build(args: &[String]) -> anyhow::Result<()> {
+    let mut encoding = "utf-8".to_string();
+    let mut pack_values = false;
+    let mut streaming = false;
+    let mut sort_chunk_size = None;
+    let mut compress = false;
+    let mut compression_level = DEFAULT_COMPRESSION_LEVEL;
+    let mut source_type = None;
+    let mut double_array = false;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--encoding" => {
+                encoding = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--encoding requires a value"))?
+                    .clone();
+            }
+            "--dict-type" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--dict-type requires a value"))?;
+                source_type = Some(value.parse::<SourceDictType>()?);
+            }
+            "--pack-values" => pack_values = true,
+            "--streaming" => streaming = true,
+            "--sort-chunk-size" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--sort-chunk-size requires a value"))?;
+                sort_chunk_size = Some(value.parse::<usize>()?);
+            }
+            "--compress" => compress = true,
+            "--double-array" => double_array = true,
+            "--compression-level" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--compression-level requires a value"))?;
+                compression_level = value.parse::<i32>()?;
+            }
+            _ => paths.push(arg),
+        }
+    }
+    let [mecab_dir, output_dir] = paths[..] else {
+        anyhow::bail!("{}", USAGE);
+    };
+
+    println!("Building dictionary from: {}", mecab_dir);
+    let interactive = std::io::stderr().is_terminal();
+    let mut timings: Vec<(BuildStage, Duration)> = Vec::new();
+    let start = Instant::now();
+    let mut builder = DictionaryBuilder::new(Path::new(mecab_dir), &encoding)
+        .with_output_dir(Path::new(output_dir))
+        .with_packed_values(pack_values)
+        .with_streaming(streaming)
+        .with_double_array(double_array);
+    if let Some(source_type) = source_type {
+        builder = builder.with_source_type(source_type);
+    }
+    if let Some(sort_chunk_size) = sort_chunk_size {
+        builder = builder.with_sort_chunk_size(sort_chunk_size);
+    }
+    if compress {
+        builder = builder.with_compression(compression_level);
+    }
+    builder.build_with_progress(|p| {
+        if interactive || p.is_finished() {
+            draw_progress(p, interactive);
+        }
+        if p.is_finished() {
+            timings.push((p.stage, p.elapsed));
+        }
+    })?;
+    let total = start.elapsed();
+
+    println!("Dictionary written to '{}'", output_dir);
+    println!();
+    println!("{:<18} {:>10}", "stage", "time");
+    for (stage, elapsed) in &timings {
+        println!("{:<18} {:>10.2?}", stage.to_string(), elapsed);
+    }
+    println!("{:<18} {:>10.2?}", "total", total);
+    Ok(())
+}
+
+/// Draw a progress bar for a build stage on stderr
+///
+/// On a terminal the bar is redrawn in place and finished stages are kept on
+/// their own line; otherwise only finished stages are printed.
+fn draw_progress(progress: &BuildProgress, interactive: bool) {
+    let filled = (BAR_WIDTH * progress.done)
+        .checked_div(progress.total)
+        .unwrap_or(BAR_WIDTH);
+    let line = format!(
+        "{:<18} [{}{}] {}/{} {:.2?}",
+        progress.stage.to_string(),
+        "#".repeat(filled),
+        " ".repeat(BAR_WIDTH - filled),
+        progress.done,
+        progress.total,
+        progress.elapsed
+    );
+    let mut stderr = std::io::stderr().lock();
+    let end = if progress.is_finished() { "\n" } else { "" };
+    if interactive {
+        let _ = write!(stderr, "\r\x1b[2K{}{}", line, end);
+    } else {
+        let _ = write!(stderr, "{}{}", line, end);
+    }
+    let _ = stderr.flush();
+}
+
+fn export(args: &[String]) -> anyhow::Result<()> {
+    let mut encoding = "utf-8".to_string();
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--encoding" => {
+                encoding = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--encoding requires a value"))?
+                    .clone();
+            }
+            _ => paths.push(arg),
+        }
+    }
+    let [sysdic_dir, output_dir] = paths[..] else {
+        anyhow::bail!("{}", USAGE);
+    };
+
+    println!("Exporting dictionary from: {}", sysdic_dir);
+    DictionaryExporter::new(Path::new(sysdic_dir))
+        .with_output_dir(Path::new(output_dir))
+        .with_encoding(&encoding)
+        .export()?;
+
+    println!("Dictionary sources written to '{}'", output_dir);
+    Ok(())
+}
+
+fn pack(args: &[String]) -> anyhow::Result<()> {
+    let [sysdic_dir, output_file] = args else {
+        anyhow::bail!("{}", USAGE);
+    };
+
+    let bundle = bundle::pack_sysdic(Path::new(sysdic_dir))?;
+    std::fs::write(output_file, &bundle)?;
+
+    println!(
+        "Dictionary bundle written to '{}' ({} bytes)",
+        output_file,
+        bundle.len()
+    );
+    Ok(())
+}
@@ -1,7 +1,194 @@
 use crate::RunomeError;
+use crate::kana::{
+    HALFWIDTH_KATAKANA, SEMI_VOICEABLE, SEMI_VOICED, VOICEABLE, VOICED, map_char, nth_char,
+};
 use regex::Regex;
+use std::ops::Range;
 use unicode_normalization::{UnicodeNormalization, is_nfc, is_nfd, is_nfkc, is_nfkd};
 
+/// Maps byte offsets in filtered text back to the text before filtering
+///
+/// The filtered text is covered by consecutive segments, each either copied
+/// unchanged from the input or produced by a replacement. Offsets inside a
+/// copied segment map one-to-one; a span touching a replacement is widened to
+/// the whole replaced input, so tokens never report part of a replacement.
+/// Input removed by a filter is not covered by any segment.
+///
+/// # Example
+/// ```rust
+/// use runome::OffsetMapping;
+///
+/// // "蛇の目は" -> "janomeは"
+/// let mut mapping = OffsetMapping::new();
+/// mapping.replace("蛇の目".len(), "janome".len());
+/// mapping.copy("は".len());
+///
+/// assert_eq!(mapping.to_original(0..6), 0..9);
+/// assert_eq!(mapping.to_original(6..9), 9..12);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OffsetMapping {
+    segments: Vec<OffsetSegment>,
+    original_len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OffsetSegment {
+    filtered: Range<usize>,
+    original: Range<usize>,
+    copied: bool,
+}
+
+impl OffsetMapping {
+    /// Create an empty mapping, to be filled with `copy` and `replace`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a mapping for a filter that left a text of `len` bytes unchanged
+    pub fn identity(len: usize) -> Self {
+        let mut mapping = Self::new();
+        mapping.copy(len);
+        mapping
+    }
+
+    /// Append `len` bytes copied unchanged from the input
+    pub fn copy(&mut self, len: usize) {
+        let start = self.original_len;
+        self.push(len, start..start + len, true);
+        self.original_len = start + len;
+    }
+
+    /// Append a replacement of `original_len` input bytes by `filtered_len` output bytes
+    ///
+    /// A `filtered_len` of 0 records a deletion.
+    pub fn replace(&mut self, original_len: usize, filtered_len: usize) {
+        let start = self.original_len;
+        self.push(filtered_len, start..start + original_len, false);
+        self.original_len = start + original_len;
+    }
+
+    /// Get the length in bytes of the filtered text
+    pub fn filtered_len(&self) -> usize {
+        self.segments
+            .last()
+            .map_or(0, |segment| segment.filtered.end)
+    }
+
+    /// Get the length in bytes of the text before filtering
+    pub fn original_len(&self) -> usize {
+        self.original_len
+    }
+
+    /// Map the start offset of a span in the filtered text to the input text
+    pub fn to_original_start(&self, offset: usize) -> usize {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.filtered.end <= offset);
+        match self.segments.get(index) {
+            Some(segment) if segment.copied => {
+                segment.original.start + (offset - segment.filtered.start)
+            }
+            Some(segment) => segment.original.start,
+            None => self.original_len,
+        }
+    }
+
+    /// Map the end offset of a span in the filtered text to the input text
+    pub fn to_original_end(&self, offset: usize) -> usize {
+        if offset == 0 {
+            return self.to_original_start(0);
+        }
+        let index = self
+            .segments
+            .partition_point(|segment| segment.filtered.end < offset);
+        match self.segments.get(index) {
+            Some(segment) if segment.copied => {
+                segment.original.start + (offset - segment.filtered.start)
+            }
+            Some(segment) => segment.original.end,
+            None => self.original_len,
+        }
+    }
+
+    /// Map a span in the filtered text to the input text
+    pub fn to_original(&self, span: Range<usize>) -> Range<usize> {
+        let start = self.to_original_start(span.start);
+        let end = self.to_original_end(span.end).max(start);
+        start..end
+    }
+
+    /// Compose with the mapping of a filter applied after this one
+    ///
+    /// # Arguments
+    /// * `later` - Mapping from the later filter's output to this filter's output
+    ///
+    /// # Returns
+    /// * `OffsetMapping` - Mapping from the later filter's output to this filter's input
+    pub fn compose(&self, later: &OffsetMapping) -> OffsetMapping {
+        let mut composed = OffsetMapping::new();
+
+        for segment in &later.segments {
+            let (start, end) = (segment.original.start, segment.original.end);
+            if !segment.copied {
+                let original = if start == end {
+                    let offset = self.to_original_start(start);
+                    offset..offset
+                } else {
+                    self.to_original(start..end)
+                };
+                composed.push(segment.filtered.len(), original, false);
+                continue;
+            }
+
+            // Split copied segments where this mapping changes between copy and replace
+            let first = self.segments.partition_point(|s| s.filtered.end <= start);
+            for inner in self.segments[first..]
+                .iter()
+                .take_while(|s| s.filtered.start < end)
+            {
+                let piece_start = start.max(inner.filtered.start);
+                let piece_end = end.min(inner.filtered.end);
+                let original = if inner.copied {
+                    let shift = inner.original.start;
+                    (shift + piece_start - inner.filtered.start)
+                        ..(shift + piece_end - inner.filtered.start)
+                } else {
+                    inner.original.clone()
+                };
+                composed.push(piece_end - piece_start, original, inner.copied);
+            }
+        }
+
+        composed.original_len = self.original_len;
+        composed
+    }
+
+    fn push(&mut self, filtered_len: usize, original: Range<usize>, copied: bool) {
+        if filtered_len == 0 {
+            return;
+        }
+        let filtered_start = self.filtered_len();
+
+        // Merge with the previous segment when both are contiguous copies
+        if let Some(last) = self.segments.last_mut()
+            && copied
+            && last.copied
+            && last.original.end == original.start
+        {
+            last.filtered.end += filtered_len;
+            last.original.end = original.end;
+            return;
+        }
+
+        self.segments.push(OffsetSegment {
+            filtered: filtered_start..filtered_start + filtered_len,
+            original,
+            copied,
+        });
+    }
+}
+
 /// Core trait for character filtering operations
 /// Mirrors Python Janome's CharFilter abstract base class
 ///
@@ -21,6 +208,23 @@ pub trait CharFilter {
     /// Returns the transformed text
     fn apply(&self, text: &str) -> Result<String, RunomeError>;
 
+    /// Apply the filter and return the offset mapping back to the input text
+    ///
+    /// The default implementation treats any change as a single replacement of
+    /// the whole text. Filters that change the text length should override it
+    /// so tokens can report precise spans in the original text.
+    fn apply_with_offsets(&self, text: &str) -> Result<(String, OffsetMapping), RunomeError> {
+        let filtered = self.apply(text)?;
+        let mapping = if filtered == text {
+            OffsetMapping::identity(text.len())
+        } else {
+            let mut mapping = OffsetMapping::new();
+            mapping.replace(text.len(), filtered.len());
+            mapping
+        };
+        Ok((filtered, mapping))
+    }
+
     /// Convenience method for direct calling (mimics Python __call__)
     fn call(&self, text: &str) -> Result<String, RunomeError> {
         self.apply(text)
@@ -73,9 +277,21 @@ impl RegexReplaceCharFilter {
 
     /// Convert Python-style backreferences (\1, \2, etc.) to Rust regex format ($1, $2, etc.)
     fn convert_backreferences(replacement: &str) -> String {
-        // Use regex to replace \1, \2, etc. with $1, $2, etc.
-        let backref_regex = Regex::new(r"\\(\d+)").unwrap();
-        backref_regex.replace_all(replacement, "$$$1").to_string()
+        // Replace \1, \2, etc. with ${1}, ${2}, etc.
+        let mut converted = String::with_capacity(replacement.len());
+        let mut chars = replacement.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\\' || !chars.peek().is_some_and(char::is_ascii_digit) {
+                converted.push(c);
+                continue;
+            }
+            converted.push_str("${");
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                converted.push(digit);
+            }
+            converted.push('}');
+        }
+        converted
     }
 }
 
@@ -86,6 +302,30 @@ impl CharFilter for RegexReplaceCharFilter {
             .replace_all(text, &self.replacement)
             .to_string())
     }
+
+    fn apply_with_offsets(&self, text: &str) -> Result<(String, OffsetMapping), RunomeError> {
+        let mut filtered = String::with_capacity(text.len());
+        let mut mapping = OffsetMapping::new();
+        let mut last_end = 0;
+
+        // Same expansion as replace_all, recording each match as a replacement
+        for captures in self.pattern.captures_iter(text) {
+            let Some(matched) = captures.get(0) else {
+                continue;
+            };
+            filtered.push_str(&text[last_end..matched.start()]);
+            mapping.copy(matched.start() - last_end);
+
+            let replacement_start = filtered.len();
+            captures.expand(&self.replacement, &mut filtered);
+            mapping.replace(matched.len(), filtered.len() - replacement_start);
+            last_end = matched.end();
+        }
+        filtered.push_str(&text[last_end..]);
+        mapping.copy(text.len() - last_end);
+
+        Ok((filtered, mapping))
+    }
 }
 
 /// Unicode normalization for text standardization
@@ -148,6 +388,24 @@ impl UnicodeNormalizeCharFilter {
             form: NormalizationForm::NFKC,
         }
     }
+
+    fn normalize(&self, text: &str) -> String {
+        match self.form {
+            NormalizationForm::NFC => text.nfc().collect::<String>(),
+            NormalizationForm::NFKC => text.nfkc().collect::<String>(),
+            NormalizationForm::NFD => text.nfd().collect::<String>(),
+            NormalizationForm::NFKD => text.nfkd().collect::<String>(),
+        }
+    }
+
+    fn push_run(filtered: &mut String, mapping: &mut OffsetMapping, run: &str, normalized: &str) {
+        filtered.push_str(normalized);
+        if run == normalized {
+            mapping.copy(run.len());
+        } else {
+            mapping.replace(run.len(), normalized.len());
+        }
+    }
 }
 
 impl CharFilter for UnicodeNormalizeCharFilter {
@@ -165,13 +423,210 @@ impl CharFilter for UnicodeNormalizeCharFilter {
         }
 
         // Apply normalization only if needed
-        let normalized = match self.form {
-            NormalizationForm::NFC => text.nfc().collect::<String>(),
-            NormalizationForm::NFKC => text.nfkc().collect::<String>(),
-            NormalizationForm::NFD => text.nfd().collect::<String>(),
-            NormalizationForm::NFKD => text.nfkd().collect::<String>(),
+        Ok(self.normalize(text))
+    }
+
+    fn apply_with_offsets(&self, text: &str) -> Result<(String, OffsetMapping), RunomeError> {
+        Ok(self.normalize_with_offsets(text))
+    }
+}
+
+impl UnicodeNormalizeCharFilter {
+    /// Normalize text, mapping offsets in the result back to the text
+    ///
+    /// Infallible form of `apply_with_offsets`.
+    pub(crate) fn normalize_with_offsets(&self, text: &str) -> (String, OffsetMapping) {
+        let needs_normalization = match self.form {
+            NormalizationForm::NFC => !is_nfc(text),
+            NormalizationForm::NFKC => !is_nfkc(text),
+            NormalizationForm::NFD => !is_nfd(text),
+            NormalizationForm::NFKD => !is_nfkd(text),
         };
-        Ok(normalized)
+        let normalized = if needs_normalization {
+            self.normalize(text)
+        } else {
+            text.to_string()
+        };
+        if normalized == text {
+            return (normalized, OffsetMapping::identity(text.len()));
+        }
+
+        // Normalize independent runs of characters separately. A new run starts
+        // at a character that does not compose with the current run, e.g. not
+        // before a combining or half-width voiced sound mark.
+        let mut filtered = String::with_capacity(normalized.len());
+        let mut mapping = OffsetMapping::new();
+        let mut run_start = 0;
+        let mut run_normalized = String::new();
+        for (offset, c) in text.char_indices().skip(1) {
+            let run = &text[run_start..offset];
+            if run_normalized.is_empty() {
+                run_normalized = self.normalize(run);
+            }
+            let next = &text[offset..offset + c.len_utf8()];
+            let joined = self.normalize(&text[run_start..offset + c.len_utf8()]);
+            if joined == format!("{}{}", run_normalized, self.normalize(next)) {
+                Self::push_run(&mut filtered, &mut mapping, run, &run_normalized);
+                run_start = offset;
+                run_normalized.clear();
+            } else {
+                run_normalized = joined;
+            }
+        }
+        if run_start < text.len() {
+            let run = &text[run_start..];
+            Self::push_run(&mut filtered, &mut mapping, run, &self.normalize(run));
+        }
+
+        // Runs normalized separately must add up to the whole text normalized
+        if filtered != normalized {
+            let mut mapping = OffsetMapping::new();
+            mapping.replace(text.len(), normalized.len());
+            return (normalized, mapping);
+        }
+        (filtered, mapping)
+    }
+}
+
+/// Half-width voiced and semi-voiced sound marks
+const HALFWIDTH_VOICED_MARK: char = '\u{FF9E}';
+const HALFWIDTH_SEMI_VOICED_MARK: char = '\u{FF9F}';
+
+/// Width characters are converted to by `WidthNormalizeCharFilter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharWidth {
+    /// Full-width (zenkaku) forms
+    Full,
+    /// Half-width (hankaku) forms
+    Half,
+}
+
+/// Converts between half-width and full-width forms of katakana and ASCII
+///
+/// Unlike NFKC, only the width of katakana (with the Japanese punctuation of
+/// the half-width katakana block) and of ASCII letters, digits and symbols
+/// changes, so other compatibility characters such as circled numbers or
+/// ligatures are kept. Half-width katakana followed by a half-width voiced
+/// sound mark become one full-width character, and full-width voiced katakana
+/// become two half-width characters. Spaces are left to the tokenizer's
+/// `SpacePolicy`.
+///
+/// # Example
+/// ```rust
+/// use runome::{CharFilter, WidthNormalizeCharFilter};
+/// let filter = WidthNormalizeCharFilter::with_default_form();
+/// let result = filter.apply("ﾒｶﾞﾊﾞｲﾄＲＡＭ").unwrap();
+/// assert_eq!(result, "メガバイトRAM");
+/// ```
+#[derive(Debug, Clone)]
+pub struct WidthNormalizeCharFilter {
+    katakana: Option<CharWidth>,
+    ascii: Option<CharWidth>,
+}
+
+impl WidthNormalizeCharFilter {
+    /// Create a new WidthNormalizeCharFilter
+    ///
+    /// # Arguments
+    /// * `katakana` - Width to convert katakana to, or None to keep it
+    /// * `ascii` - Width to convert ASCII letters, digits and symbols to, or None to keep them
+    pub fn new(katakana: Option<CharWidth>, ascii: Option<CharWidth>) -> Self {
+        Self { katakana, ascii }
+    }
+
+    /// Create with the most common conversion: half-width katakana to
+    /// full-width and full-width ASCII to half-width
+    pub fn with_default_form() -> Self {
+        Self::new(Some(CharWidth::Full), Some(CharWidth::Half))
+    }
+
+    /// Append the converted form of a character to `converted`
+    ///
+    /// `next` is the following character, which a half-width sound mark
+    /// combines with. Returns the number of characters consumed, 2 if the
+    /// sound mark was combined.
+    fn convert(&self, c: char, next: Option<char>, converted: &mut String) -> usize {
+        match (self.katakana, self.ascii) {
+            (Some(CharWidth::Full), _) if ('\u{FF61}'..='\u{FF9F}').contains(&c) => {
+                let full = nth_char(HALFWIDTH_KATAKANA, c as usize - 0xFF61);
+                let marked = match next {
+                    Some(HALFWIDTH_VOICED_MARK) => map_char(full, VOICEABLE, VOICED),
+                    Some(HALFWIDTH_SEMI_VOICED_MARK) => map_char(full, SEMI_VOICEABLE, SEMI_VOICED),
+                    _ => None,
+                };
+                converted.push(marked.unwrap_or(full));
+                if marked.is_some() { 2 } else { 1 }
+            }
+            (Some(CharWidth::Half), _) if is_katakana_or_mark(c) => {
+                if let Some(base) = map_char(c, VOICED, VOICEABLE) {
+                    converted.push(to_halfwidth_katakana(base));
+                    converted.push(HALFWIDTH_VOICED_MARK);
+                } else if let Some(base) = map_char(c, SEMI_VOICED, SEMI_VOICEABLE) {
+                    converted.push(to_halfwidth_katakana(base));
+                    converted.push(HALFWIDTH_SEMI_VOICED_MARK);
+                } else {
+                    converted.push(to_halfwidth_katakana(c));
+                }
+                1
+            }
+            (_, Some(CharWidth::Full)) if ('!'..='~').contains(&c) => {
+                converted.push(char::from_u32(c as u32 + 0xFEE0).unwrap_or(c));
+                1
+            }
+            (_, Some(CharWidth::Half)) if ('\u{FF01}'..='\u{FF5E}').contains(&c) => {
+                converted.push(char::from_u32(c as u32 - 0xFEE0).unwrap_or(c));
+                1
+            }
+            _ => {
+                converted.push(c);
+                1
+            }
+        }
+    }
+}
+
+/// Check whether a full-width character has a half-width form
+fn is_katakana_or_mark(c: char) -> bool {
+    HALFWIDTH_KATAKANA.contains(c) || VOICED.contains(c) || SEMI_VOICED.contains(c)
+}
+
+/// Get the half-width form of a full-width katakana, or the character itself
+fn to_halfwidth_katakana(c: char) -> char {
+    HALFWIDTH_KATAKANA
+        .chars()
+        .position(|candidate| candidate == c)
+        .and_then(|index| char::from_u32(0xFF61 + index as u32))
+        .unwrap_or(c)
+}
+
+impl CharFilter for WidthNormalizeCharFilter {
+    fn apply(&self, text: &str) -> Result<String, RunomeError> {
+        Ok(self.apply_with_offsets(text)?.0)
+    }
+
+    fn apply_with_offsets(&self, text: &str) -> Result<(String, OffsetMapping), RunomeError> {
+        let mut filtered = String::with_capacity(text.len());
+        let mut mapping = OffsetMapping::new();
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((offset, c)) = chars.next() {
+            let start = filtered.len();
+            let next = chars.peek().map(|&(_, next)| next);
+            let mut original_len = c.len_utf8();
+            if self.convert(c, next, &mut filtered) == 2
+                && let Some((_, mark)) = chars.next()
+            {
+                original_len += mark.len_utf8();
+            }
+
+            if filtered[start..] == text[offset..offset + original_len] {
+                mapping.copy(original_len);
+            } else {
+                mapping.replace(original_len, filtered.len() - start);
+            }
+        }
+
+        Ok((filtered, mapping))
     }
 }
 
@@ -219,6 +674,19 @@ mod tests {
         assert_eq!(result, "hell0 w0rld");
     }
 
+    #[test]
+    fn test_regex_replace_charfilter_backreferences() {
+        let filter = RegexReplaceCharFilter::new(r"(\w+)@(\w+)", r"\2 at \1x").unwrap();
+        let result = filter.apply("user@example").unwrap();
+        assert_eq!(result, "example at userx");
+
+        // A backslash not followed by a digit is kept as is
+        assert_eq!(
+            RegexReplaceCharFilter::convert_backreferences(r"a\b\12"),
+            r"a\b${12}"
+        );
+    }
+
     #[test]
     fn test_unicode_normalize_charfilter_default() {
         // Test default NFKC normalization
@@ -370,4 +838,105 @@ mod tests {
         let result = filter.apply("Ｈｅｌｌｏ World").unwrap();
         assert_eq!(result, "Hello World");
     }
+
+    #[test]
+    fn test_offset_mapping_compose() {
+        // "abc" -> "aXXc" (b replaced) -> "XXc" ("a" deleted)
+        let mut first = OffsetMapping::new();
+        first.copy(1);
+        first.replace(1, 2);
+        first.copy(1);
+        let mut second = OffsetMapping::new();
+        second.replace(1, 0);
+        second.copy(3);
+
+        let composed = first.compose(&second);
+        assert_eq!(composed.filtered_len(), 3);
+        assert_eq!(composed.original_len(), 3);
+        assert_eq!(composed.to_original(0..1), 1..2);
+        assert_eq!(composed.to_original(0..2), 1..2);
+        assert_eq!(composed.to_original(2..3), 2..3);
+    }
+
+    #[test]
+    fn test_regex_replace_charfilter_offsets() {
+        let filter = RegexReplaceCharFilter::new("蛇の目", "janome").unwrap();
+        let (filtered, mapping) = filter.apply_with_offsets("蛇の目は形態素").unwrap();
+        assert_eq!(filtered, filter.apply("蛇の目は形態素").unwrap());
+        assert_eq!(mapping.to_original(0..6), 0..9);
+        assert_eq!(mapping.to_original(6..9), 9..12);
+        assert_eq!(mapping.to_original(9..18), 12..21);
+
+        // Deletions leave gaps in the original text
+        let filter = RegexReplaceCharFilter::new(r"\s+", "").unwrap();
+        let (filtered, mapping) = filter.apply_with_offsets(" a  b ").unwrap();
+        assert_eq!(filtered, "ab");
+        assert_eq!(mapping.to_original(0..1), 1..2);
+        assert_eq!(mapping.to_original(1..2), 4..5);
+    }
+
+    #[test]
+    fn test_width_normalize_charfilter_default() {
+        let filter = WidthNormalizeCharFilter::with_default_form();
+        assert_eq!(
+            filter.apply("ｶﾞｲﾄﾞﾌﾞｯｸ｢ﾊﾟｽﾎﾟｰﾄ｣ｳﾞｧｲｵﾘﾝ").unwrap(),
+            "ガイドブック「パスポート」ヴァイオリン"
+        );
+        assert_eq!(filter.apply("ＡＢＣ１２３！？").unwrap(), "ABC123!?");
+        // Lone sound marks, and marks on kana that don't take them
+        assert_eq!(filter.apply("ﾞｱﾞﾏﾟ").unwrap(), "゛ア゛マ゜");
+        // Other compatibility characters and spaces are kept
+        assert_eq!(filter.apply("①ﬁ　カナ ｶﾅ").unwrap(), "①ﬁ　カナ カナ");
+    }
+
+    #[test]
+    fn test_width_normalize_charfilter_directions() {
+        let filter = WidthNormalizeCharFilter::new(Some(CharWidth::Half), Some(CharWidth::Full));
+        assert_eq!(
+            filter
+                .apply("ガイドブック「パスポート」ヴァイオリン")
+                .unwrap(),
+            "ｶﾞｲﾄﾞﾌﾞｯｸ｢ﾊﾟｽﾎﾟｰﾄ｣ｳﾞｧｲｵﾘﾝ"
+        );
+        assert_eq!(filter.apply("ABC123!?").unwrap(), "ＡＢＣ１２３！？");
+        // Katakana without a half-width form and hiragana are kept
+        assert_eq!(filter.apply("ヵヶひらがな").unwrap(), "ヵヶひらがな");
+
+        let katakana_only = WidthNormalizeCharFilter::new(Some(CharWidth::Full), None);
+        assert_eq!(katakana_only.apply("ｶﾅＡ").unwrap(), "カナＡ");
+        let ascii_only = WidthNormalizeCharFilter::new(None, Some(CharWidth::Half));
+        assert_eq!(ascii_only.apply("ｶﾅＡ").unwrap(), "ｶﾅA");
+    }
+
+    #[test]
+    fn test_width_normalize_charfilter_offsets() {
+        let filter = WidthNormalizeCharFilter::with_default_form();
+        let text = "ﾒｶﾞﾊﾞｲﾄ１２";
+        let (filtered, mapping) = filter.apply_with_offsets(text).unwrap();
+        assert_eq!(filtered, "メガバイト12");
+        assert_eq!(&text[mapping.to_original(3..6)], "ｶﾞ");
+        assert_eq!(&text[mapping.to_original(3..9)], "ｶﾞﾊﾞ");
+        assert_eq!(&text[mapping.to_original(15..17)], "１２");
+
+        let (_, mapping) = filter.apply_with_offsets("すもも").unwrap();
+        assert_eq!(mapping, OffsetMapping::identity("すもも".len()));
+    }
+
+    #[test]
+    fn test_unicode_normalize_charfilter_offsets() {
+        let filter = UnicodeNormalizeCharFilter::with_default_form();
+        let text = "ﾒｶﾞﾊﾞｲﾄ１２";
+        let (filtered, mapping) = filter.apply_with_offsets(text).unwrap();
+        assert_eq!(filtered, "メガバイト12");
+
+        // Half-width voiced katakana map back to both characters
+        assert_eq!(&text[mapping.to_original(0..3)], "ﾒ");
+        assert_eq!(&text[mapping.to_original(3..6)], "ｶﾞ");
+        assert_eq!(&text[mapping.to_original(3..9)], "ｶﾞﾊﾞ");
+        assert_eq!(&text[mapping.to_original(15..17)], "１２");
+
+        // Unchanged text maps one-to-one
+        let (_, mapping) = filter.apply_with_offsets("すもも").unwrap();
+        assert_eq!(mapping, OffsetMapping::identity("すもも".len()));
+    }
 }
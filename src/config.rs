@@ -0,0 +1,115 @@
+use once_cell::sync::OnceCell;
+use std::path::PathBuf;
+
+use crate::dictionary::{MatcherBackend, SystemDictionary};
+use crate::error::RunomeError;
+
+/// Process-wide defaults applied by tokenizer constructors
+///
+/// Lets an application or framework set the dictionary location and default
+/// options once at startup instead of passing them to every constructor.
+/// Arguments given explicitly to a constructor still take precedence.
+///
+/// # Example
+/// ```rust,no_run
+/// use runome::{GlobalConfig, Tokenizer, configure};
+/// use std::path::PathBuf;
+///
+/// configure(GlobalConfig {
+///     sysdic_path: Some(PathBuf::from("/opt/runome/sysdic")),
+///     default_wakati: true,
+///     ..Default::default()
+/// })
+/// .unwrap();
+///
+/// // Loads /opt/runome/sysdic and returns surface forms only
+/// let tokenizer = Tokenizer::new(None, None).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct GlobalConfig {
+    /// Directory of the shared system dictionary, instead of the automatically
+    /// resolved location (or the embedded data with `embedded-dict`)
+    pub sysdic_path: Option<PathBuf>,
+    /// Wakati mode of tokenizers created without an explicit `wakati`
+    pub default_wakati: bool,
+    /// Maximum unknown word length of tokenizers created without an explicit one
+    pub default_max_unknown_length: usize,
+    /// Verify dictionary files against the SHA-256 checksums recorded by the
+    /// builder before loading them. Off by default: hashing IPADIC takes a
+    /// few hundred milliseconds.
+    pub verify_checksums: bool,
+    /// Data structure the system dictionary matcher searches surface forms
+    /// in. The double-array trie is faster to search but is not memory-mapped,
+    /// and is built from the FST at load time unless the dictionary directory
+    /// has a prebuilt `dic.da`. When `None`, `dic.da` is used if present and
+    /// `MatcherBackend::default()` otherwise.
+    pub matcher_backend: Option<MatcherBackend>,
+}
+
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        Self {
+            sysdic_path: None,
+            default_wakati: false,
+            default_max_unknown_length: 1024,
+            verify_checksums: false,
+            matcher_backend: None,
+        }
+    }
+}
+
+static GLOBAL_CONFIG: OnceCell<GlobalConfig> = OnceCell::new();
+
+/// Set the process-wide configuration
+///
+/// Must be called once, before the shared system dictionary is first loaded,
+/// i.e. before the first `Tokenizer::new` or `SystemDictionary::instance` call.
+///
+/// # Arguments
+/// * `config` - Configuration to apply for the rest of the process
+///
+/// # Returns
+/// * `Ok(())` - Configuration applied
+/// * `Err(RunomeError)` - Error if already configured or the system dictionary
+///   is already loaded
+pub fn configure(config: GlobalConfig) -> Result<(), RunomeError> {
+    if SystemDictionary::is_initialized() {
+        return Err(RunomeError::GlobalConfigError {
+            reason: "System dictionary is already loaded".to_string(),
+        });
+    }
+    GLOBAL_CONFIG
+        .set(config)
+        .map_err(|_| RunomeError::GlobalConfigError {
+            reason: "Configuration is already set".to_string(),
+        })
+}
+
+/// Get the process-wide configuration, or the defaults if not configured
+pub(crate) fn global_config() -> &'static GlobalConfig {
+    static DEFAULT: once_cell::sync::Lazy<GlobalConfig> =
+        once_cell::sync::Lazy::new(GlobalConfig::default);
+    GLOBAL_CONFIG.get().unwrap_or(&DEFAULT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configure_after_dictionary_load() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        SystemDictionary::instance().unwrap();
+        let result = configure(GlobalConfig::default());
+        assert!(matches!(result, Err(RunomeError::GlobalConfigError { .. })));
+        assert_eq!(global_config().default_max_unknown_length, 1024);
+    }
+}
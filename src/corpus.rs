@@ -0,0 +1,295 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::{RunomeError, Token, TokenizeResult, Tokenizer};
+
+/// Callback invoked with a progress snapshot after each completed document
+type ProgressCallback<'a> = Box<dyn FnMut(&CorpusProgress) + 'a>;
+
+/// Cooperative cancellation flag shared between a corpus job and its controller
+///
+/// Cloning the token shares the underlying flag, so a scheduler can keep one
+/// clone and call `cancel()` while the job polls `is_cancelled()`.
+///
+/// # Example
+/// ```rust
+/// use runome::CancellationToken;
+/// let token = CancellationToken::new();
+/// let handle = token.clone();
+/// handle.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new, non-cancelled token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of every job observing this token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Check whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Snapshot of corpus processing progress passed to progress callbacks
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusProgress {
+    /// Number of documents fully processed
+    pub docs_done: usize,
+    /// Total number of documents in the job
+    pub docs_total: usize,
+    /// Number of tokens emitted so far
+    pub tokens_emitted: usize,
+    /// Wall-clock time since the job started
+    pub elapsed: Duration,
+    /// Estimated time remaining, based on the average time per document;
+    /// None if no document is done yet or the estimate overflows `Duration`
+    pub eta: Option<Duration>,
+}
+
+/// Estimate the time the remaining documents take at the average time per
+/// document so far
+fn estimate_eta(elapsed: Duration, docs_done: usize, docs_total: usize) -> Option<Duration> {
+    if docs_done == 0 {
+        return None;
+    }
+    let remaining = docs_total.saturating_sub(docs_done) as f64;
+    Duration::try_from_secs_f64(elapsed.as_secs_f64() * remaining / docs_done as f64).ok()
+}
+
+/// Result of a corpus job
+///
+/// When the job is cancelled, `documents` holds the tokens of every document
+/// completed before cancellation was observed; a partially tokenized document
+/// is discarded.
+#[derive(Debug, Clone)]
+pub struct CorpusOutput {
+    /// Tokens for each completed document, in input order
+    pub documents: Vec<Vec<Token>>,
+    /// True if the job stopped early because of cancellation
+    pub cancelled: bool,
+}
+
+/// Runs a tokenizer over a corpus with progress reporting and cancellation
+///
+/// # Example
+/// ```rust,no_run
+/// use runome::{CancellationToken, CorpusRunner, Tokenizer};
+///
+/// let tokenizer = Tokenizer::new(None, None).unwrap();
+/// let cancel = CancellationToken::new();
+/// let output = CorpusRunner::new(&tokenizer)
+///     .on_progress(|p| eprintln!("{}/{} docs, eta {:?}", p.docs_done, p.docs_total, p.eta))
+///     .with_cancellation(cancel.clone())
+///     .run(&["すもももももももものうち", "テスト"])
+///     .unwrap();
+/// assert!(!output.cancelled);
+/// ```
+pub struct CorpusRunner<'a> {
+    tokenizer: &'a Tokenizer,
+    progress: Option<ProgressCallback<'a>>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl<'a> CorpusRunner<'a> {
+    /// Create a new CorpusRunner using the given tokenizer
+    pub fn new(tokenizer: &'a Tokenizer) -> Self {
+        Self {
+            tokenizer,
+            progress: None,
+            cancellation: None,
+        }
+    }
+
+    /// Set a callback invoked after each completed document
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&CorpusProgress) + 'a,
+    {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a cancellation token polled between tokens
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Tokenize every document, reporting progress and honoring cancellation
+    ///
+    /// # Arguments
+    /// * `docs` - Documents to tokenize
+    ///
+    /// # Returns
+    /// * `Ok(CorpusOutput)` - Tokens per completed document and the cancellation state
+    /// * `Err(RunomeError)` - Error if tokenization of a document fails
+    pub fn run(&mut self, docs: &[&str]) -> Result<CorpusOutput, RunomeError> {
+        let start = Instant::now();
+        let mut documents = Vec::with_capacity(docs.len());
+        let mut tokens_emitted = 0;
+
+        for doc in docs {
+            if self.is_cancelled() {
+                return Ok(CorpusOutput {
+                    documents,
+                    cancelled: true,
+                });
+            }
+
+            let mut tokens = Vec::new();
+            for result in self.tokenizer.tokenize(doc, Some(false), None) {
+                if self.is_cancelled() {
+                    return Ok(CorpusOutput {
+                        documents,
+                        cancelled: true,
+                    });
+                }
+                if let TokenizeResult::Token(token) = result? {
+                    tokens.push(token);
+                }
+            }
+
+            tokens_emitted += tokens.len();
+            documents.push(tokens);
+
+            if let Some(callback) = self.progress.as_mut() {
+                let elapsed = start.elapsed();
+                let docs_done = documents.len();
+                callback(&CorpusProgress {
+                    docs_done,
+                    docs_total: docs.len(),
+                    tokens_emitted,
+                    elapsed,
+                    eta: estimate_eta(elapsed, docs_done, docs.len()),
+                });
+            }
+        }
+
+        Ok(CorpusOutput {
+            documents,
+            cancelled: false,
+        })
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_tokenizer() -> Option<Tokenizer> {
+        match Tokenizer::new(None, None) {
+            Ok(tokenizer) => Some(tokenizer),
+            Err(_) => {
+                eprintln!("Skipping test: SystemDictionary not available");
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn test_estimate_eta() {
+        let second = Duration::from_secs(1);
+        assert_eq!(estimate_eta(second, 2, 6), Some(Duration::from_secs(2)));
+        assert_eq!(estimate_eta(second, 3, 3), Some(Duration::ZERO));
+        assert_eq!(estimate_eta(second, 0, 3), None);
+
+        // Counts beyond u32 are not truncated, and overflow gives no estimate
+        let docs = (u32::MAX as usize + 1) * 2;
+        assert_eq!(estimate_eta(second, docs / 2, docs), Some(second));
+        assert_eq!(estimate_eta(Duration::MAX, 1, docs), None);
+    }
+
+    #[test]
+    fn test_cancellation_token_shared() {
+        let token = CancellationToken::new();
+        let handle = token.clone();
+        assert!(!token.is_cancelled());
+        handle.cancel();
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn test_run_reports_progress() {
+        let Some(tokenizer) = create_tokenizer() else {
+            return;
+        };
+
+        let mut reports = Vec::new();
+        let output = CorpusRunner::new(&tokenizer)
+            .on_progress(|p| reports.push(p.clone()))
+            .run(&["すもももももももものうち", "テスト", "東京"])
+            .unwrap();
+
+        assert!(!output.cancelled);
+        assert_eq!(output.documents.len(), 3);
+        assert_eq!(output.documents[0].len(), 7);
+
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].docs_done, 1);
+        assert_eq!(reports[2].docs_done, 3);
+        assert!(reports.iter().all(|p| p.docs_total == 3));
+        assert_eq!(
+            reports[2].tokens_emitted,
+            output.documents.iter().map(|d| d.len()).sum::<usize>()
+        );
+        assert_eq!(reports[2].eta, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_run_returns_partial_results_on_cancel() {
+        let Some(tokenizer) = create_tokenizer() else {
+            return;
+        };
+
+        let cancel = CancellationToken::new();
+        let handle = cancel.clone();
+        let output = CorpusRunner::new(&tokenizer)
+            .on_progress(move |p| {
+                if p.docs_done == 2 {
+                    handle.cancel();
+                }
+            })
+            .with_cancellation(cancel)
+            .run(&["すもも", "もも", "テスト", "東京"])
+            .unwrap();
+
+        assert!(output.cancelled);
+        assert_eq!(output.documents.len(), 2);
+        assert_eq!(output.documents[0][0].surface(), "すもも");
+    }
+
+    #[test]
+    fn test_run_cancelled_before_start() {
+        let Some(tokenizer) = create_tokenizer() else {
+            return;
+        };
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let output = CorpusRunner::new(&tokenizer)
+            .with_cancellation(cancel)
+            .run(&["すもも"])
+            .unwrap();
+
+        assert!(output.cancelled);
+        assert!(output.documents.is_empty());
+    }
+}
@@ -1,89 +1,290 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
-use encoding_rs::Encoding;
+use anyhow::{Context, Result, bail};
 use log::info;
 
-use super::DictionaryBuilder;
+use super::source::{SourceDictType, decode_file, for_each_line, split_csv_line};
+use super::streaming;
+use super::{BuildProgress, BuildStage, DictionaryBuilder};
+use crate::dictionary::dict::pack_single_entry_values;
+use crate::dictionary::double_array::DoubleArray;
+use crate::dictionary::lazy_entries::entry_offsets;
+use crate::dictionary::loader;
 use crate::dictionary::types::{
-    CharCategory, CharDefinitions, CodePointRange, ConnectionMatrix, DictEntry, UnknownEntries,
-    UnknownEntry,
+    CharCategory, CharDefinitions, CodePointRange, ConnectionMatrix, DictEntry, DictMetadata,
+    UnknownEntries, UnknownEntry,
 };
 
-pub fn build_dictionary(builder: &DictionaryBuilder) -> Result<()> {
+pub fn build_dictionary(
+    builder: &DictionaryBuilder,
+    progress: &mut dyn FnMut(&BuildProgress),
+) -> Result<()> {
     info!("Starting dictionary build process");
 
+    if builder.streaming && builder.pack_values {
+        bail!("Packed FST values are not supported by streaming builds");
+    }
+    if builder.compression_level.is_some() && !cfg!(feature = "zstd") {
+        bail!("Compressing dictionary files requires the `zstd` feature");
+    }
+
     // Create output directory
     fs::create_dir_all(&builder.output_dir).context("Failed to create output directory")?;
 
-    // 1. Parse CSV files into dictionary entries
-    info!("Parsing dictionary entries from CSV files");
-    let entries = parse_csv_files(&builder.mecab_dir, &builder.encoding)?;
-    info!("Parsed {} dictionary entries", entries.len());
-
-    // 2. Build FST mapping surface forms to index IDs and separate morpheme index
-    info!("Building FST and morpheme index");
-    let (fst_data, morpheme_index) = build_fst(&entries)?;
+    let source_type = match builder.source_type {
+        Some(source_type) => source_type,
+        None => SourceDictType::detect(&builder.mecab_dir, &builder.encoding)?,
+    };
+    info!("Source dictionary type: {}", source_type);
+    let csv_files = find_csv_files(&builder.mecab_dir)?;
+
+    // 1-2. Parse CSV files into dictionary entries and build the FST mapping
+    // surface forms to index IDs, with a separate morpheme index
+    let (words, max_surface_length) = if builder.streaming {
+        info!("Streaming dictionary entries to {:?}", builder.output_dir);
+        let max_surface_length =
+            streaming::build_words(builder, source_type, &csv_files, progress)?;
+        (None, max_surface_length)
+    } else {
+        info!("Parsing dictionary entries from CSV files");
+        let mut entries = Vec::new();
+        let mut reporter = ByteProgress::new(&csv_files, progress)?;
+        for csv_file in &csv_files {
+            for_each_entry(
+                csv_file,
+                &builder.encoding,
+                source_type,
+                |mut entry, bytes| {
+                    // Dictionary entry index, used for tie-breaking in Viterbi
+                    entry.morph_id = entries.len();
+                    entries.push(entry);
+                    reporter.advance(bytes);
+                    Ok(())
+                },
+            )?;
+            reporter.finish_file();
+        }
+        info!("Parsed {} dictionary entries", entries.len());
+
+        info!("Building FST and morpheme index");
+        let (fst_data, morpheme_index) = run_stage(BuildStage::BuildFst, progress, || {
+            build_fst(&entries, builder.pack_values)
+        })?;
+        let max_surface_length = DictMetadata::from_entries(&entries).max_surface_length;
+        (
+            Some((entries, fst_data, morpheme_index)),
+            max_surface_length,
+        )
+    };
 
     // 3. Parse connection matrix
     info!("Parsing connection matrix");
-    let connection_matrix = parse_matrix_def(&builder.mecab_dir, &builder.encoding)?;
+    let connection_matrix = run_stage(BuildStage::ParseMatrix, progress, || {
+        parse_matrix_def(&builder.mecab_dir, &builder.encoding)
+    })?;
 
     // 4. Parse character definitions
     info!("Parsing character definitions");
-    let char_defs = parse_char_def(&builder.mecab_dir, &builder.encoding)?;
+    let char_defs = run_stage(BuildStage::ParseCharDefs, progress, || {
+        parse_char_def(&builder.mecab_dir, &builder.encoding)
+    })?;
 
     // 5. Parse unknown word definitions
     info!("Parsing unknown word definitions");
-    let unknowns = parse_unk_def(&builder.mecab_dir, &builder.encoding)?;
+    let unknowns = run_stage(BuildStage::ParseUnknowns, progress, || {
+        parse_unk_def(&builder.mecab_dir, &builder.encoding)
+    })?;
+
+    // 6. Collect dictionary-wide metadata
+    let metadata = DictMetadata {
+        max_surface_length,
+        bos_context_id: builder.bos_context_id,
+        eos_context_id: builder.eos_context_id,
+    };
+    info!(
+        "Longest surface form: {} characters",
+        metadata.max_surface_length
+    );
+    for (name, id) in [
+        ("BOS", metadata.bos_context_id),
+        ("EOS", metadata.eos_context_id),
+    ] {
+        if id as usize >= connection_matrix.rows() {
+            bail!(
+                "{} context id {} exceeds connection matrix size {}",
+                name,
+                id,
+                connection_matrix.rows()
+            );
+        }
+    }
 
-    // 6. Serialize all data to output directory
+    // 7. Serialize all data to output directory
     info!("Serializing dictionary data");
-    save_dictionary(
-        &builder.output_dir,
-        &fst_data,
-        &morpheme_index,
-        &entries,
-        &connection_matrix,
-        &char_defs,
-        &unknowns,
-    )?;
+    run_stage(BuildStage::Save, progress, || {
+        if let Some((entries, fst_data, morpheme_index)) = &words {
+            save_words(&builder.output_dir, fst_data, morpheme_index, entries)?;
+        }
+        if builder.double_array {
+            save_double_array(&builder.output_dir)?;
+        } else {
+            // A trie left by an earlier build would no longer match the FST
+            remove_stale_file(&builder.output_dir.join(loader::DOUBLE_ARRAY_FILE))?;
+        }
+        save_definitions(
+            &builder.output_dir,
+            &connection_matrix,
+            &char_defs,
+            &unknowns,
+            &metadata,
+        )?;
+        #[cfg(feature = "zstd")]
+        if let Some(level) = builder.compression_level {
+            compress_components(&builder.output_dir, level)?;
+        }
+        write_checksums(&builder.output_dir)
+    })?;
 
     info!("Dictionary build completed successfully");
     Ok(())
 }
 
-fn parse_csv_files(mecab_dir: &Path, encoding: &str) -> Result<Vec<DictEntry>> {
-    let mut entries = Vec::new();
+/// Run a single-unit build stage, reporting its start and end
+fn run_stage<T>(
+    stage: BuildStage,
+    progress: &mut dyn FnMut(&BuildProgress),
+    f: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let start = Instant::now();
+    progress(&BuildProgress {
+        stage,
+        done: 0,
+        total: 1,
+        elapsed: Duration::ZERO,
+    });
+    let value = f()?;
+    progress(&BuildProgress {
+        stage,
+        done: 1,
+        total: 1,
+        elapsed: start.elapsed(),
+    });
+    Ok(value)
+}
+
+/// Reports the progress of parsing entries in bytes of the CSV files read
+pub(super) struct ByteProgress<'p> {
+    progress: &'p mut dyn FnMut(&BuildProgress),
+    start: Instant,
+    total: usize,
+    /// Bytes of the files already parsed
+    finished: usize,
+    /// Bytes read of the file being parsed
+    current: usize,
+    next_report: usize,
+}
 
-    // Find all CSV files in the directory
-    let csv_pattern = mecab_dir.join("*.csv");
-    let csv_files =
-        glob::glob(csv_pattern.to_str().unwrap()).context("Failed to read CSV file pattern")?;
+impl<'p> ByteProgress<'p> {
+    /// Interval between reports while parsing a file
+    const REPORT_INTERVAL: usize = 4 << 20;
+
+    pub(super) fn new(
+        csv_files: &[PathBuf],
+        progress: &'p mut dyn FnMut(&BuildProgress),
+    ) -> Result<Self> {
+        let mut total = 0;
+        for csv_file in csv_files {
+            let metadata = fs::metadata(csv_file)
+                .with_context(|| format!("Failed to read file: {:?}", csv_file))?;
+            total += metadata.len() as usize;
+        }
+        let mut reporter = Self {
+            progress,
+            start: Instant::now(),
+            total,
+            finished: 0,
+            current: 0,
+            next_report: Self::REPORT_INTERVAL,
+        };
+        reporter.report();
+        Ok(reporter)
+    }
 
-    // Get the encoding
-    let encoding = Encoding::for_label(encoding.as_bytes()).context("Unknown encoding")?;
+    /// Record the bytes read so far of the file being parsed
+    pub(super) fn advance(&mut self, bytes: usize) {
+        self.current = bytes;
+        if self.current >= self.next_report {
+            self.next_report = self.current + Self::REPORT_INTERVAL;
+            self.report();
+        }
+    }
 
-    for csv_file in csv_files {
-        let csv_file = csv_file.context("Failed to get CSV file path")?;
-        info!("Processing file: {:?}", csv_file);
+    /// Record that the file being parsed is done
+    pub(super) fn finish_file(&mut self) {
+        self.finished += self.current;
+        self.current = 0;
+        self.next_report = Self::REPORT_INTERVAL;
+        // Files may change while they are parsed; never report past the total
+        self.total = self.total.max(self.finished);
+        self.report();
+    }
 
-        let file_content =
-            fs::read(&csv_file).with_context(|| format!("Failed to read file: {:?}", csv_file))?;
+    fn report(&mut self) {
+        (self.progress)(&BuildProgress {
+            stage: BuildStage::ParseEntries,
+            done: (self.finished + self.current).min(self.total),
+            total: self.total,
+            elapsed: self.start.elapsed(),
+        });
+    }
+}
 
-        let (decoded, _, _) = encoding.decode(&file_content);
+/// Parse the entries of one CSV file, passing each with the bytes of the
+/// file read so far to `f`
+///
+/// Every line must have the column count of the source layout; the columns
+/// NAIST-jdic adds after the IPADIC ones are ignored. `morph_id` of the
+/// entries is left 0 for the caller to assign.
+pub(super) fn for_each_entry(
+    csv_file: &Path,
+    encoding: &str,
+    source_type: SourceDictType,
+    mut f: impl FnMut(DictEntry, usize) -> Result<()>,
+) -> Result<()> {
+    info!("Processing file: {:?}", csv_file);
 
-        for line in decoded.lines() {
+    for_each_line(
+        csv_file,
+        encoding,
+        Some(source_type),
+        |line_number, line, bytes| {
             let line = line.trim();
             if line.is_empty() {
-                continue;
+                return Ok(());
             }
 
-            let fields: Vec<&str> = line.split(',').collect();
-            if fields.len() < 13 {
-                continue; // Skip malformed lines
+            let fields = split_csv_line(line)
+                .with_context(|| format!("{:?} line {}", csv_file, line_number))?;
+            if fields.len() != source_type.columns() {
+                let other = [SourceDictType::Ipadic, SourceDictType::NaistJdic]
+                    .into_iter()
+                    .find(|other| other.columns() == fields.len());
+                let hint = match other {
+                    Some(other) => format!("; the source looks like {}", other),
+                    None => String::new(),
+                };
+                bail!(
+                    "{:?} line {}: expected {} columns for {}, found {}{}",
+                    csv_file,
+                    line_number,
+                    source_type.columns(),
+                    source_type,
+                    fields.len(),
+                    hint
+                );
             }
 
             let entry = DictEntry {
@@ -97,17 +298,15 @@ fn parse_csv_files(mecab_dir: &Path, encoding: &str) -> Result<Vec<DictEntry>> {
                 base_form: fields[10].to_string(),
                 reading: fields[11].to_string(),
                 phonetic: fields[12].to_string(),
-                morph_id: entries.len(), // Use current position as dictionary entry index
+                morph_id: 0,
             };
 
-            entries.push(entry);
-        }
-    }
-
-    Ok(entries)
+            f(entry, bytes)
+        },
+    )
 }
 
-fn build_fst(entries: &[DictEntry]) -> Result<(Vec<u8>, Vec<Vec<u32>>)> {
+fn build_fst(entries: &[DictEntry], pack_values: bool) -> Result<(Vec<u8>, Vec<Vec<u32>>)> {
     use std::collections::HashMap;
 
     // Group entries by surface form to handle duplicates
@@ -165,16 +364,48 @@ fn build_fst(entries: &[DictEntry]) -> Result<(Vec<u8>, Vec<Vec<u32>>)> {
     }
 
     let fst_bytes = builder.into_inner().context("Failed to build FST")?;
+
+    if !pack_values {
+        return Ok((fst_bytes, morpheme_index));
+    }
+
+    // Store single-entry surfaces' morpheme IDs directly in the FST values
+    info!("Packing single-entry FST values");
+    let fst_bytes = pack_single_entry_values(&fst_bytes, &morpheme_index)
+        .context("Failed to pack FST values")?;
     Ok((fst_bytes, morpheme_index))
 }
 
-fn parse_matrix_def(mecab_dir: &Path, encoding: &str) -> Result<ConnectionMatrix> {
-    let matrix_file = mecab_dir.join("matrix.def");
-    let encoding = Encoding::for_label(encoding.as_bytes()).context("Unknown encoding")?;
+/// List the CSV files of a MeCab dictionary directory in name order
+///
+/// The extension is matched ignoring case, and paths are never converted to
+/// strings, so directories with non-Unicode names or glob metacharacters work.
+pub(super) fn find_csv_files(mecab_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut csv_files = Vec::new();
+    for entry in fs::read_dir(mecab_dir)
+        .with_context(|| format!("Failed to read directory: {:?}", mecab_dir))?
+    {
+        let path = entry.context("Failed to get CSV file path")?.path();
+        let is_csv = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+        if is_csv && path.is_file() {
+            csv_files.push(path);
+        }
+    }
+    csv_files.sort();
+    Ok(csv_files)
+}
 
-    let file_content = fs::read(&matrix_file).context("Failed to read matrix.def")?;
+/// Path of a definition file of a MeCab dictionary directory, ignoring the
+/// case of its name
+fn def_file(mecab_dir: &Path, filename: &str) -> PathBuf {
+    loader::find_file(mecab_dir, filename).unwrap_or_else(|| mecab_dir.join(filename))
+}
 
-    let (decoded, _, _) = encoding.decode(&file_content);
+fn parse_matrix_def(mecab_dir: &Path, encoding: &str) -> Result<ConnectionMatrix> {
+    let matrix_file = def_file(mecab_dir, "matrix.def");
+    let decoded = decode_file(&matrix_file, encoding, None)?;
     let mut lines = decoded.lines();
 
     // Read matrix dimensions
@@ -188,10 +419,10 @@ fn parse_matrix_def(mecab_dir: &Path, encoding: &str) -> Result<ConnectionMatrix
     let cols: usize = dims[1].parse().context("Failed to parse matrix cols")?;
 
     // Initialize matrix
-    let mut matrix = vec![vec![0i16; cols]; rows];
+    let mut matrix = ConnectionMatrix::new(rows, cols);
 
     // Parse connection costs
-    for line in lines {
+    for (i, line) in lines.enumerate() {
         let line = line.trim();
         if line.is_empty() {
             continue;
@@ -206,21 +437,18 @@ fn parse_matrix_def(mecab_dir: &Path, encoding: &str) -> Result<ConnectionMatrix
         let col: usize = parts[1].parse().context("Failed to parse col index")?;
         let cost: i16 = parts[2].parse().context("Failed to parse cost")?;
 
-        if row < rows && col < cols {
-            matrix[row][col] = cost;
-        }
+        // Ids outside the dimensions would leave zero costs behind
+        matrix
+            .set(row, col, cost)
+            .with_context(|| format!("{:?} line {}", matrix_file, i + 2))?;
     }
 
     Ok(matrix)
 }
 
 fn parse_char_def(mecab_dir: &Path, encoding: &str) -> Result<CharDefinitions> {
-    let char_file = mecab_dir.join("char.def");
-    let encoding = Encoding::for_label(encoding.as_bytes()).context("Unknown encoding")?;
-
-    let file_content = fs::read(&char_file).context("Failed to read char.def")?;
-
-    let (decoded, _, _) = encoding.decode(&file_content);
+    let char_file = def_file(mecab_dir, "char.def");
+    let decoded = decode_file(&char_file, encoding, None)?;
 
     let mut categories = HashMap::new();
     let mut code_ranges = Vec::new();
@@ -300,12 +528,8 @@ fn parse_char_def(mecab_dir: &Path, encoding: &str) -> Result<CharDefinitions> {
 }
 
 fn parse_unk_def(mecab_dir: &Path, encoding: &str) -> Result<UnknownEntries> {
-    let unk_file = mecab_dir.join("unk.def");
-    let encoding = Encoding::for_label(encoding.as_bytes()).context("Unknown encoding")?;
-
-    let file_content = fs::read(&unk_file).context("Failed to read unk.def")?;
-
-    let (decoded, _, _) = encoding.decode(&file_content);
+    let unk_file = def_file(mecab_dir, "unk.def");
+    let decoded = decode_file(&unk_file, encoding, None)?;
 
     let mut unknowns = HashMap::new();
 
@@ -342,46 +566,670 @@ fn parse_unk_def(mecab_dir: &Path, encoding: &str) -> Result<UnknownEntries> {
     Ok(unknowns)
 }
 
-fn save_dictionary(
+/// Write the FST, morpheme index and entries of an in-memory build
+fn save_words(
     output_dir: &Path,
     fst_data: &[u8],
     morpheme_index: &[Vec<u32>],
     entries: &[DictEntry],
-    connection_matrix: &ConnectionMatrix,
-    char_defs: &CharDefinitions,
-    unknowns: &UnknownEntries,
 ) -> Result<()> {
     // Save FST
     let fst_path = output_dir.join("dic.fst");
     fs::write(&fst_path, fst_data).context("Failed to write FST file")?;
 
     // Save morpheme index (maps FST index IDs to vectors of morpheme IDs)
-    let morpheme_index_path = output_dir.join("morpheme_index.bin");
-    let encoded =
-        bincode::serialize(morpheme_index).context("Failed to serialize morpheme index")?;
-    fs::write(&morpheme_index_path, encoded).context("Failed to write morpheme index file")?;
+    write_component(&output_dir.join("morpheme_index.bin"), morpheme_index)
+        .context("Failed to write morpheme index file")?;
 
     // Save dictionary entries
-    let entries_path = output_dir.join("entries.bin");
-    let encoded = bincode::serialize(entries).context("Failed to serialize entries")?;
-    fs::write(&entries_path, encoded).context("Failed to write entries file")?;
+    write_component(&output_dir.join("entries.bin"), entries)
+        .context("Failed to write entries file")?;
+
+    // Save the offset of each entry, for loading entries on demand
+    write_component(&output_dir.join("entries.idx"), &entry_offsets(entries)?)
+        .context("Failed to write entry offsets file")?;
+
+    Ok(())
+}
+
+/// Convert the written FST to a double-array trie and write it next to it
+///
+/// Reads `dic.fst` back so that streaming builds, which write the FST
+/// while merging, are covered too.
+fn save_double_array(output_dir: &Path) -> Result<()> {
+    let fst_data = fs::read(output_dir.join("dic.fst")).context("Failed to read FST file")?;
+    let fst = fst::Map::new(fst_data).context("Failed to read FST file")?;
+    let da = DoubleArray::from_fst(&fst)?;
+    fs::write(output_dir.join(loader::DOUBLE_ARRAY_FILE), da.to_bytes())
+        .context("Failed to write double-array trie file")?;
+    info!("Wrote double-array trie of {} surface forms", da.len());
+    Ok(())
+}
 
+/// Remove a file of an earlier build that this build does not write
+fn remove_stale_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {:?}", path))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Write the components parsed from the definition files and the metadata
+fn save_definitions(
+    output_dir: &Path,
+    connection_matrix: &ConnectionMatrix,
+    char_defs: &CharDefinitions,
+    unknowns: &UnknownEntries,
+    metadata: &DictMetadata,
+) -> Result<()> {
     // Save connection matrix
-    let connections_path = output_dir.join("connections.bin");
-    let encoded =
-        bincode::serialize(connection_matrix).context("Failed to serialize connection matrix")?;
-    fs::write(&connections_path, encoded).context("Failed to write connections file")?;
+    write_component(&output_dir.join("connections.bin"), connection_matrix)
+        .context("Failed to write connections file")?;
 
     // Save character definitions
-    let char_defs_path = output_dir.join("char_defs.bin");
-    let encoded = bincode::serialize(char_defs).context("Failed to serialize char definitions")?;
-    fs::write(&char_defs_path, encoded).context("Failed to write char definitions file")?;
+    write_component(&output_dir.join("char_defs.bin"), char_defs)
+        .context("Failed to write char definitions file")?;
 
     // Save unknown word definitions
-    let unknowns_path = output_dir.join("unknowns.bin");
-    let encoded = bincode::serialize(unknowns).context("Failed to serialize unknown entries")?;
-    fs::write(&unknowns_path, encoded).context("Failed to write unknowns file")?;
+    write_component(&output_dir.join("unknowns.bin"), unknowns)
+        .context("Failed to write unknowns file")?;
+
+    // Save dictionary metadata
+    write_component(&output_dir.join("metadata.bin"), metadata)
+        .context("Failed to write metadata file")?;
 
     info!("Dictionary files saved to: {:?}", output_dir);
     Ok(())
 }
+
+/// Write the SHA-256 checksum of every file of the dictionary to the manifest
+fn write_checksums(output_dir: &Path) -> Result<()> {
+    let mut manifest = String::new();
+    let optional = [loader::DOUBLE_ARRAY_FILE]
+        .into_iter()
+        .filter(|filename| output_dir.join(filename).is_file());
+    for filename in OUTPUT_FILES.into_iter().chain(optional) {
+        let checksum = loader::sha256_file(&output_dir.join(filename))
+            .with_context(|| format!("Failed to hash {}", filename))?;
+        manifest.push_str(&format!("{}  {}\n", checksum, filename));
+    }
+    fs::write(output_dir.join(loader::CHECKSUM_MANIFEST), manifest)
+        .context("Failed to write checksum manifest")?;
+    Ok(())
+}
+
+/// Write a component preceded by the format header the loader checks
+fn write_component<T: serde::Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    let mut data = loader::format_header().to_vec();
+    bincode::serialize_into(&mut data, value)?;
+    fs::write(path, data)?;
+    Ok(())
+}
+
+/// Compress the bincode components written to the output directory with zstd
+///
+/// Files are compressed as streams to a temporary file that replaces them,
+/// so large entry files don't need to fit in memory. The format header is
+/// kept uncompressed in front of the compressed data.
+#[cfg(feature = "zstd")]
+fn compress_components(output_dir: &Path, level: i32) -> Result<()> {
+    use std::io::{Read, Write};
+
+    for filename in COMPRESSED_COMPONENTS {
+        let path = output_dir.join(filename);
+        let compressed_path = output_dir.join(format!("{}.zst", filename));
+        let mut input =
+            fs::File::open(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        let mut output = std::io::BufWriter::new(
+            fs::File::create(&compressed_path)
+                .with_context(|| format!("Failed to create {:?}", compressed_path))?,
+        );
+        let mut header = [0; loader::HEADER_LEN];
+        input
+            .read_exact(&mut header)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        output.write_all(&header)?;
+        zstd::stream::copy_encode(&mut input, output, level)
+            .with_context(|| format!("Failed to compress {:?}", path))?;
+        fs::rename(&compressed_path, &path)
+            .with_context(|| format!("Failed to replace {:?}", path))?;
+    }
+    info!("Compressed dictionary files with zstd level {}", level);
+    Ok(())
+}
+
+/// Files of a compiled dictionary, as listed in the checksum manifest
+const OUTPUT_FILES: [&str; 8] = [
+    "char_defs.bin",
+    "connections.bin",
+    "dic.fst",
+    "entries.bin",
+    "entries.idx",
+    "metadata.bin",
+    "morpheme_index.bin",
+    "unknowns.bin",
+];
+
+/// Components compressed by `compress_components`
+#[cfg(feature = "zstd")]
+const COMPRESSED_COMPONENTS: [&str; 7] = [
+    "entries.bin",
+    "entries.idx",
+    "morpheme_index.bin",
+    "connections.bin",
+    "char_defs.bin",
+    "unknowns.bin",
+    "metadata.bin",
+];
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::dict_builder::export::tests::write_mecab_dir;
+    use crate::dict_builder::source::tests::write_naist_jdic_dir;
+    use crate::dict_builder::{BuildStage, DictionaryBuilder, SourceDictType};
+    use crate::dictionary::{DictionaryResource, MatcherBackend, SystemDictionary, loader};
+    use crate::error::{ErrorCode, RunomeError};
+
+    use super::OUTPUT_FILES;
+
+    fn build(source: &Path, output: &Path) {
+        write_mecab_dir(source);
+        DictionaryBuilder::new(source, "utf-8")
+            .with_output_dir(output)
+            .build()
+            .unwrap();
+    }
+
+    /// Files written by the builder, as the loader looks them up
+    fn written_files() -> Vec<String> {
+        let mut names: Vec<String> = OUTPUT_FILES.iter().map(|name| name.to_string()).collect();
+        names.push(loader::CHECKSUM_MANIFEST.to_string());
+        names.sort();
+        names
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_build_output_names() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        // Glob metacharacters in the source directory name
+        let source = temp_dir.path().join("mecab[ipadic]");
+        let output = temp_dir.path().join("sysdic");
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &output);
+
+        assert_eq!(file_names(&output), written_files());
+        let resource = DictionaryResource::load(output.as_os_str()).unwrap();
+        assert_eq!(resource.get_entries().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_build_reports_progress() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        write_mecab_dir(&source);
+
+        let mut reports = Vec::new();
+        DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&output)
+            .build_with_progress(|p| reports.push(p.clone()))
+            .unwrap();
+
+        // Every stage starts and finishes, in order
+        let finished: Vec<BuildStage> = reports
+            .iter()
+            .filter(|p| p.is_finished())
+            .map(|p| p.stage)
+            .collect();
+        assert_eq!(finished, BuildStage::ALL);
+        assert!(reports.iter().all(|p| p.done <= p.total));
+
+        // Parsing entries counts bytes, reporting after each CSV file
+        let csv_bytes: usize = ["Noun.csv", "Postp.csv"]
+            .iter()
+            .map(|name| std::fs::metadata(source.join(name)).unwrap().len() as usize)
+            .sum();
+        let entries: Vec<(usize, usize)> = reports
+            .iter()
+            .filter(|p| p.stage == BuildStage::ParseEntries)
+            .map(|p| (p.done, p.total))
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries.first(), Some(&(0, csv_bytes)));
+        assert_eq!(entries.last(), Some(&(csv_bytes, csv_bytes)));
+        assert!(entries.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn test_build_naist_jdic() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("naist"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        write_naist_jdic_dir(&source);
+
+        DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&output)
+            .build()
+            .unwrap();
+        let entries = loader::load_entries(&output).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].surface, "すもも");
+        assert_eq!(entries[0].part_of_speech, "名詞,一般,*,*");
+        assert_eq!(entries[0].phonetic, "スモモ");
+    }
+
+    #[test]
+    fn test_build_layout_mismatch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("naist"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        write_naist_jdic_dir(&source);
+
+        let message = DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&output)
+            .with_source_type(SourceDictType::Ipadic)
+            .build()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.contains("line 1: expected 13 columns for IPADIC, found 15"),
+            "{}",
+            message
+        );
+        assert!(message.contains("looks like NAIST-jdic"), "{}", message);
+
+        // A malformed line is reported rather than skipped
+        std::fs::write(
+            source.join("naist-jdic.csv"),
+            "すもも,1,1,7546,名詞,一般,*,*,*,*,すもも,スモモ,スモモ,,\nもも,1,1\n",
+        )
+        .unwrap();
+        let message = DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&output)
+            .build()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.contains("line 2: expected 15 columns"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_build_matrix_id_out_of_range() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        write_mecab_dir(&source);
+        std::fs::write(source.join("matrix.def"), "3 3\n0 0 0\n1 3 -100\n").unwrap();
+
+        let error = DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&output)
+            .build()
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("line 3"), "{:#}", error);
+        assert!(matches!(
+            error.downcast_ref::<RunomeError>(),
+            Some(RunomeError::DictValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_csv_extension_ignores_case() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        write_mecab_dir(&source);
+        std::fs::rename(source.join("Postp.csv"), source.join("POSTP.CSV")).unwrap();
+        std::fs::rename(source.join("matrix.def"), source.join("MATRIX.DEF")).unwrap();
+
+        DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&output)
+            .build()
+            .unwrap();
+        assert_eq!(loader::load_entries(&output).unwrap().len(), 3);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_build_compressed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("mecab");
+        let (plain, compressed) = (temp_dir.path().join("a"), temp_dir.path().join("b"));
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &plain);
+        DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&compressed)
+            .with_compression(3)
+            .build()
+            .unwrap();
+
+        assert_eq!(file_names(&compressed), written_files());
+        for filename in OUTPUT_FILES {
+            let data = std::fs::read(compressed.join(filename)).unwrap();
+            let data = loader::strip_header(&data, filename).unwrap();
+            assert_eq!(
+                loader::is_compressed(data),
+                filename != "dic.fst",
+                "{}",
+                filename
+            );
+        }
+        assert_eq!(
+            loader::load_entries(&plain).unwrap(),
+            loader::load_entries(&compressed).unwrap()
+        );
+        assert_eq!(
+            loader::load_metadata(&plain).unwrap(),
+            loader::load_metadata(&compressed).unwrap()
+        );
+
+        // Bundles keep the compressed components as they are
+        let bundle = crate::dictionary::bundle::pack_sysdic(&compressed).unwrap();
+        let resource = DictionaryResource::load_bundle(&bundle).unwrap();
+        assert_eq!(resource.get_entries().unwrap().len(), 3);
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn test_compression_requires_feature() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        write_mecab_dir(&source);
+
+        let message = DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&output)
+            .with_compression(3)
+            .build()
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("`zstd` feature"), "{}", message);
+
+        // A zstd frame header, as written by a build with the feature
+        build(&source, &output);
+        std::fs::write(output.join("unknowns.bin"), [0x28, 0xb5, 0x2f, 0xfd, 0]).unwrap();
+        let message = loader::load_unknown_entries(&output)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.contains("unknowns is zstd-compressed"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_format_version() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &output);
+
+        for filename in OUTPUT_FILES.iter().filter(|name| **name != "dic.fst") {
+            let data = std::fs::read(output.join(filename)).unwrap();
+            assert_eq!(
+                data[..loader::HEADER_LEN],
+                loader::format_header(),
+                "{}",
+                filename
+            );
+        }
+
+        // Files written before the header was added still load
+        let legacy = temp_dir.path().join("legacy");
+        std::fs::create_dir(&legacy).unwrap();
+        for filename in OUTPUT_FILES {
+            let data = std::fs::read(output.join(filename)).unwrap();
+            let data = loader::strip_header(&data, filename).unwrap();
+            std::fs::write(legacy.join(filename), data).unwrap();
+        }
+        assert_eq!(
+            loader::load_entries(&legacy).unwrap(),
+            loader::load_entries(&output).unwrap()
+        );
+        assert!(DictionaryResource::load_lazy(&legacy).unwrap().is_lazy());
+
+        // A dictionary written by a newer format version is rejected
+        let path = output.join("connections.bin");
+        let mut data = std::fs::read(&path).unwrap();
+        data[loader::FORMAT_MAGIC.len()..loader::HEADER_LEN].copy_from_slice(&99u32.to_le_bytes());
+        std::fs::write(&path, data).unwrap();
+        let error = loader::load_connections(&output).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::DictionaryVersion);
+        match error.root() {
+            RunomeError::DictFormatVersionMismatch {
+                component,
+                expected,
+                found,
+            } => {
+                assert_eq!(component, "connections");
+                assert_eq!(*expected, loader::FORMAT_VERSION);
+                assert_eq!(*found, 99);
+            }
+            other => panic!("expected a version mismatch, got {:?}", other),
+        }
+        assert!(DictionaryResource::load(&output).is_err());
+    }
+
+    #[test]
+    fn test_checksums() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &output);
+
+        let manifest = std::fs::read_to_string(output.join(loader::CHECKSUM_MANIFEST)).unwrap();
+        assert_eq!(manifest.lines().count(), OUTPUT_FILES.len());
+        assert!(manifest.contains(&format!(
+            "{}  entries.bin\n",
+            loader::sha256_file(&output.join("entries.bin")).unwrap()
+        )));
+        loader::verify_checksums(&output).unwrap();
+
+        // A truncated download
+        let path = output.join("morpheme_index.bin");
+        let data = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &data[..data.len() / 2]).unwrap();
+        match loader::verify_checksums(&output) {
+            Err(RunomeError::DictChecksumMismatch { filename, .. }) => {
+                assert_eq!(filename, "morpheme_index.bin")
+            }
+            other => panic!("expected a checksum mismatch, got {:?}", other),
+        }
+
+        // Missing files are left to the loader, and so is a missing manifest
+        std::fs::remove_file(&path).unwrap();
+        loader::verify_checksums(&output).unwrap();
+        std::fs::remove_file(output.join(loader::CHECKSUM_MANIFEST)).unwrap();
+        loader::verify_checksums(&output).unwrap();
+    }
+
+    #[test]
+    fn test_build_double_array() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        write_mecab_dir(&source);
+        let builder = DictionaryBuilder::new(&source, "utf-8").with_output_dir(&output);
+        builder.build().unwrap();
+        assert!(loader::load_double_array(&output).unwrap().is_none());
+
+        let builder = builder.with_double_array(true);
+        builder.build().unwrap();
+        let manifest = std::fs::read_to_string(output.join(loader::CHECKSUM_MANIFEST)).unwrap();
+        assert!(manifest.contains(&format!("  {}\n", loader::DOUBLE_ARRAY_FILE)));
+        loader::verify_checksums(&output).unwrap();
+        let da = loader::load_double_array(&output).unwrap().unwrap();
+        assert_eq!(da.len(), 3);
+        assert!(da.get("すもも".as_bytes()).is_some());
+
+        // Without a configured backend the loader picks up the trie
+        let sys_dict = SystemDictionary::new(&output).unwrap();
+        assert_eq!(sys_dict.matcher_backend(), MatcherBackend::DoubleArray);
+        let surfaces: Vec<&str> = sys_dict
+            .lookup("すもも")
+            .unwrap()
+            .iter()
+            .map(|entry| entry.surface.as_str())
+            .collect();
+        assert_eq!(surfaces, vec!["すもも"]);
+        let mmap_dict = SystemDictionary::new_mmap(&output).unwrap();
+        assert_eq!(mmap_dict.matcher_backend(), MatcherBackend::DoubleArray);
+
+        // Rebuilding without the trie removes the stale one
+        builder.with_double_array(false).build().unwrap();
+        assert_eq!(file_names(&output), written_files());
+        assert_eq!(
+            SystemDictionary::new(&output).unwrap().matcher_backend(),
+            MatcherBackend::default()
+        );
+    }
+
+    #[test]
+    fn test_load_error_codes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &output);
+
+        let path = output.join("connections.bin");
+        let data = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &data[..data.len() / 2]).unwrap();
+        let error = DictionaryResource::load(&output).err().unwrap();
+        assert_eq!(error.code(), ErrorCode::DictionaryCorrupt);
+        assert_eq!(error.path(), Some(path.as_path()));
+
+        std::fs::remove_file(&path).unwrap();
+        let error = DictionaryResource::load(&output).err().unwrap();
+        assert_eq!(error.code(), ErrorCode::DictionaryMissing);
+
+        let error = DictionaryResource::load(temp_dir.path().join("none"))
+            .err()
+            .unwrap();
+        assert_eq!(error.code(), ErrorCode::DictionaryMissing);
+    }
+
+    #[test]
+    fn test_lazy_lookup_reports_corrupt_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &output);
+
+        // Make the surface of every entry invalid UTF-8
+        let offsets: Vec<u64> = loader::deserialize_component(
+            &std::fs::read(output.join("entries.idx")).unwrap(),
+            "entry_offsets",
+        )
+        .unwrap();
+        let path = output.join("entries.bin");
+        let mut data = std::fs::read(&path).unwrap();
+        for offset in offsets {
+            data[loader::HEADER_LEN + offset as usize + 8] = 0xFF;
+        }
+        std::fs::write(&path, &data).unwrap();
+
+        let dict = SystemDictionary::new_lazy(&output).unwrap();
+        let error = dict.lookup("すもも").err().unwrap();
+        assert!(matches!(
+            error,
+            RunomeError::DictDeserializationError { .. }
+        ));
+        assert_eq!(error.code(), ErrorCode::DictionaryCorrupt);
+        assert!(dict.lookup_exact("もも").is_err());
+        assert!(dict.entries().is_err());
+    }
+
+    #[test]
+    fn test_load_ignores_file_name_case() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (temp_dir.path().join("mecab"), temp_dir.path().join("out"));
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &output);
+
+        // As left behind by copying through a case-insensitive filesystem
+        for filename in ["entries.bin", "dic.fst", "metadata.bin"] {
+            std::fs::rename(
+                output.join(filename),
+                output.join(filename.to_ascii_uppercase()),
+            )
+            .unwrap();
+        }
+        let resource = DictionaryResource::load(&output).unwrap();
+        assert_eq!(resource.get_entries().unwrap().len(), 3);
+        assert!(loader::load_metadata(&output).unwrap().is_some());
+        assert!(loader::find_file(&output, "missing.bin").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_unicode_paths() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join(OsStr::from_bytes(b"mecab\xff"));
+        let output = temp_dir.path().join(OsStr::from_bytes(b"sysdic\xfe"));
+        assert!(output.to_str().is_none());
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &output);
+
+        assert_eq!(file_names(&output), written_files());
+        let resource = DictionaryResource::load(output.as_os_str()).unwrap();
+        assert_eq!(resource.get_entries().unwrap().len(), 3);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_windows_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (source, output) = (
+            temp_dir.path().join("mecab"),
+            temp_dir.path().join("sysdic"),
+        );
+        std::fs::create_dir(&source).unwrap();
+        build(&source, &output);
+        assert_eq!(file_names(&output), written_files());
+
+        // Extended-length path with the \\?\ prefix
+        let verbatim = std::fs::canonicalize(&output).unwrap();
+        assert!(verbatim.to_string_lossy().starts_with(r"\\?\"));
+        assert_eq!(
+            DictionaryResource::load(&verbatim)
+                .unwrap()
+                .get_entries()
+                .unwrap()
+                .len(),
+            3
+        );
+
+        // Mixed separators and a differently cased directory name
+        let mixed = format!("{}/SYSDIC", temp_dir.path().display());
+        assert_eq!(
+            DictionaryResource::load(&mixed)
+                .unwrap()
+                .get_entries()
+                .unwrap()
+                .len(),
+            3
+        );
+
+        // File names resolve through the case-insensitive filesystem
+        assert!(loader::find_file(&output, "ENTRIES.BIN").is_some());
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use crate::dictionary::{DictEntry, SystemDictionary};
+use crate::error::RunomeError;
+
+/// Surface lengths from this one on share a single bucket
+const MAX_LENGTH_BUCKET: usize = 8;
+
+/// Fewest costs a length bucket needs to be used on its own; sparser
+/// buckets fall back to all lengths of the POS
+const MIN_BUCKET_SAMPLES: usize = 5;
+
+/// Word cost distribution of dictionary entries by POS and surface length
+///
+/// Costs are recorded under every level of the POS, so a query for `名詞`
+/// covers all nouns while `名詞,固有名詞,人名` only covers person names.
+/// Trailing `*` fields of a POS are ignored, so the `名詞,*,*,*` POS of
+/// simplified user dictionary entries is the same as `名詞`.
+#[derive(Debug, Default, Clone)]
+pub struct CostStats {
+    costs: HashMap<String, BTreeMap<usize, Vec<i16>>>,
+}
+
+impl CostStats {
+    /// Create empty statistics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect statistics from dictionary entries
+    ///
+    /// # Arguments
+    /// * `entries` - Dictionary entries to collect costs from
+    ///
+    /// # Returns
+    /// * `CostStats` - Statistics over all given entries
+    pub fn from_entries(entries: &[DictEntry]) -> Self {
+        let mut stats = Self::new();
+        for entry in entries {
+            stats.add(entry);
+        }
+        stats
+    }
+
+    /// Record the cost of one more entry
+    ///
+    /// # Arguments
+    /// * `entry` - Dictionary entry to record
+    pub fn add(&mut self, entry: &DictEntry) {
+        let bucket = entry.surface.chars().count().min(MAX_LENGTH_BUCKET);
+        let fields = pos_fields(&entry.part_of_speech);
+        for level in 1..=fields.len() {
+            self.costs
+                .entry(fields[..level].join(","))
+                .or_default()
+                .entry(bucket)
+                .or_default()
+                .push(entry.cost);
+        }
+    }
+
+    /// Estimate the cost of a word from entries with the same POS
+    ///
+    /// Returns the median cost of entries with the same POS and surface
+    /// length, or of all entries with the POS if there are too few of that
+    /// length.
+    ///
+    /// # Arguments
+    /// * `pos` - Comma-separated POS, e.g. `名詞,固有名詞` or `名詞,*,*,*`
+    /// * `surface_len` - Length of the surface form in characters
+    ///
+    /// # Returns
+    /// * `Some(i16)` - Estimated cost
+    /// * `None` - No entries with the POS were recorded
+    pub fn estimate(&self, pos: &str, surface_len: usize) -> Option<i16> {
+        let buckets = self.costs.get(&pos_fields(pos).join(","))?;
+        let bucket = surface_len.min(MAX_LENGTH_BUCKET);
+        match buckets.get(&bucket) {
+            Some(costs) if costs.len() >= MIN_BUCKET_SAMPLES => Some(median(costs.clone())),
+            _ => Some(median(buckets.values().flatten().copied().collect())),
+        }
+    }
+}
+
+/// Split a POS into its fields, without trailing `*` fields
+fn pos_fields(pos: &str) -> Vec<&str> {
+    let mut fields: Vec<&str> = pos.split(',').collect();
+    while fields
+        .last()
+        .is_some_and(|field| *field == "*" || field.is_empty())
+    {
+        fields.pop();
+    }
+    fields
+}
+
+fn median(mut costs: Vec<i16>) -> i16 {
+    let middle = costs.len() / 2;
+    *costs.select_nth_unstable(middle).1
+}
+
+/// Statistics of the shared system dictionary, collected on first use
+static SYSTEM_COST_STATS: OnceLock<CostStats> = OnceLock::new();
+
+/// Estimate a word cost for a new user dictionary entry
+///
+/// Derives the cost from the entries of the shared system dictionary with
+/// the same POS (see `CostStats::estimate`). The statistics are collected on
+/// the first call.
+///
+/// # Arguments
+/// * `pos` - Comma-separated POS, e.g. `名詞,固有名詞` or `名詞,*,*,*`
+/// * `surface_len` - Length of the surface form in characters
+///
+/// # Returns
+/// * `Ok(i16)` - Estimated cost
+/// * `Err(RunomeError)` - Error if the system dictionary cannot be loaded or
+///   has no entries with the POS
+pub fn estimate_cost(pos: &str, surface_len: usize) -> Result<i16, RunomeError> {
+    let stats = match SYSTEM_COST_STATS.get() {
+        Some(stats) => stats,
+        None => {
+            let sys_dict = SystemDictionary::instance()?;
+            let entries = sys_dict.entries()?;
+            SYSTEM_COST_STATS.get_or_init(|| CostStats::from_entries(entries))
+        }
+    };
+    stats
+        .estimate(pos, surface_len)
+        .ok_or_else(|| RunomeError::UserDictError {
+            reason: format!("No system dictionary entries with POS {:?}", pos),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(surface: &str, pos: &str, cost: i16) -> DictEntry {
+        DictEntry {
+            surface: surface.to_string(),
+            left_id: 1,
+            right_id: 1,
+            cost,
+            part_of_speech: pos.to_string(),
+            inflection_type: "*".to_string(),
+            inflection_form: "*".to_string(),
+            base_form: surface.to_string(),
+            reading: "*".to_string(),
+            phonetic: "*".to_string(),
+            morph_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_cost_stats_estimate() {
+        let mut entries = Vec::new();
+        for cost in [4000, 4100, 4200, 4300, 4400] {
+            entries.push(entry("あい", "名詞,一般,*,*", cost));
+        }
+        entries.push(entry("あ", "名詞,一般,*,*", 9000));
+        entries.push(entry("あいう", "名詞,固有名詞,人名,名", 7000));
+        entries.push(entry("あ", "助詞,格助詞,一般,*", 100));
+        let stats = CostStats::from_entries(&entries);
+
+        // Enough entries of length 2
+        assert_eq!(stats.estimate("名詞,一般,*,*", 2), Some(4200));
+        // A sparse bucket falls back to all lengths of the POS
+        assert_eq!(stats.estimate("名詞,一般", 1), Some(4300));
+        assert_eq!(stats.estimate("名詞,*,*,*", 3), Some(4300));
+        assert_eq!(stats.estimate("名詞,固有名詞", 10), Some(7000));
+        assert_eq!(stats.estimate("助詞", 1), Some(100));
+        assert_eq!(stats.estimate("動詞", 2), None);
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let noun = estimate_cost("名詞,*,*,*", 3).unwrap();
+        assert!((0..15000).contains(&noun), "noun cost {}", noun);
+        let person = estimate_cost("名詞,固有名詞,人名", 3).unwrap();
+        assert!((0..15000).contains(&person), "person name cost {}", person);
+        assert!(estimate_cost("存在しない品詞", 2).is_err());
+    }
+}
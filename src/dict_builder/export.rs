@@ -0,0 +1,321 @@
+use std::fs;
+
+use anyhow::{Context, Result, bail};
+use encoding_rs::Encoding;
+use log::{info, warn};
+
+use super::DictionaryExporter;
+use crate::dictionary::loader;
+use crate::dictionary::types::{CharDefinitions, ConnectionMatrix, DictEntry, UnknownEntries};
+
+/// File the dictionary entries are written to; the split of the original
+/// CSV files is not recorded in a compiled dictionary
+pub const ENTRIES_CSV: &str = "dic.csv";
+
+pub fn export_dictionary(exporter: &DictionaryExporter) -> Result<()> {
+    info!("Exporting dictionary from {:?}", exporter.sysdic_dir);
+    let encoding = Encoding::for_label(exporter.encoding.as_bytes()).context("Unknown encoding")?;
+
+    let entries = loader::load_entries(&exporter.sysdic_dir)?;
+    let connections = loader::load_connections(&exporter.sysdic_dir)?;
+    let char_defs = loader::load_char_definitions(&exporter.sysdic_dir)?;
+    let unknowns = loader::load_unknown_entries(&exporter.sysdic_dir)?;
+    if let Some(metadata) = loader::load_metadata(&exporter.sysdic_dir)?
+        && (metadata.bos_context_id, metadata.eos_context_id) != (0, 0)
+    {
+        // MeCab sources have no place for these; they must be passed to
+        // DictionaryBuilder::with_boundary_context_ids when rebuilding
+        warn!(
+            "BOS/EOS context ids {}/{} are not part of the exported sources",
+            metadata.bos_context_id, metadata.eos_context_id
+        );
+    }
+
+    fs::create_dir_all(&exporter.output_dir).context("Failed to create output directory")?;
+    let write = |filename: &str, content: String| -> Result<()> {
+        let (encoded, _, had_errors) = encoding.encode(&content);
+        if had_errors {
+            bail!(
+                "{} contains characters not representable in {}",
+                filename,
+                encoding.name()
+            );
+        }
+        fs::write(exporter.output_dir.join(filename), encoded)
+            .with_context(|| format!("Failed to write {}", filename))
+    };
+
+    info!("Writing {} entries to {}", entries.len(), ENTRIES_CSV);
+    write(ENTRIES_CSV, format_entries(&entries)?)?;
+    write("matrix.def", format_matrix_def(&connections))?;
+    let categories = category_order(&char_defs);
+    write("char.def", format_char_def(&char_defs, &categories))?;
+    write("unk.def", format_unk_def(&unknowns, &categories))?;
+
+    info!("Dictionary exported to {:?}", exporter.output_dir);
+    Ok(())
+}
+
+/// Format entries as IPADIC CSV lines, in morpheme ID order
+///
+/// Fields containing commas or double quotes are quoted as in RFC 4180. The
+/// part of speech is stored joined with commas and written as its four
+/// columns.
+fn format_entries(entries: &[DictEntry]) -> Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+    for entry in entries {
+        let (left_id, right_id, cost) = (
+            entry.left_id.to_string(),
+            entry.right_id.to_string(),
+            entry.cost.to_string(),
+        );
+        let mut record = vec![entry.surface.as_str(), &left_id, &right_id, &cost];
+        record.extend(entry.part_of_speech.splitn(4, ','));
+        record.extend([
+            entry.inflection_type.as_str(),
+            &entry.inflection_form,
+            &entry.base_form,
+            &entry.reading,
+            &entry.phonetic,
+        ]);
+        writer
+            .write_record(&record)
+            .with_context(|| format!("Failed to format entry {}", entry.morph_id))?;
+    }
+    let bytes = writer.into_inner().context("Failed to format entries")?;
+    String::from_utf8(bytes).context("Failed to format entries")
+}
+
+/// Format the connection matrix as matrix.def, listing every cell
+fn format_matrix_def(connections: &ConnectionMatrix) -> String {
+    let mut output = format!("{} {}\n", connections.rows(), connections.cols());
+    for (row, costs) in connections.iter_rows().enumerate() {
+        for (col, cost) in costs.iter().enumerate() {
+            output.push_str(&format!("{} {} {}\n", row, col, cost));
+        }
+    }
+    output
+}
+
+/// Category names with DEFAULT first and the rest sorted, since the
+/// definition order of char.def is not kept in a compiled dictionary
+fn category_order(char_defs: &CharDefinitions) -> Vec<&str> {
+    let mut names: Vec<&str> = char_defs.categories.keys().map(String::as_str).collect();
+    names.sort_by_key(|name| (*name != "DEFAULT", *name));
+    names
+}
+
+/// Format category definitions followed by code point ranges as char.def
+fn format_char_def(char_defs: &CharDefinitions, categories: &[&str]) -> String {
+    let mut output = String::new();
+    for name in categories {
+        let category = &char_defs.categories[*name];
+        output.push_str(&format!(
+            "{} {} {} {}\n",
+            name, category.invoke as u8, category.group as u8, category.length
+        ));
+    }
+    output.push('\n');
+    for range in &char_defs.code_ranges {
+        let mut line = if range.from == range.to {
+            format!("0x{:04X}", range.from as u32)
+        } else {
+            format!("0x{:04X}..0x{:04X}", range.from as u32, range.to as u32)
+        };
+        line.push(' ');
+        line.push_str(&range.category);
+        for compat in &range.compat_categories {
+            line.push(' ');
+            line.push_str(compat);
+        }
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output
+}
+
+/// Format unknown word entries as unk.def, grouped by category
+fn format_unk_def(unknowns: &UnknownEntries, categories: &[&str]) -> String {
+    // Categories without a char.def definition are kept, after the others
+    let mut extra: Vec<&str> = unknowns
+        .keys()
+        .map(String::as_str)
+        .filter(|name| !categories.contains(name))
+        .collect();
+    extra.sort();
+
+    let mut output = String::new();
+    for name in categories.iter().chain(&extra) {
+        for entry in unknowns.get(*name).into_iter().flatten() {
+            output.push_str(&format!(
+                "{},{},{},{},{},*,*,*\n",
+                name, entry.left_id, entry.right_id, entry.cost, entry.part_of_speech
+            ));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::dict_builder::{DictionaryBuilder, DictionaryExporter};
+    use crate::dictionary::loader;
+
+    /// Write a minimal MeCab dictionary source directory
+    pub(crate) fn write_mecab_dir(dir: &std::path::Path) {
+        let files = [
+            (
+                "Noun.csv",
+                "すもも,1,1,7546,名詞,一般,*,*,*,*,すもも,スモモ,スモモ\n\
+                 もも,1,1,7219,名詞,一般,*,*,*,*,もも,モモ,モモ\n",
+            ),
+            ("Postp.csv", "も,2,2,4669,助詞,係助詞,*,*,*,*,も,モ,モ\n"),
+            ("matrix.def", "3 3\n0 0 0\n0 1 -100\n1 2 -200\n2 1 50\n"),
+            (
+                "char.def",
+                "DEFAULT 0 1 0\nHIRAGANA 0 1 2\nKANJI 0 0 2\n\n\
+                 0x3041..0x309F HIRAGANA\n0x4E00..0x9FFF KANJI HIRAGANA\n0x3005 KANJI\n",
+            ),
+            (
+                "unk.def",
+                "DEFAULT,0,0,5000,記号,一般,*,*,*,*,*\n\
+                 HIRAGANA,1,1,3000,名詞,一般,*,*,*,*,*\n\
+                 KANJI,1,1,4000,名詞,一般,*,*,*,*,*\n\
+                 KANJI,2,2,4500,名詞,固有名詞,*,*,*,*,*\n",
+            ),
+        ];
+        for (name, content) in files {
+            std::fs::write(dir.join(name), content).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_export_roundtrip() {
+        let source = tempfile::tempdir().unwrap();
+        let compiled = tempfile::tempdir().unwrap();
+        let exported = tempfile::tempdir().unwrap();
+        let recompiled = tempfile::tempdir().unwrap();
+        write_mecab_dir(source.path());
+
+        DictionaryBuilder::new(source.path(), "utf-8")
+            .with_output_dir(compiled.path())
+            .build()
+            .unwrap();
+        DictionaryExporter::new(compiled.path())
+            .with_output_dir(exported.path())
+            .export()
+            .unwrap();
+        DictionaryBuilder::new(exported.path(), "utf-8")
+            .with_output_dir(recompiled.path())
+            .build()
+            .unwrap();
+
+        let csv = std::fs::read_to_string(exported.path().join("dic.csv")).unwrap();
+        assert!(csv.contains("も,2,2,4669,助詞,係助詞,*,*,*,*,も,モ,モ\n"));
+
+        let (a, b) = (compiled.path(), recompiled.path());
+        assert_eq!(
+            loader::load_entries(a).unwrap(),
+            loader::load_entries(b).unwrap()
+        );
+        assert_eq!(
+            loader::load_connections(a).unwrap(),
+            loader::load_connections(b).unwrap()
+        );
+        // CharDefinitions and UnknownEntry do not implement PartialEq
+        assert_eq!(
+            format!(
+                "{:?}",
+                loader::load_char_definitions(a).unwrap().code_ranges
+            ),
+            format!(
+                "{:?}",
+                loader::load_char_definitions(b).unwrap().code_ranges
+            )
+        );
+        let (unknowns_a, unknowns_b) = (
+            loader::load_unknown_entries(a).unwrap(),
+            loader::load_unknown_entries(b).unwrap(),
+        );
+        assert_eq!(unknowns_a.len(), unknowns_b.len());
+        for (category, entries) in &unknowns_a {
+            assert_eq!(
+                format!("{:?}", entries),
+                format!("{:?}", unknowns_b[category])
+            );
+        }
+        assert_eq!(
+            loader::load_metadata(a).unwrap(),
+            loader::load_metadata(b).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_export_euc_jp() {
+        let source = tempfile::tempdir().unwrap();
+        let compiled = tempfile::tempdir().unwrap();
+        let exported = tempfile::tempdir().unwrap();
+        write_mecab_dir(source.path());
+
+        DictionaryBuilder::new(source.path(), "utf-8")
+            .with_output_dir(compiled.path())
+            .build()
+            .unwrap();
+        DictionaryExporter::new(compiled.path())
+            .with_output_dir(exported.path())
+            .with_encoding("euc-jp")
+            .export()
+            .unwrap();
+
+        let bytes = std::fs::read(exported.path().join("dic.csv")).unwrap();
+        let (decoded, _, had_errors) = encoding_rs::EUC_JP.decode(&bytes);
+        assert!(!had_errors);
+        assert!(decoded.starts_with("すもも,1,1,7546,"));
+    }
+
+    #[test]
+    fn test_export_quotes_fields() {
+        let source = tempfile::tempdir().unwrap();
+        let compiled = tempfile::tempdir().unwrap();
+        let exported = tempfile::tempdir().unwrap();
+        let recompiled = tempfile::tempdir().unwrap();
+        write_mecab_dir(source.path());
+        std::fs::write(
+            source.path().join("Symbol.csv"),
+            "\"1,000\",1,1,5000,名詞,数,*,*,*,*,\"1,000\",センエン,センエン\n\
+             \"\"\"\",2,2,3000,記号,括弧開,*,*,*,*,\"\"\"\",\",\",\",\"\n",
+        )
+        .unwrap();
+
+        DictionaryBuilder::new(source.path(), "utf-8")
+            .with_output_dir(compiled.path())
+            .build()
+            .unwrap();
+        let entries = loader::load_entries(compiled.path()).unwrap();
+        assert!(
+            entries
+                .iter()
+                .any(|e| e.surface == "1,000" && e.base_form == "1,000")
+        );
+        assert!(
+            entries
+                .iter()
+                .any(|e| e.surface == "\"" && e.reading == ",")
+        );
+
+        DictionaryExporter::new(compiled.path())
+            .with_output_dir(exported.path())
+            .export()
+            .unwrap();
+        let csv = std::fs::read_to_string(exported.path().join("dic.csv")).unwrap();
+        assert!(csv.contains("\"1,000\",1,1,5000,名詞,数,*,*,*,*,\"1,000\",センエン,センエン\n"));
+
+        DictionaryBuilder::new(exported.path(), "utf-8")
+            .with_output_dir(recompiled.path())
+            .build()
+            .unwrap();
+        assert_eq!(entries, loader::load_entries(recompiled.path()).unwrap());
+    }
+}
@@ -1,12 +1,114 @@
 use anyhow::Result;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 pub mod build;
+pub mod cost;
+pub mod export;
+pub mod source;
+mod streaming;
+
+pub use cost::{CostStats, estimate_cost};
+pub use source::SourceDictType;
+
+/// Stage of a dictionary build, in the order the stages run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildStage {
+    /// Parsing the word entries of the CSV files
+    ParseEntries,
+    /// Building the FST and morpheme index over the surface forms
+    BuildFst,
+    /// Parsing matrix.def
+    ParseMatrix,
+    /// Parsing char.def
+    ParseCharDefs,
+    /// Parsing unk.def
+    ParseUnknowns,
+    /// Writing the compiled files to the output directory
+    Save,
+}
+
+impl BuildStage {
+    /// Every stage, in the order they run
+    pub const ALL: [BuildStage; 6] = [
+        BuildStage::ParseEntries,
+        BuildStage::BuildFst,
+        BuildStage::ParseMatrix,
+        BuildStage::ParseCharDefs,
+        BuildStage::ParseUnknowns,
+        BuildStage::Save,
+    ];
+}
+
+impl fmt::Display for BuildStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BuildStage::ParseEntries => "parse entries",
+            BuildStage::BuildFst => "build FST",
+            BuildStage::ParseMatrix => "parse matrix.def",
+            BuildStage::ParseCharDefs => "parse char.def",
+            BuildStage::ParseUnknowns => "parse unk.def",
+            BuildStage::Save => "save",
+        })
+    }
+}
+
+/// Snapshot of dictionary build progress passed to progress callbacks
+///
+/// Each stage reports once when it starts, with `done` 0, and once when it
+/// finishes, with `done` equal to `total`. Parsing entries counts bytes of the
+/// CSV files and also reports after each file and every few megabytes; a
+/// streaming build counts merged entries while building the FST. Other stages
+/// count as a single unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildProgress {
+    /// Stage in progress
+    pub stage: BuildStage,
+    /// Units of work of the stage completed
+    pub done: usize,
+    /// Total units of work of the stage
+    pub total: usize,
+    /// Wall-clock time spent in the stage so far
+    pub elapsed: Duration,
+}
+
+impl BuildProgress {
+    /// Check whether the stage is complete
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.total
+    }
+}
 
 pub struct DictionaryBuilder {
     pub mecab_dir: PathBuf,
     pub encoding: String,
     pub output_dir: PathBuf,
+    /// Store single-entry surfaces' morpheme IDs directly in FST values.
+    /// Off by default: on IPADIC it grows dic.fst by ~28% without faster
+    /// lookups (see benches/fst_packing.rs).
+    pub pack_values: bool,
+    /// Context ids of the BOS and EOS nodes recorded in the dictionary
+    /// metadata. MeCab dictionaries use 0 for both, the default.
+    pub bos_context_id: u16,
+    pub eos_context_id: u16,
+    /// Layout of the source CSV files; detected from the files when `None`
+    pub source_type: Option<SourceDictType>,
+    /// Build in bounded memory, streaming entries to disk and sorting surfaces
+    /// in on-disk runs; needed for sources as large as mecab-ipadic-NEologd.
+    /// Produces the same files as an in-memory build, except for the
+    /// numbering of FST index IDs. Not compatible with `pack_values`.
+    pub streaming: bool,
+    /// Number of surfaces sorted in memory per on-disk run of a streaming build
+    pub sort_chunk_size: usize,
+    /// zstd level to compress the bincode components with, for distribution.
+    /// `dic.fst` stays uncompressed so it can still be memory-mapped. Needs
+    /// the `zstd` feature, which the loader also needs to read them.
+    pub compression_level: Option<i32>,
+    /// Also write `dic.da`, a double-array trie of the surface forms that the
+    /// loader reads instead of converting `dic.fst` when the double-array
+    /// matcher backend is used. Roughly doubles the size of the FST on disk.
+    pub double_array: bool,
 }
 
 impl DictionaryBuilder {
@@ -15,6 +117,14 @@ impl DictionaryBuilder {
             mecab_dir: mecab_dir.to_path_buf(),
             encoding: encoding.to_string(),
             output_dir: PathBuf::from("sysdic"),
+            pack_values: false,
+            bos_context_id: 0,
+            eos_context_id: 0,
+            source_type: None,
+            streaming: false,
+            sort_chunk_size: 1_000_000,
+            compression_level: None,
+            double_array: false,
         }
     }
 
@@ -23,7 +133,99 @@ impl DictionaryBuilder {
         self
     }
 
+    pub fn with_packed_values(mut self, pack_values: bool) -> Self {
+        self.pack_values = pack_values;
+        self
+    }
+
+    pub fn with_boundary_context_ids(mut self, bos_context_id: u16, eos_context_id: u16) -> Self {
+        self.bos_context_id = bos_context_id;
+        self.eos_context_id = eos_context_id;
+        self
+    }
+
+    /// Set the layout of the source CSV files instead of detecting it
+    pub fn with_source_type(mut self, source_type: SourceDictType) -> Self {
+        self.source_type = Some(source_type);
+        self
+    }
+
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    pub fn with_sort_chunk_size(mut self, sort_chunk_size: usize) -> Self {
+        self.sort_chunk_size = sort_chunk_size;
+        self
+    }
+
+    pub fn with_compression(mut self, level: i32) -> Self {
+        self.compression_level = Some(level);
+        self
+    }
+
+    pub fn with_double_array(mut self, double_array: bool) -> Self {
+        self.double_array = double_array;
+        self
+    }
+
     pub fn build(&self) -> Result<()> {
-        build::build_dictionary(self)
+        self.build_with_progress(|_| {})
+    }
+
+    /// Build the dictionary, reporting the progress and timing of each stage
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use runome::DictionaryBuilder;
+    /// use std::path::Path;
+    ///
+    /// DictionaryBuilder::new(Path::new("mecab-ipadic-2.7.0-20070801"), "euc-jp")
+    ///     .build_with_progress(|p| {
+    ///         if p.is_finished() {
+    ///             eprintln!("{}: {:?}", p.stage, p.elapsed);
+    ///         }
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn build_with_progress<F>(&self, mut progress: F) -> Result<()>
+    where
+        F: FnMut(&BuildProgress),
+    {
+        build::build_dictionary(self, &mut progress)
+    }
+}
+
+/// Reconstructs MeCab dictionary sources (CSV, matrix.def, char.def and
+/// unk.def) from a compiled system dictionary
+pub struct DictionaryExporter {
+    pub sysdic_dir: PathBuf,
+    pub output_dir: PathBuf,
+    /// Encoding of the written files, e.g. "euc-jp" to match upstream IPADIC
+    pub encoding: String,
+}
+
+impl DictionaryExporter {
+    pub fn new(sysdic_dir: &Path) -> Self {
+        Self {
+            sysdic_dir: sysdic_dir.to_path_buf(),
+            output_dir: PathBuf::from("exported"),
+            encoding: "utf-8".to_string(),
+        }
+    }
+
+    pub fn with_output_dir(mut self, output_dir: &Path) -> Self {
+        self.output_dir = output_dir.to_path_buf();
+        self
+    }
+
+    pub fn with_encoding(mut self, encoding: &str) -> Self {
+        self.encoding = encoding.to_string();
+        self
+    }
+
+    pub fn export(&self) -> Result<()> {
+        export::export_dictionary(self)
     }
 }
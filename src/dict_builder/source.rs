@@ -0,0 +1,387 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use encoding_rs::{DecoderResult, Encoding};
+
+/// Layout of a MeCab system dictionary source directory
+///
+/// Both layouts share matrix.def, char.def and unk.def formats; they differ
+/// in the columns of the word CSV files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceDictType {
+    /// mecab-ipadic, 13 columns: surface,left_id,right_id,cost,pos_major,pos_minor1,pos_minor2,pos_minor3,infl_type,infl_form,base_form,reading,phonetic
+    Ipadic,
+    /// mecab-naist-jdic, 15 columns: the IPADIC columns followed by spelling
+    /// variants and related words, which are not stored in the compiled dictionary
+    NaistJdic,
+}
+
+impl SourceDictType {
+    /// Number of columns of each line of the word CSV files
+    pub fn columns(&self) -> usize {
+        match self {
+            SourceDictType::Ipadic => 13,
+            SourceDictType::NaistJdic => 15,
+        }
+    }
+
+    /// Encodings the dictionary is distributed in, for error messages
+    pub fn usual_encodings(&self) -> &'static str {
+        match self {
+            SourceDictType::Ipadic => "euc-jp",
+            SourceDictType::NaistJdic => "euc-jp or utf-8",
+        }
+    }
+
+    /// Detect the layout of a MeCab dictionary source directory
+    ///
+    /// A `naist-jdic.csv` file identifies NAIST-jdic; otherwise the layout is
+    /// chosen by the column count of the first line of the first CSV file.
+    ///
+    /// # Arguments
+    /// * `mecab_dir` - MeCab dictionary source directory
+    /// * `encoding` - Encoding of the source files
+    ///
+    /// # Returns
+    /// * `Ok(SourceDictType)` - Detected layout
+    /// * `Err` - Error if there are no CSV files or the column count matches no layout
+    pub fn detect(mecab_dir: &Path, encoding: &str) -> Result<Self> {
+        let csv_files = super::build::find_csv_files(mecab_dir)?;
+        let is_naist_file = |path: &Path| {
+            path.file_name()
+                .is_some_and(|name| name.eq_ignore_ascii_case("naist-jdic.csv"))
+        };
+        if csv_files.iter().any(|path| is_naist_file(path)) {
+            return Ok(SourceDictType::NaistJdic);
+        }
+
+        let Some(first_file) = csv_files.first() else {
+            bail!("No CSV files found in {:?}", mecab_dir);
+        };
+        let decoded = decode_file(first_file, encoding, None)?;
+        let Some((line_number, line)) = decoded
+            .lines()
+            .enumerate()
+            .find(|(_, line)| !line.trim().is_empty())
+        else {
+            bail!("{:?} has no entries", first_file);
+        };
+
+        let columns = split_csv_line(line.trim())?.len();
+        [SourceDictType::Ipadic, SourceDictType::NaistJdic]
+            .into_iter()
+            .find(|source| source.columns() == columns)
+            .with_context(|| {
+                format!(
+                    "Unrecognized dictionary layout: {:?} line {} has {} columns, \
+                     expected 13 (IPADIC) or 15 (NAIST-jdic)",
+                    first_file,
+                    line_number + 1,
+                    columns
+                )
+            })
+    }
+}
+
+impl fmt::Display for SourceDictType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SourceDictType::Ipadic => "IPADIC",
+            SourceDictType::NaistJdic => "NAIST-jdic",
+        })
+    }
+}
+
+impl FromStr for SourceDictType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ipadic" => Ok(SourceDictType::Ipadic),
+            "naist-jdic" | "naist_jdic" | "naistjdic" => Ok(SourceDictType::NaistJdic),
+            _ => bail!(
+                "Unknown dictionary type '{}', expected 'ipadic' or 'naist-jdic'",
+                s
+            ),
+        }
+    }
+}
+
+/// Read and decode a source file, failing on bytes invalid in the encoding
+///
+/// # Arguments
+/// * `path` - File to read
+/// * `encoding` - Encoding label, e.g. "euc-jp"
+/// * `source` - Layout of the dictionary, if known, to suggest its usual encodings
+pub(crate) fn decode_file(
+    path: &Path,
+    encoding: &str,
+    source: Option<SourceDictType>,
+) -> Result<String> {
+    let decoder = Encoding::for_label(encoding.as_bytes())
+        .with_context(|| format!("Unknown encoding '{}'", encoding))?;
+    let content = fs::read(path).with_context(|| format!("Failed to read file: {:?}", path))?;
+
+    let (decoded, _, had_errors) = decoder.decode(&content);
+    if had_errors {
+        let hint = match source {
+            Some(source) => format!("; {} is usually {}", source, source.usual_encodings()),
+            None => String::new(),
+        };
+        bail!(
+            "{:?} is not valid {}, check the source encoding{}",
+            path,
+            decoder.name(),
+            hint
+        );
+    }
+    Ok(decoded.into_owned())
+}
+
+/// Read a source file line by line, failing on bytes invalid in the encoding
+///
+/// The file is decoded in fixed-size blocks, so memory use doesn't depend on
+/// its size. `f` is called with the 1-based line number, the line without its
+/// terminator, and the number of bytes of the file read so far.
+///
+/// # Arguments
+/// * `path` - File to read
+/// * `encoding` - Encoding label, e.g. "euc-jp"
+/// * `source` - Layout of the dictionary, if known, to suggest its usual encodings
+/// * `f` - Called for each line
+pub(crate) fn for_each_line(
+    path: &Path,
+    encoding: &str,
+    source: Option<SourceDictType>,
+    mut f: impl FnMut(usize, &str, usize) -> Result<()>,
+) -> Result<()> {
+    const BLOCK_SIZE: usize = 64 << 10;
+
+    let encoding = Encoding::for_label(encoding.as_bytes())
+        .with_context(|| format!("Unknown encoding '{}'", encoding))?;
+    let mut file =
+        fs::File::open(path).with_context(|| format!("Failed to read file: {:?}", path))?;
+    let mut decoder = encoding.new_decoder();
+    let mut block = vec![0u8; BLOCK_SIZE];
+    let mut pending = String::new();
+    let mut bytes_read = 0;
+    let mut line_number = 0;
+
+    loop {
+        let n = file
+            .read(&mut block)
+            .with_context(|| format!("Failed to read file: {:?}", path))?;
+        bytes_read += n;
+        let last = n == 0;
+
+        let mut input = &block[..n];
+        loop {
+            let (result, read) =
+                decoder.decode_to_string_without_replacement(input, &mut pending, last);
+            input = &input[read..];
+            match result {
+                DecoderResult::InputEmpty => break,
+                DecoderResult::OutputFull => pending.reserve(input.len().max(BLOCK_SIZE) * 3),
+                DecoderResult::Malformed(..) => {
+                    let hint = match source {
+                        Some(source) => {
+                            format!("; {} is usually {}", source, source.usual_encodings())
+                        }
+                        None => String::new(),
+                    };
+                    bail!(
+                        "{:?} line {} is not valid {}, check the source encoding{}",
+                        path,
+                        line_number + pending.matches('\n').count() + 1,
+                        encoding.name(),
+                        hint
+                    );
+                }
+            }
+        }
+
+        let mut start = 0;
+        while let Some(end) = pending[start..].find('\n') {
+            line_number += 1;
+            let line = &pending[start..start + end];
+            f(
+                line_number,
+                line.strip_suffix('\r').unwrap_or(line),
+                bytes_read,
+            )?;
+            start += end + 1;
+        }
+        pending.drain(..start);
+
+        if last {
+            if !pending.is_empty() {
+                line_number += 1;
+                f(
+                    line_number,
+                    pending.strip_suffix('\r').unwrap_or(&pending),
+                    bytes_read,
+                )?;
+            }
+            return Ok(());
+        }
+    }
+}
+
+/// Split a source CSV line into its fields
+///
+/// Fields may be double-quoted as in RFC 4180, which MeCab accepts for
+/// surfaces and readings containing commas or double quotes. Lines without
+/// quotes are split on commas without copying.
+pub(crate) fn split_csv_line(line: &str) -> Result<Vec<Cow<'_, str>>> {
+    if !line.contains('"') {
+        return Ok(line.split(',').map(Cow::Borrowed).collect());
+    }
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(line.as_bytes());
+    let mut record = csv::StringRecord::new();
+    reader
+        .read_record(&mut record)
+        .with_context(|| format!("Malformed CSV line: {}", line))?;
+    Ok(record
+        .iter()
+        .map(|field| Cow::Owned(field.to_string()))
+        .collect())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::dict_builder::export::tests::write_mecab_dir;
+
+    /// Rewrite the CSV files of a source directory in NAIST-jdic layout
+    pub(crate) fn write_naist_jdic_dir(dir: &Path) {
+        write_mecab_dir(dir);
+        std::fs::remove_file(dir.join("Noun.csv")).unwrap();
+        std::fs::remove_file(dir.join("Postp.csv")).unwrap();
+        std::fs::write(
+            dir.join("naist-jdic.csv"),
+            "すもも,1,1,7546,名詞,一般,*,*,*,*,すもも,スモモ,スモモ,李/酢桃,\n\
+             もも,1,1,7219,名詞,一般,*,*,*,*,もも,モモ,モモ,桃,\n\
+             も,2,2,4669,助詞,係助詞,*,*,*,*,も,モ,モ,,\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_detect() {
+        let ipadic = tempfile::tempdir().unwrap();
+        write_mecab_dir(ipadic.path());
+        assert_eq!(
+            SourceDictType::detect(ipadic.path(), "utf-8").unwrap(),
+            SourceDictType::Ipadic
+        );
+
+        let naist = tempfile::tempdir().unwrap();
+        write_naist_jdic_dir(naist.path());
+        assert_eq!(
+            SourceDictType::detect(naist.path(), "utf-8").unwrap(),
+            SourceDictType::NaistJdic
+        );
+
+        // NAIST-jdic layout under another file name
+        std::fs::rename(
+            naist.path().join("naist-jdic.csv"),
+            naist.path().join("words.csv"),
+        )
+        .unwrap();
+        assert_eq!(
+            SourceDictType::detect(naist.path(), "utf-8").unwrap(),
+            SourceDictType::NaistJdic
+        );
+    }
+
+    #[test]
+    fn test_detect_unknown_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mecab_dir(dir.path());
+        std::fs::write(dir.path().join("Adj.csv"), "\nあ,1,1,100,形容詞\n").unwrap();
+
+        let message = SourceDictType::detect(dir.path(), "utf-8")
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("Adj.csv"), "{}", message);
+        assert!(message.contains("line 2 has 5 columns"), "{}", message);
+    }
+
+    #[test]
+    fn test_split_csv_line() {
+        assert_eq!(split_csv_line("も,2,2").unwrap(), ["も", "2", "2"]);
+        assert_eq!(
+            split_csv_line("\"1,000\",1,\"\"\"\",").unwrap(),
+            ["1,000", "1", "\"", ""]
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "ipadic".parse::<SourceDictType>().unwrap(),
+            SourceDictType::Ipadic
+        );
+        assert_eq!(
+            "NAIST-jdic".parse::<SourceDictType>().unwrap(),
+            SourceDictType::NaistJdic
+        );
+        assert!("unidic".parse::<SourceDictType>().is_err());
+    }
+
+    #[test]
+    fn test_decode_file_rejects_wrong_encoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Noun.csv");
+        // EUC-JP bytes of "すもも"
+        std::fs::write(&path, b"\xa4\xb9\xa4\xe2\xa4\xe2\n").unwrap();
+
+        assert_eq!(decode_file(&path, "euc-jp", None).unwrap(), "すもも\n");
+        let message = decode_file(&path, "utf-8", Some(SourceDictType::NaistJdic))
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("not valid UTF-8"), "{}", message);
+        assert!(message.contains("NAIST-jdic is usually"), "{}", message);
+    }
+
+    #[test]
+    fn test_for_each_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Noun.csv");
+        // EUC-JP lines, with CRLF and no final newline, crossing block boundaries
+        let mut content = Vec::new();
+        for _ in 0..20000 {
+            content.extend_from_slice(b"\xa4\xb9\xa4\xe2\xa4\xe2\r\n");
+        }
+        content.extend_from_slice(b"\xa4\xe2");
+        std::fs::write(&path, &content).unwrap();
+
+        let mut lines = Vec::new();
+        let mut last_bytes = 0;
+        for_each_line(&path, "euc-jp", None, |number, line, bytes| {
+            assert!(bytes >= last_bytes);
+            last_bytes = bytes;
+            lines.push((number, line.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(lines.len(), 20001);
+        assert!(lines[..20000].iter().all(|(_, line)| line == "すもも"));
+        assert_eq!(lines[20000], (20001, "も".to_string()));
+        assert_eq!(last_bytes, content.len());
+
+        let message = for_each_line(&path, "utf-8", Some(SourceDictType::Ipadic), |_, _, _| {
+            Ok(())
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(message.contains("line 1 is not valid UTF-8"), "{}", message);
+    }
+}
@@ -0,0 +1,433 @@
+//! Bounded-memory build of the word components of a dictionary
+//!
+//! Sources such as mecab-ipadic-NEologd have millions of entries, too many to
+//! hold with their FST input in memory. Entries are written to `entries.bin`
+//! as they are parsed, while their surfaces are sorted in runs of
+//! `sort_chunk_size` written to a temporary directory. The runs are then
+//! merged in surface order straight into the FST and morpheme index files.
+//! Every file has the same format as an in-memory build.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use log::info;
+
+use super::build::{ByteProgress, for_each_entry};
+use super::source::SourceDictType;
+use super::{BuildProgress, BuildStage, DictionaryBuilder};
+use crate::dictionary::loader;
+
+/// Name of the directory holding sorted runs, inside the output directory
+const RUNS_DIR: &str = ".runome-build-runs";
+
+/// Entries merged between progress reports
+const MERGE_REPORT_INTERVAL: usize = 1 << 16;
+
+/// Parse the CSV files and write `entries.bin`, `entries.idx`, `dic.fst` and
+/// `morpheme_index.bin`
+///
+/// # Returns
+/// * `Ok(usize)` - Length in characters of the longest surface form
+pub(super) fn build_words(
+    builder: &DictionaryBuilder,
+    source_type: SourceDictType,
+    csv_files: &[PathBuf],
+    progress: &mut dyn FnMut(&BuildProgress),
+) -> Result<usize> {
+    let output_dir = &builder.output_dir;
+    let runs = RunsDir::create(output_dir.join(RUNS_DIR))?;
+    let mut sorter = SurfaceSorter::new(&runs.path, builder.sort_chunk_size.max(1));
+    let mut entries = CountedSeqWriter::create(&output_dir.join("entries.bin"))?;
+    let mut entry_offsets = CountedSeqWriter::create(&output_dir.join("entries.idx"))?;
+    let mut max_surface_length = 0;
+
+    let mut reporter = ByteProgress::new(csv_files, progress)?;
+    for csv_file in csv_files {
+        for_each_entry(
+            csv_file,
+            &builder.encoding,
+            source_type,
+            |mut entry, bytes| {
+                // Dictionary entry index, used for tie-breaking in Viterbi
+                entry.morph_id = entries.len();
+                max_surface_length = max_surface_length.max(entry.surface.chars().count());
+                entry_offsets.push(&entries.position())?;
+                entries.push(&entry)?;
+                sorter.push(entry.surface, entry.morph_id as u32)?;
+                reporter.advance(bytes);
+                Ok(())
+            },
+        )?;
+        reporter.finish_file();
+    }
+    let entry_count = entries.len();
+    entries.finish().context("Failed to write entries file")?;
+    entry_offsets
+        .finish()
+        .context("Failed to write entry offsets file")?;
+    info!(
+        "Parsed {} dictionary entries into {} sorted runs",
+        entry_count,
+        sorter.runs.len() + 1
+    );
+
+    merge_into_fst(output_dir, sorter, entry_count, progress)?;
+    Ok(max_surface_length)
+}
+
+/// Merge the sorted runs into the FST, grouping the morpheme IDs of each surface
+fn merge_into_fst(
+    output_dir: &Path,
+    sorter: SurfaceSorter,
+    entry_count: usize,
+    progress: &mut dyn FnMut(&BuildProgress),
+) -> Result<()> {
+    let start = Instant::now();
+    let mut report = |done| {
+        progress(&BuildProgress {
+            stage: BuildStage::BuildFst,
+            done,
+            total: entry_count,
+            elapsed: start.elapsed(),
+        })
+    };
+    report(0);
+
+    let fst_file = File::create(output_dir.join("dic.fst")).context("Failed to write FST file")?;
+    let mut fst_builder =
+        fst::MapBuilder::new(BufWriter::new(fst_file)).context("Failed to create FST builder")?;
+    let mut morpheme_index = CountedSeqWriter::create(&output_dir.join("morpheme_index.bin"))?;
+
+    let mut current: Option<(String, Vec<u32>)> = None;
+    let mut merged = 0;
+    for pair in sorter.into_sorted()? {
+        let (surface, morph_id) = pair?;
+        match current.as_mut() {
+            Some((current_surface, ids)) if *current_surface == surface => ids.push(morph_id),
+            _ => {
+                if let Some((surface, ids)) = current.take() {
+                    let index_id = morpheme_index.len() as u64;
+                    fst_builder
+                        .insert(surface.as_bytes(), index_id)
+                        .context("Failed to insert into FST")?;
+                    morpheme_index.push(&ids)?;
+                }
+                current = Some((surface, vec![morph_id]));
+            }
+        }
+        merged += 1;
+        if merged % MERGE_REPORT_INTERVAL == 0 {
+            report(merged);
+        }
+    }
+    if let Some((surface, ids)) = current {
+        let index_id = morpheme_index.len() as u64;
+        fst_builder
+            .insert(surface.as_bytes(), index_id)
+            .context("Failed to insert into FST")?;
+        morpheme_index.push(&ids)?;
+    }
+
+    info!(
+        "Built FST with {} unique surface forms, total entries: {}",
+        morpheme_index.len(),
+        entry_count
+    );
+    fst_builder.finish().context("Failed to build FST")?;
+    morpheme_index
+        .finish()
+        .context("Failed to write morpheme index file")?;
+    report(entry_count);
+    Ok(())
+}
+
+/// Writes a bincode sequence one element at a time
+///
+/// bincode encodes a sequence as its length followed by the elements, so the
+/// length is written as a placeholder and patched when the writer finishes.
+/// After the format header, the file then deserializes as a `Vec` of the
+/// elements.
+struct CountedSeqWriter {
+    writer: BufWriter<File>,
+    len: usize,
+    /// Bytes written so far
+    position: u64,
+}
+
+impl CountedSeqWriter {
+    fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&loader::format_header())?;
+        bincode::serialize_into(&mut writer, &0u64)?;
+        Ok(Self {
+            writer,
+            len: 0,
+            position: std::mem::size_of::<u64>() as u64,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Offset of the next element, after the format header
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn push<T: serde::Serialize>(&mut self, value: &T) -> Result<()> {
+        bincode::serialize_into(&mut self.writer, value)?;
+        self.position += bincode::serialized_size(value)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.writer
+            .seek(SeekFrom::Start(loader::HEADER_LEN as u64))?;
+        bincode::serialize_into(&mut self.writer, &(self.len as u64))?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Sorts (surface, morpheme ID) pairs in bounded memory with on-disk runs
+struct SurfaceSorter {
+    dir: PathBuf,
+    chunk_size: usize,
+    chunk: Vec<(String, u32)>,
+    runs: Vec<PathBuf>,
+}
+
+impl SurfaceSorter {
+    fn new(dir: &Path, chunk_size: usize) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            chunk_size,
+            chunk: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, surface: String, morph_id: u32) -> Result<()> {
+        self.chunk.push((surface, morph_id));
+        if self.chunk.len() >= self.chunk_size {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Sort the pairs in memory and write them to a new run
+    fn spill(&mut self) -> Result<()> {
+        self.chunk.sort_unstable();
+        let path = self.dir.join(format!("run-{:05}.bin", self.runs.len()));
+        let mut run = CountedSeqWriter::create(&path)?;
+        for pair in self.chunk.drain(..) {
+            run.push(&pair)?;
+        }
+        run.finish()
+            .with_context(|| format!("Failed to write sorted run {:?}", path))?;
+        self.runs.push(path);
+        Ok(())
+    }
+
+    /// Iterate over every pair in (surface, morpheme ID) order
+    fn into_sorted(mut self) -> Result<SortedPairs> {
+        // The last chunk never hits the disk
+        self.chunk.sort_unstable();
+        let mut sources = vec![RunReader::Memory(
+            std::mem::take(&mut self.chunk).into_iter(),
+        )];
+        for path in &self.runs {
+            sources.push(RunReader::open(path)?);
+        }
+
+        let mut heap = BinaryHeap::with_capacity(sources.len());
+        for (source, reader) in sources.iter_mut().enumerate() {
+            if let Some(pair) = reader.next_pair()? {
+                heap.push(Reverse((pair, source)));
+            }
+        }
+        Ok(SortedPairs { sources, heap })
+    }
+}
+
+/// Pairs of one sorted run
+enum RunReader {
+    Memory(std::vec::IntoIter<(String, u32)>),
+    File {
+        reader: BufReader<File>,
+        remaining: u64,
+    },
+}
+
+impl RunReader {
+    fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+        let mut reader = BufReader::new(file);
+        reader.read_exact(&mut [0; loader::HEADER_LEN])?;
+        let remaining = bincode::deserialize_from(&mut reader)?;
+        Ok(RunReader::File { reader, remaining })
+    }
+
+    fn next_pair(&mut self) -> Result<Option<(String, u32)>> {
+        match self {
+            RunReader::Memory(pairs) => Ok(pairs.next()),
+            RunReader::File { reader, remaining } => {
+                if *remaining == 0 {
+                    return Ok(None);
+                }
+                *remaining -= 1;
+                Ok(Some(bincode::deserialize_from(reader)?))
+            }
+        }
+    }
+}
+
+/// K-way merge of sorted runs
+struct SortedPairs {
+    sources: Vec<RunReader>,
+    heap: BinaryHeap<Reverse<((String, u32), usize)>>,
+}
+
+impl Iterator for SortedPairs {
+    type Item = Result<(String, u32)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((pair, source)) = self.heap.pop()?;
+        match self.sources[source].next_pair() {
+            Ok(Some(next)) => self.heap.push(Reverse((next, source))),
+            Ok(None) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        Some(Ok(pair))
+    }
+}
+
+/// Temporary directory for sorted runs, removed when dropped
+struct RunsDir {
+    path: PathBuf,
+}
+
+impl RunsDir {
+    fn create(path: PathBuf) -> Result<Self> {
+        if path.exists() {
+            // Left behind by an interrupted build
+            fs::remove_dir_all(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+        }
+        fs::create_dir_all(&path).with_context(|| format!("Failed to create {:?}", path))?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for RunsDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict_builder::export::tests::write_mecab_dir;
+    use crate::dictionary::DictionaryResource;
+
+    #[test]
+    fn test_surface_sorter_merges_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sorter = SurfaceSorter::new(dir.path(), 3);
+        let surfaces = ["も", "すもも", "もも", "うち", "も", "の", "もも", "すもも"];
+        for (id, surface) in surfaces.iter().enumerate() {
+            sorter.push(surface.to_string(), id as u32).unwrap();
+        }
+        assert_eq!(sorter.runs.len(), 2);
+
+        let sorted: Vec<(String, u32)> = sorter
+            .into_sorted()
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        let mut expected: Vec<(String, u32)> = surfaces
+            .iter()
+            .enumerate()
+            .map(|(id, surface)| (surface.to_string(), id as u32))
+            .collect();
+        expected.sort();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn test_streaming_build_matches_in_memory_build() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("mecab");
+        let (in_memory, streamed) = (temp_dir.path().join("a"), temp_dir.path().join("b"));
+        std::fs::create_dir(&source).unwrap();
+        write_mecab_dir(&source);
+        // More entries than a sort chunk, with duplicate surfaces across chunks
+        let mut extra = String::new();
+        for i in 0..50 {
+            extra.push_str(&format!(
+                "語{},1,1,{},名詞,一般,*,*,*,*,語{},ゴ,ゴ\n",
+                i % 7,
+                1000 + i,
+                i % 7
+            ));
+        }
+        std::fs::write(source.join("Extra.csv"), extra).unwrap();
+
+        DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&in_memory)
+            .build()
+            .unwrap();
+        DictionaryBuilder::new(&source, "utf-8")
+            .with_output_dir(&streamed)
+            .with_streaming(true)
+            .with_sort_chunk_size(8)
+            .build()
+            .unwrap();
+
+        assert!(!streamed.join(RUNS_DIR).exists());
+        assert_eq!(
+            loader::load_entries(&in_memory).unwrap(),
+            loader::load_entries(&streamed).unwrap()
+        );
+        assert_eq!(
+            loader::load_metadata(&in_memory).unwrap(),
+            loader::load_metadata(&streamed).unwrap()
+        );
+        assert_eq!(
+            std::fs::read(in_memory.join("entries.idx")).unwrap(),
+            std::fs::read(streamed.join("entries.idx")).unwrap()
+        );
+
+        // Index IDs may differ, but every surface maps to the same entries
+        let (a, b) = (
+            DictionaryResource::load(&in_memory).unwrap(),
+            DictionaryResource::load(&streamed).unwrap(),
+        );
+        let surfaces: std::collections::BTreeSet<&str> = a
+            .get_entries()
+            .unwrap()
+            .iter()
+            .map(|e| e.surface.as_str())
+            .collect();
+        for surface in surfaces {
+            let ids = |resource: &DictionaryResource| {
+                let matcher =
+                    crate::dictionary::Matcher::new(resource.get_fst_bytes().to_vec()).unwrap();
+                let (_, index_ids) = matcher.run(surface, false).unwrap();
+                index_ids
+                    .into_iter()
+                    .flat_map(|id| matcher.lookup_morpheme_ids(id, resource.get_morpheme_index()))
+                    .collect::<Vec<u32>>()
+            };
+            assert_eq!(ids(&a), ids(&b), "{}", surface);
+        }
+    }
+}
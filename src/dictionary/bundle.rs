@@ -0,0 +1,115 @@
+//! Single-buffer packaging of a compiled system dictionary
+//!
+//! A bundle holds every sysdic component in one byte buffer, for
+//! environments without a filesystem such as WASM builds in the browser,
+//! where the dictionary is fetched as a single `ArrayBuffer`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::loader;
+use crate::error::{PathContext, RunomeError};
+
+/// Leading bytes identifying a dictionary bundle
+pub const BUNDLE_MAGIC: &[u8; 8] = b"RUNOMEDB";
+
+/// Components stored in a bundle, all required
+pub(crate) const BUNDLE_COMPONENTS: [&str; 6] = [
+    "entries.bin",
+    "connections.bin",
+    "char_defs.bin",
+    "unknowns.bin",
+    "dic.fst",
+    "morpheme_index.bin",
+];
+
+/// Pack a compiled system dictionary directory into a bundle
+///
+/// `metadata.bin` is included when present, converted to the current format.
+///
+/// # Arguments
+/// * `sysdic_dir` - Path to directory containing dictionary data
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - Bundle bytes, loadable with `SystemDictionary::from_bundle`
+/// * `Err(RunomeError)` - Error if a component is missing or unreadable
+pub fn pack_sysdic(sysdic_dir: &Path) -> Result<Vec<u8>, RunomeError> {
+    let mut components = Vec::new();
+    for filename in BUNDLE_COMPONENTS {
+        let path = loader::validate_file_exists(sysdic_dir, filename)?;
+        let data = std::fs::read(&path).with_path(&path)?;
+        components.push((filename.to_string(), data));
+    }
+    if let Some(metadata) = loader::load_metadata(sysdic_dir)? {
+        components.push(("metadata.bin".to_string(), serialize(&metadata)?));
+    }
+
+    let mut bundle = BUNDLE_MAGIC.to_vec();
+    bundle.extend(serialize(&components)?);
+    Ok(bundle)
+}
+
+/// Split a bundle into its components, keyed by file name
+pub(crate) fn unpack_bundle(bundle: &[u8]) -> Result<HashMap<String, Vec<u8>>, RunomeError> {
+    let data =
+        bundle
+            .strip_prefix(BUNDLE_MAGIC)
+            .ok_or_else(|| RunomeError::DictValidationError {
+                reason: "Not a runome dictionary bundle".to_string(),
+            })?;
+    let components: Vec<(String, Vec<u8>)> = loader::deserialize_component(data, "bundle")?;
+    let components: HashMap<String, Vec<u8>> = components.into_iter().collect();
+
+    if let Some(missing) = BUNDLE_COMPONENTS
+        .iter()
+        .find(|filename| !components.contains_key(**filename))
+    {
+        return Err(RunomeError::DictFileMissing {
+            filename: missing.to_string(),
+        });
+    }
+    Ok(components)
+}
+
+fn serialize<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, RunomeError> {
+    bincode::serialize(value).map_err(|e| RunomeError::DictSerializationError {
+        component: "bundle".to_string(),
+        source: e,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::SystemDictionary;
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let bundle = pack_sysdic(&sysdic_path).unwrap();
+        assert!(bundle.starts_with(BUNDLE_MAGIC));
+
+        let from_bundle = SystemDictionary::from_bundle(&bundle).unwrap();
+        let from_dir = SystemDictionary::new(&sysdic_path).unwrap();
+        assert_eq!(
+            from_bundle.entries().unwrap().len(),
+            from_dir.entries().unwrap().len()
+        );
+        assert_eq!(from_bundle.metadata(), from_dir.metadata());
+        assert_eq!(
+            from_bundle.lookup("すもも").unwrap(),
+            from_dir.lookup("すもも").unwrap()
+        );
+
+        assert!(SystemDictionary::from_bundle(b"not a bundle").is_err());
+        assert!(SystemDictionary::from_bundle(&bundle[..bundle.len() / 2]).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::dictionary::{ConnectionMatrix, DictEntry, UserDictFormat, UserDictionary, loader};
+use crate::error::{PathContext, RunomeError};
+
+/// Precompiled user dictionary
+///
+/// Holds the FST, morpheme index and entries of a user dictionary in the same
+/// binary layout as the system dictionary (`dic.fst`, `morpheme_index.bin`,
+/// `entries.bin`), so large user dictionaries can be loaded without parsing
+/// CSV and rebuilding the FST at every startup.
+pub struct CompiledUserDictionary {
+    entries: Vec<DictEntry>,
+    morpheme_index: Vec<Vec<u32>>,
+    fst_bytes: Vec<u8>,
+}
+
+impl CompiledUserDictionary {
+    /// Compile a user dictionary CSV file into a binary dictionary directory
+    ///
+    /// # Arguments
+    /// * `csv_path` - Path to CSV file containing user dictionary entries
+    /// * `format` - Format of the CSV file (IPADIC or Simpledic)
+    /// * `output_dir` - Directory to write the compiled dictionary files to
+    ///
+    /// # Returns
+    /// * `Ok(())` - Dictionary compiled successfully
+    /// * `Err(RunomeError)` - Error if CSV parsing, FST building or writing fails
+    pub fn compile(
+        csv_path: &Path,
+        format: UserDictFormat,
+        output_dir: &Path,
+    ) -> Result<(), RunomeError> {
+        let entries = UserDictionary::load_entries(csv_path, format)?;
+        Self::write(&entries, output_dir)
+    }
+
+    /// Compile a user dictionary CSV file in the given encoding into a binary
+    /// dictionary directory
+    ///
+    /// # Arguments
+    /// * `csv_path` - Path to CSV file containing user dictionary entries
+    /// * `format` - Format of the CSV file (IPADIC or Simpledic)
+    /// * `encoding` - Character encoding of the CSV file (UTF-8, EUC-JP, Shift_JIS, etc.)
+    /// * `output_dir` - Directory to write the compiled dictionary files to
+    ///
+    /// # Returns
+    /// * `Ok(())` - Dictionary compiled successfully
+    /// * `Err(RunomeError)` - Error if decoding, CSV parsing, FST building or writing fails
+    pub fn compile_with_encoding(
+        csv_path: &Path,
+        format: UserDictFormat,
+        encoding: &'static encoding_rs::Encoding,
+        output_dir: &Path,
+    ) -> Result<(), RunomeError> {
+        let entries = UserDictionary::load_entries_with_encoding(csv_path, format, encoding)?;
+        Self::write(&entries, output_dir)
+    }
+
+    fn write(entries: &[DictEntry], output_dir: &Path) -> Result<(), RunomeError> {
+        let (fst_bytes, morpheme_index) = UserDictionary::build_fst_bytes(entries)?;
+
+        fs::create_dir_all(output_dir).with_path(output_dir)?;
+        let fst_path = output_dir.join("dic.fst");
+        fs::write(&fst_path, &fst_bytes).with_path(&fst_path)?;
+        let index_path = output_dir.join("morpheme_index.bin");
+        fs::write(
+            &index_path,
+            Self::serialize(&morpheme_index, "morpheme_index")?,
+        )
+        .with_path(&index_path)?;
+        let entries_path = output_dir.join("entries.bin");
+        fs::write(&entries_path, Self::serialize(entries, "entries")?).with_path(&entries_path)?;
+
+        log::info!(
+            "Compiled {} user dictionary entries to {:?}",
+            entries.len(),
+            output_dir
+        );
+        Ok(())
+    }
+
+    /// Load a compiled user dictionary directory
+    ///
+    /// # Arguments
+    /// * `path` - Directory written by `CompiledUserDictionary::compile`
+    ///
+    /// # Returns
+    /// * `Ok(CompiledUserDictionary)` - Successfully loaded dictionary
+    /// * `Err(RunomeError)` - Error if a file is missing or cannot be deserialized
+    pub fn load(path: &Path) -> Result<Self, RunomeError> {
+        let entries = loader::load_entries(path)?;
+        let morpheme_index = loader::load_morpheme_index(path)?;
+        let fst_bytes = loader::load_fst_bytes(path)?;
+
+        Ok(Self {
+            entries,
+            morpheme_index,
+            fst_bytes,
+        })
+    }
+
+    /// Get the number of entries in the dictionary
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the dictionary has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Convert into a UserDictionary usable by the tokenizer
+    ///
+    /// # Arguments
+    /// * `connections` - Reference to system dictionary connection matrix
+    ///
+    /// # Returns
+    /// * `Ok(UserDictionary)` - User dictionary backed by the compiled data
+    /// * `Err(RunomeError)` - Error if the FST data is invalid
+    pub fn into_user_dictionary(
+        self,
+        connections: Arc<ConnectionMatrix>,
+    ) -> Result<UserDictionary, RunomeError> {
+        UserDictionary::from_parts(
+            self.entries,
+            self.morpheme_index,
+            self.fst_bytes,
+            connections,
+        )
+    }
+
+    fn serialize<T: serde::Serialize + ?Sized>(
+        value: &T,
+        component: &str,
+    ) -> Result<Vec<u8>, RunomeError> {
+        bincode::serialize(value).map_err(|e| RunomeError::DictSerializationError {
+            component: component.to_string(),
+            source: e,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+    use std::io::Write;
+
+    fn create_temp_csv(content: &str) -> tempfile::NamedTempFile {
+        let mut temp_file = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+        temp_file
+            .write_all(content.as_bytes())
+            .expect("Failed to write to temp file");
+        temp_file
+    }
+
+    fn create_mock_connections() -> Arc<ConnectionMatrix> {
+        Arc::new(
+            ConnectionMatrix::from_rows(vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]]).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_compile_and_load_roundtrip() {
+        let csv_content = "\
+東京スカイツリー,1,1,-1000,名詞,固有名詞,一般,*,*,*,東京スカイツリー,トウキョウスカイツリー,トウキョウスカイツリー
+東武スカイツリーライン,2,2,-2000,名詞,固有名詞,一般,*,*,*,東武スカイツリーライン,トウブスカイツリーライン,トウブスカイツリーライン
+東京,1,1,-500,名詞,固有名詞,地域,*,*,*,東京,トウキョウ,トーキョー";
+        let temp_file = create_temp_csv(csv_content);
+        let output_dir = tempfile::tempdir().unwrap();
+
+        CompiledUserDictionary::compile(
+            temp_file.path(),
+            UserDictFormat::Ipadic,
+            output_dir.path(),
+        )
+        .unwrap();
+
+        let compiled = CompiledUserDictionary::load(output_dir.path()).unwrap();
+        assert_eq!(compiled.len(), 3);
+
+        let user_dict = compiled
+            .into_user_dictionary(create_mock_connections())
+            .unwrap();
+        let csv_dict = UserDictionary::new(
+            temp_file.path(),
+            UserDictFormat::Ipadic,
+            create_mock_connections(),
+        )
+        .unwrap();
+
+        for surface in ["東京スカイツリー", "東武スカイツリーライン", "東京"] {
+            let compiled_results = user_dict.lookup(surface).unwrap();
+            let csv_results = csv_dict.lookup(surface).unwrap();
+            assert_eq!(compiled_results, csv_results);
+        }
+        assert_eq!(user_dict.get_trans_cost(1, 2).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_compile_with_encoding() {
+        let csv_content = "東京スカイツリー,1,1,-1000,名詞,固有名詞,一般,*,*,*,東京スカイツリー,トウキョウスカイツリー,トウキョウスカイツリー\n";
+        let (encoded, _, _) = encoding_rs::EUC_JP.encode(csv_content);
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        temp_file.write_all(&encoded).unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+
+        CompiledUserDictionary::compile_with_encoding(
+            temp_file.path(),
+            UserDictFormat::Ipadic,
+            encoding_rs::EUC_JP,
+            output_dir.path(),
+        )
+        .unwrap();
+
+        let user_dict = CompiledUserDictionary::load(output_dir.path())
+            .unwrap()
+            .into_user_dictionary(create_mock_connections())
+            .unwrap();
+        let results = user_dict.lookup("東京スカイツリー").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].reading, "トウキョウスカイツリー");
+    }
+
+    #[test]
+    fn test_load_missing_directory() {
+        let result = CompiledUserDictionary::load(Path::new("/nonexistent/userdic"));
+        assert!(matches!(
+            result,
+            Err(RunomeError::DictDirectoryNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let result = CompiledUserDictionary::load(output_dir.path());
+        assert!(matches!(result, Err(RunomeError::DictFileMissing { .. })));
+    }
+}
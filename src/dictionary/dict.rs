@@ -1,14 +1,24 @@
-use fst::Map;
+use fst::raw::Output;
+use fst::{Map, Streamer};
+use memmap2::Mmap;
+use std::ops::Deref;
 use std::sync::Arc;
 
-use super::{DictionaryResource, loader, types::DictEntry};
+use super::{
+    DictionaryResource,
+    double_array::DoubleArray,
+    loader,
+    types::{ConnectionMatrix, DictEntry, MemoryStats},
+};
 use crate::error::RunomeError;
 
 /// Dictionary trait providing core morpheme lookup functionality
 ///
 /// This trait mirrors the interface of Janome's Python Dictionary class,
 /// providing lookup and connection cost methods for morphological analysis.
-pub trait Dictionary {
+/// Dictionaries are read-only once loaded and shared between threads behind
+/// an `Arc`, hence the `Send + Sync` bound.
+pub trait Dictionary: Send + Sync {
     /// Look up morphemes matching a surface form
     ///
     /// Returns a vector of references to DictEntry structs containing
@@ -35,14 +45,111 @@ pub trait Dictionary {
     /// * `Ok(i16)` - Connection cost
     /// * `Err(RunomeError)` - Error if IDs are invalid
     fn get_trans_cost(&self, left_id: u16, right_id: u16) -> Result<i16, RunomeError>;
+
+    /// Get the context ids of the sentence boundary nodes
+    ///
+    /// BOS connects to the first word with its id as right id, and the last
+    /// word connects to EOS with its id as left id. MeCab dictionaries such as
+    /// IPADIC use 0 for both, which is the default.
+    ///
+    /// # Returns
+    /// * `(u16, u16)` - BOS and EOS context ids
+    fn boundary_context_ids(&self) -> (u16, u16) {
+        (0, 0)
+    }
+}
+
+/// Flag bit set on FST output values that hold a morpheme ID directly
+///
+/// Surfaces with a single dictionary entry are stored as
+/// `PACKED_MORPHEME_FLAG | morpheme_id`, skipping the morpheme index on lookup.
+/// Values without the flag are morpheme index IDs, so dictionaries built
+/// without packing stay readable.
+pub const PACKED_MORPHEME_FLAG: u64 = 1 << 63;
+
+/// Backing storage for FST data
+///
+/// `Owned` data lives on the heap of the current process. `Mapped` data is a
+/// read-only memory map of `dic.fst`, whose pages are shared by every process
+/// mapping the same file.
+#[derive(Clone)]
+pub enum FstData {
+    Owned(Vec<u8>),
+    Mapped(Arc<Mmap>),
+}
+
+impl Deref for FstData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FstData::Owned(bytes) => bytes,
+            FstData::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+impl FstData {
+    /// Heap bytes and memory-mapped bytes of the data
+    pub(crate) fn memory_usage(&self) -> (usize, usize) {
+        match self {
+            FstData::Owned(bytes) => (bytes.capacity(), 0),
+            FstData::Mapped(mmap) => (0, mmap.len()),
+        }
+    }
+
+    /// Check whether two values share the same memory map
+    fn shares_map(&self, other: &FstData) -> bool {
+        match (self, other) {
+            (FstData::Mapped(a), FstData::Mapped(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl AsRef<[u8]> for FstData {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl std::fmt::Debug for FstData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FstData::Owned(bytes) => write!(f, "FstData::Owned({} bytes)", bytes.len()),
+            FstData::Mapped(mmap) => write!(f, "FstData::Mapped({} bytes)", mmap.len()),
+        }
+    }
+}
+
+/// Data structure a `Matcher` searches surface forms in
+///
+/// The FST is compact and can be memory-mapped. The double-array trie takes
+/// more memory but makes the common prefix search run for every lattice
+/// position cheaper. It is the default with the `double-array` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatcherBackend {
+    /// Finite state transducer from the fst crate
+    #[cfg_attr(not(feature = "double-array"), default)]
+    Fst,
+    /// Double-array trie built from the FST
+    #[cfg_attr(feature = "double-array", default)]
+    DoubleArray,
 }
 
 /// Matcher struct for FST-based string matching
 ///
 /// Handles finite state transducer operations to efficiently map
-/// surface form strings to morpheme IDs using the fst crate.
+/// surface form strings to morpheme IDs using the fst crate, or a
+/// double-array trie holding the same keys (see `MatcherBackend`).
 pub struct Matcher {
-    fst: Map<Vec<u8>>,
+    trie: Trie,
+}
+
+/// Searchable structure of a `Matcher`
+enum Trie {
+    Fst(Map<FstData>),
+    DoubleArray(DoubleArray),
 }
 
 impl Matcher {
@@ -55,10 +162,81 @@ impl Matcher {
     /// * `Ok(Matcher)` - Successfully created matcher
     /// * `Err(RunomeError)` - Error if FST data is invalid
     pub fn new(fst_bytes: Vec<u8>) -> Result<Self, RunomeError> {
-        let fst = Map::new(fst_bytes).map_err(|e| RunomeError::DictValidationError {
-            reason: format!("Failed to create FST: {}", e),
-        })?;
-        Ok(Self { fst })
+        Self::from_data(FstData::Owned(fst_bytes))
+    }
+
+    /// Create new Matcher from owned or memory-mapped FST data
+    ///
+    /// # Arguments
+    /// * `fst_data` - FST data, either on the heap or memory-mapped
+    ///
+    /// # Returns
+    /// * `Ok(Matcher)` - Successfully created matcher
+    /// * `Err(RunomeError)` - Error if FST data is invalid
+    pub fn from_data(fst_data: FstData) -> Result<Self, RunomeError> {
+        let fst = Map::new(fst_data).map_err(|e| RunomeError::InvalidFst { source: e })?;
+        Ok(Self {
+            trie: Trie::Fst(fst),
+        })
+    }
+
+    /// Create new Matcher from FST data, searching it with the given backend
+    ///
+    /// With `MatcherBackend::DoubleArray` the FST is converted to a
+    /// double-array trie and then dropped.
+    ///
+    /// # Arguments
+    /// * `fst_data` - FST data, either on the heap or memory-mapped
+    /// * `backend` - Data structure to search surface forms in
+    ///
+    /// # Returns
+    /// * `Ok(Matcher)` - Successfully created matcher
+    /// * `Err(RunomeError)` - Error if FST data is invalid
+    pub fn with_backend(fst_data: FstData, backend: MatcherBackend) -> Result<Self, RunomeError> {
+        let matcher = Self::from_data(fst_data)?;
+        match (backend, &matcher.trie) {
+            (MatcherBackend::DoubleArray, Trie::Fst(fst)) => {
+                Ok(Self::from_double_array(DoubleArray::from_fst(fst)?))
+            }
+            _ => Ok(matcher),
+        }
+    }
+
+    /// Create new Matcher searching a double-array trie
+    ///
+    /// # Arguments
+    /// * `da` - Double-array trie mapping surfaces to morpheme index IDs
+    ///
+    /// # Returns
+    /// * `Matcher` - Matcher using the double-array backend
+    pub fn from_double_array(da: DoubleArray) -> Self {
+        Self {
+            trie: Trie::DoubleArray(da),
+        }
+    }
+
+    /// Get the data structure the matcher searches
+    pub fn backend(&self) -> MatcherBackend {
+        match self.trie {
+            Trie::Fst(_) => MatcherBackend::Fst,
+            Trie::DoubleArray(_) => MatcherBackend::DoubleArray,
+        }
+    }
+
+    /// Get the FST data the matcher reads, if it uses the FST backend
+    pub(crate) fn data(&self) -> Option<&FstData> {
+        match &self.trie {
+            Trie::Fst(fst) => Some(fst.as_fst().as_inner()),
+            Trie::DoubleArray(_) => None,
+        }
+    }
+
+    /// Get the heap and mapped bytes used by the searched structure
+    pub(crate) fn memory_usage(&self) -> (usize, usize) {
+        match &self.trie {
+            Trie::Fst(fst) => fst.as_fst().as_inner().memory_usage(),
+            Trie::DoubleArray(da) => (da.heap_size(), 0),
+        }
     }
 
     /// Run FST matching on input word
@@ -87,42 +265,21 @@ impl Matcher {
                 return Ok((false, Vec::new()));
             }
 
-            match self.fst.get(word) {
+            let found = match &self.trie {
+                Trie::Fst(fst) => fst.get(word),
+                Trie::DoubleArray(da) => da.get(word.as_bytes()),
+            };
+            match found {
                 Some(index_id) => Ok((true, vec![index_id])),
                 None => Ok((false, Vec::new())),
             }
         }
     }
 
-    /// Optimized prefix matching that iterates over char boundaries
+    /// Prefix matching with a single FST traversal
     fn run_prefix_match(&self, word: &str) -> Result<(bool, Vec<u64>), RunomeError> {
-        if word.is_empty() {
-            return Ok((false, Vec::new()));
-        }
-
-        // Pre-allocate with reasonable capacity to reduce reallocations
-        let mut all_index_ids = Vec::with_capacity(word.chars().count());
-
-        // Use FST's range query for more efficient prefix matching
-        // This avoids repeated FST lookups for each prefix
-        let mut last_byte_pos = 0;
-
-        for (byte_pos, _) in word.char_indices().skip(1) {
-            let prefix = &word[..byte_pos];
-            if let Some(index_id) = self.fst.get(prefix) {
-                all_index_ids.push(index_id);
-            } else {
-                break; // No more prefixes match, exit early
-            }
-            last_byte_pos = byte_pos;
-        }
-
-        // Don't forget the full word
-        if last_byte_pos < word.len() {
-            if let Some(index_id) = self.fst.get(word) {
-                all_index_ids.push(index_id);
-            }
-        }
+        let mut all_index_ids = Vec::new();
+        self.walk_prefixes(word, |_, index_id| all_index_ids.push(index_id));
 
         if all_index_ids.is_empty() {
             Ok((false, Vec::new()))
@@ -134,10 +291,54 @@ impl Matcher {
         }
     }
 
+    /// Find all keys of the FST that are prefixes of a word
+    ///
+    /// Walks the automaton (or trie) once along the bytes of `word` instead of looking
+    /// up each prefix from the root.
+    ///
+    /// # Arguments
+    /// * `word` - Input string to match
+    ///
+    /// # Returns
+    /// * `Vec<(usize, u64)>` - Byte length and index ID of each matching prefix, shortest first
+    pub fn prefix_matches(&self, word: &str) -> Vec<(usize, u64)> {
+        let mut matches = Vec::new();
+        self.walk_prefixes(word, |len, index_id| matches.push((len, index_id)));
+        matches
+    }
+
+    /// Call `found` with the byte length and output of every key that is a
+    /// prefix of `word`, shortest first
+    ///
+    /// The walk stops as soon as no key continues with the next byte.
+    #[inline]
+    fn walk_prefixes(&self, word: &str, mut found: impl FnMut(usize, u64)) {
+        let fst = match &self.trie {
+            Trie::Fst(fst) => fst.as_fst(),
+            Trie::DoubleArray(da) => return da.common_prefix_search(word.as_bytes(), found),
+        };
+        let mut node = fst.root();
+        let mut output = Output::zero();
+
+        for (i, &byte) in word.as_bytes().iter().enumerate() {
+            let Some(index) = node.find_input(byte) else {
+                break;
+            };
+            let transition = node.transition(index);
+            output = output.cat(transition.out);
+            node = fst.node(transition.addr);
+            // Keys are strings, so final states are only reached at char boundaries
+            if node.is_final() {
+                found(i + 1, output.cat(node.final_output()).value());
+            }
+        }
+    }
+
     /// Decode FST index ID to morpheme IDs using separate morpheme index
     ///
     /// With the separate index approach, the FST stores simple index IDs,
     /// and we use those to look up the actual morpheme IDs from the morpheme index.
+    /// Values packed with `PACKED_MORPHEME_FLAG` carry the morpheme ID directly.
     ///
     /// # Arguments
     /// * `index_id` - The u64 index ID from FST
@@ -145,18 +346,66 @@ impl Matcher {
     ///
     /// # Returns
     /// * `Vec<u32>` - Vector of morpheme IDs for this surface form
-    fn lookup_morpheme_ids(&self, index_id: u64, morpheme_index: &[Vec<u32>]) -> Vec<u32> {
+    pub fn lookup_morpheme_ids(&self, index_id: u64, morpheme_index: &[Vec<u32>]) -> Vec<u32> {
+        // Packed value: single morpheme ID stored in the FST output itself
+        if index_id & PACKED_MORPHEME_FLAG != 0 {
+            return vec![(index_id & !PACKED_MORPHEME_FLAG) as u32];
+        }
+
         // Simple lookup: FST index ID directly maps to morpheme index entry
         if let Some(morpheme_ids) = morpheme_index.get(index_id as usize) {
             morpheme_ids.clone()
         } else {
             // This should not happen if the data is consistent
-            eprintln!("Warning: Invalid morpheme index ID: {}", index_id);
+            log::warn!("Invalid morpheme index ID: {}", index_id);
             Vec::new()
         }
     }
 }
 
+/// Rewrite FST output values of single-entry surfaces as packed morpheme IDs
+///
+/// Mirrors MeCab storing token data directly in the trie: for surfaces whose
+/// morpheme index entry has exactly one morpheme ID, the FST value becomes
+/// `PACKED_MORPHEME_FLAG | morpheme_id`. The morpheme index is left unchanged,
+/// so multi-entry surfaces keep their index IDs.
+///
+/// # Arguments
+/// * `fst_bytes` - FST mapping surfaces to morpheme index IDs
+/// * `morpheme_index` - Morpheme index the FST values refer to
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - Rewritten FST bytes
+/// * `Err(RunomeError)` - Error if the FST is invalid or cannot be rebuilt
+pub fn pack_single_entry_values(
+    fst_bytes: &[u8],
+    morpheme_index: &[Vec<u32>],
+) -> Result<Vec<u8>, RunomeError> {
+    let fst = Map::new(fst_bytes.to_vec()).map_err(|e| RunomeError::InvalidFst { source: e })?;
+
+    let mut builder = fst::MapBuilder::memory();
+    let mut stream = fst.stream();
+    while let Some((surface, index_id)) = stream.next() {
+        let value = match morpheme_index.get(index_id as usize) {
+            Some(ids) if ids.len() == 1 => PACKED_MORPHEME_FLAG | ids[0] as u64,
+            _ => index_id,
+        };
+        builder
+            .insert(surface, value)
+            .map_err(|e| RunomeError::FstBuildError {
+                reason: "Failed to insert packed value".to_string(),
+                source: e,
+            })?;
+    }
+
+    builder
+        .into_inner()
+        .map_err(|e| RunomeError::FstBuildError {
+            reason: "Failed to build FST".to_string(),
+            source: e,
+        })
+}
+
 /// RAMDictionary implementation using DictionaryResource and Matcher
 ///
 /// Combines dictionary data storage (DictionaryResource) with FST-based
@@ -170,7 +419,9 @@ impl RAMDictionary {
     /// Create new RAMDictionary from DictionaryResource and sysdic directory
     ///
     /// Loads FST bytes directly from the sysdic directory and creates a Matcher instance
-    /// for efficient string-to-morpheme-ID mapping.
+    /// for efficient string-to-morpheme-ID mapping, using the backend of
+    /// `GlobalConfig::matcher_backend`. A double-array trie is read from
+    /// `dic.da` when the directory has one, instead of being built from the FST.
     ///
     /// # Arguments
     /// * `resource` - DictionaryResource containing all dictionary data
@@ -183,28 +434,152 @@ impl RAMDictionary {
         resource: DictionaryResource,
         sysdic_dir: &std::path::Path,
     ) -> Result<Self, RunomeError> {
-        // Load FST bytes directly using loader
-        let fst_bytes = loader::load_fst_bytes(sysdic_dir)?;
-        let matcher = Matcher::new(fst_bytes)?;
+        let backend = crate::config::global_config().matcher_backend;
+        let matcher = match Self::prebuilt_matcher(sysdic_dir, backend)? {
+            Some(matcher) => matcher,
+            None => {
+                // Load FST bytes directly using loader
+                let fst_bytes = loader::load_fst_bytes(sysdic_dir)?;
+                Matcher::with_backend(FstData::Owned(fst_bytes), backend.unwrap_or_default())?
+            }
+        };
 
         Ok(Self { resource, matcher })
     }
 
+    /// Create new RAMDictionary using the FST data held by the DictionaryResource,
+    /// or the double-array trie of its sysdic directory
+    ///
+    /// Like `from_resource`, but reads `dic.da` from the sysdic directory when
+    /// it is present and `GlobalConfig::matcher_backend` does not ask for the FST.
+    ///
+    /// # Arguments
+    /// * `resource` - DictionaryResource containing all dictionary data
+    /// * `sysdic_dir` - Path to the sysdic directory the resource was loaded from
+    ///
+    /// # Returns
+    /// * `Ok(RAMDictionary)` - Successfully created dictionary
+    /// * `Err(RunomeError)` - Error if the FST or double-array trie is invalid
+    pub fn from_resource_in(
+        resource: DictionaryResource,
+        sysdic_dir: &std::path::Path,
+    ) -> Result<Self, RunomeError> {
+        let backend = crate::config::global_config().matcher_backend;
+        match Self::prebuilt_matcher(sysdic_dir, backend)? {
+            Some(matcher) => Ok(Self { resource, matcher }),
+            None => Self::from_resource_with_backend(resource, backend.unwrap_or_default()),
+        }
+    }
+
+    /// Load the double-array trie written by the builder, unless the FST is requested
+    fn prebuilt_matcher(
+        sysdic_dir: &std::path::Path,
+        backend: Option<MatcherBackend>,
+    ) -> Result<Option<Matcher>, RunomeError> {
+        if backend == Some(MatcherBackend::Fst) {
+            return Ok(None);
+        }
+        Ok(loader::load_double_array(sysdic_dir)?.map(Matcher::from_double_array))
+    }
+
+    /// Create new RAMDictionary using the FST data held by the DictionaryResource
+    ///
+    /// Unlike `new`, this does not touch the filesystem, so it works for
+    /// resources that were not loaded from a sysdic directory. A memory-mapped
+    /// FST is shared with the resource rather than copied. The matcher uses
+    /// the backend of `GlobalConfig::matcher_backend`.
+    ///
+    /// # Arguments
+    /// * `resource` - DictionaryResource containing all dictionary data
+    ///
+    /// # Returns
+    /// * `Ok(RAMDictionary)` - Successfully created dictionary
+    /// * `Err(RunomeError)` - Error if FST creation fails
+    pub fn from_resource(resource: DictionaryResource) -> Result<Self, RunomeError> {
+        let backend = crate::config::global_config().matcher_backend;
+        Self::from_resource_with_backend(resource, backend.unwrap_or_default())
+    }
+
+    /// Create new RAMDictionary from a DictionaryResource with the given matcher backend
+    ///
+    /// # Arguments
+    /// * `resource` - DictionaryResource containing all dictionary data
+    /// * `backend` - Data structure the matcher searches surface forms in
+    ///
+    /// # Returns
+    /// * `Ok(RAMDictionary)` - Successfully created dictionary
+    /// * `Err(RunomeError)` - Error if FST creation fails
+    pub fn from_resource_with_backend(
+        resource: DictionaryResource,
+        backend: MatcherBackend,
+    ) -> Result<Self, RunomeError> {
+        let matcher = Matcher::with_backend(resource.get_fst_data().clone(), backend)?;
+
+        Ok(Self { resource, matcher })
+    }
+
+    /// Get the data structure the matcher searches
+    pub fn matcher_backend(&self) -> MatcherBackend {
+        self.matcher.backend()
+    }
+
     /// Get reference to the embedded DictionaryResource
     pub fn get_resource(&self) -> &DictionaryResource {
         &self.resource
     }
 
+    /// Get the approximate memory used by the dictionary
+    ///
+    /// Includes the FST or double-array trie of the matcher, unless it shares the memory map of
+    /// the resource's FST.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut stats = self.resource.memory_stats();
+        let shared = self
+            .matcher
+            .data()
+            .is_some_and(|data| data.shares_map(self.resource.get_fst_data()));
+        if !shared {
+            let (heap, mapped) = self.matcher.memory_usage();
+            stats.fst += heap;
+            stats.mapped += mapped;
+        }
+        stats
+    }
+
     /// Get connection matrix for user dictionary use
     ///
     /// Returns a reference to the connection matrix used by this dictionary.
     /// This is needed for UserDictionary initialization.
     ///
     /// # Returns
-    /// * `Arc<Vec<Vec<i16>>>` - Shared reference to connection matrix
-    pub fn get_connection_matrix(&self) -> Arc<Vec<Vec<i16>>> {
+    /// * `Arc<ConnectionMatrix>` - Shared reference to connection matrix
+    pub fn get_connection_matrix(&self) -> Arc<ConnectionMatrix> {
         self.resource.get_connection_matrix()
     }
+
+    /// Look up the entries whose surface is exactly the given string
+    ///
+    /// Unlike `lookup`, entries of shorter prefixes are not returned.
+    ///
+    /// # Arguments
+    /// * `surface` - Surface form string to look up
+    ///
+    /// # Returns
+    /// * `Ok(Vec<&DictEntry>)` - Homographs of the surface, in morpheme ID order
+    /// * `Err(RunomeError)` - Error if lookup fails
+    pub fn lookup_exact(&self, surface: &str) -> Result<Vec<&DictEntry>, RunomeError> {
+        let (_, index_ids) = self.matcher.run(surface, false)?;
+        let morpheme_index = self.resource.get_morpheme_index();
+        let mut entries = Vec::new();
+        for index_id in index_ids {
+            for morpheme_id in self.matcher.lookup_morpheme_ids(index_id, morpheme_index) {
+                if let Some(entry) = self.resource.get_entry(morpheme_id as usize)? {
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(entries)
+    }
 }
 
 impl Dictionary for RAMDictionary {
@@ -224,7 +599,6 @@ impl Dictionary for RAMDictionary {
 
         // 3. Get morpheme index and dictionary entries
         let morpheme_index = self.resource.get_morpheme_index();
-        let entries = self.resource.get_entries();
         let mut results = Vec::new();
 
         // 4. For each index ID, look up the morpheme IDs and resolve to entries
@@ -233,16 +607,17 @@ impl Dictionary for RAMDictionary {
 
             for morpheme_id in morpheme_ids {
                 // Validate morpheme ID is within bounds
-                if let Some(entry) = entries.get(morpheme_id as usize) {
+                if let Some(entry) = self.resource.get_entry(morpheme_id as usize)? {
                     // Filter out entries with empty surface forms
                     if !entry.surface.is_empty() {
                         results.push(entry);
                     }
                 } else {
                     // Log warning but continue processing other valid IDs
-                    eprintln!(
-                        "Warning: Invalid morpheme ID {} for surface '{}', skipping",
-                        morpheme_id, surface
+                    log::warn!(
+                        "Invalid morpheme ID {} for surface '{}', skipping",
+                        morpheme_id,
+                        surface
                     );
                 }
             }
@@ -256,6 +631,11 @@ impl Dictionary for RAMDictionary {
         // Delegate to DictionaryResource connection cost method
         self.resource.get_connection_cost(left_id, right_id)
     }
+
+    fn boundary_context_ids(&self) -> (u16, u16) {
+        let metadata = self.resource.metadata();
+        (metadata.bos_context_id, metadata.eos_context_id)
+    }
 }
 
 #[cfg(test)]
@@ -328,10 +708,10 @@ mod tests {
         // Verify it's the right kind of error
         if let Err(error) = matcher_result {
             match error {
-                RunomeError::DictValidationError { reason } => {
-                    assert!(reason.contains("Failed to create FST"));
+                RunomeError::InvalidFst { .. } => {
+                    assert_eq!(error.code(), crate::ErrorCode::DictionaryCorrupt);
                 }
-                _ => panic!("Expected DictValidationError, got: {:?}", error),
+                _ => panic!("Expected InvalidFst, got: {:?}", error),
             }
         }
     }
@@ -378,6 +758,124 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pack_single_entry_values() {
+        // "すもも" has one morpheme, "もも" has two
+        let morpheme_index = vec![vec![7], vec![3, 4]];
+        let mut builder = fst::MapBuilder::memory();
+        builder.insert("すもも", 0).unwrap();
+        builder.insert("もも", 1).unwrap();
+        let fst_bytes = builder.into_inner().unwrap();
+
+        let packed = pack_single_entry_values(&fst_bytes, &morpheme_index).unwrap();
+        let matcher = Matcher::new(packed).unwrap();
+
+        let (matched, index_ids) = matcher.run("すもも", false).unwrap();
+        assert!(matched);
+        assert_eq!(index_ids, vec![PACKED_MORPHEME_FLAG | 7]);
+        assert_eq!(
+            matcher.lookup_morpheme_ids(index_ids[0], &morpheme_index),
+            vec![7]
+        );
+
+        let (matched, index_ids) = matcher.run("もも", false).unwrap();
+        assert!(matched);
+        assert_eq!(index_ids, vec![1]);
+        assert_eq!(
+            matcher.lookup_morpheme_ids(index_ids[0], &morpheme_index),
+            vec![3, 4]
+        );
+    }
+
+    #[test]
+    fn test_prefix_matches() {
+        let mut builder = fst::MapBuilder::memory();
+        builder.insert("東", 1).unwrap();
+        builder.insert("東京", 2).unwrap();
+        builder.insert("東京都庁", 4).unwrap();
+        builder.insert("東北", 3).unwrap();
+        let matcher = Matcher::new(builder.into_inner().unwrap()).unwrap();
+
+        // "東京都" is not a key, but the longer "東京都庁" still matches
+        assert_eq!(
+            matcher.prefix_matches("東京都庁舎"),
+            vec![(3, 1), (6, 2), (12, 4)]
+        );
+        assert_eq!(matcher.prefix_matches("東"), vec![(3, 1)]);
+        assert!(matcher.prefix_matches("京都").is_empty());
+        assert!(matcher.prefix_matches("").is_empty());
+
+        let (matched, index_ids) = matcher.run("東京都庁舎", true).unwrap();
+        assert!(matched);
+        assert_eq!(index_ids, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_double_array_backend() {
+        let mut builder = fst::MapBuilder::memory();
+        builder.insert("東", 1).unwrap();
+        builder.insert("東京", 2).unwrap();
+        builder.insert("東京都庁", 4).unwrap();
+        builder.insert("東北", 3).unwrap();
+        let fst_data = FstData::Owned(builder.into_inner().unwrap());
+        let fst = Matcher::with_backend(fst_data.clone(), MatcherBackend::Fst).unwrap();
+        let da = Matcher::with_backend(fst_data, MatcherBackend::DoubleArray).unwrap();
+        assert_eq!(fst.backend(), MatcherBackend::Fst);
+        assert_eq!(da.backend(), MatcherBackend::DoubleArray);
+        assert!(da.data().is_none());
+
+        for word in ["東京都庁舎", "東北", "東", "京都", ""] {
+            assert_eq!(da.prefix_matches(word), fst.prefix_matches(word));
+            assert_eq!(da.run(word, true).unwrap(), fst.run(word, true).unwrap());
+            assert_eq!(da.run(word, false).unwrap(), fst.run(word, false).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_double_array_backend_sysdic() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let fst = RAMDictionary::from_resource_with_backend(
+            DictionaryResource::load(&sysdic_path).unwrap(),
+            MatcherBackend::Fst,
+        )
+        .unwrap();
+        let da = RAMDictionary::from_resource_with_backend(
+            DictionaryResource::load(&sysdic_path).unwrap(),
+            MatcherBackend::DoubleArray,
+        )
+        .unwrap();
+        assert_eq!(da.matcher_backend(), MatcherBackend::DoubleArray);
+
+        for text in ["すもももももももものうち", "東京都庁", "形態素解析", "ｱｲｳ"]
+        {
+            for (i, _) in text.char_indices() {
+                let suffix = &text[i..];
+                let expected: Vec<_> = fst
+                    .lookup(suffix)
+                    .unwrap()
+                    .iter()
+                    .map(|e| &e.surface)
+                    .collect();
+                let actual: Vec<_> = da
+                    .lookup(suffix)
+                    .unwrap()
+                    .iter()
+                    .map(|e| &e.surface)
+                    .collect();
+                assert_eq!(actual, expected, "Lookup mismatch for '{}'", suffix);
+            }
+        }
+        assert!(da.memory_stats().fst > 0);
+    }
+
     #[test]
     fn test_matcher_run_prefix_match() {
         // Skip test if sysdic directory doesn't exist (e.g., in CI)
@@ -539,8 +1037,7 @@ mod tests {
             let cost_result = ram_dict.get_trans_cost(left_id, right_id);
 
             // Should not fail for valid IDs within matrix bounds
-            if cost_result.is_ok() {
-                let cost = cost_result.unwrap();
+            if let Ok(cost) = cost_result {
                 // Connection costs are valid integers (can be positive, negative, or zero)
                 // Just verify we got a valid i16 value - no need to check sign
                 assert!(
@@ -790,4 +1287,44 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_lookup_returns_all_homographs() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let resource =
+            DictionaryResource::load(&sysdic_path).expect("Failed to load DictionaryResource");
+        let ram_dict =
+            RAMDictionary::new(resource, &sysdic_path).expect("Failed to create RAMDictionary");
+
+        // Every entry sharing a surface must be returned through the morpheme index
+        for surface in ["も", "もも", "の"] {
+            let expected: Vec<usize> = ram_dict
+                .get_resource()
+                .get_entries()
+                .unwrap()
+                .iter()
+                .filter(|entry| entry.surface == surface)
+                .map(|entry| entry.morph_id)
+                .collect();
+            assert!(expected.len() > 1, "'{}' should have homographs", surface);
+
+            let mut found: Vec<usize> = ram_dict
+                .lookup(surface)
+                .unwrap()
+                .into_iter()
+                .filter(|entry| entry.surface == surface)
+                .map(|entry| entry.morph_id)
+                .collect();
+            found.sort_unstable();
+            assert_eq!(found, expected, "Homograph mismatch for '{}'", surface);
+        }
+    }
 }
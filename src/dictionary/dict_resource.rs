@@ -1,46 +1,400 @@
 use crate::error::RunomeError;
+use crate::intern;
+use std::borrow::Cow;
 use std::path::Path;
 use std::sync::Arc;
 
-use super::{loader, types::*};
+use super::lazy_entries::{self, EntryStore};
+use super::{dict::FstData, loader, types::*};
+
+/// Dictionary files that may be absent when loading in degraded mode
+const OPTIONAL_FILES: [&str; 2] = ["char_defs.bin", "unknowns.bin"];
+
+/// Part-of-speech assigned to unknown words in degraded mode (IPADIC DEFAULT category)
+const DEGRADED_UNKNOWN_POS: &str = "記号,一般,*,*";
+
+/// Word cost assigned to unknown words in degraded mode, high enough that
+/// any dictionary entry covering the same span wins
+const DEGRADED_UNKNOWN_COST: i16 = 10000;
 
 /// Container for all dictionary resources
 pub struct DictionaryResource {
-    entries: Vec<DictEntry>,
-    connections: ConnectionMatrix,
-    connections_arc: Arc<Vec<Vec<i16>>>, // Shared reference for user dictionaries
+    entries: EntryStore,
+    connections: Arc<ConnectionMatrix>, // Shared with user dictionaries
     char_defs: CharDefinitions,
+    char_index: CodePointIndex,
     unknowns: UnknownEntries,
-    fst_bytes: Vec<u8>,
+    fst_bytes: FstData,
     morpheme_index: Vec<Vec<u32>>,
+    missing_components: Vec<String>, // Optional files replaced by fallbacks (degraded mode)
+    metadata: DictMetadata,
+}
+
+/// Interval index over the code point ranges of char.def
+///
+/// Ranges may overlap, e.g. KANJINUMERIC inside KANJI. The code space is cut
+/// at every range boundary into intervals that each lie entirely inside or
+/// outside every range, so the ranges of a character are those of its
+/// interval, found by binary search instead of scanning all ranges.
+#[derive(Debug)]
+struct CodePointIndex {
+    /// First code point of each interval, ascending; an interval ends where
+    /// the next one starts, and the last one covers no range
+    starts: Vec<u32>,
+    /// Start of each interval's range list in `ranges`, plus the total length
+    offsets: Vec<u32>,
+    /// Indices into the code point ranges, in char.def order per interval
+    ranges: Vec<u32>,
+}
+
+impl CodePointIndex {
+    fn new(code_ranges: &[CodePointRange]) -> Self {
+        let mut starts: Vec<u32> = code_ranges
+            .iter()
+            .flat_map(|range| [range.from as u32, range.to as u32 + 1])
+            .collect();
+        starts.sort_unstable();
+        starts.dedup();
+
+        let mut offsets = Vec::with_capacity(starts.len() + 1);
+        let mut ranges = Vec::new();
+        for &start in &starts {
+            offsets.push(ranges.len() as u32);
+            ranges.extend(
+                code_ranges
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, range)| (range.from as u32..=range.to as u32).contains(&start))
+                    .map(|(i, _)| i as u32),
+            );
+        }
+        offsets.push(ranges.len() as u32);
+
+        Self {
+            starts,
+            offsets,
+            ranges,
+        }
+    }
+
+    /// Estimated heap memory of the index
+    fn heap_size(&self) -> usize {
+        (self.starts.capacity() + self.offsets.capacity() + self.ranges.capacity())
+            * std::mem::size_of::<u32>()
+    }
+
+    /// Get the indices of the ranges containing a character
+    fn lookup(&self, ch: char) -> &[u32] {
+        let interval = self.starts.partition_point(|&start| start <= ch as u32);
+        if interval == 0 {
+            return &[];
+        }
+        let begin = self.offsets[interval - 1] as usize;
+        let end = self.offsets[interval] as usize;
+        &self.ranges[begin..end]
+    }
 }
 
 impl DictionaryResource {
     /// Load all dictionary components from sysdic directory
-    pub fn load(sysdic_dir: &Path) -> Result<Self, RunomeError> {
+    ///
+    /// The directory may be given as any path type, including `OsStr` paths
+    /// that are not valid Unicode and Windows paths with a `\\?\` prefix.
+    /// Component files are matched ignoring the case of their names.
+    pub fn load(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        Self::load_with(sysdic_dir.as_ref(), load_all_entries, |dir| {
+            Ok(FstData::Owned(loader::load_fst_bytes(dir)?))
+        })
+    }
+
+    /// Load all dictionary components, memory-mapping the FST instead of reading it
+    ///
+    /// The FST stays in the page cache and is shared by every process that maps
+    /// the same `dic.fst`, so multi-process deployments only pay for it once.
+    ///
+    /// # Arguments
+    /// * `sysdic_dir` - Path to directory containing dictionary data
+    ///
+    /// # Returns
+    /// * `Ok(DictionaryResource)` - Successfully loaded resource
+    /// * `Err(RunomeError)` - Error if a component is missing, invalid or cannot be mapped
+    pub fn load_mmap(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        Self::load_with(sysdic_dir.as_ref(), load_all_entries, map_fst)
+    }
+
+    /// Load dictionary components, decoding entries only when they are looked up
+    ///
+    /// `entries.bin` and the FST are memory-mapped, so startup doesn't depend
+    /// on the number of entries. Each entry is decoded on first access and
+    /// kept. `get_entries` still works, but decodes every entry again into one
+    /// list, so callers that only look entries up should use `get_entry`.
+    /// Compressed dictionaries can't be decoded piecewise and are loaded
+    /// eagerly; check `is_lazy()`.
+    ///
+    /// # Arguments
+    /// * `sysdic_dir` - Path to directory containing dictionary data
+    ///
+    /// # Returns
+    /// * `Ok(DictionaryResource)` - Successfully loaded resource
+    /// * `Err(RunomeError)` - Error if a component is missing, invalid or cannot be mapped
+    pub fn load_lazy(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        Self::load_with(sysdic_dir.as_ref(), lazy_entries::open, map_fst)
+    }
+
+    fn load_with(
+        sysdic_dir: &Path,
+        load_entries: impl FnOnce(&Path) -> Result<EntryStore, RunomeError>,
+        load_fst: impl FnOnce(&Path) -> Result<FstData, RunomeError>,
+    ) -> Result<Self, RunomeError> {
         loader::validate_sysdic_directory(sysdic_dir)?;
+        let start = std::time::Instant::now();
+        log::debug!("Loading dictionary from {:?}", sysdic_dir);
 
-        let entries = loader::load_entries(sysdic_dir)?;
-        let connections = loader::load_connections(sysdic_dir)?;
-        let connections_arc = Arc::new(connections.clone()); // Share with user dictionaries
+        let entries = load_entries(sysdic_dir)?;
+        let connections = Arc::new(loader::load_connections(sysdic_dir)?);
         let char_defs = loader::load_char_definitions(sysdic_dir)?;
         let unknowns = loader::load_unknown_entries(sysdic_dir)?;
-        let fst_bytes = loader::load_fst_bytes(sysdic_dir)?;
+        let fst_bytes = load_fst(sysdic_dir)?;
+        let morpheme_index = loader::load_morpheme_index(sysdic_dir)?;
+        let metadata = Self::resolve_metadata(sysdic_dir, &entries)?;
+
+        log::debug!(
+            "Loaded {} dictionary entries from {:?} in {:?}",
+            entries.len(),
+            sysdic_dir,
+            start.elapsed()
+        );
+
+        Ok(Self {
+            entries,
+            connections,
+            char_index: CodePointIndex::new(&char_defs.code_ranges),
+            char_defs,
+            unknowns,
+            fst_bytes,
+            morpheme_index,
+            missing_components: Vec::new(),
+            metadata,
+        })
+    }
+
+    /// Load dictionary components, tolerating missing unknown-word data
+    ///
+    /// Unlike `load`, a missing `char_defs.bin` or `unknowns.bin` is not an error.
+    /// Each missing file is replaced by a minimal fallback on its own:
+    /// - without `char_defs.bin`, every character is classified as DEFAULT and
+    ///   unknown words are emitted one character at a time
+    /// - without `unknowns.bin`, every character category gets a single
+    ///   high-cost unknown entry of part of speech 記号,一般
+    ///
+    /// With both missing, only dictionary-based segmentation is available.
+    /// Useful for minimal deployments that ship just the FST, entries and
+    /// connection matrix.
+    ///
+    /// # Arguments
+    /// * `sysdic_dir` - Path to directory containing dictionary data
+    ///
+    /// # Returns
+    /// * `Ok(DictionaryResource)` - Loaded resource; check `is_degraded()` for fallbacks
+    /// * `Err(RunomeError)` - Error if a required component is missing or invalid
+    pub fn load_degraded(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        let sysdic_dir = sysdic_dir.as_ref();
+        let missing_components: Vec<String> = OPTIONAL_FILES
+            .iter()
+            .filter(|filename| loader::find_file(sysdic_dir, filename).is_none())
+            .map(|filename| filename.to_string())
+            .collect();
+
+        if missing_components.is_empty() {
+            return Self::load(sysdic_dir);
+        }
+        loader::validate_sysdic_directory(sysdic_dir)?;
+
+        log::warn!(
+            "Loading dictionary in degraded mode, missing: {}",
+            missing_components.join(", ")
+        );
+
+        let entries = EntryStore::Loaded(loader::load_entries(sysdic_dir)?);
+        let connections = Arc::new(loader::load_connections(sysdic_dir)?);
+        let fst_bytes = FstData::Owned(loader::load_fst_bytes(sysdic_dir)?);
         let morpheme_index = loader::load_morpheme_index(sysdic_dir)?;
+        let is_missing = |filename: &str| missing_components.iter().any(|m| m == filename);
+        let char_defs = if is_missing("char_defs.bin") {
+            Self::degraded_char_definitions()
+        } else {
+            loader::load_char_definitions(sysdic_dir)?
+        };
+        let mut unknowns = if is_missing("unknowns.bin") {
+            UnknownEntries::new()
+        } else {
+            loader::load_unknown_entries(sysdic_dir)?
+        };
+        // Categories without unknown entries would leave their characters
+        // uncovered, so every category gets at least the fallback entry
+        for category in char_defs.categories.keys() {
+            unknowns
+                .entry(category.clone())
+                .or_insert_with(|| vec![Self::degraded_unknown_entry()]);
+        }
+        let metadata = Self::resolve_metadata(sysdic_dir, &entries)?;
 
         Ok(Self {
             entries,
             connections,
-            connections_arc,
+            char_index: CodePointIndex::new(&char_defs.code_ranges),
             char_defs,
             unknowns,
             fst_bytes,
             morpheme_index,
+            missing_components,
+            metadata,
         })
     }
 
+    /// Read `metadata.bin`, or derive the metadata from the entries for
+    /// dictionaries built without it
+    fn resolve_metadata(
+        sysdic_dir: &Path,
+        entries: &EntryStore,
+    ) -> Result<DictMetadata, RunomeError> {
+        match loader::load_metadata(sysdic_dir)? {
+            Some(metadata) => Ok(metadata),
+            None => entries.derive_metadata(),
+        }
+    }
+
+    /// Load all dictionary components from the data compiled into the binary
+    ///
+    /// Available with the `embedded-dict` feature. No filesystem access is needed,
+    /// which allows single-artifact CLI tools and WASM builds.
+    ///
+    /// # Returns
+    /// * `Ok(DictionaryResource)` - Successfully loaded resource
+    /// * `Err(RunomeError)` - Error if the embedded data cannot be deserialized
+    #[cfg(feature = "embedded-dict")]
+    pub fn load_embedded() -> Result<Self, RunomeError> {
+        use super::embedded;
+
+        Self::from_serialized(
+            |component| match component {
+                "entries.bin" => Ok(embedded::ENTRIES),
+                "connections.bin" => Ok(embedded::CONNECTIONS),
+                "char_defs.bin" => Ok(embedded::CHAR_DEFS),
+                "unknowns.bin" => Ok(embedded::UNKNOWNS),
+                "dic.fst" => Ok(embedded::FST),
+                "morpheme_index.bin" => Ok(embedded::MORPHEME_INDEX),
+                filename => Err(RunomeError::DictFileMissing {
+                    filename: filename.to_string(),
+                }),
+            },
+            None,
+        )
+    }
+
+    /// Load all dictionary components from a bundle created by
+    /// `bundle::pack_sysdic`
+    ///
+    /// No filesystem access is needed, so a dictionary fetched at runtime
+    /// (e.g. by a WASM build in the browser) can be used directly.
+    ///
+    /// # Arguments
+    /// * `bundle` - Bundle bytes
+    ///
+    /// # Returns
+    /// * `Ok(DictionaryResource)` - Successfully loaded resource
+    /// * `Err(RunomeError)` - Error if the bundle is invalid or incomplete
+    pub fn load_bundle(bundle: &[u8]) -> Result<Self, RunomeError> {
+        let components = super::bundle::unpack_bundle(bundle)?;
+        let metadata = components
+            .get("metadata.bin")
+            .map(|data| loader::deserialize_component(data, "metadata"))
+            .transpose()?;
+        Self::from_serialized(|component| Ok(&components[component]), metadata)
+    }
+
+    /// Deserialize all components from the bytes of their sysdic files
+    fn from_serialized<'a>(
+        component: impl Fn(&str) -> Result<&'a [u8], RunomeError>,
+        metadata: Option<DictMetadata>,
+    ) -> Result<Self, RunomeError> {
+        let entries: Vec<DictEntry> =
+            loader::deserialize_component(component("entries.bin")?, "entries")?;
+        let connections: Arc<ConnectionMatrix> = Arc::new(loader::deserialize_component(
+            component("connections.bin")?,
+            "connections",
+        )?);
+        let char_defs: CharDefinitions =
+            loader::deserialize_component(component("char_defs.bin")?, "char_defs")?;
+        let unknowns = loader::deserialize_component(component("unknowns.bin")?, "unknowns")?;
+        let morpheme_index =
+            loader::deserialize_component(component("morpheme_index.bin")?, "morpheme_index")?;
+        let metadata = metadata.unwrap_or_else(|| DictMetadata::from_entries(&entries));
+
+        Ok(Self {
+            entries: EntryStore::Loaded(entries),
+            connections,
+            char_index: CodePointIndex::new(&char_defs.code_ranges),
+            char_defs,
+            unknowns,
+            fst_bytes: FstData::Owned(component("dic.fst")?.to_vec()),
+            morpheme_index,
+            missing_components: Vec::new(),
+            metadata,
+        })
+    }
+
+    /// Character definitions used without `char_defs.bin`: DEFAULT only,
+    /// one character at a time
+    fn degraded_char_definitions() -> CharDefinitions {
+        let mut categories = std::collections::HashMap::new();
+        categories.insert(
+            "DEFAULT".to_string(),
+            CharCategory {
+                invoke: false,
+                group: false,
+                length: 1,
+            },
+        );
+
+        CharDefinitions {
+            categories,
+            code_ranges: Vec::new(),
+        }
+    }
+
+    /// Unknown word entry used for categories without `unknowns.bin`
+    fn degraded_unknown_entry() -> UnknownEntry {
+        UnknownEntry {
+            left_id: 0,
+            right_id: 0,
+            cost: DEGRADED_UNKNOWN_COST,
+            part_of_speech: DEGRADED_UNKNOWN_POS.to_string(),
+        }
+    }
+
+    /// Check if this resource was loaded with fallback unknown-word data
+    pub fn is_degraded(&self) -> bool {
+        !self.missing_components.is_empty()
+    }
+
+    /// Get the optional dictionary files that were missing at load time
+    pub fn missing_components(&self) -> &[String] {
+        &self.missing_components
+    }
+
+    /// Get the length in characters of the longest surface form
+    pub fn max_surface_length(&self) -> usize {
+        self.metadata.max_surface_length
+    }
+
+    /// Get the dictionary-wide metadata
+    pub fn metadata(&self) -> &DictMetadata {
+        &self.metadata
+    }
+
     /// Load and validate all dictionary components from sysdic directory
-    pub fn load_and_validate(sysdic_dir: &Path) -> Result<Self, RunomeError> {
+    pub fn load_and_validate(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
         let resource = Self::load(sysdic_dir)?;
         resource.validate()?;
         Ok(resource)
@@ -49,7 +403,7 @@ impl DictionaryResource {
     /// Validate the integrity of loaded dictionary data
     pub fn validate(&self) -> Result<(), RunomeError> {
         // Validate entries have reasonable values
-        if self.entries.is_empty() {
+        if self.entries.len() == 0 {
             return Err(RunomeError::DictValidationError {
                 reason: "Dictionary entries are empty".to_string(),
             });
@@ -62,21 +416,6 @@ impl DictionaryResource {
             });
         }
 
-        // Check that all rows in connection matrix have same length
-        let first_row_len = self.connections[0].len();
-        for (i, row) in self.connections.iter().enumerate() {
-            if row.len() != first_row_len {
-                return Err(RunomeError::DictValidationError {
-                    reason: format!(
-                        "Connection matrix row {} has inconsistent length: {} vs expected {}",
-                        i,
-                        row.len(),
-                        first_row_len
-                    ),
-                });
-            }
-        }
-
         // Validate character definitions
         if self.char_defs.categories.is_empty() {
             return Err(RunomeError::DictValidationError {
@@ -84,7 +423,8 @@ impl DictionaryResource {
             });
         }
 
-        if self.char_defs.code_ranges.is_empty() {
+        // Degraded mode classifies every character as DEFAULT without code ranges
+        if self.char_defs.code_ranges.is_empty() && !self.is_degraded() {
             return Err(RunomeError::DictValidationError {
                 reason: "Character code ranges are empty".to_string(),
             });
@@ -110,8 +450,13 @@ impl DictionaryResource {
         }
 
         // Validate entry IDs are within reasonable bounds for connection matrix
-        let max_id = (self.connections.len() - 1) as u16;
-        for (i, entry) in self.entries.iter().enumerate() {
+        let max_id = (self.connections.rows() - 1) as u16;
+        for i in 0..self.entries.len() {
+            let Some(entry) = self.entries.get(i)? else {
+                return Err(RunomeError::DictValidationError {
+                    reason: format!("Entry {} is missing", i),
+                });
+            };
             if entry.left_id > max_id {
                 return Err(RunomeError::DictValidationError {
                     reason: format!(
@@ -130,20 +475,86 @@ impl DictionaryResource {
             }
         }
 
+        // Validate sentence boundary context ids
+        for (name, id) in [
+            ("BOS", self.metadata.bos_context_id),
+            ("EOS", self.metadata.eos_context_id),
+        ] {
+            if id > max_id {
+                return Err(RunomeError::DictValidationError {
+                    reason: format!(
+                        "{} context id {} exceeds connection matrix bounds (max: {})",
+                        name, id, max_id
+                    ),
+                });
+            }
+        }
+
         Ok(())
     }
 
     /// Get all dictionary entries
-    pub fn get_entries(&self) -> &[DictEntry] {
-        &self.entries
+    ///
+    /// With lazy loading, the first call decodes every entry.
+    ///
+    /// # Returns
+    /// * `Ok(&[DictEntry])` - All entries, in morpheme ID order
+    /// * `Err(RunomeError)` - Error if lazily loaded entries cannot be decoded
+    pub fn get_entries(&self) -> Result<&[DictEntry], RunomeError> {
+        self.entries.all()
+    }
+
+    /// Get the dictionary entry with the given morpheme ID
+    ///
+    /// With lazy loading, the entry is decoded on first access.
+    ///
+    /// # Arguments
+    /// * `morph_id` - Morpheme ID, the index of the entry in `entries.bin`
+    ///
+    /// # Returns
+    /// * `Ok(Some(&DictEntry))` - The entry
+    /// * `Ok(None)` - If the ID is out of range
+    /// * `Err(RunomeError)` - Error if the lazily loaded entry cannot be decoded
+    #[inline]
+    pub fn get_entry(&self, morph_id: usize) -> Result<Option<&DictEntry>, RunomeError> {
+        self.entries.get(morph_id)
+    }
+
+    /// Get the number of dictionary entries
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Get the approximate memory used by the resource
+    ///
+    /// The connection matrix is counted in full even though user dictionaries
+    /// share it.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let (entries, entries_mapped) = self.entries.memory_usage();
+        let (fst, fst_mapped) = self.fst_bytes.memory_usage();
+        MemoryStats {
+            entries,
+            fst,
+            connections: self.connections.heap_size(),
+            morpheme_index: morpheme_index_heap_size(&self.morpheme_index),
+            char_defs: self.char_defs.heap_size()
+                + self.char_index.heap_size()
+                + unknowns_heap_size(&self.unknowns),
+            mapped: entries_mapped + fst_mapped,
+            ..MemoryStats::default()
+        }
+    }
+
+    /// Check if entries are decoded on demand rather than at load time
+    pub fn is_lazy(&self) -> bool {
+        self.entries.is_lazy()
     }
 
     /// Get connection cost between left and right part-of-speech IDs
+    #[inline]
     pub fn get_connection_cost(&self, left_id: u16, right_id: u16) -> Result<i16, RunomeError> {
         self.connections
-            .get(left_id as usize)
-            .and_then(|row| row.get(right_id as usize))
-            .copied()
+            .get(left_id, right_id)
             .ok_or(RunomeError::InvalidConnectionId { left_id, right_id })
     }
 
@@ -153,42 +564,106 @@ impl DictionaryResource {
     /// This is needed for UserDictionary initialization.
     ///
     /// # Returns
-    /// * `Arc<Vec<Vec<i16>>>` - Shared reference to connection matrix
-    pub fn get_connection_matrix(&self) -> Arc<Vec<Vec<i16>>> {
-        Arc::clone(&self.connections_arc)
+    /// * `Arc<ConnectionMatrix>` - Shared reference to connection matrix
+    pub fn get_connection_matrix(&self) -> Arc<ConnectionMatrix> {
+        Arc::clone(&self.connections)
     }
 
     /// Get character category for a given character (returns first match)
     pub fn get_char_category(&self, ch: char) -> Option<&CharCategory> {
-        for range in &self.char_defs.code_ranges {
-            if ch >= range.from && ch <= range.to {
-                return self.char_defs.categories.get(&range.category);
-            }
-        }
-        None
+        self.matching_ranges(ch)
+            .next()
+            .and_then(|range| self.char_defs.categories.get(&range.category))
     }
 
     /// Get all character categories for a given character
     /// Returns a HashMap where keys are category names and values are compatible categories
     /// This matches the Python SystemDictionary.get_char_categories() behavior
     pub fn get_char_categories(&self, ch: char) -> std::collections::HashMap<String, Vec<String>> {
-        let mut result = std::collections::HashMap::new();
+        self.get_char_categories_ordered(ch).into_iter().collect()
+    }
+
+    /// Get all character categories for a given character in char.def order
+    ///
+    /// Same content as `get_char_categories`, but as a list ordered by the first
+    /// matching code point range, so callers iterating the categories behave the
+    /// same in every run. A category matched by several ranges keeps its first
+    /// position and the compatible categories of the last range, like
+    /// `get_char_categories`.
+    pub fn get_char_categories_ordered(&self, ch: char) -> Vec<(String, Vec<String>)> {
+        let mut result: Vec<(String, Vec<String>)> = Vec::new();
 
         // Find all matching code point ranges for this character
-        for range in &self.char_defs.code_ranges {
-            if ch >= range.from && ch <= range.to {
-                result.insert(range.category.clone(), range.compat_categories.clone());
+        for range in self.matching_ranges(ch) {
+            match result.iter_mut().find(|(cate, _)| *cate == range.category) {
+                Some((_, compat)) => compat.clone_from(&range.compat_categories),
+                None => result.push((range.category.clone(), range.compat_categories.clone())),
             }
         }
 
         // Default category if no matches found
         if result.is_empty() {
-            result.insert("DEFAULT".to_string(), Vec::new());
+            result.push(("DEFAULT".to_string(), Vec::new()));
         }
 
         result
     }
 
+    /// Get the category names of a character, each followed by its compatible categories
+    ///
+    /// Flattened form of `get_char_categories_ordered` with interned names, so
+    /// classifying a character doesn't allocate a string per category.
+    pub fn get_char_category_names(&self, ch: char) -> Vec<Cow<'static, str>> {
+        let mut matched: Vec<(&str, &[String])> = Vec::new();
+        for range in self.matching_ranges(ch) {
+            match matched.iter_mut().find(|(cate, _)| *cate == range.category) {
+                Some((_, compat)) => *compat = &range.compat_categories,
+                None => matched.push((&range.category, &range.compat_categories)),
+            }
+        }
+
+        if matched.is_empty() {
+            return vec![Cow::Borrowed(intern::CHAR_CATEGORY_DEFAULT)];
+        }
+
+        let mut names = Vec::with_capacity(matched.iter().map(|(_, c)| c.len() + 1).sum());
+        for (category, compat_categories) in matched {
+            names.push(intern::intern_pooled(category));
+            names.extend(compat_categories.iter().map(|c| intern::intern_pooled(c)));
+        }
+        names
+    }
+
+    /// Check whether a character can continue an unknown word of a category
+    ///
+    /// True if the category is one of the character's categories or one of
+    /// their compatible categories, as in Janome's grouping check
+    /// `cate in _cates or any(cate in _compat_cates for _compat_cates in _cates.values())`.
+    /// Like `get_char_categories`, only the last range of a category counts
+    /// for its compatible categories.
+    pub fn is_char_compatible(&self, ch: char, category: &str) -> bool {
+        let matched: Vec<&CodePointRange> = self.matching_ranges(ch).collect();
+        if matched.is_empty() {
+            return category == intern::CHAR_CATEGORY_DEFAULT;
+        }
+
+        matched.iter().enumerate().any(|(i, range)| {
+            range.category == category
+                || (range.compat_categories.iter().any(|c| c == category)
+                    && !matched[i + 1..]
+                        .iter()
+                        .any(|later| later.category == range.category))
+        })
+    }
+
+    /// Get the code point ranges containing a character, in char.def order
+    fn matching_ranges(&self, ch: char) -> impl Iterator<Item = &CodePointRange> {
+        self.char_index
+            .lookup(ch)
+            .iter()
+            .map(|&i| &self.char_defs.code_ranges[i as usize])
+    }
+
     /// Get unknown entries for a specific category
     pub fn get_unknown_entries(&self, category: &str) -> Option<&[UnknownEntry]> {
         self.unknowns.get(category).map(|v| v.as_slice())
@@ -199,6 +674,16 @@ impl DictionaryResource {
         &self.fst_bytes
     }
 
+    /// Get the FST storage, e.g. to share a memory map with a Matcher
+    pub fn get_fst_data(&self) -> &FstData {
+        &self.fst_bytes
+    }
+
+    /// Check if the FST is memory-mapped rather than held on the heap
+    pub fn is_fst_mapped(&self) -> bool {
+        matches!(self.fst_bytes, FstData::Mapped(_))
+    }
+
     /// Get morpheme index for mapping FST index IDs to vectors of morpheme IDs
     pub fn get_morpheme_index(&self) -> &[Vec<u32>] {
         &self.morpheme_index
@@ -232,10 +717,20 @@ impl DictionaryResource {
     }
 }
 
+/// Deserialize every entry of `entries.bin` at load time
+fn load_all_entries(sysdic_dir: &Path) -> Result<EntryStore, RunomeError> {
+    loader::load_entries(sysdic_dir).map(EntryStore::Loaded)
+}
+
+/// Memory-map `dic.fst`
+fn map_fst(sysdic_dir: &Path) -> Result<FstData, RunomeError> {
+    Ok(FstData::Mapped(Arc::new(loader::map_fst_file(sysdic_dir)?)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     fn get_test_sysdic_path() -> PathBuf {
         // Assuming tests are run from the project root
@@ -262,7 +757,7 @@ mod tests {
 
         // Verify all components were loaded and are non-empty
         assert!(
-            !dict.entries.is_empty(),
+            !dict.get_entries().unwrap().is_empty(),
             "Dictionary entries should not be empty"
         );
         assert!(
@@ -281,11 +776,11 @@ mod tests {
 
         // Verify reasonable data sizes
         assert!(
-            dict.entries.len() > 1000,
+            dict.get_entries().unwrap().len() > 1000,
             "Should have substantial number of entries"
         );
         assert!(
-            dict.connections.len() > 100,
+            dict.connections.rows() > 100,
             "Should have substantial connection matrix"
         );
         assert!(
@@ -302,6 +797,157 @@ mod tests {
         );
     }
 
+    fn code_range(from: char, to: char, category: &str) -> CodePointRange {
+        CodePointRange {
+            from,
+            to,
+            category: category.to_string(),
+            compat_categories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_code_point_index() {
+        let index = CodePointIndex::new(&[
+            code_range('\u{4E00}', '\u{9FFF}', "KANJI"),
+            code_range('a', 'z', "ALPHA"),
+            code_range('\u{4E00}', '\u{4E00}', "KANJINUMERIC"),
+            code_range('\u{10FFFF}', '\u{10FFFF}', "LAST"),
+        ]);
+        assert_eq!(index.lookup('\0'), &[] as &[u32]);
+        assert_eq!(index.lookup('a'), &[1]);
+        assert_eq!(index.lookup('z'), &[1]);
+        assert_eq!(index.lookup('{'), &[] as &[u32]);
+        // Overlapping ranges keep char.def order
+        assert_eq!(index.lookup('\u{4E00}'), &[0, 2]);
+        assert_eq!(index.lookup('\u{4E01}'), &[0]);
+        assert_eq!(index.lookup('\u{9FFF}'), &[0]);
+        assert_eq!(index.lookup('\u{A000}'), &[] as &[u32]);
+        assert_eq!(index.lookup('\u{10FFFF}'), &[3]);
+
+        let empty = CodePointIndex::new(&[]);
+        assert_eq!(empty.lookup('a'), &[] as &[u32]);
+    }
+
+    #[test]
+    fn test_code_point_index_matches_linear_scan() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let dict = DictionaryResource::load(&sysdic_path).unwrap();
+        for ch in ('\0'..='\u{FFFF}').chain('\u{1F000}'..='\u{1FFFF}') {
+            let expected: Vec<u32> = dict
+                .char_defs
+                .code_ranges
+                .iter()
+                .enumerate()
+                .filter(|(_, range)| ch >= range.from && ch <= range.to)
+                .map(|(i, _)| i as u32)
+                .collect();
+            assert_eq!(dict.char_index.lookup(ch), expected, "{:?}", ch);
+        }
+    }
+
+    #[test]
+    fn test_get_char_categories_ordered() {
+        let sysdic_path = PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let dict = DictionaryResource::load(&sysdic_path).unwrap();
+        for ch in ['一', 'ア', 'a', '〇', '☆'] {
+            let ordered = dict.get_char_categories_ordered(ch);
+            assert_eq!(dict.get_char_categories_ordered(ch), ordered);
+
+            // Same content as the HashMap version, in char.def order
+            let as_map: std::collections::HashMap<_, _> = ordered.iter().cloned().collect();
+            assert_eq!(as_map, dict.get_char_categories(ch));
+            let positions: Vec<usize> = ordered
+                .iter()
+                .map(|(cate, _)| {
+                    dict.char_defs
+                        .code_ranges
+                        .iter()
+                        .position(|range| {
+                            range.category == *cate && range.from <= ch && ch <= range.to
+                        })
+                        .unwrap()
+                })
+                .collect();
+            assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        }
+
+        let kanji_numeric = dict.get_char_categories_ordered('一');
+        assert!(
+            kanji_numeric.len() > 1,
+            "Expected multiple categories for 一"
+        );
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    #[test]
+    fn test_load_embedded() {
+        let dict = DictionaryResource::load_embedded().unwrap();
+        assert!(dict.validate().is_ok());
+        assert!(!dict.is_degraded());
+        assert!(dict.get_entries().unwrap().len() > 1000);
+        assert!(dict.max_surface_length() > 0);
+    }
+
+    #[test]
+    fn test_max_surface_length() {
+        let sysdic_path = get_test_sysdic_path();
+
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let dict = DictionaryResource::load(&sysdic_path).unwrap();
+        let expected = dict
+            .get_entries()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.surface.chars().count())
+            .max()
+            .unwrap();
+        assert_eq!(dict.max_surface_length(), expected);
+    }
+
+    #[test]
+    fn test_load_metadata_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata_path = dir.path().join("metadata.bin");
+
+        // Metadata written before the boundary context ids were recorded
+        std::fs::write(&metadata_path, bincode::serialize(&42usize).unwrap()).unwrap();
+        let metadata = loader::load_metadata(dir.path()).unwrap().unwrap();
+        assert_eq!(metadata.max_surface_length, 42);
+        assert_eq!((metadata.bos_context_id, metadata.eos_context_id), (0, 0));
+
+        let expected = DictMetadata {
+            max_surface_length: 42,
+            bos_context_id: 3,
+            eos_context_id: 7,
+        };
+        std::fs::write(&metadata_path, bincode::serialize(&expected).unwrap()).unwrap();
+        assert_eq!(loader::load_metadata(dir.path()).unwrap(), Some(expected));
+    }
+
     #[test]
     fn test_load_and_validate_success() {
         let sysdic_path = get_test_sysdic_path();
@@ -356,7 +1002,7 @@ mod tests {
         }
 
         let dict = DictionaryResource::load(&sysdic_path).expect("Failed to load dictionary");
-        let entries = dict.get_entries();
+        let entries = dict.get_entries().unwrap();
 
         assert!(!entries.is_empty(), "Should have dictionary entries");
 
@@ -399,7 +1045,7 @@ mod tests {
         );
 
         // Test boundary cases
-        let max_id = (dict.connections.len() - 1) as u16;
+        let max_id = (dict.connections.rows() - 1) as u16;
         let boundary_cost = dict.get_connection_cost(max_id, max_id);
         assert!(
             boundary_cost.is_ok(),
@@ -593,6 +1239,150 @@ mod tests {
         );
     }
 
+    /// Copy the required dictionary files (everything except unknown-word data)
+    fn create_degraded_sysdic(sysdic_path: &Path) -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        for filename in [
+            "entries.bin",
+            "connections.bin",
+            "dic.fst",
+            "morpheme_index.bin",
+        ] {
+            std::fs::copy(sysdic_path.join(filename), temp_dir.path().join(filename))
+                .expect("Failed to copy dictionary file");
+        }
+        temp_dir
+    }
+
+    #[test]
+    fn test_load_degraded_missing_unknown_data() {
+        let sysdic_path = get_test_sysdic_path();
+
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let temp_dir = create_degraded_sysdic(&sysdic_path);
+
+        // Strict loading still fails on the missing files
+        match DictionaryResource::load(temp_dir.path()) {
+            Err(RunomeError::DictFileMissing { filename }) => {
+                assert_eq!(filename, "char_defs.bin");
+            }
+            other => panic!("Expected DictFileMissing error, got: {:?}", other.err()),
+        }
+
+        let dict = DictionaryResource::load_degraded(temp_dir.path())
+            .expect("Degraded loading should succeed");
+        assert!(dict.is_degraded());
+        assert_eq!(
+            dict.missing_components(),
+            &["char_defs.bin".to_string(), "unknowns.bin".to_string()]
+        );
+        assert!(dict.validate().is_ok());
+
+        // Every character falls back to DEFAULT with single-character unknowns
+        let categories = dict.get_char_categories('あ');
+        assert_eq!(categories.len(), 1);
+        assert!(categories.contains_key("DEFAULT"));
+        assert!(!dict.unknown_grouping("DEFAULT"));
+        assert_eq!(dict.unknown_length("DEFAULT"), 1);
+        let unknowns = dict.get_unknown_entries("DEFAULT").unwrap();
+        assert_eq!(unknowns.len(), 1);
+        assert_eq!(unknowns[0].part_of_speech, "記号,一般,*,*");
+    }
+
+    #[test]
+    fn test_load_degraded_missing_char_defs_only() {
+        let sysdic_path = get_test_sysdic_path();
+
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let temp_dir = create_degraded_sysdic(&sysdic_path);
+        std::fs::copy(
+            sysdic_path.join("unknowns.bin"),
+            temp_dir.path().join("unknowns.bin"),
+        )
+        .unwrap();
+
+        let dict = DictionaryResource::load_degraded(temp_dir.path()).unwrap();
+        assert_eq!(dict.missing_components(), &["char_defs.bin".to_string()]);
+        assert!(dict.validate().is_ok());
+
+        // Characters fall back to DEFAULT, but the unknown entries are the dictionary's
+        let categories = dict.get_char_categories('あ');
+        assert_eq!(categories.len(), 1);
+        assert!(categories.contains_key("DEFAULT"));
+        let complete = DictionaryResource::load(&sysdic_path).unwrap();
+        assert_eq!(
+            format!("{:?}", dict.get_unknown_entries("DEFAULT")),
+            format!("{:?}", complete.get_unknown_entries("DEFAULT"))
+        );
+        assert!(dict.get_unknown_entries("KANJI").is_some());
+    }
+
+    #[test]
+    fn test_load_degraded_missing_unknowns_only() {
+        let sysdic_path = get_test_sysdic_path();
+
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let temp_dir = create_degraded_sysdic(&sysdic_path);
+        std::fs::copy(
+            sysdic_path.join("char_defs.bin"),
+            temp_dir.path().join("char_defs.bin"),
+        )
+        .unwrap();
+
+        let dict = DictionaryResource::load_degraded(temp_dir.path()).unwrap();
+        assert_eq!(dict.missing_components(), &["unknowns.bin".to_string()]);
+        assert!(dict.validate().is_ok());
+
+        // Character categories are the dictionary's, each with the fallback entry
+        let categories = dict.get_char_categories('漢');
+        assert!(categories.contains_key("KANJI"));
+        assert!(dict.char_defs.code_ranges.len() > 10);
+        for category in dict.char_defs.categories.keys() {
+            let unknowns = dict.get_unknown_entries(category).unwrap();
+            assert_eq!(unknowns.len(), 1);
+            assert_eq!(unknowns[0].part_of_speech, "記号,一般,*,*");
+        }
+    }
+
+    #[test]
+    fn test_load_degraded_complete_directory() {
+        let sysdic_path = get_test_sysdic_path();
+
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let dict = DictionaryResource::load_degraded(&sysdic_path).unwrap();
+        assert!(!dict.is_degraded());
+        assert!(dict.missing_components().is_empty());
+        assert!(dict.char_defs.code_ranges.len() > 10);
+    }
+
     #[test]
     fn test_load_missing_directory() {
         let nonexistent_dir = PathBuf::from("/definitely/nonexistent/directory");
@@ -627,19 +1417,16 @@ mod tests {
         let dict = DictionaryResource::load(&sysdic_path).expect("Failed to load dictionary");
 
         // Verify connection matrix is square
-        let rows = dict.connections.len();
-        for (i, row) in dict.connections.iter().enumerate() {
-            assert_eq!(
-                row.len(),
-                dict.connections[0].len(),
-                "Connection matrix row {} has inconsistent length",
-                i
-            );
-        }
+        let rows = dict.connections.rows();
+        assert_eq!(
+            dict.connections.cols(),
+            rows,
+            "Connection matrix is not square"
+        );
 
         // Verify all entries have valid connection IDs
         let max_id = (rows - 1) as u16;
-        for (i, entry) in dict.entries.iter().enumerate() {
+        for (i, entry) in dict.get_entries().unwrap().iter().enumerate() {
             assert!(
                 entry.left_id <= max_id,
                 "Entry {} has left_id {} exceeding matrix bounds (max: {})",
@@ -0,0 +1,418 @@
+use fst::{IntoStreamer, Map, Streamer};
+
+use crate::error::RunomeError;
+
+/// Magic bytes at the start of a serialized double-array trie
+const MAGIC: &[u8; 4] = b"RDA1";
+
+/// Check value of slots not used by any node
+const UNUSED: u32 = u32::MAX;
+
+/// Double-array trie mapping byte strings to u64 values
+///
+/// Nodes live in two parallel arrays: the child of node `s` for byte `b` is
+/// slot `base[s] + b + 1`, valid only if `check` of that slot is `s`. A key
+/// ends at node `s` if slot `base[s]` (label 0) has `s` as its check, in which
+/// case the `base` of that terminal slot indexes `values`. A transition is a
+/// pair of array reads, which makes common prefix search cheaper than on an FST.
+#[derive(Debug, Clone)]
+pub struct DoubleArray {
+    base: Vec<u32>,
+    check: Vec<u32>,
+    values: Vec<u64>,
+}
+
+impl DoubleArray {
+    /// Build a double-array trie from keys and their values
+    ///
+    /// # Arguments
+    /// * `entries` - Non-empty keys in strictly increasing byte order, with their values
+    ///
+    /// # Returns
+    /// * `Ok(DoubleArray)` - Successfully built trie
+    /// * `Err(RunomeError)` - Error if keys are empty, unsorted or duplicated
+    pub fn build<K: AsRef<[u8]>>(entries: &[(K, u64)]) -> Result<Self, RunomeError> {
+        for (i, (key, _)) in entries.iter().enumerate() {
+            if key.as_ref().is_empty() {
+                return Err(RunomeError::InvalidArgument {
+                    reason: "Double-array trie keys must not be empty".to_string(),
+                });
+            }
+            if i > 0 && entries[i - 1].0.as_ref() >= key.as_ref() {
+                return Err(RunomeError::InvalidArgument {
+                    reason: "Double-array trie keys must be sorted and unique".to_string(),
+                });
+            }
+        }
+
+        let mut builder = Builder {
+            base: vec![0],
+            check: vec![UNUSED],
+            values: Vec::with_capacity(entries.len()),
+            next_check_pos: 1,
+        };
+        if !entries.is_empty() {
+            let keys: Vec<(&[u8], u64)> = entries
+                .iter()
+                .map(|(key, value)| (key.as_ref(), *value))
+                .collect();
+            builder.insert(&keys, 0, 0);
+        }
+
+        let mut da = Self {
+            base: builder.base,
+            check: builder.check,
+            values: builder.values,
+        };
+        da.shrink();
+        Ok(da)
+    }
+
+    /// Build a double-array trie holding the same keys and values as an FST
+    ///
+    /// # Arguments
+    /// * `fst` - FST map to convert
+    ///
+    /// # Returns
+    /// * `Ok(DoubleArray)` - Successfully built trie
+    /// * `Err(RunomeError)` - Error if the FST contains an empty key
+    pub fn from_fst<D: AsRef<[u8]>>(fst: &Map<D>) -> Result<Self, RunomeError> {
+        let mut entries = Vec::with_capacity(fst.len());
+        let mut stream = fst.into_stream();
+        while let Some((key, value)) = stream.next() {
+            // The matcher never reports empty matches, so the empty key can be dropped
+            if !key.is_empty() {
+                entries.push((key.to_vec(), value));
+            }
+        }
+        Self::build(&entries)
+    }
+
+    /// Get the number of keys in the trie
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check whether the trie has no keys
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get the value of a key
+    ///
+    /// # Arguments
+    /// * `key` - Key to look up
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Value of the key, or None if it is not in the trie
+    pub fn get(&self, key: &[u8]) -> Option<u64> {
+        let mut node = 0;
+        for &byte in key {
+            node = self.child(node, byte as usize + 1)?;
+        }
+        self.value(node)
+    }
+
+    /// Call `found` with the byte length and value of every key that is a
+    /// prefix of `word`, shortest first
+    ///
+    /// The walk stops as soon as no key continues with the next byte.
+    #[inline]
+    pub fn common_prefix_search(&self, word: &[u8], mut found: impl FnMut(usize, u64)) {
+        let mut node = 0;
+        for (i, &byte) in word.iter().enumerate() {
+            let Some(next) = self.child(node, byte as usize + 1) else {
+                break;
+            };
+            node = next;
+            if let Some(value) = self.value(node) {
+                found(i + 1, value);
+            }
+        }
+    }
+
+    /// Get the approximate heap memory used by the trie, in bytes
+    pub fn heap_size(&self) -> usize {
+        (self.base.capacity() + self.check.capacity()) * std::mem::size_of::<u32>()
+            + self.values.capacity() * std::mem::size_of::<u64>()
+    }
+
+    /// Serialize the trie
+    ///
+    /// # Returns
+    /// * `Vec<u8>` - Little-endian encoding readable by `from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + self.heap_size());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&(self.base.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.values.len() as u32).to_le_bytes());
+        for &base in &self.base {
+            bytes.extend_from_slice(&base.to_le_bytes());
+        }
+        for &check in &self.check {
+            bytes.extend_from_slice(&check.to_le_bytes());
+        }
+        for &value in &self.values {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserialize a trie written by `to_bytes`
+    ///
+    /// # Arguments
+    /// * `bytes` - Serialized trie
+    ///
+    /// # Returns
+    /// * `Ok(DoubleArray)` - Successfully read trie
+    /// * `Err(RunomeError)` - Error if the data is truncated or inconsistent
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, RunomeError> {
+        let invalid = |reason: &str| RunomeError::DictValidationError {
+            reason: format!("Invalid double-array trie: {}", reason),
+        };
+
+        let (header, body) = bytes
+            .split_at_checked(12)
+            .ok_or_else(|| invalid("truncated header"))?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("bad magic"));
+        }
+        let slots = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let values = u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize;
+        if slots == 0 || body.len() != slots * 8 + values * 8 {
+            return Err(invalid("unexpected length"));
+        }
+
+        let (base, rest) = body.split_at(slots * 4);
+        let (check, value_bytes) = rest.split_at(slots * 4);
+        let read_u32s = |data: &[u8]| -> Vec<u32> {
+            data.chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect()
+        };
+        let da = Self {
+            base: read_u32s(base),
+            check: read_u32s(check),
+            values: value_bytes
+                .chunks_exact(8)
+                .map(|chunk| {
+                    let mut buf = [0u8; 8];
+                    buf.copy_from_slice(chunk);
+                    u64::from_le_bytes(buf)
+                })
+                .collect(),
+        };
+
+        // Terminal slots must point into the values so lookups cannot go out of bounds
+        let dangling = (0..slots).any(|slot| {
+            let parent = da.check[slot];
+            parent != UNUSED
+                && (parent as usize >= slots
+                    || (da.base[parent as usize] as usize == slot
+                        && da.base[slot] as usize >= values))
+        });
+        if dangling {
+            return Err(invalid("node out of range"));
+        }
+        Ok(da)
+    }
+
+    /// Follow the transition with the given label, 0 being the end of a key
+    #[inline]
+    fn child(&self, node: usize, label: usize) -> Option<usize> {
+        let slot = self.base[node] as usize + label;
+        (self.check.get(slot) == Some(&(node as u32))).then_some(slot)
+    }
+
+    /// Get the value of the key ending at a node
+    #[inline]
+    fn value(&self, node: usize) -> Option<u64> {
+        let slot = self.child(node, 0)?;
+        self.values.get(self.base[slot] as usize).copied()
+    }
+
+    /// Drop unused slots at the end of the arrays
+    fn shrink(&mut self) {
+        let used = self
+            .check
+            .iter()
+            .rposition(|&check| check != UNUSED)
+            .map_or(1, |slot| slot + 1);
+        self.base.truncate(used);
+        self.check.truncate(used);
+        self.base.shrink_to_fit();
+        self.check.shrink_to_fit();
+        self.values.shrink_to_fit();
+    }
+}
+
+/// Working state of `DoubleArray::build`
+struct Builder {
+    base: Vec<u32>,
+    check: Vec<u32>,
+    values: Vec<u64>,
+    /// Slots before this position are (nearly) all used, so placement starts here
+    next_check_pos: usize,
+}
+
+impl Builder {
+    /// Place the children of `node`, the trie node of the common prefix of
+    /// `keys` of length `depth`, then the subtrees below them
+    fn insert(&mut self, keys: &[(&[u8], u64)], depth: usize, node: usize) {
+        // Keys are sorted, so the labels come out in increasing order with
+        // the key ending here (label 0) first
+        let mut children: Vec<(usize, std::ops::Range<usize>)> = Vec::new();
+        for (i, (key, _)) in keys.iter().enumerate() {
+            let label = key.get(depth).map_or(0, |&byte| byte as usize + 1);
+            match children.last_mut() {
+                Some((last, range)) if *last == label => range.end = i + 1,
+                _ => children.push((label, i..i + 1)),
+            }
+        }
+
+        let base = self.find_base(&children);
+        self.base[node] = base as u32;
+        for (label, _) in &children {
+            self.check[base + label] = node as u32;
+        }
+
+        for (label, range) in children {
+            let slot = base + label;
+            if label == 0 {
+                self.base[slot] = self.values.len() as u32;
+                self.values.push(keys[range.start].1);
+            } else {
+                self.insert(&keys[range], depth + 1, slot);
+            }
+        }
+    }
+
+    /// Find the lowest base at which all child labels land on free slots
+    fn find_base(&mut self, children: &[(usize, std::ops::Range<usize>)]) -> usize {
+        let first = children[0].0;
+        let last = children[children.len() - 1].0;
+        let mut pos = self.next_check_pos.max(first + 1) - 1;
+        let mut used = 0;
+        let mut first_free = None;
+
+        let base = loop {
+            pos += 1;
+            self.reserve(pos);
+            if self.check[pos] != UNUSED {
+                used += 1;
+                continue;
+            }
+            if first_free.is_none() {
+                first_free = Some(pos);
+            }
+            let base = pos - first;
+            self.reserve(base + last);
+            if children
+                .iter()
+                .all(|(label, _)| self.check[base + label] == UNUSED)
+            {
+                break base;
+            }
+        };
+
+        // Skip past densely packed regions so later searches start further on
+        let start = first_free.unwrap_or(pos);
+        if start > self.next_check_pos {
+            self.next_check_pos = start;
+        }
+        if used * 20 >= (pos - self.next_check_pos + 1) * 19 {
+            self.next_check_pos = pos;
+        }
+        base
+    }
+
+    /// Grow the arrays so that `slot` is in range
+    fn reserve(&mut self, slot: usize) {
+        if slot >= self.check.len() {
+            let len = (slot + 1).max(self.check.len() * 2);
+            self.base.resize(len, 0);
+            self.check.resize(len, UNUSED);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> DoubleArray {
+        let mut entries = vec![
+            ("東".as_bytes(), 1),
+            ("東京".as_bytes(), 2),
+            ("東京都".as_bytes(), 3),
+            ("京都".as_bytes(), 4),
+            ("a".as_bytes(), 5),
+            ("ab".as_bytes(), 6),
+        ];
+        entries.sort();
+        DoubleArray::build(&entries).unwrap()
+    }
+
+    #[test]
+    fn test_get() {
+        let da = sample();
+        assert_eq!(da.len(), 6);
+        assert_eq!(da.get("東京".as_bytes()), Some(2));
+        assert_eq!(da.get("京都".as_bytes()), Some(4));
+        assert_eq!(da.get(b"ab"), Some(6));
+        assert_eq!(da.get("京".as_bytes()), None);
+        assert_eq!(da.get(b"abc"), None);
+        assert_eq!(da.get(b""), None);
+    }
+
+    #[test]
+    fn test_common_prefix_search() {
+        let da = sample();
+        let mut matches = Vec::new();
+        da.common_prefix_search("東京都庁".as_bytes(), |len, value| {
+            matches.push((len, value))
+        });
+        assert_eq!(matches, vec![(3, 1), (6, 2), (9, 3)]);
+
+        matches.clear();
+        da.common_prefix_search(b"xyz", |len, value| matches.push((len, value)));
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_build_rejects_unsorted_keys() {
+        assert!(DoubleArray::build(&[("b", 1), ("a", 2)]).is_err());
+        assert!(DoubleArray::build(&[("a", 1), ("a", 2)]).is_err());
+        assert!(DoubleArray::build(&[("", 1)]).is_err());
+        assert!(DoubleArray::build::<&str>(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_fst_matches_fst() {
+        let mut keys: Vec<String> = (0..2000)
+            .map(|i| format!("{:x}語{}", i * 7919, i))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        let fst =
+            Map::from_iter(keys.iter().enumerate().map(|(i, k)| (k.as_str(), i as u64))).unwrap();
+        let da = DoubleArray::from_fst(&fst).unwrap();
+
+        assert_eq!(da.len(), keys.len());
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(da.get(key.as_bytes()), Some(i as u64));
+        }
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let da = sample();
+        let restored = DoubleArray::from_bytes(&da.to_bytes()).unwrap();
+        assert_eq!(restored.get("東京都".as_bytes()), Some(3));
+        assert_eq!(restored.len(), da.len());
+
+        let bytes = da.to_bytes();
+        assert!(DoubleArray::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(DoubleArray::from_bytes(b"XXXX\0\0\0\0\0\0\0\0").is_err());
+    }
+}
@@ -0,0 +1,18 @@
+//! System dictionary data compiled into the binary
+//!
+//! Enabled by the `embedded-dict` feature. `build.rs` copies the sysdic
+//! artifacts into `OUT_DIR`, and they are included here with `include_bytes!`
+//! so the crate can run without any dictionary files on the filesystem.
+
+macro_rules! sysdic_bytes {
+    ($filename:literal) => {
+        include_bytes!(concat!(env!("OUT_DIR"), "/sysdic/", $filename))
+    };
+}
+
+pub(crate) static ENTRIES: &[u8] = sysdic_bytes!("entries.bin");
+pub(crate) static CONNECTIONS: &[u8] = sysdic_bytes!("connections.bin");
+pub(crate) static CHAR_DEFS: &[u8] = sysdic_bytes!("char_defs.bin");
+pub(crate) static UNKNOWNS: &[u8] = sysdic_bytes!("unknowns.bin");
+pub(crate) static FST: &[u8] = sysdic_bytes!("dic.fst");
+pub(crate) static MORPHEME_INDEX: &[u8] = sysdic_bytes!("morpheme_index.bin");
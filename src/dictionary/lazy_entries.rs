@@ -0,0 +1,351 @@
+//! On-demand decoding of dictionary entries
+//!
+//! In lazy mode `entries.bin` is memory-mapped instead of deserialized, and
+//! each entry is decoded the first time it is looked up, found through the
+//! offsets recorded in `entries.idx`. Startup then costs a memory map and the
+//! offset table instead of decoding every entry.
+
+use std::fs;
+use std::path::Path;
+
+use memmap2::Mmap;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use super::loader;
+use super::types::{DictEntry, DictMetadata, entries_heap_size};
+use crate::error::{PathContext, RunomeError};
+
+/// Dictionary entries, either all decoded at load time or decoded on demand
+pub(crate) enum EntryStore {
+    Loaded(Vec<DictEntry>),
+    Lazy(LazyEntries),
+}
+
+impl EntryStore {
+    /// Number of entries
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            EntryStore::Loaded(entries) => entries.len(),
+            EntryStore::Lazy(lazy) => lazy.slots.len(),
+        }
+    }
+
+    /// Get the entry with the given morpheme ID, decoding it if needed
+    ///
+    /// Returns `Ok(None)` if the ID is out of range, and an error if the entry
+    /// cannot be decoded.
+    pub(crate) fn get(&self, morph_id: usize) -> Result<Option<&DictEntry>, RunomeError> {
+        match self {
+            EntryStore::Loaded(entries) => Ok(entries.get(morph_id)),
+            EntryStore::Lazy(lazy) => lazy.get(morph_id),
+        }
+    }
+
+    /// Get all entries, decoding the whole of `entries.bin` in lazy mode
+    pub(crate) fn all(&self) -> Result<&[DictEntry], RunomeError> {
+        match self {
+            EntryStore::Loaded(entries) => Ok(entries),
+            EntryStore::Lazy(lazy) => lazy.all(),
+        }
+    }
+
+    pub(crate) fn is_lazy(&self) -> bool {
+        matches!(self, EntryStore::Lazy(_))
+    }
+
+    /// Estimated heap memory and memory-mapped bytes of the entries
+    ///
+    /// In lazy mode only the entries decoded so far count as heap memory.
+    pub(crate) fn memory_usage(&self) -> (usize, usize) {
+        match self {
+            EntryStore::Loaded(entries) => (entries_heap_size(entries), 0),
+            EntryStore::Lazy(lazy) => {
+                let decoded: usize = lazy
+                    .slots
+                    .iter()
+                    .filter_map(|slot| slot.get())
+                    .map(|entry| std::mem::size_of::<DictEntry>() + entry.heap_size())
+                    .sum();
+                let heap = lazy.offsets.capacity() * std::mem::size_of::<u64>()
+                    + std::mem::size_of_val(&*lazy.slots)
+                    + decoded
+                    + lazy.all.get().map_or(0, entries_heap_size);
+                (heap, lazy.data.len())
+            }
+        }
+    }
+
+    /// Derive the metadata of dictionaries built without `metadata.bin`
+    ///
+    /// In lazy mode the surfaces are read in place, without decoding entries.
+    pub(crate) fn derive_metadata(&self) -> Result<DictMetadata, RunomeError> {
+        match self {
+            EntryStore::Loaded(entries) => Ok(DictMetadata::from_entries(entries)),
+            EntryStore::Lazy(lazy) => {
+                let mut max_surface_length = 0;
+                for_each_layout(lazy.payload(), lazy.slots.len() as u64, |_, layout| {
+                    max_surface_length = max_surface_length.max(layout.surface.chars().count());
+                })?;
+                Ok(DictMetadata {
+                    max_surface_length,
+                    bos_context_id: 0,
+                    eos_context_id: 0,
+                })
+            }
+        }
+    }
+}
+
+/// Memory-mapped `entries.bin` with entries decoded on first access
+pub(crate) struct LazyEntries {
+    data: Mmap,
+    /// Length of the format header of `data`
+    header_len: usize,
+    /// Byte offset of each entry, after the format header
+    offsets: Vec<u64>,
+    slots: Box<[OnceCell<Box<DictEntry>>]>,
+    /// Every entry, decoded at once for callers needing them all
+    all: OnceCell<Vec<DictEntry>>,
+}
+
+impl LazyEntries {
+    /// Serialized entries, after the format header
+    fn payload(&self) -> &[u8] {
+        &self.data[self.header_len..]
+    }
+
+    fn get(&self, morph_id: usize) -> Result<Option<&DictEntry>, RunomeError> {
+        let Some(slot) = self.slots.get(morph_id) else {
+            return Ok(None);
+        };
+        let entry = slot.get_or_try_init(|| {
+            let offset = self.offsets[morph_id] as usize;
+            bincode::deserialize(&self.payload()[offset..])
+                .map(Box::new)
+                .map_err(|e| RunomeError::DictDeserializationError {
+                    component: format!("entries (morpheme ID {})", morph_id),
+                    source: e,
+                })
+        })?;
+        Ok(Some(entry))
+    }
+
+    fn all(&self) -> Result<&[DictEntry], RunomeError> {
+        let entries = self
+            .all
+            .get_or_try_init(|| loader::deserialize_component(&self.data, "entries"))?;
+        Ok(entries)
+    }
+}
+
+/// Open the entries of a sysdic directory for on-demand decoding
+///
+/// Offsets are read from `entries.idx`. Dictionaries built before it was
+/// written are scanned once to find them, which doesn't allocate but reads the
+/// whole file. A compressed `entries.bin` can't be decoded piecewise, so all
+/// of its entries are loaded instead.
+///
+/// # Arguments
+/// * `sysdic_dir` - Path to directory containing dictionary data
+///
+/// # Returns
+/// * `Ok(EntryStore)` - Lazily decoded entries, or loaded ones if compressed
+/// * `Err(RunomeError)` - Error if the files are missing or inconsistent
+pub(crate) fn open(sysdic_dir: &Path) -> Result<EntryStore, RunomeError> {
+    let path = loader::validate_file_exists(sysdic_dir, "entries.bin")?;
+    let file = fs::File::open(&path).with_path(&path)?;
+    // SAFETY: compiled dictionary files are never written after the build, and
+    // the mapping is only ever read
+    let data = unsafe { Mmap::map(&file) }.with_path(&path)?;
+
+    let payload = loader::strip_header(&data, "entries").with_path(&path)?;
+    let header_len = data.len() - payload.len();
+    if loader::is_compressed(payload) {
+        log::info!("entries.bin is compressed, loading all entries");
+        return loader::deserialize_component(&data, "entries")
+            .map(EntryStore::Loaded)
+            .with_path(&path);
+    }
+
+    let count: u64 = loader::deserialize_component(&data, "entries").with_path(&path)?;
+    let offsets: Vec<u64> = match loader::find_file(sysdic_dir, "entries.idx") {
+        Some(index_path) => {
+            let index = fs::read(&index_path).with_path(&index_path)?;
+            loader::deserialize_component(&index, "entry_offsets").with_path(&index_path)?
+        }
+        None => {
+            log::debug!("No entries.idx in {:?}, scanning entries.bin", sysdic_dir);
+            scan_offsets(payload, count).with_path(&path)?
+        }
+    };
+
+    if offsets.len() as u64 != count || offsets.iter().any(|&offset| offset >= payload.len() as u64)
+    {
+        return Err(RunomeError::DictValidationError {
+            reason: format!(
+                "entries.idx doesn't match entries.bin: {} offsets for {} entries",
+                offsets.len(),
+                count
+            ),
+        });
+    }
+
+    let slots = std::iter::repeat_with(OnceCell::new)
+        .take(offsets.len())
+        .collect();
+    Ok(EntryStore::Lazy(LazyEntries {
+        data,
+        header_len,
+        offsets,
+        slots,
+        all: OnceCell::new(),
+    }))
+}
+
+/// Serialized layout of `DictEntry`, borrowing its strings
+#[derive(Serialize, Deserialize)]
+struct EntryLayout<'a> {
+    surface: &'a str,
+    left_id: u16,
+    right_id: u16,
+    cost: i16,
+    part_of_speech: &'a str,
+    inflection_type: &'a str,
+    inflection_form: &'a str,
+    base_form: &'a str,
+    reading: &'a str,
+    phonetic: &'a str,
+    morph_id: usize,
+}
+
+/// Find the offset of each entry by walking the serialized entries
+fn scan_offsets(data: &[u8], count: u64) -> Result<Vec<u64>, RunomeError> {
+    let mut offsets = Vec::with_capacity(count as usize);
+    for_each_layout(data, count, |offset, _| offsets.push(offset))?;
+    Ok(offsets)
+}
+
+/// Walk the serialized entries, passing the offset and layout of each to `f`
+fn for_each_layout(
+    data: &[u8],
+    count: u64,
+    mut f: impl FnMut(u64, &EntryLayout),
+) -> Result<(), RunomeError> {
+    let to_error = |e| RunomeError::DictDeserializationError {
+        component: "entries".to_string(),
+        source: e,
+    };
+    let mut offset = std::mem::size_of::<u64>() as u64;
+    for _ in 0..count {
+        let layout: EntryLayout = data
+            .get(offset as usize..)
+            .ok_or_else(|| bincode::ErrorKind::SizeLimit.into())
+            .and_then(bincode::deserialize)
+            .map_err(to_error)?;
+        f(offset, &layout);
+        offset += bincode::serialized_size(&layout).map_err(to_error)?;
+    }
+    Ok(())
+}
+
+/// Byte offset of each entry in the serialized form of `entries`, not
+/// counting the format header
+///
+/// Written to `entries.idx` by the dictionary builder.
+pub(crate) fn entry_offsets(entries: &[DictEntry]) -> Result<Vec<u64>, RunomeError> {
+    let mut offsets = Vec::with_capacity(entries.len());
+    let mut offset = std::mem::size_of::<u64>() as u64;
+    for entry in entries {
+        offsets.push(offset);
+        offset +=
+            bincode::serialized_size(entry).map_err(|e| RunomeError::DictDeserializationError {
+                component: "entries".to_string(),
+                source: e,
+            })?;
+    }
+    Ok(offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(surface: &str, morph_id: usize) -> DictEntry {
+        DictEntry {
+            surface: surface.to_string(),
+            left_id: 1,
+            right_id: 2,
+            cost: -300,
+            part_of_speech: "名詞,一般,*,*".to_string(),
+            inflection_type: "*".to_string(),
+            inflection_form: "*".to_string(),
+            base_form: surface.to_string(),
+            reading: "ヨミ".to_string(),
+            phonetic: "ヨミ".to_string(),
+            morph_id,
+        }
+    }
+
+    #[test]
+    fn test_lazy_entries() {
+        let entries: Vec<DictEntry> = ["すもも", "も", "もも", "の", "うち"]
+            .iter()
+            .enumerate()
+            .map(|(id, surface)| entry(surface, id))
+            .collect();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("entries.bin"),
+            bincode::serialize(&entries).unwrap(),
+        )
+        .unwrap();
+
+        // Offsets found by scanning match the ones the builder records
+        let store = open(dir.path()).unwrap();
+        let EntryStore::Lazy(lazy) = &store else {
+            panic!("entries should be lazy");
+        };
+        assert_eq!(lazy.offsets, entry_offsets(&entries).unwrap());
+
+        fs::write(
+            dir.path().join("entries.idx"),
+            bincode::serialize(&entry_offsets(&entries).unwrap()).unwrap(),
+        )
+        .unwrap();
+        let store = open(dir.path()).unwrap();
+        assert!(store.is_lazy());
+        assert_eq!(store.len(), 5);
+        assert_eq!(store.get(2).unwrap(), Some(&entries[2]));
+        assert_eq!(store.get(0).unwrap(), Some(&entries[0]));
+        assert_eq!(store.get(5).unwrap(), None);
+        assert_eq!(store.all().unwrap(), entries.as_slice());
+
+        // An entry that fails to decode is an error, not a missing entry
+        let mut data = bincode::serialize(&entries).unwrap();
+        let offsets = entry_offsets(&entries).unwrap();
+        // First byte of the surface, after its u64 length prefix
+        data[offsets[2] as usize + 8] = 0xFF;
+        fs::write(dir.path().join("entries.bin"), &data).unwrap();
+        let store = open(dir.path()).unwrap();
+        assert!(matches!(
+            store.get(2),
+            Err(RunomeError::DictDeserializationError { .. })
+        ));
+        assert_eq!(store.get(0).unwrap(), Some(&entries[0]));
+        assert!(store.all().is_err());
+        fs::write(
+            dir.path().join("entries.bin"),
+            bincode::serialize(&entries).unwrap(),
+        )
+        .unwrap();
+
+        // An index written for other entries is rejected
+        fs::write(
+            dir.path().join("entries.idx"),
+            bincode::serialize(&entry_offsets(&entries[..3]).unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert!(open(dir.path()).is_err());
+    }
+}
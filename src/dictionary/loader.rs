@@ -1,50 +1,160 @@
-use crate::dictionary::types::{CharDefinitions, ConnectionMatrix, DictEntry, UnknownEntries};
-use crate::error::RunomeError;
+use crate::dictionary::double_array::DoubleArray;
+use crate::dictionary::types::{
+    CharDefinitions, ConnectionMatrix, DictEntry, DictMetadata, UnknownEntries,
+};
+use crate::error::{PathContext, RunomeError};
+use memmap2::Mmap;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Load dictionary entries from sysdic directory
-pub fn load_entries(sysdic_dir: &Path) -> Result<Vec<DictEntry>, RunomeError> {
-    let file_path = validate_file_exists(sysdic_dir, "entries.bin")?;
-    let data = fs::read(&file_path)?;
+/// Magic number at the start of every dictionary component written by the builder
+pub const FORMAT_MAGIC: [u8; 4] = *b"RNMD";
+
+/// Version of the dictionary component format, written after `FORMAT_MAGIC`
+///
+/// Bump it whenever the serialized layout of a component changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Oldest format version the loader still reads
+///
+/// Version 0 stands for files written before the header was added, whose
+/// layout is the same as version 1.
+pub const MIN_FORMAT_VERSION: u32 = 0;
 
+/// Length of the magic number and format version preceding each component
+pub const HEADER_LEN: usize = FORMAT_MAGIC.len() + std::mem::size_of::<u32>();
+
+/// Header written at the start of every dictionary component
+pub fn format_header() -> [u8; HEADER_LEN] {
+    let mut header = [0; HEADER_LEN];
+    header[..FORMAT_MAGIC.len()].copy_from_slice(&FORMAT_MAGIC);
+    header[FORMAT_MAGIC.len()..].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    header
+}
+
+/// Check the format header of a component and return the data after it
+///
+/// Data without a header is read as format version 0. No component written
+/// before the header was added can start with `FORMAT_MAGIC`: each starts
+/// with a count or id that would have to be over a billion.
+///
+/// # Arguments
+/// * `data` - Raw bytes of the component file
+/// * `component` - Component name, for error messages
+///
+/// # Returns
+/// * `Ok(&[u8])` - Data following the header
+/// * `Err(RunomeError)` - `DictFormatVersionMismatch` if the version isn't supported
+pub fn strip_header<'a>(data: &'a [u8], component: &str) -> Result<&'a [u8], RunomeError> {
+    let Some(rest) = data.strip_prefix(&FORMAT_MAGIC) else {
+        return Ok(data);
+    };
+    let Some((version, payload)) = rest.split_first_chunk::<4>() else {
+        return Err(RunomeError::DictValidationError {
+            reason: format!("Truncated format header in {}", component),
+        });
+    };
+    let found = u32::from_le_bytes(*version);
+    if !(MIN_FORMAT_VERSION..=FORMAT_VERSION).contains(&found) {
+        return Err(RunomeError::DictFormatVersionMismatch {
+            component: component.to_string(),
+            expected: FORMAT_VERSION,
+            found,
+        });
+    }
+    Ok(payload)
+}
+
+/// Magic number at the start of every zstd frame
+///
+/// No bincode-encoded component can start with it: every component starts
+/// with a small count or id that would have to be over 4 billion.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Check whether the data of a component is zstd-compressed
+pub fn is_compressed(data: &[u8]) -> bool {
+    data.starts_with(&ZSTD_MAGIC)
+}
+
+/// Decompress a component compressed by the builder, passing other data through
+///
+/// # Arguments
+/// * `data` - Raw bytes of the component file
+/// * `component` - Component name, for error messages
+///
+/// # Returns
+/// * `Ok(Cow<[u8]>)` - Uncompressed data
+/// * `Err(RunomeError)` - Error if the data is corrupt, or the `zstd` feature
+///   is disabled
+pub fn decompress<'a>(data: &'a [u8], component: &str) -> Result<Cow<'a, [u8]>, RunomeError> {
+    if !is_compressed(data) {
+        return Ok(Cow::Borrowed(data));
+    }
+
+    #[cfg(feature = "zstd")]
+    {
+        zstd::stream::decode_all(data).map(Cow::Owned).map_err(|e| {
+            RunomeError::DictValidationError {
+                reason: format!("Failed to decompress {}: {}", component, e),
+            }
+        })
+    }
+    #[cfg(not(feature = "zstd"))]
+    {
+        Err(RunomeError::DictValidationError {
+            reason: format!(
+                "{} is zstd-compressed; enable the `zstd` feature to load it",
+                component
+            ),
+        })
+    }
+}
+
+/// Deserialize a bincode-encoded dictionary component from raw bytes
+///
+/// Used for dictionary data that does not come from a sysdic directory,
+/// such as the data compiled into the binary by the `embedded-dict` feature.
+/// The format header is checked, and components compressed by the builder
+/// are decompressed first.
+pub fn deserialize_component<T: serde::de::DeserializeOwned>(
+    data: &[u8],
+    component: &str,
+) -> Result<T, RunomeError> {
+    let data = decompress(strip_header(data, component)?, component)?;
     bincode::deserialize(&data).map_err(|e| RunomeError::DictDeserializationError {
-        component: "entries".to_string(),
+        component: component.to_string(),
         source: e,
     })
 }
 
+/// Load dictionary entries from sysdic directory
+pub fn load_entries(sysdic_dir: &Path) -> Result<Vec<DictEntry>, RunomeError> {
+    let file_path = validate_file_exists(sysdic_dir, "entries.bin")?;
+    let data = fs::read(&file_path).with_path(&file_path)?;
+    deserialize_component(&data, "entries").with_path(&file_path)
+}
+
 /// Load connection matrix from sysdic directory
 pub fn load_connections(sysdic_dir: &Path) -> Result<ConnectionMatrix, RunomeError> {
     let file_path = validate_file_exists(sysdic_dir, "connections.bin")?;
-    let data = fs::read(&file_path)?;
-
-    bincode::deserialize(&data).map_err(|e| RunomeError::DictDeserializationError {
-        component: "connections".to_string(),
-        source: e,
-    })
+    let data = fs::read(&file_path).with_path(&file_path)?;
+    deserialize_component(&data, "connections").with_path(&file_path)
 }
 
 /// Load character definitions from sysdic directory
 pub fn load_char_definitions(sysdic_dir: &Path) -> Result<CharDefinitions, RunomeError> {
     let file_path = validate_file_exists(sysdic_dir, "char_defs.bin")?;
-    let data = fs::read(&file_path)?;
-
-    bincode::deserialize(&data).map_err(|e| RunomeError::DictDeserializationError {
-        component: "char_defs".to_string(),
-        source: e,
-    })
+    let data = fs::read(&file_path).with_path(&file_path)?;
+    deserialize_component(&data, "char_defs").with_path(&file_path)
 }
 
 /// Load unknown entries from sysdic directory
 pub fn load_unknown_entries(sysdic_dir: &Path) -> Result<UnknownEntries, RunomeError> {
     let file_path = validate_file_exists(sysdic_dir, "unknowns.bin")?;
-    let data = fs::read(&file_path)?;
-
-    bincode::deserialize(&data).map_err(|e| RunomeError::DictDeserializationError {
-        component: "unknowns".to_string(),
-        source: e,
-    })
+    let data = fs::read(&file_path).with_path(&file_path)?;
+    deserialize_component(&data, "unknowns").with_path(&file_path)
 }
 
 /// Load morpheme index from sysdic directory
@@ -53,23 +163,177 @@ pub fn load_unknown_entries(sysdic_dir: &Path) -> Result<UnknownEntries, RunomeE
 /// allowing storage of multiple morpheme IDs per surface form.
 pub fn load_morpheme_index(sysdic_dir: &Path) -> Result<Vec<Vec<u32>>, RunomeError> {
     let file_path = validate_file_exists(sysdic_dir, "morpheme_index.bin")?;
-    let data = fs::read(&file_path)?;
+    let data = fs::read(&file_path).with_path(&file_path)?;
+    deserialize_component(&data, "morpheme_index").with_path(&file_path)
+}
 
-    bincode::deserialize(&data).map_err(|e| RunomeError::DictDeserializationError {
-        component: "morpheme_index".to_string(),
-        source: e,
-    })
+/// Load dictionary metadata from sysdic directory
+///
+/// `metadata.bin` was added after the other components, so dictionaries built
+/// by older versions of the builder do not have it and `Ok(None)` is returned.
+/// Metadata written before the boundary context ids were recorded is read
+/// with both ids set to 0.
+pub fn load_metadata(sysdic_dir: &Path) -> Result<Option<DictMetadata>, RunomeError> {
+    check_directory(sysdic_dir)?;
+
+    let Some(file_path) = find_file(sysdic_dir, "metadata.bin") else {
+        return Ok(None);
+    };
+    let data = fs::read(&file_path).with_path(&file_path)?;
+    load_metadata_bytes(&data).with_path(&file_path)
+}
+
+/// Decode `metadata.bin`, accepting the older formats
+fn load_metadata_bytes(data: &[u8]) -> Result<Option<DictMetadata>, RunomeError> {
+    let data = decompress(strip_header(data, "metadata")?, "metadata")?;
+
+    if data.len() == std::mem::size_of::<u64>() {
+        return bincode::deserialize::<u64>(&data)
+            .map(|max_surface_length| {
+                Some(DictMetadata {
+                    max_surface_length: max_surface_length as usize,
+                    bos_context_id: 0,
+                    eos_context_id: 0,
+                })
+            })
+            .map_err(|e| RunomeError::DictDeserializationError {
+                component: "metadata".to_string(),
+                source: e,
+            });
+    }
+
+    bincode::deserialize(&data)
+        .map(Some)
+        .map_err(|e| RunomeError::DictDeserializationError {
+            component: "metadata".to_string(),
+            source: e,
+        })
 }
 
 /// Load FST bytes from sysdic directory
 pub fn load_fst_bytes(sysdic_dir: &Path) -> Result<Vec<u8>, RunomeError> {
     let file_path = validate_file_exists(sysdic_dir, "dic.fst")?;
-    let data = fs::read(&file_path)?;
-    Ok(data)
+    fs::read(&file_path).with_path(&file_path)
+}
+
+/// Memory-map the FST file from sysdic directory
+///
+/// The mapping is read-only and backed by the page cache, so every process
+/// mapping the same file shares its physical pages instead of holding a copy.
+/// The file must not be modified or truncated while it is mapped.
+pub fn map_fst_file(sysdic_dir: &Path) -> Result<Mmap, RunomeError> {
+    let file_path = validate_file_exists(sysdic_dir, "dic.fst")?;
+    let file = fs::File::open(&file_path).with_path(&file_path)?;
+    // SAFETY: compiled dictionary files are never written after the build, and
+    // the mapping is only ever read through the FST
+    unsafe { Mmap::map(&file) }.with_path(&file_path)
+}
+
+/// Name of the optional double-array trie file written next to `dic.fst`
+pub const DOUBLE_ARRAY_FILE: &str = "dic.da";
+
+/// Load the double-array trie from sysdic directory, if the builder wrote one
+///
+/// # Arguments
+/// * `sysdic_dir` - Path to directory containing dictionary data
+///
+/// # Returns
+/// * `Ok(Some(DoubleArray))` - Successfully loaded trie
+/// * `Ok(None)` - The directory has no double-array trie
+/// * `Err(RunomeError)` - Error if the file cannot be read or is invalid
+pub fn load_double_array(sysdic_dir: &Path) -> Result<Option<DoubleArray>, RunomeError> {
+    let Some(file_path) = find_file(sysdic_dir, DOUBLE_ARRAY_FILE) else {
+        return Ok(None);
+    };
+    let bytes = fs::read(&file_path).with_path(&file_path)?;
+    DoubleArray::from_bytes(&bytes)
+        .map(Some)
+        .with_path(&file_path)
+}
+
+/// Name of the checksum manifest written by the builder
+///
+/// Each line holds the hex SHA-256 digest of a dictionary file, two spaces and
+/// its name, the format of `sha256sum`, so it can also be checked with
+/// `sha256sum -c`.
+pub const CHECKSUM_MANIFEST: &str = "checksums.sha256";
+
+/// Compute the hex SHA-256 digest of a file
+pub fn sha256_file(path: &Path) -> Result<String, RunomeError> {
+    let mut hasher = Sha256::new();
+    let mut file = fs::File::open(path).with_path(path)?;
+    std::io::copy(&mut file, &mut hasher).with_path(path)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Verify dictionary files against the checksum manifest
+///
+/// Files listed in the manifest but absent are skipped; loading reports the
+/// missing files that are required. Dictionaries built before the manifest
+/// was written have nothing to verify against, which is logged.
+///
+/// # Arguments
+/// * `sysdic_dir` - Path to directory containing dictionary data
+///
+/// # Returns
+/// * `Ok(())` - Every present file matches its checksum
+/// * `Err(RunomeError)` - `DictChecksumMismatch` for the first file that doesn't
+pub fn verify_checksums(sysdic_dir: &Path) -> Result<(), RunomeError> {
+    let Some(manifest_path) = find_file(sysdic_dir, CHECKSUM_MANIFEST) else {
+        log::warn!(
+            "No {} in {:?}, skipping checksum verification",
+            CHECKSUM_MANIFEST,
+            sysdic_dir
+        );
+        return Ok(());
+    };
+
+    let manifest = fs::read_to_string(&manifest_path).with_path(&manifest_path)?;
+    for (line_number, line) in manifest.lines().enumerate() {
+        let Some((expected, filename)) = line.split_once("  ") else {
+            return Err(RunomeError::DictValidationError {
+                reason: format!(
+                    "Invalid line {} in {}: {}",
+                    line_number + 1,
+                    CHECKSUM_MANIFEST,
+                    line
+                ),
+            });
+        };
+        let Some(path) = find_file(sysdic_dir, filename) else {
+            continue;
+        };
+        let found = sha256_file(&path)?;
+        if !found.eq_ignore_ascii_case(expected) {
+            return Err(RunomeError::DictChecksumMismatch {
+                filename: filename.to_string(),
+                expected: expected.to_string(),
+                found,
+            });
+        }
+    }
+    log::debug!("Verified dictionary checksums in {:?}", sysdic_dir);
+    Ok(())
 }
 
 /// Validate that sysdic directory exists and is accessible
+///
+/// With `GlobalConfig::verify_checksums` set, the dictionary files are also
+/// verified against their checksums, so this is called once per load.
 pub fn validate_sysdic_directory(path: &Path) -> Result<(), RunomeError> {
+    check_directory(path)?;
+    if crate::config::global_config().verify_checksums {
+        verify_checksums(path)?;
+    }
+    Ok(())
+}
+
+/// Check that a path is an existing directory
+fn check_directory(path: &Path) -> Result<(), RunomeError> {
     if !path.exists() {
         return Err(RunomeError::DictDirectoryNotFound {
             path: path.display().to_string(),
@@ -87,20 +351,52 @@ pub fn validate_sysdic_directory(path: &Path) -> Result<(), RunomeError> {
 
 /// Validate that a required file exists in the sysdic directory
 pub fn validate_file_exists(sysdic_dir: &Path, filename: &str) -> Result<PathBuf, RunomeError> {
-    validate_sysdic_directory(sysdic_dir)?;
+    check_directory(sysdic_dir)?;
 
-    let file_path = sysdic_dir.join(filename);
-    if !file_path.exists() {
-        return Err(RunomeError::DictFileMissing {
-            filename: filename.to_string(),
-        });
+    if let Some(file_path) = find_file(sysdic_dir, filename) {
+        return Ok(file_path);
     }
 
-    if !file_path.is_file() {
+    let file_path = sysdic_dir.join(filename);
+    if file_path.exists() {
         return Err(RunomeError::DictValidationError {
             reason: format!("Path is not a file: {}", file_path.display()),
         });
     }
 
-    Ok(file_path)
+    Err(RunomeError::DictFileMissing {
+        filename: filename.to_string(),
+    })
+}
+
+/// Find a file in a directory, ignoring the ASCII case of its name
+///
+/// The exact name is tried first. Otherwise the directory is scanned, so
+/// dictionaries copied through case-insensitive filesystems (Windows, macOS),
+/// which may have changed the case of file names, load on case-sensitive ones.
+/// Names that are not valid Unicode never match.
+///
+/// # Arguments
+/// * `dir` - Directory to search
+/// * `filename` - File name to look for
+///
+/// # Returns
+/// * `Some(PathBuf)` - Path of the matching regular file
+/// * `None` - If no regular file matches
+pub fn find_file(dir: &Path, filename: &str) -> Option<PathBuf> {
+    let exact = dir.join(filename);
+    if exact.is_file() {
+        return Some(exact);
+    }
+
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.eq_ignore_ascii_case(filename))
+                && path.is_file()
+        })
 }
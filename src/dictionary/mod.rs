@@ -1,14 +1,38 @@
-pub mod dict;
-pub mod dict_resource;
+pub mod bundle;
+pub mod compiled_user_dict;
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+pub(crate) mod dict;
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+pub(crate) mod dict_resource;
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+pub(crate) mod double_array;
+#[cfg(feature = "embedded-dict")]
+mod embedded;
+pub(crate) mod lazy_entries;
+#[cfg(feature = "unstable")]
 pub mod loader;
+#[cfg(not(feature = "unstable"))]
+pub(crate) mod loader;
 pub mod system_dict;
 #[cfg(test)]
 pub mod system_dict_tests;
 pub mod types;
 pub mod user_dict;
 
-pub use dict::{Dictionary, Matcher, RAMDictionary};
-pub use dict_resource::DictionaryResource;
+pub use compiled_user_dict::CompiledUserDictionary;
+pub use dict::{Dictionary, MatcherBackend};
 pub use system_dict::SystemDictionary;
 pub use types::*;
 pub use user_dict::{UserDictFormat, UserDictionary};
+
+// Internal building blocks, public only with the `unstable` feature
+#[cfg(feature = "unstable")]
+pub use dict::{FstData, Matcher, RAMDictionary, pack_single_entry_values};
+#[cfg(not(feature = "unstable"))]
+pub(crate) use dict::{Matcher, RAMDictionary};
+#[cfg(feature = "unstable")]
+pub use dict_resource::DictionaryResource;
+#[cfg(not(feature = "unstable"))]
+pub(crate) use dict_resource::DictionaryResource;
+#[cfg(feature = "unstable")]
+pub use double_array::DoubleArray;
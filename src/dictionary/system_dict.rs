@@ -1,10 +1,13 @@
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 
-use super::{Dictionary, DictionaryResource, RAMDictionary};
-use crate::dictionary::types::{DictEntry, UnknownEntry};
+use super::{Dictionary, DictionaryResource, MatcherBackend, RAMDictionary};
+use crate::dictionary::types::{
+    ConnectionMatrix, DictEntry, DictMetadata, MemoryStats, UnknownEntry,
+};
 use crate::error::RunomeError;
 
 /// SystemDictionary combines known word lookup with character classification
@@ -18,17 +21,26 @@ pub struct SystemDictionary {
 }
 
 /// Singleton instance with thread-safe lazy initialization
-static SYSTEM_DICT_INSTANCE: Lazy<Arc<Mutex<Option<Arc<SystemDictionary>>>>> =
-    Lazy::new(|| Arc::new(Mutex::new(None)));
+///
+/// Reads after initialization take no lock, so tokenizers created in many
+/// threads do not contend on it.
+static SYSTEM_DICT_INSTANCE: OnceCell<Arc<SystemDictionary>> = OnceCell::new();
+
+/// Dictionaries loaded with `instance_at`, keyed by canonical directory path
+///
+/// Entries are weak, so a dictionary is freed once no tokenizer uses it.
+static SYSTEM_DICT_REGISTRY: Lazy<Mutex<HashMap<PathBuf, Weak<SystemDictionary>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
 impl SystemDictionary {
     /// Get the sysdic path, trying bundled location first, then relative path
     ///
     /// # Returns
     /// * `PathBuf` - Path to the sysdic directory
+    #[cfg_attr(feature = "embedded-dict", allow(dead_code))]
     fn get_sysdic_path() -> PathBuf {
         // Try bundled path first (set by build.rs)
-        if let Ok(bundled_path) = std::env::var("SYSDIC_PATH") {
+        if let Some(bundled_path) = std::env::var_os("SYSDIC_PATH") {
             let path = PathBuf::from(bundled_path);
             if path.exists() {
                 return path;
@@ -39,8 +51,8 @@ impl SystemDictionary {
         #[cfg(feature = "python")]
         {
             // First try the bundled location within the Python package
-            if let Ok(module_path) = std::env::var("CARGO_MANIFEST_DIR") {
-                let package_sysdic = PathBuf::from(&module_path).join("runome/sysdic");
+            if let Some(module_path) = std::env::var_os("CARGO_MANIFEST_DIR") {
+                let package_sysdic = PathBuf::from(&module_path).join("runome").join("sysdic");
                 if package_sysdic.exists() {
                     return package_sysdic;
                 }
@@ -51,14 +63,16 @@ impl SystemDictionary {
             // Only try this if we're running in a Python extension context
             if std::env::var("PYTHONPATH").is_ok() || std::env::var("VIRTUAL_ENV").is_ok() {
                 use pyo3::prelude::*;
-                if let Ok(py_result) = Python::with_gil(|py| -> PyResult<Option<PathBuf>> {
+                if let Ok(Some(path)) = Python::with_gil(|py| -> PyResult<Option<PathBuf>> {
                     // Import the runome module to get its location
                     let runome_module = py.import("runome")?;
                     let file_attr = runome_module.getattr("__file__")?;
-                    let module_file: String = file_attr.extract()?;
+                    let module_file: PathBuf = file_attr.extract()?;
 
                     // Get the directory containing the module
-                    let module_dir = PathBuf::from(module_file).parent().unwrap().to_path_buf();
+                    let Some(module_dir) = module_file.parent() else {
+                        return Ok(None);
+                    };
                     let sysdic_path = module_dir.join("sysdic");
 
                     if sysdic_path.exists() {
@@ -67,9 +81,7 @@ impl SystemDictionary {
                         Ok(None)
                     }
                 }) {
-                    if let Some(path) = py_result {
-                        return path;
-                    }
+                    return path;
                 }
             }
 
@@ -80,7 +92,7 @@ impl SystemDictionary {
             // Search upward in the directory tree for a runome package
             for _ in 0..5 {
                 // Limit search depth
-                let candidate_path = search_dir.join("runome/sysdic");
+                let candidate_path = search_dir.join("runome").join("sysdic");
                 if candidate_path.exists() {
                     return candidate_path;
                 }
@@ -109,37 +121,109 @@ impl SystemDictionary {
     /// Returns a shared reference to the singleton SystemDictionary instance,
     /// creating it if it doesn't exist. Uses lazy initialization with thread safety.
     ///
+    /// # Fork safety
+    /// Once initialized, the singleton is immutable, so a process forked
+    /// afterwards inherits it and shares its pages copy-on-write with the parent.
+    /// Do not fork while another thread may be initializing it: the child could
+    /// inherit a held lock. Preforking servers should call `instance()` or
+    /// `preload_shared()` in the parent before forking workers.
+    ///
     /// # Returns
     /// * `Ok(Arc<SystemDictionary>)` - Shared reference to singleton instance
     /// * `Err(RunomeError)` - Error if initialization fails
     pub fn instance() -> Result<Arc<SystemDictionary>, RunomeError> {
-        let instance_lock =
-            SYSTEM_DICT_INSTANCE
-                .lock()
-                .map_err(|_| RunomeError::SystemDictInitError {
-                    reason: "Failed to acquire SystemDictionary lock".to_string(),
-                })?;
+        if let Some(path) = &crate::config::global_config().sysdic_path {
+            return Self::instance_with(|| Self::new(path));
+        }
+        // Create new instance from the embedded data, or using sysdic path resolution
+        #[cfg(feature = "embedded-dict")]
+        return Self::instance_with(Self::new_embedded);
+        #[cfg(not(feature = "embedded-dict"))]
+        Self::instance_with(|| Self::new(Self::get_sysdic_path()))
+    }
 
-        if let Some(ref instance) = *instance_lock {
-            return Ok(Arc::clone(instance));
+    /// Initialize the singleton with a memory-mapped FST for multi-process use
+    ///
+    /// Intended for preforking servers (e.g. gunicorn with `--preload`): call it
+    /// in the parent before forking. Workers inherit the loaded dictionary and
+    /// share its pages with the parent, and the FST is backed by the page cache,
+    /// so it is also shared with unrelated processes mapping the same sysdic.
+    /// If the singleton is already initialized, it is returned unchanged.
+    ///
+    /// With the `embedded-dict` feature, this is the same as `instance()`.
+    ///
+    /// # Returns
+    /// * `Ok(Arc<SystemDictionary>)` - Shared reference to singleton instance
+    /// * `Err(RunomeError)` - Error if initialization fails
+    pub fn preload_shared() -> Result<Arc<SystemDictionary>, RunomeError> {
+        if let Some(path) = &crate::config::global_config().sysdic_path {
+            return Self::instance_with(|| Self::new_mmap(path));
         }
+        #[cfg(feature = "embedded-dict")]
+        return Self::instance();
+        #[cfg(not(feature = "embedded-dict"))]
+        Self::instance_with(|| Self::new_mmap(Self::get_sysdic_path()))
+    }
 
-        drop(instance_lock);
+    /// Check whether the singleton has been initialized
+    pub(crate) fn is_initialized() -> bool {
+        SYSTEM_DICT_INSTANCE.get().is_some()
+    }
 
-        // Create new instance using sysdic path resolution
-        let sysdic_path = Self::get_sysdic_path();
-        let new_instance = Arc::new(Self::new(&sysdic_path)?);
+    /// Return the singleton, initializing it with `init` if needed
+    ///
+    /// Concurrent callers block until the first one has loaded the dictionary,
+    /// so it is loaded once. If `init` fails, the next call tries again.
+    fn instance_with(
+        init: impl FnOnce() -> Result<Self, RunomeError>,
+    ) -> Result<Arc<SystemDictionary>, RunomeError> {
+        SYSTEM_DICT_INSTANCE
+            .get_or_try_init(|| {
+                log::debug!("Initializing shared SystemDictionary instance");
+                init().map(Arc::new)
+            })
+            .cloned()
+    }
 
-        let mut instance_lock =
-            SYSTEM_DICT_INSTANCE
+    /// Get a SystemDictionary loaded from a specific directory
+    ///
+    /// Unlike `instance()`, which resolves the sysdic location automatically,
+    /// this loads the dictionary from the given directory. Dictionaries are
+    /// kept in a registry keyed by the canonical path of the directory, so
+    /// tokenizers for the same directory share one copy while dictionaries
+    /// from different directories (e.g. IPADIC and NEologd) coexist. A
+    /// dictionary is dropped from the registry once it is no longer used.
+    /// The shared singleton is not touched, so it can be used alongside
+    /// `instance()`.
+    ///
+    /// # Arguments
+    /// * `sysdic_dir` - Path to directory containing dictionary data
+    ///
+    /// # Returns
+    /// * `Ok(Arc<SystemDictionary>)` - Shared reference to the loaded dictionary
+    /// * `Err(RunomeError)` - Error if loading fails
+    pub fn instance_at(sysdic_dir: impl AsRef<Path>) -> Result<Arc<SystemDictionary>, RunomeError> {
+        let sysdic_dir = sysdic_dir.as_ref();
+        let Ok(key) = sysdic_dir.canonicalize() else {
+            // Let the loader report the missing directory
+            return Ok(Arc::new(Self::new(sysdic_dir)?));
+        };
+
+        let mut registry =
+            SYSTEM_DICT_REGISTRY
                 .lock()
                 .map_err(|_| RunomeError::SystemDictInitError {
-                    reason: "Failed to acquire SystemDictionary lock for initialization"
-                        .to_string(),
+                    reason: "Failed to acquire SystemDictionary registry lock".to_string(),
                 })?;
+        if let Some(instance) = registry.get(&key).and_then(Weak::upgrade) {
+            return Ok(instance);
+        }
 
-        *instance_lock = Some(new_instance.clone());
-        Ok(new_instance)
+        log::debug!("Loading SystemDictionary from {:?}", key);
+        let instance = Arc::new(Self::new(&key)?);
+        registry.retain(|_, dictionary| dictionary.strong_count() > 0);
+        registry.insert(key, Arc::downgrade(&instance));
+        Ok(instance)
     }
 
     /// Create new SystemDictionary from sysdic directory
@@ -153,7 +237,9 @@ impl SystemDictionary {
     /// # Returns
     /// * `Ok(SystemDictionary)` - Successfully created dictionary
     /// * `Err(RunomeError)` - Error if loading fails
-    pub fn new(sysdic_dir: &Path) -> Result<Self, RunomeError> {
+    pub fn new(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        let sysdic_dir = sysdic_dir.as_ref();
+
         // Load dictionary resource
         let resource = DictionaryResource::load(sysdic_dir)?;
 
@@ -163,6 +249,200 @@ impl SystemDictionary {
         Ok(Self { ram_dict })
     }
 
+    /// Create new SystemDictionary with a memory-mapped FST
+    ///
+    /// See `DictionaryResource::load_mmap`. The other components are loaded
+    /// onto the heap as usual.
+    ///
+    /// # Arguments
+    /// * `sysdic_dir` - Path to directory containing dictionary data
+    ///
+    /// # Returns
+    /// * `Ok(SystemDictionary)` - Successfully created dictionary
+    /// * `Err(RunomeError)` - Error if loading or mapping fails
+    pub fn new_mmap(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        let resource = DictionaryResource::load_mmap(&sysdic_dir)?;
+        let ram_dict = RAMDictionary::from_resource_in(resource, sysdic_dir.as_ref())?;
+
+        Ok(Self { ram_dict })
+    }
+
+    /// Create new SystemDictionary that decodes entries on first lookup
+    ///
+    /// See `DictionaryResource::load_lazy`. Startup is dominated by mapping
+    /// `entries.bin` and the FST instead of decoding every entry, which helps
+    /// short-lived processes that tokenize little text.
+    ///
+    /// # Arguments
+    /// * `sysdic_dir` - Path to directory containing dictionary data
+    ///
+    /// # Returns
+    /// * `Ok(SystemDictionary)` - Successfully created dictionary
+    /// * `Err(RunomeError)` - Error if loading or mapping fails
+    pub fn new_lazy(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        let resource = DictionaryResource::load_lazy(&sysdic_dir)?;
+        let ram_dict = RAMDictionary::from_resource_in(resource, sysdic_dir.as_ref())?;
+
+        Ok(Self { ram_dict })
+    }
+
+    /// Check if entries of this dictionary are decoded on first lookup
+    pub fn is_lazy(&self) -> bool {
+        self.ram_dict.get_resource().is_lazy()
+    }
+
+    /// Check if the FST of this dictionary is memory-mapped
+    pub fn is_fst_mapped(&self) -> bool {
+        self.ram_dict.get_resource().is_fst_mapped()
+    }
+
+    /// Get the data structure surface forms are looked up in
+    pub fn matcher_backend(&self) -> MatcherBackend {
+        self.ram_dict.matcher_backend()
+    }
+
+    /// Create new SystemDictionary tolerating missing unknown-word data
+    ///
+    /// Loads the dictionary with `DictionaryResource::load_degraded`, so a missing
+    /// `char_defs.bin` or `unknowns.bin` is replaced by a minimal fallback
+    /// instead of failing; with both missing, tokenization is dictionary-only
+    /// with single-character unknown words.
+    ///
+    /// # Arguments
+    /// * `sysdic_dir` - Path to directory containing dictionary data
+    ///
+    /// # Returns
+    /// * `Ok(SystemDictionary)` - Successfully created dictionary
+    /// * `Err(RunomeError)` - Error if a required component fails to load
+    pub fn new_degraded(sysdic_dir: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        let sysdic_dir = sysdic_dir.as_ref();
+        let resource = DictionaryResource::load_degraded(sysdic_dir)?;
+        let ram_dict = RAMDictionary::new(resource, sysdic_dir)?;
+
+        Ok(Self { ram_dict })
+    }
+
+    /// Create new SystemDictionary from the data compiled into the binary
+    ///
+    /// Available with the `embedded-dict` feature. When the feature is enabled,
+    /// `instance()` uses this instead of searching for a sysdic directory.
+    ///
+    /// # Returns
+    /// * `Ok(SystemDictionary)` - Successfully created dictionary
+    /// * `Err(RunomeError)` - Error if the embedded data cannot be loaded
+    #[cfg(feature = "embedded-dict")]
+    pub fn new_embedded() -> Result<Self, RunomeError> {
+        let resource = DictionaryResource::load_embedded()?;
+        let ram_dict = RAMDictionary::from_resource(resource)?;
+
+        Ok(Self { ram_dict })
+    }
+
+    /// Create new SystemDictionary from a dictionary bundle
+    ///
+    /// See `DictionaryResource::load_bundle`. Bundles are created from a sysdic
+    /// directory with `bundle::pack_sysdic` or `runome-build-dict bundle`.
+    ///
+    /// # Arguments
+    /// * `bundle` - Bundle bytes
+    ///
+    /// # Returns
+    /// * `Ok(SystemDictionary)` - Successfully created dictionary
+    /// * `Err(RunomeError)` - Error if the bundle is invalid or incomplete
+    pub fn from_bundle(bundle: &[u8]) -> Result<Self, RunomeError> {
+        let resource = DictionaryResource::load_bundle(bundle)?;
+        let ram_dict = RAMDictionary::from_resource(resource)?;
+
+        Ok(Self { ram_dict })
+    }
+
+    /// Check if this dictionary was loaded with fallback unknown-word data
+    pub fn is_degraded(&self) -> bool {
+        self.ram_dict.get_resource().is_degraded()
+    }
+
+    /// Get the length in characters of the longest surface form
+    pub fn max_surface_length(&self) -> usize {
+        self.ram_dict.get_resource().max_surface_length()
+    }
+
+    /// Get all known word entries, in morpheme ID order
+    ///
+    /// For a dictionary created with `new_lazy`, the first call decodes every entry.
+    ///
+    /// # Returns
+    /// * `Ok(&[DictEntry])` - All entries
+    /// * `Err(RunomeError)` - Error if lazily loaded entries cannot be decoded
+    pub fn entries(&self) -> Result<&[DictEntry], RunomeError> {
+        self.ram_dict.get_resource().get_entries()
+    }
+
+    /// Iterate over all known word entries, in morpheme ID order
+    ///
+    /// Unlike `entries`, this does not collect every entry at once, so a
+    /// dictionary created with `new_lazy` only decodes entries as they are
+    /// visited. An entry that cannot be decoded is yielded as an error.
+    pub fn iter_entries(&self) -> impl Iterator<Item = Result<&DictEntry, RunomeError>> {
+        let resource = self.ram_dict.get_resource();
+        (0..resource.entry_count())
+            .filter_map(move |morph_id| resource.get_entry(morph_id).transpose())
+    }
+
+    /// Get the number of known word entries
+    pub fn entry_count(&self) -> usize {
+        self.ram_dict.get_resource().entry_count()
+    }
+
+    /// Get the entry with the given morpheme ID
+    ///
+    /// # Arguments
+    /// * `morph_id` - Morpheme ID, as in `DictEntry::morph_id`
+    ///
+    /// # Returns
+    /// * `Ok(Some(&DictEntry))` - The entry
+    /// * `Ok(None)` - If the ID is out of range
+    /// * `Err(RunomeError)` - Error if the lazily loaded entry cannot be decoded
+    pub fn entry(&self, morph_id: usize) -> Result<Option<&DictEntry>, RunomeError> {
+        self.ram_dict.get_resource().get_entry(morph_id)
+    }
+
+    /// Look up the entries whose surface is exactly the given string
+    ///
+    /// # Arguments
+    /// * `surface` - Surface form string to look up
+    ///
+    /// # Returns
+    /// * `Ok(Vec<&DictEntry>)` - Homographs of the surface, in morpheme ID order
+    /// * `Err(RunomeError)` - Error if lookup fails
+    pub fn lookup_exact(&self, surface: &str) -> Result<Vec<&DictEntry>, RunomeError> {
+        self.ram_dict.lookup_exact(surface)
+    }
+
+    /// Get the number of entries sharing a surface form
+    ///
+    /// # Arguments
+    /// * `surface` - Surface form string to look up
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of homographs, 0 for an unknown surface
+    /// * `Err(RunomeError)` - Error if lookup fails
+    pub fn homograph_count(&self, surface: &str) -> Result<usize, RunomeError> {
+        Ok(self.lookup_exact(surface)?.len())
+    }
+
+    /// Get the approximate memory used by the dictionary
+    ///
+    /// Compare a dictionary loaded with `new` against one loaded with
+    /// `new_mmap` or `new_lazy` to see how much heap memory mapping saves.
+    pub fn memory_stats(&self) -> MemoryStats {
+        self.ram_dict.memory_stats()
+    }
+
+    /// Get the dictionary-wide metadata, including the sentence boundary context ids
+    pub fn metadata(&self) -> &DictMetadata {
+        self.ram_dict.get_resource().metadata()
+    }
+
     /// Look up known words only (delegates to RAMDictionary)
     ///
     /// Performs dictionary lookup for known words using the embedded RAMDictionary.
@@ -200,8 +480,8 @@ impl SystemDictionary {
     /// This is needed for UserDictionary initialization.
     ///
     /// # Returns
-    /// * `Arc<Vec<Vec<i16>>>` - Shared reference to connection matrix
-    pub fn get_connection_matrix(&self) -> Arc<Vec<Vec<i16>>> {
+    /// * `Arc<ConnectionMatrix>` - Shared reference to connection matrix
+    pub fn get_connection_matrix(&self) -> Arc<ConnectionMatrix> {
         self.ram_dict.get_connection_matrix()
     }
 
@@ -219,6 +499,23 @@ impl SystemDictionary {
         self.ram_dict.get_resource().get_char_categories(ch)
     }
 
+    /// Check whether a character can continue an unknown word of a category
+    ///
+    /// Uses the compatible categories of char.def, so e.g. a KANJINUMERIC
+    /// character continues a KANJI word only if char.def lists KANJI for it.
+    ///
+    /// # Arguments
+    /// * `ch` - Character following the unknown word
+    /// * `category` - Character category of the unknown word
+    ///
+    /// # Returns
+    /// True if the character belongs to the category or a compatible one
+    pub fn is_char_compatible(&self, ch: char, category: &str) -> bool {
+        self.ram_dict
+            .get_resource()
+            .is_char_compatible(ch, category)
+    }
+
     /// Check if unknown word processing should always be invoked for category
     ///
     /// # Arguments
@@ -282,19 +579,14 @@ impl SystemDictionary {
     /// * `c` - Character to classify
     ///
     /// # Returns
-    /// * `Ok(Vec<String>)` - Vector of category names that apply to this character
+    /// * `Ok(Vec<Cow<'static, str>>)` - Category names (each followed by its compatible categories) in char.def order, interned
     /// * `Err(RunomeError)` - Error if character classification fails
-    pub fn get_char_categories_result(&self, c: char) -> Result<Vec<String>, RunomeError> {
-        let categories = self.get_char_categories(c);
-        let mut result = Vec::new();
-
-        // Add primary categories and their compatible categories
-        for (category, compat_categories) in categories {
-            result.push(category);
-            result.extend(compat_categories);
-        }
-
-        Ok(result)
+    pub fn get_char_categories_result(
+        &self,
+        c: char,
+    ) -> Result<Vec<Cow<'static, str>>, RunomeError> {
+        // Ordered, so unknown-word candidates enter the lattice in a stable order
+        Ok(self.ram_dict.get_resource().get_char_category_names(c))
     }
 
     /// Get unknown word entries for a character category (Result version)
@@ -382,6 +674,10 @@ impl Dictionary for SystemDictionary {
     fn get_trans_cost(&self, left_id: u16, right_id: u16) -> Result<i16, RunomeError> {
         self.get_trans_cost(left_id, right_id)
     }
+
+    fn boundary_context_ids(&self) -> (u16, u16) {
+        self.ram_dict.boundary_context_ids()
+    }
 }
 
 #[cfg(test)]
@@ -465,6 +761,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_entry_introspection() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let sys_dict = SystemDictionary::instance().unwrap();
+        assert_eq!(sys_dict.iter_entries().count(), sys_dict.entry_count());
+        assert_eq!(sys_dict.entry_count(), sys_dict.entries().unwrap().len());
+
+        let expected: Vec<usize> = sys_dict
+            .iter_entries()
+            .map(Result::unwrap)
+            .filter(|entry| entry.surface == "もも")
+            .map(|entry| entry.morph_id)
+            .collect();
+        let found: Vec<usize> = sys_dict
+            .lookup_exact("もも")
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.morph_id)
+            .collect();
+        assert_eq!(found, expected);
+        assert_eq!(sys_dict.homograph_count("もも").unwrap(), expected.len());
+        assert_eq!(sys_dict.homograph_count("存在しない語").unwrap(), 0);
+
+        let morph_id = expected[0];
+        assert_eq!(sys_dict.entry(morph_id).unwrap().unwrap().surface, "もも");
+        assert!(sys_dict.entry(sys_dict.entry_count()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_memory_stats_by_backend() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let ram = SystemDictionary::new(&sysdic_path).unwrap().memory_stats();
+        assert!(ram.entries > 0 && ram.fst > 0 && ram.connections > 0);
+        assert!(ram.morpheme_index > 0 && ram.char_defs > 0);
+        assert_eq!(ram.mapped, 0);
+        assert_eq!(ram.total(), ram.heap());
+
+        // The FST is mapped once, shared by the resource and the matcher
+        // unless the matcher searches a double-array trie built on the heap
+        let mmap_dict = SystemDictionary::new_mmap(&sysdic_path).unwrap();
+        let mmap = mmap_dict.memory_stats();
+        if mmap_dict.matcher_backend() == MatcherBackend::Fst {
+            assert_eq!(mmap.fst, 0);
+        }
+        assert!(mmap.mapped > 0 && mmap.mapped < ram.fst);
+        assert_eq!(mmap.entries, ram.entries);
+
+        // Lazy entries only count once decoded
+        let lazy_dict = SystemDictionary::new_lazy(&sysdic_path).unwrap();
+        let lazy = lazy_dict.memory_stats();
+        assert!(lazy.entries < ram.entries);
+        assert!(lazy.mapped > mmap.mapped);
+        lazy_dict.lookup("すもも").unwrap();
+        assert!(lazy_dict.memory_stats().entries > lazy.entries);
+    }
+
     #[test]
     fn test_get_trans_cost_delegation() {
         let sysdic_path = get_test_sysdic_path();
@@ -736,6 +1104,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_char_compatible() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let sys_dict = SystemDictionary::instance().unwrap();
+
+        // Primary categories and compatible categories both continue a word
+        assert!(sys_dict.is_char_compatible('一', "KANJI"));
+        assert!(sys_dict.is_char_compatible('一', "KANJINUMERIC"));
+        assert!(!sys_dict.is_char_compatible('漢', "KANJINUMERIC"));
+        assert!(sys_dict.is_char_compatible('b', "ALPHA"));
+        assert!(!sys_dict.is_char_compatible('b', "NUMERIC"));
+
+        // Characters without a range are DEFAULT only
+        assert!(sys_dict.is_char_compatible('😀', "DEFAULT"));
+        assert!(!sys_dict.is_char_compatible('😀', "ALPHA"));
+
+        // Agrees with the categories listed for each character
+        for ch in ['一', '〇', 'ア', 'ー', 'Ａ', '1', '。', ' '] {
+            let categories = sys_dict.get_char_categories(ch);
+            for category in [
+                "KANJI",
+                "KANJINUMERIC",
+                "KATAKANA",
+                "HIRAGANA",
+                "ALPHA",
+                "NUMERIC",
+                "SYMBOL",
+                "SPACE",
+            ] {
+                let expected = categories.contains_key(category)
+                    || categories
+                        .values()
+                        .any(|compat| compat.iter().any(|c| c == category));
+                assert_eq!(
+                    sys_dict.is_char_compatible(ch, category),
+                    expected,
+                    "{:?} {}",
+                    ch,
+                    category
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_unknown_word_processing_properties() {
         let sysdic_path = get_test_sysdic_path();
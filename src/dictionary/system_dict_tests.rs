@@ -444,4 +444,143 @@ mod error_handling_tests {
             "Should return same number of entries"
         );
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_instance_at_registry() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        // Different spellings of the same directory share one dictionary
+        let dict1 = SystemDictionary::instance_at(&sysdic_path).unwrap();
+        let dict2 = SystemDictionary::instance_at(sysdic_path.join(".")).unwrap();
+        assert!(Arc::ptr_eq(&dict1, &dict2));
+        assert!(!Arc::ptr_eq(&dict1, &SystemDictionary::instance().unwrap()));
+
+        // Another directory gets its own dictionary
+        let other_dir = tempfile::tempdir().unwrap();
+        for entry in std::fs::read_dir(&sysdic_path).unwrap() {
+            let entry = entry.unwrap();
+            let target = entry.path().canonicalize().unwrap();
+            std::os::unix::fs::symlink(target, other_dir.path().join(entry.file_name())).unwrap();
+        }
+        let other = SystemDictionary::instance_at(other_dir.path()).unwrap();
+        assert!(!Arc::ptr_eq(&dict1, &other));
+        assert_eq!(
+            other.lookup("東京").unwrap().len(),
+            dict1.lookup("東京").unwrap().len()
+        );
+
+        // Unused dictionaries are released
+        let weak = Arc::downgrade(&other);
+        drop(other);
+        assert!(weak.upgrade().is_none());
+
+        assert!(SystemDictionary::instance_at("/nonexistent/sysdic").is_err());
+    }
+}
+
+#[cfg(test)]
+mod multiprocess_tests {
+    use super::*;
+
+    #[test]
+    fn test_new_mmap_matches_heap_dictionary() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let mapped = SystemDictionary::new_mmap(&sysdic_path).unwrap();
+        let heap = SystemDictionary::new(&sysdic_path).unwrap();
+        assert!(mapped.is_fst_mapped());
+        assert!(!heap.is_fst_mapped());
+
+        for surface in ["すもももももももものうち", "東京都", "形態素解析"] {
+            let mapped_entries = mapped.lookup(surface).unwrap();
+            let heap_entries = heap.lookup(surface).unwrap();
+            assert!(!mapped_entries.is_empty());
+            assert_eq!(mapped_entries, heap_entries);
+        }
+    }
+
+    #[test]
+    fn test_new_lazy_matches_heap_dictionary() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let lazy = SystemDictionary::new_lazy(&sysdic_path).unwrap();
+        let heap = SystemDictionary::new(&sysdic_path).unwrap();
+        assert!(lazy.is_lazy());
+        assert!(!heap.is_lazy());
+        assert_eq!(lazy.metadata(), heap.metadata());
+
+        for surface in ["すもももももももものうち", "東京都", "形態素解析"] {
+            let lazy_entries = lazy.lookup(surface).unwrap();
+            assert!(!lazy_entries.is_empty());
+            assert_eq!(lazy_entries, heap.lookup(surface).unwrap());
+        }
+
+        let resource = crate::dictionary::DictionaryResource::load_lazy(&sysdic_path).unwrap();
+        let heap_entries = heap.entries().unwrap();
+        let last = heap_entries.len() - 1;
+        assert_eq!(resource.entry_count(), heap_entries.len());
+        assert_eq!(resource.get_entry(last).unwrap(), heap_entries.get(last));
+        assert_eq!(resource.get_entry(last + 1).unwrap(), None);
+        assert_eq!(resource.get_entries().unwrap(), heap_entries);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dictionary_usable_after_fork() {
+        let sysdic_path = get_test_sysdic_path();
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        // Load in the parent, as a preforking server would
+        let sys_dict = SystemDictionary::instance().unwrap();
+        let expected = sys_dict.lookup("東京").unwrap().len();
+
+        // SAFETY: the child only reads the inherited dictionary and exits with
+        // _exit, without touching locks that other test threads may hold
+        let pid = unsafe { libc::fork() };
+        assert!(pid >= 0, "fork failed");
+        if pid == 0 {
+            let ok = sys_dict
+                .lookup("東京")
+                .is_ok_and(|entries| entries.len() == expected);
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) };
+        }
+
+        let mut status = 0;
+        let waited = unsafe { libc::waitpid(pid, &mut status, 0) };
+        assert_eq!(waited, pid);
+        assert!(libc::WIFEXITED(status));
+        assert_eq!(
+            libc::WEXITSTATUS(status),
+            0,
+            "Child failed to use inherited dictionary"
+        );
+    }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::RunomeError;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct DictEntry {
     pub surface: String,
@@ -44,5 +46,309 @@ pub struct UnknownEntry {
     pub part_of_speech: String,
 }
 
-pub type ConnectionMatrix = Vec<Vec<i16>>;
+/// Dictionary-wide metadata recorded at build time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DictMetadata {
+    /// Length in characters of the longest surface form in the dictionary
+    pub max_surface_length: usize,
+    /// Context id of the BOS node, used as its right id in connection costs
+    pub bos_context_id: u16,
+    /// Context id of the EOS node, used as its left id in connection costs
+    pub eos_context_id: u16,
+}
+
+impl DictMetadata {
+    /// Create metadata for the given entries with the MeCab default boundary
+    /// context id 0 for both BOS and EOS
+    pub fn from_entries(entries: &[DictEntry]) -> Self {
+        Self {
+            max_surface_length: entries
+                .iter()
+                .map(|entry| entry.surface.chars().count())
+                .max()
+                .unwrap_or(0),
+            bos_context_id: 0,
+            eos_context_id: 0,
+        }
+    }
+}
+
+/// Connection costs between context ids, stored row-major in one vector
+///
+/// Row `left_id` holds the costs of connecting a node with that right
+/// context id to nodes with each left context id, as in MeCab's matrix.def.
+/// The flat layout avoids a second indirection in the Viterbi inner loop.
+///
+/// Serialized as a sequence of rows, the format of `Vec<Vec<i16>>`, so
+/// existing sysdic files load unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionMatrix {
+    costs: Vec<i16>,
+    rows: usize,
+    cols: usize,
+}
+
+impl ConnectionMatrix {
+    /// Create a matrix of zero costs
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            costs: vec![0; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Create a matrix from its rows
+    ///
+    /// # Returns
+    /// * `Ok(ConnectionMatrix)` - The matrix
+    /// * `Err(RunomeError)` - Error if the rows differ in length
+    pub fn from_rows(rows: Vec<Vec<i16>>) -> Result<Self, RunomeError> {
+        let cols = rows.first().map_or(0, Vec::len);
+        let mut costs = Vec::with_capacity(rows.len() * cols);
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != cols {
+                return Err(RunomeError::DictValidationError {
+                    reason: format!(
+                        "Connection matrix row {} has inconsistent length: {} vs expected {}",
+                        i,
+                        row.len(),
+                        cols
+                    ),
+                });
+            }
+            costs.extend_from_slice(row);
+        }
+        Ok(Self {
+            costs,
+            rows: rows.len(),
+            cols,
+        })
+    }
+
+    /// Number of rows, i.e. right context ids of the left node
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns, i.e. left context ids of the right node
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Check if the matrix has no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Get a connection cost, or `None` if an id is out of range
+    #[inline]
+    pub fn get(&self, left_id: u16, right_id: u16) -> Option<i16> {
+        let (left_id, right_id) = (left_id as usize, right_id as usize);
+        (left_id < self.rows && right_id < self.cols)
+            .then(|| self.costs[left_id * self.cols + right_id])
+    }
+
+    /// Set a connection cost
+    ///
+    /// # Returns
+    /// * `Ok(())` - The cost was set
+    /// * `Err(RunomeError)` - Error if an id is out of range
+    pub fn set(&mut self, left_id: usize, right_id: usize, cost: i16) -> Result<(), RunomeError> {
+        if left_id >= self.rows || right_id >= self.cols {
+            return Err(RunomeError::DictValidationError {
+                reason: format!(
+                    "Connection ids {} {} out of range for a {}x{} matrix",
+                    left_id, right_id, self.rows, self.cols
+                ),
+            });
+        }
+        self.costs[left_id * self.cols + right_id] = cost;
+        Ok(())
+    }
+
+    /// Get the costs of one row
+    pub fn row(&self, left_id: usize) -> Option<&[i16]> {
+        (left_id < self.rows).then(|| &self.costs[left_id * self.cols..(left_id + 1) * self.cols])
+    }
+
+    /// Iterate over the rows
+    pub fn iter_rows(&self) -> impl ExactSizeIterator<Item = &[i16]> {
+        (0..self.rows).map(|left_id| &self.costs[left_id * self.cols..(left_id + 1) * self.cols])
+    }
+}
+
+impl Serialize for ConnectionMatrix {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter_rows())
+    }
+}
+
+impl<'de> Deserialize<'de> for ConnectionMatrix {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<Vec<i16>>::deserialize(deserializer)?;
+        Self::from_rows(rows).map_err(serde::de::Error::custom)
+    }
+}
+
 pub type UnknownEntries = std::collections::HashMap<String, Vec<UnknownEntry>>;
+
+/// Approximate memory used by a dictionary or tokenizer, in bytes
+///
+/// Heap sizes are estimated from the capacities of the data structures and
+/// leave out allocator overhead. Memory-mapped files are reported separately
+/// in `mapped`: their pages are loaded on demand and shared by every process
+/// mapping the same file, so they are not private memory of the process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Decoded dictionary entries, including their strings
+    pub entries: usize,
+    /// FST data held on the heap
+    pub fst: usize,
+    /// Connection cost matrix
+    pub connections: usize,
+    /// Morpheme index mapping FST outputs to entries
+    pub morpheme_index: usize,
+    /// Character definitions, their code point index and unknown word entries
+    pub char_defs: usize,
+    /// User dictionary entries, morpheme index and FST
+    pub user_dict: usize,
+    /// Strings interned at runtime, shared by every tokenizer of the process
+    pub string_pool: usize,
+    /// Memory-mapped dictionary files
+    pub mapped: usize,
+}
+
+impl MemoryStats {
+    /// Get the total heap memory
+    pub fn heap(&self) -> usize {
+        self.entries
+            + self.fst
+            + self.connections
+            + self.morpheme_index
+            + self.char_defs
+            + self.user_dict
+            + self.string_pool
+    }
+
+    /// Get the total of heap and memory-mapped memory
+    pub fn total(&self) -> usize {
+        self.heap() + self.mapped
+    }
+}
+
+impl DictEntry {
+    /// Estimated heap memory of the entry's strings
+    pub(crate) fn heap_size(&self) -> usize {
+        self.surface.capacity()
+            + self.part_of_speech.capacity()
+            + self.inflection_type.capacity()
+            + self.inflection_form.capacity()
+            + self.base_form.capacity()
+            + self.reading.capacity()
+            + self.phonetic.capacity()
+    }
+}
+
+impl ConnectionMatrix {
+    /// Estimated heap memory of the costs
+    pub(crate) fn heap_size(&self) -> usize {
+        self.costs.capacity() * std::mem::size_of::<i16>()
+    }
+}
+
+/// Estimated heap memory of a list of entries, including their strings
+pub(crate) fn entries_heap_size(entries: &Vec<DictEntry>) -> usize {
+    entries.capacity() * std::mem::size_of::<DictEntry>()
+        + entries.iter().map(DictEntry::heap_size).sum::<usize>()
+}
+
+/// Estimated heap memory of a morpheme index
+pub(crate) fn morpheme_index_heap_size(index: &Vec<Vec<u32>>) -> usize {
+    index.capacity() * std::mem::size_of::<Vec<u32>>()
+        + index
+            .iter()
+            .map(|ids| ids.capacity() * std::mem::size_of::<u32>())
+            .sum::<usize>()
+}
+
+/// Estimated heap memory of a hash map's table, without what its keys and
+/// values own
+pub(crate) fn hash_table_size<K, V, S>(map: &std::collections::HashMap<K, V, S>) -> usize {
+    map.capacity() * (std::mem::size_of::<(K, V)>() + 1)
+}
+
+impl CharDefinitions {
+    /// Estimated heap memory of the definitions
+    pub(crate) fn heap_size(&self) -> usize {
+        hash_table_size(&self.categories)
+            + self.categories.keys().map(String::capacity).sum::<usize>()
+            + self.code_ranges.capacity() * std::mem::size_of::<CodePointRange>()
+            + self
+                .code_ranges
+                .iter()
+                .map(|range| {
+                    range.category.capacity()
+                        + range.compat_categories.capacity() * std::mem::size_of::<String>()
+                        + range
+                            .compat_categories
+                            .iter()
+                            .map(String::capacity)
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+}
+
+/// Estimated heap memory of unknown word entries
+pub(crate) fn unknowns_heap_size(unknowns: &UnknownEntries) -> usize {
+    hash_table_size(unknowns)
+        + unknowns
+            .iter()
+            .map(|(category, entries)| {
+                category.capacity()
+                    + entries.capacity() * std::mem::size_of::<UnknownEntry>()
+                    + entries
+                        .iter()
+                        .map(|entry| entry.part_of_speech.capacity())
+                        .sum::<usize>()
+            })
+            .sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_matrix() {
+        let rows = vec![vec![0, 1, 2], vec![3, 4, 5]];
+        let matrix = ConnectionMatrix::from_rows(rows.clone()).unwrap();
+        assert_eq!((matrix.rows(), matrix.cols()), (2, 3));
+        assert_eq!(matrix.get(1, 2), Some(5));
+        assert_eq!(matrix.get(2, 0), None);
+        assert_eq!(matrix.get(0, 3), None);
+        assert_eq!(matrix.row(1), Some(&[3, 4, 5][..]));
+        assert!(ConnectionMatrix::from_rows(vec![vec![0, 1], vec![2]]).is_err());
+
+        // Serialized like the nested vectors of earlier versions
+        let encoded = bincode::serialize(&matrix).unwrap();
+        assert_eq!(encoded, bincode::serialize(&rows).unwrap());
+        assert_eq!(
+            bincode::deserialize::<ConnectionMatrix>(&encoded).unwrap(),
+            matrix
+        );
+
+        let mut matrix = ConnectionMatrix::new(2, 2);
+        matrix.set(1, 0, -7).unwrap();
+        assert!(matches!(
+            matrix.set(2, 0, 9),
+            Err(RunomeError::DictValidationError { .. })
+        ));
+        assert!(matrix.set(0, 2, 9).is_err());
+        assert_eq!(
+            matrix.iter_rows().collect::<Vec<_>>(),
+            [&[0, 0][..], &[-7, 0][..]]
+        );
+    }
+}
@@ -1,9 +1,10 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
 
-use crate::dictionary::{DictEntry, Dictionary, Matcher};
-use crate::error::RunomeError;
+use crate::dictionary::types::{entries_heap_size, morpheme_index_heap_size};
+use crate::dictionary::{ConnectionMatrix, DictEntry, Dictionary, Matcher, MemoryStats};
+use crate::error::{PathContext, RunomeError};
 
 /// User dictionary format types
 #[derive(Debug, Clone, PartialEq)]
@@ -20,10 +21,10 @@ pub enum UserDictFormat {
 /// building FST for efficient lookup, and integrating with the tokenizer.
 /// Uses the same pattern as system dictionary for handling multiple morpheme IDs.
 pub struct UserDictionary {
-    entries: Vec<DictEntry>,         // All user dictionary entries
-    morpheme_index: Vec<Vec<u32>>,   // Maps FST index IDs to morpheme ID vectors
-    matcher: Matcher,                // FST matcher for surface form lookup
-    connections: Arc<Vec<Vec<i16>>>, // Reference to system dictionary connections
+    entries: Vec<DictEntry>,            // All user dictionary entries
+    morpheme_index: Vec<Vec<u32>>,      // Maps FST index IDs to morpheme ID vectors
+    matcher: Matcher,                   // FST matcher for surface form lookup
+    connections: Arc<ConnectionMatrix>, // Reference to system dictionary connections
 }
 
 impl UserDictionary {
@@ -40,7 +41,7 @@ impl UserDictionary {
     pub fn new(
         csv_path: &Path,
         format: UserDictFormat,
-        connections: Arc<Vec<Vec<i16>>>,
+        connections: Arc<ConnectionMatrix>,
     ) -> Result<Self, RunomeError> {
         let entries = Self::load_entries(csv_path, format)?;
         let (matcher, morpheme_index) = Self::build_fst(&entries)?;
@@ -68,7 +69,7 @@ impl UserDictionary {
         csv_path: &Path,
         format: UserDictFormat,
         encoding: &'static encoding_rs::Encoding,
-        connections: Arc<Vec<Vec<i16>>>,
+        connections: Arc<ConnectionMatrix>,
     ) -> Result<Self, RunomeError> {
         let entries = Self::load_entries_with_encoding(csv_path, format, encoding)?;
         let (matcher, morpheme_index) = Self::build_fst(&entries)?;
@@ -81,15 +82,67 @@ impl UserDictionary {
         })
     }
 
+    /// Create new UserDictionary from a Janome-compatible simplified CSV file
+    ///
+    /// Each line has 3 fields: `surface,pos_major,reading`. Left/right ids are
+    /// set to 0 and the cost to -32000 so that user entries win over system
+    /// dictionary entries, matching Janome's simpledic handling.
+    ///
+    /// # Arguments
+    /// * `csv_path` - Path to simplified CSV file
+    /// * `connections` - Reference to system dictionary connection matrix
+    ///
+    /// # Returns
+    /// * `Ok(UserDictionary)` - Successfully created user dictionary
+    /// * `Err(RunomeError)` - Error if CSV parsing or FST building fails
+    pub fn from_simpledic_csv(
+        csv_path: &Path,
+        connections: Arc<ConnectionMatrix>,
+    ) -> Result<Self, RunomeError> {
+        Self::new(csv_path, UserDictFormat::Simpledic, connections)
+    }
+
+    /// Create new UserDictionary from a simplified CSV file, with word costs
+    /// estimated from the system dictionary
+    ///
+    /// Unlike `from_simpledic_csv`, entries do not unconditionally win over
+    /// system dictionary entries: each gets the typical cost of system entries
+    /// with the same POS and surface length (see `dict_builder::estimate_cost`).
+    ///
+    /// # Arguments
+    /// * `csv_path` - Path to simplified CSV file
+    /// * `connections` - Reference to system dictionary connection matrix
+    ///
+    /// # Returns
+    /// * `Ok(UserDictionary)` - Successfully created user dictionary
+    /// * `Err(RunomeError)` - Error if CSV parsing, cost estimation or FST building fails
+    pub fn from_simpledic_csv_with_estimated_costs(
+        csv_path: &Path,
+        connections: Arc<ConnectionMatrix>,
+    ) -> Result<Self, RunomeError> {
+        let mut entries = Self::load_entries(csv_path, UserDictFormat::Simpledic)?;
+        for entry in &mut entries {
+            entry.cost = crate::dict_builder::estimate_cost(
+                &entry.part_of_speech,
+                entry.surface.chars().count(),
+            )?;
+        }
+        let (matcher, morpheme_index) = Self::build_fst(&entries)?;
+
+        Ok(Self {
+            entries,
+            morpheme_index,
+            matcher,
+            connections,
+        })
+    }
+
     /// Load dictionary entries from CSV file
-    fn load_entries(
+    pub(crate) fn load_entries(
         csv_path: &Path,
         format: UserDictFormat,
     ) -> Result<Vec<DictEntry>, RunomeError> {
-        let content =
-            std::fs::read_to_string(csv_path).map_err(|e| RunomeError::UserDictError {
-                reason: format!("Failed to read CSV file {:?}: {}", csv_path, e),
-            })?;
+        let content = std::fs::read_to_string(csv_path).with_path(csv_path)?;
 
         let mut entries = Vec::new();
         for line in content.lines() {
@@ -99,8 +152,12 @@ impl UserDictionary {
             }
 
             let entry = match format {
-                UserDictFormat::Ipadic => Self::parse_ipadic_line(line, entries.len())?,
-                UserDictFormat::Simpledic => Self::parse_simpledic_line(line, entries.len())?,
+                UserDictFormat::Ipadic => {
+                    Self::parse_ipadic_line(line, entries.len()).with_path(csv_path)?
+                }
+                UserDictFormat::Simpledic => {
+                    Self::parse_simpledic_line(line, entries.len()).with_path(csv_path)?
+                }
             };
 
             entries.push(entry);
@@ -116,15 +173,13 @@ impl UserDictionary {
     }
 
     /// Load dictionary entries from CSV file with specified encoding
-    fn load_entries_with_encoding(
+    pub(crate) fn load_entries_with_encoding(
         csv_path: &Path,
         format: UserDictFormat,
         encoding: &'static encoding_rs::Encoding,
     ) -> Result<Vec<DictEntry>, RunomeError> {
         // Read file as bytes
-        let bytes = std::fs::read(csv_path).map_err(|e| RunomeError::UserDictError {
-            reason: format!("Failed to read CSV file {:?}: {}", csv_path, e),
-        })?;
+        let bytes = std::fs::read(csv_path).with_path(csv_path)?;
 
         // Decode using specified encoding
         let (content, _encoding_used, had_errors) = encoding.decode(&bytes);
@@ -146,8 +201,12 @@ impl UserDictionary {
             }
 
             let entry = match format {
-                UserDictFormat::Ipadic => Self::parse_ipadic_line(line, entries.len())?,
-                UserDictFormat::Simpledic => Self::parse_simpledic_line(line, entries.len())?,
+                UserDictFormat::Ipadic => {
+                    Self::parse_ipadic_line(line, entries.len()).with_path(csv_path)?
+                }
+                UserDictFormat::Simpledic => {
+                    Self::parse_simpledic_line(line, entries.len()).with_path(csv_path)?
+                }
             };
 
             entries.push(entry);
@@ -231,10 +290,40 @@ impl UserDictionary {
         })
     }
 
+    /// Create UserDictionary from already-built components
+    ///
+    /// Used when loading a precompiled user dictionary, where the FST and
+    /// morpheme index were built ahead of time.
+    pub(crate) fn from_parts(
+        entries: Vec<DictEntry>,
+        morpheme_index: Vec<Vec<u32>>,
+        fst_bytes: Vec<u8>,
+        connections: Arc<ConnectionMatrix>,
+    ) -> Result<Self, RunomeError> {
+        let matcher = Matcher::new(fst_bytes)?;
+
+        Ok(Self {
+            entries,
+            morpheme_index,
+            matcher,
+            connections,
+        })
+    }
+
     /// Build FST from dictionary entries, supporting multiple morpheme IDs per surface form
     fn build_fst(entries: &[DictEntry]) -> Result<(Matcher, Vec<Vec<u32>>), RunomeError> {
-        // Group entries by surface form to handle duplicates
-        let mut surface_groups: HashMap<String, Vec<u32>> = HashMap::new();
+        let (fst_bytes, morpheme_index) = Self::build_fst_bytes(entries)?;
+        let matcher = Matcher::new(fst_bytes)?;
+        Ok((matcher, morpheme_index))
+    }
+
+    /// Build raw FST bytes and morpheme index from dictionary entries
+    pub(crate) fn build_fst_bytes(
+        entries: &[DictEntry],
+    ) -> Result<(Vec<u8>, Vec<Vec<u32>>), RunomeError> {
+        // Group entries by surface form to handle duplicates; a BTreeMap keeps the
+        // index IDs, and so the lookup order, the same in every run
+        let mut surface_groups: BTreeMap<String, Vec<u32>> = BTreeMap::new();
         for (id, entry) in entries.iter().enumerate() {
             surface_groups
                 .entry(entry.surface.clone())
@@ -245,8 +334,9 @@ impl UserDictionary {
         // Create separate morpheme index for storing multiple morpheme IDs
         let mut morpheme_index: Vec<Vec<u32>> = Vec::new();
 
-        // Create surface form to index ID mappings
-        let mut surface_to_index: Vec<(String, u64)> = surface_groups
+        // Create surface form to index ID mappings, already sorted by surface
+        // form as required for FST building
+        let surface_to_index: Vec<(String, u64)> = surface_groups
             .iter()
             .map(|(surface, ids)| {
                 // Store morpheme IDs in separate index, FST stores only the index ID
@@ -256,15 +346,13 @@ impl UserDictionary {
             })
             .collect();
 
-        // Sort by surface form (required for FST building)
-        surface_to_index.sort_by(|a, b| a.0.cmp(&b.0));
-
         // Build FST
         let mut builder = fst::MapBuilder::memory();
         for (surface, index_id) in surface_to_index {
             builder.insert(surface.as_bytes(), index_id).map_err(|e| {
                 RunomeError::FstBuildError {
-                    reason: format!("Failed to insert '{}': {}", surface, e),
+                    reason: format!("Failed to insert '{}'", surface),
+                    source: e,
                 }
             })?;
         }
@@ -272,12 +360,79 @@ impl UserDictionary {
         let fst_bytes = builder
             .into_inner()
             .map_err(|e| RunomeError::FstBuildError {
-                reason: format!("Failed to build FST: {}", e),
+                reason: "Failed to build FST".to_string(),
+                source: e,
             })?;
 
-        let matcher = Matcher::new(fst_bytes)?;
-        Ok((matcher, morpheme_index))
+        Ok((fst_bytes, morpheme_index))
+    }
+
+    /// Get the length in characters of the longest surface form
+    pub fn max_surface_length(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|entry| entry.surface.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the approximate memory used by the dictionary
+    ///
+    /// The connection matrix, shared with the system dictionary, is not counted.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let (fst, mapped) = self.matcher.memory_usage();
+        MemoryStats {
+            entries: entries_heap_size(&self.entries),
+            fst,
+            morpheme_index: morpheme_index_heap_size(&self.morpheme_index),
+            mapped,
+            ..MemoryStats::default()
+        }
+    }
+
+    /// Get all entries, in morpheme ID order
+    pub fn entries(&self) -> &[DictEntry] {
+        &self.entries
     }
+
+    /// Get the entry with the given morpheme ID
+    ///
+    /// # Arguments
+    /// * `morph_id` - Morpheme ID, as in `DictEntry::morph_id`
+    ///
+    /// # Returns
+    /// * `Some(&DictEntry)` - The entry
+    /// * `None` - If the ID is out of range
+    pub fn entry(&self, morph_id: usize) -> Option<&DictEntry> {
+        self.entries.get(morph_id)
+    }
+
+    /// Look up the entries whose surface is exactly the given string
+    ///
+    /// # Arguments
+    /// * `surface` - Surface form string to look up
+    ///
+    /// # Returns
+    /// * `Ok(Vec<&DictEntry>)` - Homographs of the surface, in morpheme ID order
+    /// * `Err(RunomeError)` - Error if lookup fails
+    pub fn lookup_exact(&self, surface: &str) -> Result<Vec<&DictEntry>, RunomeError> {
+        let (_, index_ids) = self.matcher.run(surface, false)?;
+        Ok(index_ids
+            .into_iter()
+            .flat_map(|index_id| self.lookup_morpheme_ids(index_id))
+            .filter_map(|morpheme_id| self.entries.get(morpheme_id as usize))
+            .collect())
+    }
+
+    /// Get the number of entries sharing a surface form
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of homographs, 0 for an unknown surface
+    /// * `Err(RunomeError)` - Error if lookup fails
+    pub fn homograph_count(&self, surface: &str) -> Result<usize, RunomeError> {
+        Ok(self.lookup_exact(surface)?.len())
+    }
+
     /// Decode FST index ID to morpheme IDs using separate morpheme index
     fn lookup_morpheme_ids(&self, index_id: u64) -> Vec<u32> {
         if let Some(morpheme_ids) = self.morpheme_index.get(index_id as usize) {
@@ -323,15 +478,9 @@ impl Dictionary for UserDictionary {
 
     fn get_trans_cost(&self, left_id: u16, right_id: u16) -> Result<i16, RunomeError> {
         // Delegate to system dictionary connections
-        if let Some(row) = self.connections.get(left_id as usize) {
-            if let Some(cost) = row.get(right_id as usize) {
-                Ok(*cost)
-            } else {
-                Err(RunomeError::InvalidConnectionId { left_id, right_id })
-            }
-        } else {
-            Err(RunomeError::InvalidConnectionId { left_id, right_id })
-        }
+        self.connections
+            .get(left_id, right_id)
+            .ok_or(RunomeError::InvalidConnectionId { left_id, right_id })
     }
 }
 
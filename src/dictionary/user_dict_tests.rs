@@ -2,7 +2,7 @@ use std::io::Write;
 use std::path::Path;
 use std::sync::Arc;
 
-use crate::dictionary::{DictEntry, UserDictFormat, UserDictionary};
+use crate::dictionary::{ConnectionMatrix, DictEntry, UserDictFormat, UserDictionary};
 use crate::error::RunomeError;
 
 /// Helper function to create a temporary CSV file with given content
@@ -15,9 +15,11 @@ fn create_temp_csv(content: &str) -> tempfile::NamedTempFile {
 }
 
 /// Helper function to create mock connection matrix
-fn create_mock_connections() -> Arc<Vec<Vec<i16>>> {
+fn create_mock_connections() -> Arc<ConnectionMatrix> {
     // Create a simple 3x3 connection matrix for testing
-    Arc::new(vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]])
+    Arc::new(
+        ConnectionMatrix::from_rows(vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]]).unwrap(),
+    )
 }
 
 #[cfg(test)]
@@ -208,10 +210,11 @@ mod csv_parsing_tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            RunomeError::UserDictError { reason } => {
-                assert!(reason.contains("Failed to read CSV file"));
+            RunomeError::FileIo { path, source } => {
+                assert_eq!(path, nonexistent_path);
+                assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
             }
-            _ => panic!("Expected UserDictError"),
+            other => panic!("Expected FileIo, got {:?}", other),
         }
     }
 }
@@ -543,6 +546,21 @@ mod integration_tests {
         assert_eq!(user_dict.entries.len(), 2);
     }
 
+    #[test]
+    fn test_user_dictionary_from_simpledic_csv() {
+        let csv_content = "東京スカイツリー,カスタム名詞,トウキョウスカイツリー";
+
+        let temp_file = create_temp_csv(csv_content);
+        let connections = create_mock_connections();
+
+        let user_dict = UserDictionary::from_simpledic_csv(temp_file.path(), connections).unwrap();
+        assert_eq!(user_dict.entries.len(), 1);
+        assert_eq!(user_dict.entries[0].left_id, 0);
+        assert_eq!(user_dict.entries[0].right_id, 0);
+        assert_eq!(user_dict.entries[0].cost, -32000);
+        assert_eq!(user_dict.entries[0].part_of_speech, "カスタム名詞,*,*,*");
+    }
+
     #[test]
     fn test_user_dictionary_creation_invalid_file() {
         let nonexistent_path = Path::new("/nonexistent/file.csv");
@@ -886,6 +904,31 @@ mod dictionary_lookup_tests {
         assert_eq!(results[0].reading, "トウキョウスカイツリー");
         assert_eq!(results[0].phonetic, "トウキョウスカイツリー");
     }
+
+    #[test]
+    fn test_introspection() {
+        let csv_content = "\
+東京,1288,1288,4569,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トウキョウ
+東京,1285,1285,4000,名詞,固有名詞,人名,一般,*,*,東京,トウキョウ,トウキョウ
+東京タワー,1288,1288,3000,名詞,固有名詞,一般,*,*,*,東京タワー,トウキョウタワー,トウキョウタワー";
+
+        let temp_file = create_temp_csv(csv_content);
+        let connections = create_mock_connections();
+        let user_dict =
+            UserDictionary::new(temp_file.path(), UserDictFormat::Ipadic, connections).unwrap();
+
+        assert_eq!(user_dict.entries().len(), 3);
+        assert_eq!(user_dict.entry(2).unwrap().surface, "東京タワー");
+        assert!(user_dict.entry(3).is_none());
+
+        // Exact lookup leaves out "東京" entries found as a prefix
+        let exact = user_dict.lookup_exact("東京タワー").unwrap();
+        assert_eq!(exact.len(), 1);
+        assert_eq!(user_dict.lookup("東京タワー").unwrap().len(), 3);
+        assert_eq!(user_dict.homograph_count("東京").unwrap(), 2);
+        assert_eq!(user_dict.homograph_count("東").unwrap(), 0);
+        assert_eq!(user_dict.homograph_count("").unwrap(), 0);
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,65 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
 use thiserror::Error;
 
+/// Stable, machine-readable classification of a `RunomeError`
+///
+/// Several variants share a code when an application would handle them the
+/// same way. Codes and their string forms don't change between releases,
+/// unlike error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The dictionary directory or a required dictionary file doesn't exist
+    DictionaryMissing,
+    /// Dictionary data can't be decoded or is inconsistent
+    DictionaryCorrupt,
+    /// The dictionary was built by an incompatible version of runome
+    DictionaryVersion,
+    /// A user dictionary source is malformed
+    UserDictionary,
+    /// An argument or configuration value is invalid
+    InvalidArgument,
+    /// Text input, such as annotations or a gold corpus, is malformed
+    InvalidInput,
+    /// The input exceeds a configured limit
+    InputTooLarge,
+    /// Tokenization exceeded its time budget
+    Timeout,
+    /// A char filter, token filter or analyzer failed
+    Filter,
+    /// Reading or writing a file failed
+    Io,
+    /// An internal invariant was broken
+    Internal,
+}
+
+impl ErrorCode {
+    /// Get the string form of the code, such as `"dictionary_missing"`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::DictionaryMissing => "dictionary_missing",
+            ErrorCode::DictionaryCorrupt => "dictionary_corrupt",
+            ErrorCode::DictionaryVersion => "dictionary_version",
+            ErrorCode::UserDictionary => "user_dictionary",
+            ErrorCode::InvalidArgument => "invalid_argument",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::InputTooLarge => "input_too_large",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::Filter => "filter",
+            ErrorCode::Io => "io",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum RunomeError {
     // Dictionary loading errors
@@ -16,6 +76,37 @@ pub enum RunomeError {
         source: bincode::Error,
     },
 
+    #[error(
+        "Dictionary {component} has format version {found}, expected {expected}; rebuild the dictionary with this version of runome"
+    )]
+    DictFormatVersionMismatch {
+        component: String,
+        expected: u32,
+        found: u32,
+    },
+
+    #[error(
+        "Checksum mismatch for dictionary file {filename}: expected {expected}, found {found}; the file may be corrupted or truncated"
+    )]
+    DictChecksumMismatch {
+        filename: String,
+        expected: String,
+        found: String,
+    },
+
+    #[error("Failed to serialize dictionary {component}: {source}")]
+    DictSerializationError {
+        component: String,
+        #[source]
+        source: bincode::Error,
+    },
+
+    #[error("Invalid FST: {source}")]
+    InvalidFst {
+        #[source]
+        source: fst::Error,
+    },
+
     #[error("Invalid connection matrix access: left_id={left_id}, right_id={right_id}")]
     InvalidConnectionId { left_id: u16, right_id: u16 },
 
@@ -35,8 +126,12 @@ pub enum RunomeError {
     #[error("CSV parsing error at line {line}: {reason}")]
     CsvParseError { line: usize, reason: String },
 
-    #[error("FST building error: {reason}")]
-    FstBuildError { reason: String },
+    #[error("FST building error: {reason}: {source}")]
+    FstBuildError {
+        reason: String,
+        #[source]
+        source: fst::Error,
+    },
 
     // CharFilter errors
     #[error("Invalid regex pattern: {pattern}")]
@@ -52,6 +147,9 @@ pub enum RunomeError {
     #[error("CharFilter error: {message}")]
     CharFilterError { message: String },
 
+    #[error("Invalid argument: {reason}")]
+    InvalidArgument { reason: String },
+
     // Analyzer errors
     #[error("Analyzer error: {message}")]
     AnalyzerError { message: String },
@@ -59,13 +157,232 @@ pub enum RunomeError {
     #[error("Invalid tokenizer configuration: {reason}")]
     InvalidTokenizerConfig { reason: String },
 
+    #[error("Global configuration error: {reason}")]
+    GlobalConfigError { reason: String },
+
     #[error("Filter chain error: {message}")]
     FilterChainError { message: String },
 
+    #[error("Tokenization timed out after {timeout:?}")]
+    TokenizeTimeout { timeout: std::time::Duration },
+
+    #[error("More than {limit} lattice candidates start at byte {position}")]
+    CandidateLimitExceeded { position: usize, limit: usize },
+
+    #[error("Annotated input parse error at line {line}: {reason}")]
+    AnnotationParseError { line: usize, reason: String },
+
+    #[error("Invalid fixed span {start}..{end}: {reason}")]
+    InvalidSpanConstraint {
+        start: usize,
+        end: usize,
+        reason: String,
+    },
+
+    // Evaluation errors
+    #[error("Gold corpus parse error at line {line}: {reason}")]
+    GoldCorpusParseError { line: usize, reason: String },
+
     // General IO errors
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
-    // Future: Add variants for other components (FST, tokenizer, etc.)
+
+    #[error("IO error on {}: {source}", path.display())]
+    FileIo {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    // Context
+    #[error("{}: {source}", path.display())]
+    InFile {
+        path: PathBuf,
+        #[source]
+        source: Box<RunomeError>,
+    },
+}
+
+impl RunomeError {
+    /// Get the stable code classifying this error
+    ///
+    /// Errors wrapped with a file path report the code of the wrapped error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            RunomeError::DictDirectoryNotFound { .. } | RunomeError::DictFileMissing { .. } => {
+                ErrorCode::DictionaryMissing
+            }
+            RunomeError::DictDeserializationError { .. }
+            | RunomeError::DictChecksumMismatch { .. }
+            | RunomeError::InvalidFst { .. }
+            | RunomeError::InvalidConnectionId { .. }
+            | RunomeError::DictValidationError { .. }
+            | RunomeError::CharClassificationError { .. }
+            | RunomeError::SystemDictInitError { .. } => ErrorCode::DictionaryCorrupt,
+            RunomeError::DictFormatVersionMismatch { .. } => ErrorCode::DictionaryVersion,
+            RunomeError::UserDictError { .. }
+            | RunomeError::CsvParseError { .. }
+            | RunomeError::FstBuildError { .. } => ErrorCode::UserDictionary,
+            RunomeError::InvalidRegexPattern { .. }
+            | RunomeError::InvalidNormalizationForm { .. }
+            | RunomeError::InvalidArgument { .. }
+            | RunomeError::InvalidTokenizerConfig { .. }
+            | RunomeError::GlobalConfigError { .. }
+            | RunomeError::InvalidSpanConstraint { .. } => ErrorCode::InvalidArgument,
+            RunomeError::AnnotationParseError { .. } | RunomeError::GoldCorpusParseError { .. } => {
+                ErrorCode::InvalidInput
+            }
+            RunomeError::CandidateLimitExceeded { .. } => ErrorCode::InputTooLarge,
+            RunomeError::TokenizeTimeout { .. } => ErrorCode::Timeout,
+            RunomeError::CharFilterError { .. }
+            | RunomeError::AnalyzerError { .. }
+            | RunomeError::FilterChainError { .. } => ErrorCode::Filter,
+            RunomeError::Io(_) | RunomeError::FileIo { .. } => ErrorCode::Io,
+            RunomeError::DictSerializationError { .. } => ErrorCode::Internal,
+            RunomeError::InFile { source, .. } => source.code(),
+        }
+    }
+
+    /// Get the file the error is about, if known
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            RunomeError::DictDirectoryNotFound { path } => Some(Path::new(path)),
+            RunomeError::FileIo { path, .. } | RunomeError::InFile { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Get the line of the input the error is about, if known
+    ///
+    /// For user dictionaries this is the number of the entry, counting from 1.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            RunomeError::CsvParseError { line, .. }
+            | RunomeError::AnnotationParseError { line, .. }
+            | RunomeError::GoldCorpusParseError { line, .. } => Some(*line),
+            RunomeError::InFile { source, .. } => source.line(),
+            _ => None,
+        }
+    }
+
+    /// Get the error wrapped by file path context, or this error itself
+    ///
+    /// Match on the root error to handle specific variants regardless of
+    /// the context attached to them.
+    pub fn root(&self) -> &RunomeError {
+        match self {
+            RunomeError::InFile { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// Attach the path of the file being processed to the error
+    ///
+    /// Errors that already name a file are returned unchanged.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file the error occurred in
+    pub fn with_path(self, path: impl Into<PathBuf>) -> Self {
+        if self.path().is_some() {
+            return self;
+        }
+        match self {
+            RunomeError::Io(source) => RunomeError::FileIo {
+                path: path.into(),
+                source,
+            },
+            error => RunomeError::InFile {
+                path: path.into(),
+                source: Box::new(error),
+            },
+        }
+    }
+}
+
+/// Attach a file path to the error of a result
+pub(crate) trait PathContext<T> {
+    fn with_path(self, path: &Path) -> Result<T>;
+}
+
+impl<T> PathContext<T> for std::io::Result<T> {
+    fn with_path(self, path: &Path) -> Result<T> {
+        self.map_err(|source| RunomeError::FileIo {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+impl<T> PathContext<T> for Result<T> {
+    fn with_path(self, path: &Path) -> Result<T> {
+        self.map_err(|error| error.with_path(path))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, RunomeError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_error_codes() {
+        let missing = RunomeError::DictDirectoryNotFound {
+            path: "/no/sysdic".to_string(),
+        };
+        assert_eq!(missing.code(), ErrorCode::DictionaryMissing);
+        assert_eq!(missing.path(), Some(Path::new("/no/sysdic")));
+
+        let too_large = RunomeError::CandidateLimitExceeded {
+            position: 3,
+            limit: 10,
+        };
+        assert_eq!(too_large.code(), ErrorCode::InputTooLarge);
+        assert_eq!(too_large.code().as_str(), "input_too_large");
+        assert_eq!(
+            ErrorCode::DictionaryCorrupt.to_string(),
+            "dictionary_corrupt"
+        );
+    }
+
+    #[test]
+    fn test_with_path() {
+        let error = RunomeError::CsvParseError {
+            line: 3,
+            reason: "Expected 13 fields, got 6".to_string(),
+        }
+        .with_path("user.csv");
+        assert_eq!(error.code(), ErrorCode::UserDictionary);
+        assert_eq!(error.path(), Some(Path::new("user.csv")));
+        assert_eq!(error.line(), Some(3));
+        assert_eq!(
+            error.to_string(),
+            "user.csv: CSV parsing error at line 3: Expected 13 fields, got 6"
+        );
+        assert!(matches!(error.root(), RunomeError::CsvParseError { .. }));
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "CSV parsing error at line 3: Expected 13 fields, got 6"
+        );
+
+        // The innermost path is kept
+        let error = error.with_path("other.csv");
+        assert_eq!(error.path(), Some(Path::new("user.csv")));
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        let error = RunomeError::Io(io).with_path("entries.bin");
+        assert!(matches!(error, RunomeError::FileIo { .. }));
+        assert_eq!(error.code(), ErrorCode::Io);
+        assert!(error.source().unwrap().is::<std::io::Error>());
+    }
+
+    #[test]
+    fn test_path_context() {
+        let path = Path::new("/nonexistent/dic.fst");
+        let result: Result<Vec<u8>> = std::fs::read(path).with_path(path);
+        let error = result.unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Io);
+        assert_eq!(error.path(), Some(Path::new("/nonexistent/dic.fst")));
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::error::PathContext;
+use crate::{RunomeError, Token, TokenizeResult, Tokenizer};
+
+/// Format of a gold-standard segmented corpus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldFormat {
+    /// One sentence per line with words separated by whitespace (no POS)
+    Wakati,
+    /// One word per line as `surface\tpos`, sentences terminated by `EOS` or a
+    /// blank line (BCCWJ short-unit / MeCab-like layouts)
+    ///
+    /// The POS column may hold comma-separated levels (`名詞,固有名詞`) and is
+    /// optional per line; further tab-separated columns are ignored.
+    Tabular,
+}
+
+/// A word of a gold-standard sentence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldToken {
+    pub surface: String,
+    pub pos: Option<String>,
+}
+
+/// A gold-standard segmented sentence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldSentence {
+    pub tokens: Vec<GoldToken>,
+}
+
+impl GoldSentence {
+    /// Get the unsegmented sentence text
+    pub fn text(&self) -> String {
+        self.tokens.iter().map(|t| t.surface.as_str()).collect()
+    }
+}
+
+/// Parse a gold-standard corpus
+///
+/// # Arguments
+/// * `content` - Corpus contents
+/// * `format` - Layout of the corpus
+///
+/// # Returns
+/// * `Ok(Vec<GoldSentence>)` - Parsed sentences, skipping empty ones
+/// * `Err(RunomeError)` - Error if a line has an empty surface
+pub fn parse_gold(content: &str, format: GoldFormat) -> Result<Vec<GoldSentence>, RunomeError> {
+    let mut sentences = Vec::new();
+    match format {
+        GoldFormat::Wakati => {
+            for line in content.lines() {
+                let tokens: Vec<GoldToken> = line
+                    .split_whitespace()
+                    .map(|surface| GoldToken {
+                        surface: surface.to_string(),
+                        pos: None,
+                    })
+                    .collect();
+                if !tokens.is_empty() {
+                    sentences.push(GoldSentence { tokens });
+                }
+            }
+        }
+        GoldFormat::Tabular => {
+            let mut tokens = Vec::new();
+            for (i, line) in content.lines().enumerate() {
+                let line = line.trim_end_matches('\r');
+                if line.trim().is_empty() || line == "EOS" {
+                    if !tokens.is_empty() {
+                        sentences.push(GoldSentence {
+                            tokens: std::mem::take(&mut tokens),
+                        });
+                    }
+                    continue;
+                }
+                let mut columns = line.split('\t');
+                let surface = columns.next().unwrap_or_default();
+                if surface.is_empty() {
+                    return Err(RunomeError::GoldCorpusParseError {
+                        line: i + 1,
+                        reason: "Empty surface".to_string(),
+                    });
+                }
+                let pos = columns
+                    .next()
+                    .filter(|pos| !pos.is_empty())
+                    .map(str::to_string);
+                tokens.push(GoldToken {
+                    surface: surface.to_string(),
+                    pos,
+                });
+            }
+            if !tokens.is_empty() {
+                sentences.push(GoldSentence { tokens });
+            }
+        }
+    }
+    Ok(sentences)
+}
+
+/// Segmentation and POS accuracy over a gold-standard corpus
+///
+/// Boundary scores count the character offsets between words inside each
+/// sentence (sentence start and end are excluded since they always agree).
+/// POS accuracy is measured over gold words that have a POS and that the
+/// tokenizer segmented identically; a gold POS matches when its levels are a
+/// prefix of the token's part of speech.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvalReport {
+    /// Number of evaluated sentences
+    pub sentences: usize,
+    /// Number of gold words
+    pub gold_tokens: usize,
+    /// Number of tokens produced by the tokenizer
+    pub system_tokens: usize,
+    /// Number of tokens whose span matches a gold word exactly
+    pub matched_tokens: usize,
+    /// Number of gold word boundaries
+    pub gold_boundaries: usize,
+    /// Number of tokenizer boundaries
+    pub system_boundaries: usize,
+    /// Number of tokenizer boundaries that are also gold boundaries
+    pub correct_boundaries: usize,
+    /// Number of matched words with a gold POS
+    pub pos_evaluated: usize,
+    /// Number of matched words whose POS agrees with the gold POS
+    pub pos_correct: usize,
+}
+
+impl EvalReport {
+    /// Boundary precision (1.0 when the tokenizer produced no boundaries)
+    pub fn boundary_precision(&self) -> f64 {
+        ratio(self.correct_boundaries, self.system_boundaries)
+    }
+
+    /// Boundary recall (1.0 when the gold corpus has no boundaries)
+    pub fn boundary_recall(&self) -> f64 {
+        ratio(self.correct_boundaries, self.gold_boundaries)
+    }
+
+    /// Boundary F1 score
+    pub fn boundary_f1(&self) -> f64 {
+        f1(self.boundary_precision(), self.boundary_recall())
+    }
+
+    /// Word-level precision, counting tokens whose span matches a gold word
+    pub fn token_precision(&self) -> f64 {
+        ratio(self.matched_tokens, self.system_tokens)
+    }
+
+    /// Word-level recall, counting gold words reproduced by the tokenizer
+    pub fn token_recall(&self) -> f64 {
+        ratio(self.matched_tokens, self.gold_tokens)
+    }
+
+    /// Word-level F1 score
+    pub fn token_f1(&self) -> f64 {
+        f1(self.token_precision(), self.token_recall())
+    }
+
+    /// POS accuracy, or `None` if no matched word has a gold POS
+    pub fn pos_accuracy(&self) -> Option<f64> {
+        (self.pos_evaluated > 0).then(|| self.pos_correct as f64 / self.pos_evaluated as f64)
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        1.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+fn f1(precision: f64, recall: f64) -> f64 {
+    if precision + recall == 0.0 {
+        0.0
+    } else {
+        2.0 * precision * recall / (precision + recall)
+    }
+}
+
+/// Evaluates a tokenizer against gold-standard segmented corpora
+///
+/// # Example
+/// ```rust,no_run
+/// use runome::{Evaluator, Tokenizer};
+/// use runome::eval::{GoldFormat, parse_gold};
+///
+/// let tokenizer = Tokenizer::new(None, None).unwrap();
+/// let gold = parse_gold("すもも も もも も もも の うち\n", GoldFormat::Wakati).unwrap();
+/// let report = Evaluator::new(&tokenizer).evaluate(&gold).unwrap();
+/// println!("boundary F1: {:.4}", report.boundary_f1());
+/// ```
+pub struct Evaluator<'a> {
+    tokenizer: &'a Tokenizer,
+}
+
+impl<'a> Evaluator<'a> {
+    /// Create a new Evaluator using the given tokenizer
+    pub fn new(tokenizer: &'a Tokenizer) -> Self {
+        Self { tokenizer }
+    }
+
+    /// Tokenize every gold sentence and score the result
+    ///
+    /// # Arguments
+    /// * `sentences` - Gold-standard sentences
+    ///
+    /// # Returns
+    /// * `Ok(EvalReport)` - Accumulated scores
+    /// * `Err(RunomeError)` - Error if tokenization fails
+    pub fn evaluate(&self, sentences: &[GoldSentence]) -> Result<EvalReport, RunomeError> {
+        let mut report = EvalReport::default();
+        for sentence in sentences {
+            let text = sentence.text();
+            let mut tokens = Vec::new();
+            for result in self.tokenizer.tokenize(&text, Some(false), None) {
+                if let TokenizeResult::Token(token) = result? {
+                    tokens.push(token);
+                }
+            }
+            score_sentence(&mut report, sentence, &tokens);
+        }
+        Ok(report)
+    }
+
+    /// Read a gold-standard corpus file and score the tokenizer on it
+    ///
+    /// # Arguments
+    /// * `path` - Path to a UTF-8 corpus file
+    /// * `format` - Layout of the corpus
+    ///
+    /// # Returns
+    /// * `Ok(EvalReport)` - Accumulated scores
+    /// * `Err(RunomeError)` - Error if reading, parsing or tokenization fails
+    pub fn evaluate_file(
+        &self,
+        path: &Path,
+        format: GoldFormat,
+    ) -> Result<EvalReport, RunomeError> {
+        let content = fs::read_to_string(path).with_path(path)?;
+        self.evaluate(&parse_gold(&content, format).with_path(path)?)
+    }
+}
+
+/// Character spans of consecutive surfaces
+fn spans<'s>(surfaces: impl Iterator<Item = &'s str>) -> Vec<(usize, usize)> {
+    let mut start = 0;
+    surfaces
+        .map(|surface| {
+            let end = start + surface.chars().count();
+            let span = (start, end);
+            start = end;
+            span
+        })
+        .collect()
+}
+
+fn score_sentence(report: &mut EvalReport, sentence: &GoldSentence, tokens: &[Token]) {
+    let gold_spans = spans(sentence.tokens.iter().map(|t| t.surface.as_str()));
+    let system_spans = spans(tokens.iter().map(|t| t.surface()));
+
+    // Internal boundaries: the end of every word but the last
+    let boundaries = |spans: &[(usize, usize)]| -> BTreeSet<usize> {
+        spans.iter().rev().skip(1).map(|&(_, end)| end).collect()
+    };
+    let gold_boundaries = boundaries(&gold_spans);
+    let system_boundaries = boundaries(&system_spans);
+
+    report.sentences += 1;
+    report.gold_tokens += gold_spans.len();
+    report.system_tokens += system_spans.len();
+    report.gold_boundaries += gold_boundaries.len();
+    report.system_boundaries += system_boundaries.len();
+    report.correct_boundaries += gold_boundaries.intersection(&system_boundaries).count();
+
+    for (gold, span) in sentence.tokens.iter().zip(&gold_spans) {
+        let Ok(index) = system_spans.binary_search(span) else {
+            continue;
+        };
+        report.matched_tokens += 1;
+        if let Some(pos) = &gold.pos {
+            report.pos_evaluated += 1;
+            if pos_matches(pos, tokens[index].part_of_speech()) {
+                report.pos_correct += 1;
+            }
+        }
+    }
+}
+
+/// Check that the gold POS levels are a prefix of the system POS, ignoring `*`
+fn pos_matches(gold: &str, system: &str) -> bool {
+    let mut system_levels = system.split(',');
+    gold.split(',')
+        .filter(|level| *level != "*")
+        .all(|level| system_levels.next() == Some(level))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::NodeType;
+
+    fn token(surface: &str, pos: &str) -> Token {
+        Token::new(
+            surface.to_string(),
+            pos.to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            surface.to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            NodeType::SysDict,
+        )
+    }
+
+    #[test]
+    fn test_parse_gold_formats() {
+        let wakati = parse_gold("すもも も もも\n\n東京 へ\n", GoldFormat::Wakati).unwrap();
+        assert_eq!(wakati.len(), 2);
+        assert_eq!(wakati[0].text(), "すももももも");
+        assert_eq!(wakati[1].tokens[1].surface, "へ");
+
+        let tabular = parse_gold(
+            "すもも\t名詞,一般\tスモモ\nも\t助詞\nEOS\n東京\n\nへ\t助詞\n",
+            GoldFormat::Tabular,
+        )
+        .unwrap();
+        assert_eq!(tabular.len(), 3);
+        assert_eq!(tabular[0].tokens[0].pos.as_deref(), Some("名詞,一般"));
+        assert_eq!(tabular[1].tokens[0].pos, None);
+
+        let result = parse_gold("\t名詞\n", GoldFormat::Tabular);
+        assert!(matches!(
+            result,
+            Err(RunomeError::GoldCorpusParseError { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_score_sentence() {
+        let gold = parse_gold(
+            "すもも\t名詞\nも\t助詞\nもも\t名詞,一般\nEOS\n",
+            GoldFormat::Tabular,
+        )
+        .unwrap();
+        // Boundaries: gold {3, 4}, system {3, 5}; only "すもも" is segmented correctly
+        let tokens = vec![
+            token("すもも", "名詞,一般,*,*"),
+            token("もも", "助詞,係助詞,*,*"),
+            token("も", "名詞,一般,*,*"),
+        ];
+        let mut report = EvalReport::default();
+        score_sentence(&mut report, &gold[0], &tokens);
+
+        assert_eq!(report.gold_boundaries, 2);
+        assert_eq!(report.system_boundaries, 2);
+        assert_eq!(report.correct_boundaries, 1);
+        assert_eq!(report.boundary_f1(), 0.5);
+        assert_eq!(report.matched_tokens, 1);
+        assert_eq!(report.token_recall(), 1.0 / 3.0);
+        assert_eq!(report.pos_accuracy(), Some(1.0));
+    }
+
+    #[test]
+    fn test_pos_matches() {
+        assert!(pos_matches("名詞", "名詞,一般,*,*"));
+        assert!(pos_matches("名詞,一般,*", "名詞,一般,*,*"));
+        assert!(!pos_matches("名詞,固有名詞", "名詞,一般,*,*"));
+        assert!(!pos_matches("助詞", "名詞,一般,*,*"));
+    }
+
+    #[test]
+    fn test_evaluate_with_tokenizer() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!("Skipping test: sysdic directory not found");
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let gold = parse_gold("すもも も もも も もも の うち\n", GoldFormat::Wakati).unwrap();
+        let report = Evaluator::new(&tokenizer).evaluate(&gold).unwrap();
+        assert_eq!(report.sentences, 1);
+        assert_eq!(report.boundary_f1(), 1.0);
+        assert_eq!(report.token_f1(), 1.0);
+        assert_eq!(report.pos_accuracy(), None);
+    }
+}
@@ -0,0 +1,471 @@
+use std::io::{self, Write};
+
+use crate::Token;
+use crate::lattice::NodeType;
+
+/// Output formatter converting tokenized sentences into text
+///
+/// Each call formats one sentence (the tokens of one input text) including
+/// its terminator, so formatted sentences can be concatenated into a file.
+pub trait Formatter {
+    /// Format the tokens of one sentence
+    fn format(&self, tokens: &[Token]) -> String;
+
+    /// Write the formatted tokens of one sentence to a writer
+    fn write_to(&self, writer: &mut dyn Write, tokens: &[Token]) -> io::Result<()> {
+        writer.write_all(self.format(tokens).as_bytes())
+    }
+}
+
+/// ChaSen-compatible formatter, matching `mecab -Ochasen`
+///
+/// Each token is written as
+/// `surface\treading\tbase_form\tpos\tinfl_type\tinfl_form`, where the
+/// part-of-speech levels are joined with `-` and `*` fields are left empty.
+/// Unknown words use the surface as reading and base form. Each sentence ends
+/// with an `EOS` line.
+#[derive(Debug, Clone, Default)]
+pub struct ChasenFormatter;
+
+impl ChasenFormatter {
+    /// Create a new ChasenFormatter
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn format_token(token: &Token) -> String {
+        let pos = token
+            .part_of_speech()
+            .split(',')
+            .filter(|level| *level != "*")
+            .collect::<Vec<_>>()
+            .join("-");
+
+        let (reading, base_form) = if token.node_type() == NodeType::Unknown {
+            (token.surface(), token.surface())
+        } else {
+            (
+                empty_if_unset(token.reading()),
+                empty_if_unset(token.base_form()),
+            )
+        };
+
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            token.surface(),
+            reading,
+            base_form,
+            pos,
+            empty_if_unset(token.infl_type()),
+            empty_if_unset(token.infl_form())
+        )
+    }
+}
+
+/// ChaSen leaves unset (`*`) fields empty
+fn empty_if_unset(value: &str) -> &str {
+    if value == "*" { "" } else { value }
+}
+
+impl Formatter for ChasenFormatter {
+    fn format(&self, tokens: &[Token]) -> String {
+        let mut output = String::new();
+        for token in tokens {
+            output.push_str(&Self::format_token(token));
+            output.push('\n');
+        }
+        output.push_str("EOS\n");
+        output
+    }
+}
+
+/// MeCab-compatible formatter, matching `mecab`'s default output
+///
+/// Each token is written as
+/// `surface\tpos,infl_type,infl_form,base_form,reading,phonetic` and each
+/// sentence ends with an `EOS` line. As MeCab does with IPADIC, unknown words
+/// omit the base form, reading and phonetic fields, leaving seven features.
+#[derive(Debug, Clone, Default)]
+pub struct MecabFormatter;
+
+impl MecabFormatter {
+    /// Create a new MecabFormatter
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn format_token(token: &Token) -> String {
+        if token.node_type() == NodeType::Unknown {
+            format!(
+                "{}\t{},{},{},*",
+                token.surface(),
+                token.part_of_speech(),
+                token.infl_type(),
+                token.infl_form()
+            )
+        } else {
+            token.to_janome_string()
+        }
+    }
+}
+
+impl Formatter for MecabFormatter {
+    fn format(&self, tokens: &[Token]) -> String {
+        let mut output = String::new();
+        for token in tokens {
+            output.push_str(&Self::format_token(token));
+            output.push('\n');
+        }
+        output.push_str("EOS\n");
+        output
+    }
+}
+
+/// CoNLL-U formatter for Universal Dependencies tooling
+///
+/// Each sentence starts with a `# text =` comment and each token becomes a
+/// row of the ten CoNLL-U columns: ID, FORM, LEMMA (the base form, or the
+/// surface when unset), UPOS mapped from the IPADIC part of speech, XPOS (the
+/// IPADIC part of speech joined with `-`), and `_` for FEATS, HEAD, DEPREL
+/// and DEPS. Whitespace tokens are not written as rows; MISC is
+/// `SpaceAfter=No` unless the token is followed by whitespace. Each sentence
+/// ends with a blank line.
+#[derive(Debug, Clone, Default)]
+pub struct ConlluFormatter;
+
+impl ConlluFormatter {
+    /// Create a new ConlluFormatter
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Map an IPADIC part of speech to a Universal POS tag
+    pub fn upos(part_of_speech: &str) -> &'static str {
+        let mut levels = part_of_speech.split(',');
+        let major = levels.next().unwrap_or("");
+        let minor = levels.next().unwrap_or("*");
+        match (major, minor) {
+            ("名詞", "固有名詞") => "PROPN",
+            ("名詞", "代名詞") => "PRON",
+            ("名詞", "数") => "NUM",
+            ("名詞", "形容動詞語幹") => "ADJ",
+            ("名詞", _) | ("接頭詞", _) => "NOUN",
+            ("動詞", "非自立") => "AUX",
+            ("動詞", _) => "VERB",
+            ("形容詞", _) => "ADJ",
+            ("副詞", _) => "ADV",
+            ("連体詞", _) => "DET",
+            ("接続詞", _) => "CCONJ",
+            ("助詞", "接続助詞") => "SCONJ",
+            ("助詞", "終助詞") => "PART",
+            ("助詞", _) => "ADP",
+            ("助動詞", _) => "AUX",
+            ("感動詞", _) | ("フィラー", _) => "INTJ",
+            ("記号", "句点" | "読点" | "括弧開" | "括弧閉") => "PUNCT",
+            ("記号", _) => "SYM",
+            _ => "X",
+        }
+    }
+}
+
+impl Formatter for ConlluFormatter {
+    fn format(&self, tokens: &[Token]) -> String {
+        let is_space = |token: &Token| token.surface().trim().is_empty();
+        let text: String = tokens.iter().map(|token| token.surface()).collect();
+        let mut output = format!("# text = {}\n", text.trim());
+
+        let words: Vec<(usize, &Token)> = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| !is_space(token))
+            .collect();
+        for (id, (index, token)) in words.iter().enumerate() {
+            let lemma = match token.base_form() {
+                "*" => token.surface(),
+                base_form => base_form,
+            };
+            let xpos = token
+                .part_of_speech()
+                .split(',')
+                .filter(|level| *level != "*")
+                .collect::<Vec<_>>()
+                .join("-");
+            let space_after = tokens.get(index + 1).is_some_and(is_space);
+            output.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t_\t_\t_\t_\t{}\n",
+                id + 1,
+                token.surface(),
+                lemma,
+                Self::upos(token.part_of_speech()),
+                xpos,
+                if space_after { "_" } else { "SpaceAfter=No" }
+            ));
+        }
+        output.push('\n');
+        output
+    }
+}
+
+/// Janome-compatible formatter, matching `print(token)` for each token
+///
+/// Each token is written as `Token::to_janome_string` followed by a newline,
+/// byte-for-byte like Janome's `str(token)`. Janome prints no sentence
+/// terminator, so neither does this formatter.
+#[derive(Debug, Clone, Default)]
+pub struct JanomeFormatter;
+
+impl JanomeFormatter {
+    /// Create a new JanomeFormatter
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Formatter for JanomeFormatter {
+    fn format(&self, tokens: &[Token]) -> String {
+        let mut output = String::new();
+        for token in tokens {
+            output.push_str(&token.to_janome_string());
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// Wakati (space-separated surface forms) writer, matching `mecab -Owakati`
+///
+/// Surfaces are joined with the separator (a single space by default) and each
+/// sentence ends with a newline.
+#[derive(Debug, Clone)]
+pub struct WakatiWriter {
+    separator: String,
+}
+
+impl WakatiWriter {
+    /// Create a new WakatiWriter separating surfaces with a single space
+    pub fn new() -> Self {
+        Self {
+            separator: " ".to_string(),
+        }
+    }
+
+    /// Create a new WakatiWriter with a custom separator
+    pub fn with_separator(separator: &str) -> Self {
+        Self {
+            separator: separator.to_string(),
+        }
+    }
+
+    /// Format surface strings, e.g. the output of `tokenize` in wakati mode
+    pub fn format_surfaces<S: AsRef<str>>(&self, surfaces: &[S]) -> String {
+        let mut output = surfaces
+            .iter()
+            .map(|surface| surface.as_ref())
+            .collect::<Vec<_>>()
+            .join(&self.separator);
+        output.push('\n');
+        output
+    }
+}
+
+impl Default for WakatiWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for WakatiWriter {
+    fn format(&self, tokens: &[Token]) -> String {
+        let surfaces: Vec<&str> = tokens.iter().map(|token| token.surface()).collect();
+        self.format_surfaces(&surfaces)
+    }
+}
+
+/// Write items as JSON Lines, one JSON object per line
+///
+/// Works with any serializable stream, e.g. the `Token`s of an analysis or
+/// the `TokenizeResult`s of `Tokenizer::tokenize`, for piping results into
+/// data pipelines. Requires the `serde` feature.
+///
+/// # Arguments
+/// * `writer` - Destination of the JSON Lines output
+/// * `items` - Items to write, each on its own line
+///
+/// # Example
+/// ```rust,no_run
+/// use runome::{Tokenizer, formatter::write_json_lines};
+///
+/// let tokenizer = Tokenizer::new(None, None).unwrap();
+/// let results = tokenizer.tokenize("すもももももももものうち", None, None);
+/// let tokens: Vec<_> = results.collect::<Result<_, _>>().unwrap();
+/// write_json_lines(&mut std::io::stdout(), &tokens).unwrap();
+/// ```
+#[cfg(feature = "serde")]
+pub fn write_json_lines<I>(writer: &mut dyn Write, items: I) -> io::Result<()>
+where
+    I: IntoIterator,
+    I::Item: serde::Serialize,
+{
+    for item in items {
+        serde_json::to_writer(&mut *writer, &item)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tokens() -> Vec<Token> {
+        vec![
+            Token::new(
+                "すもも".to_string(),
+                "名詞,一般,*,*".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                "すもも".to_string(),
+                "スモモ".to_string(),
+                "スモモ".to_string(),
+                NodeType::SysDict,
+            ),
+            Token::new(
+                "食べ".to_string(),
+                "動詞,自立,*,*".to_string(),
+                "一段".to_string(),
+                "連用形".to_string(),
+                "食べる".to_string(),
+                "タベ".to_string(),
+                "タベ".to_string(),
+                NodeType::SysDict,
+            ),
+            Token::new(
+                "ＸＹＺ".to_string(),
+                "名詞,固有名詞,組織,*".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                NodeType::Unknown,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_chasen_formatter() {
+        let output = ChasenFormatter::new().format(&sample_tokens());
+        assert_eq!(
+            output,
+            "すもも\tスモモ\tすもも\t名詞-一般\t\t\n\
+             食べ\tタベ\t食べる\t動詞-自立\t一段\t連用形\n\
+             ＸＹＺ\tＸＹＺ\tＸＹＺ\t名詞-固有名詞-組織\t\t\n\
+             EOS\n"
+        );
+    }
+
+    #[test]
+    fn test_chasen_formatter_empty_sentence() {
+        assert_eq!(ChasenFormatter::new().format(&[]), "EOS\n");
+    }
+
+    #[test]
+    fn test_mecab_formatter() {
+        let output = MecabFormatter::new().format(&sample_tokens());
+        assert_eq!(
+            output,
+            "すもも\t名詞,一般,*,*,*,*,すもも,スモモ,スモモ\n\
+             食べ\t動詞,自立,*,*,一段,連用形,食べる,タベ,タベ\n\
+             ＸＹＺ\t名詞,固有名詞,組織,*,*,*,*\n\
+             EOS\n"
+        );
+        assert_eq!(MecabFormatter::new().format(&[]), "EOS\n");
+    }
+
+    #[test]
+    fn test_conllu_formatter() {
+        let mut tokens = sample_tokens();
+        tokens.insert(
+            2,
+            Token::new(
+                " ".to_string(),
+                "記号,空白,*,*".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                " ".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                NodeType::Unknown,
+            ),
+        );
+        let output = ConlluFormatter::new().format(&tokens);
+        assert_eq!(
+            output,
+            "# text = すもも食べ ＸＹＺ\n\
+             1\tすもも\tすもも\tNOUN\t名詞-一般\t_\t_\t_\t_\tSpaceAfter=No\n\
+             2\t食べ\t食べる\tVERB\t動詞-自立\t_\t_\t_\t_\t_\n\
+             3\tＸＹＺ\tＸＹＺ\tPROPN\t名詞-固有名詞-組織\t_\t_\t_\t_\tSpaceAfter=No\n\
+             \n"
+        );
+        assert_eq!(ConlluFormatter::upos("助詞,係助詞,*,*"), "ADP");
+        assert_eq!(ConlluFormatter::upos("動詞,非自立,*,*"), "AUX");
+        assert_eq!(ConlluFormatter::upos("記号,句点,*,*"), "PUNCT");
+    }
+
+    #[test]
+    fn test_janome_formatter() {
+        let output = JanomeFormatter::new().format(&sample_tokens());
+        assert_eq!(
+            output,
+            "すもも\t名詞,一般,*,*,*,*,すもも,スモモ,スモモ\n\
+             食べ\t動詞,自立,*,*,一段,連用形,食べる,タベ,タベ\n\
+             ＸＹＺ\t名詞,固有名詞,組織,*,*,*,*,*,*\n"
+        );
+        assert_eq!(JanomeFormatter::new().format(&[]), "");
+    }
+
+    #[test]
+    fn test_wakati_writer() {
+        let writer = WakatiWriter::new();
+        assert_eq!(writer.format(&sample_tokens()), "すもも 食べ ＸＹＺ\n");
+        assert_eq!(writer.format_surfaces(&["すもも", "も"]), "すもも も\n");
+
+        let writer = WakatiWriter::with_separator("/");
+        assert_eq!(writer.format(&sample_tokens()), "すもも/食べ/ＸＹＺ\n");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_write_json_lines() {
+        let tokens = sample_tokens();
+        let mut buffer = Vec::new();
+        write_json_lines(&mut buffer, &tokens).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            r#"{"surface":"すもも","part_of_speech":"名詞,一般,*,*","infl_type":"*","infl_form":"*","base_form":"すもも","reading":"スモモ","phonetic":"スモモ","node_type":"SysDict","span":null}"#
+        );
+
+        let parsed: Vec<Token> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed, tokens);
+    }
+
+    #[test]
+    fn test_write_to() {
+        let mut buffer = Vec::new();
+        WakatiWriter::new()
+            .write_to(&mut buffer, &sample_tokens())
+            .unwrap();
+        ChasenFormatter::new().write_to(&mut buffer, &[]).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "すもも 食べ ＸＹＺ\nEOS\n"
+        );
+    }
+}
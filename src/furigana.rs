@@ -0,0 +1,349 @@
+//! Furigana (ruby) annotation
+//!
+//! `furigana` splits a text into segments that together cover it exactly,
+//! attaching a hiragana reading to the segments containing kanji. Readings
+//! come from the dictionary, so words the dictionary does not know are left
+//! unannotated. Okurigana are split off where the reading allows it, so
+//! "食べる" becomes "食" (た) followed by plain "べる".
+
+use std::ops::Range;
+
+use crate::error::RunomeError;
+use crate::kana;
+use crate::tokenizer::Tokenizer;
+
+/// Readings longer than this are not aligned to the surface, bounding the
+/// backtracking in `align`
+const MAX_ALIGN_LEN: usize = 64;
+
+/// A segment of annotated text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruby {
+    /// Byte range of the segment in the input text
+    pub span: Range<usize>,
+    /// Text of the segment, as in the input
+    pub base: String,
+    /// Reading in hiragana, for segments containing kanji
+    pub reading: Option<String>,
+}
+
+/// Generate furigana for a text
+///
+/// # Arguments
+/// * `tokenizer` - Tokenizer providing the readings
+/// * `text` - Input text
+///
+/// # Returns
+/// * `Ok(Vec<Ruby>)` - Segments in text order; their spans cover the whole text
+/// * `Err(RunomeError)` - Error if tokenization fails
+///
+/// # Example
+/// ```rust,no_run
+/// use runome::{Tokenizer, furigana};
+///
+/// let tokenizer = Tokenizer::new(None, None).unwrap();
+/// let ruby = furigana(&tokenizer, "漢字を読む").unwrap();
+/// assert_eq!(ruby[0].base, "漢字");
+/// assert_eq!(ruby[0].reading.as_deref(), Some("かんじ"));
+/// assert_eq!(ruby[1].base, "を");
+/// assert_eq!(ruby[1].reading, None);
+/// ```
+pub fn furigana(tokenizer: &Tokenizer, text: &str) -> Result<Vec<Ruby>, RunomeError> {
+    let mut segments = Vec::new();
+    let mut cursor = 0;
+    for token in tokenizer.tokenize_to_vec(text)? {
+        let Some(span) = token.span() else {
+            continue;
+        };
+        if span.start < cursor {
+            continue;
+        }
+        push_plain(&mut segments, text, cursor..span.start);
+        cursor = span.end;
+
+        let base = &text[span.clone()];
+        let reading = token.reading();
+        if !base.chars().any(is_kanji) || reading.is_empty() || reading == "*" {
+            push_plain(&mut segments, text, span);
+            continue;
+        }
+
+        let reading = kana::katakana_to_hiragana(reading);
+        // A surface changed by normalization cannot be aligned to the input
+        let aligned = if token.surface() == base {
+            align(base, &reading)
+        } else {
+            None
+        };
+        match aligned {
+            Some(parts) => {
+                for (range, part_reading) in parts {
+                    let range = span.start + range.start..span.start + range.end;
+                    match part_reading {
+                        Some(part_reading) => segments.push(Ruby {
+                            base: text[range.clone()].to_string(),
+                            span: range,
+                            reading: Some(part_reading),
+                        }),
+                        None => push_plain(&mut segments, text, range),
+                    }
+                }
+            }
+            None => segments.push(Ruby {
+                base: base.to_string(),
+                span,
+                reading: Some(reading),
+            }),
+        }
+    }
+    push_plain(&mut segments, text, cursor..text.len());
+    Ok(segments)
+}
+
+/// Render furigana segments as HTML `<ruby>` markup
+///
+/// Annotated segments become `<ruby>base<rt>reading</rt></ruby>`; other
+/// segments are written as escaped text.
+///
+/// # Arguments
+/// * `segments` - Segments returned by `furigana`
+///
+/// # Returns
+/// * HTML fragment
+pub fn to_ruby_html(segments: &[Ruby]) -> String {
+    let mut html = String::new();
+    for segment in segments {
+        match &segment.reading {
+            Some(reading) => {
+                html.push_str("<ruby>");
+                html.push_str(&html_escape(&segment.base));
+                html.push_str("<rt>");
+                html.push_str(&html_escape(reading));
+                html.push_str("</rt></ruby>");
+            }
+            None => html.push_str(&html_escape(&segment.base)),
+        }
+    }
+    html
+}
+
+/// Append an unannotated segment, merging it with a preceding one
+fn push_plain(segments: &mut Vec<Ruby>, text: &str, span: Range<usize>) {
+    if span.is_empty() {
+        return;
+    }
+    if let Some(last) = segments.last_mut()
+        && last.reading.is_none()
+        && last.span.end == span.start
+    {
+        last.span.end = span.end;
+        last.base.push_str(&text[span]);
+        return;
+    }
+    segments.push(Ruby {
+        base: text[span.clone()].to_string(),
+        span,
+        reading: None,
+    });
+}
+
+/// Split a surface into kanji and non-kanji runs and match them to the reading
+///
+/// Non-kanji runs must appear in the reading as is (compared in hiragana) and
+/// each kanji run takes the reading in between.
+///
+/// # Returns
+/// * Byte ranges in the surface with the reading of each kanji run, or `None`
+///   if the reading does not fit the surface
+fn align(surface: &str, reading: &str) -> Option<Vec<(Range<usize>, Option<String>)>> {
+    let reading: Vec<char> = reading.chars().collect();
+    if reading.len() > MAX_ALIGN_LEN {
+        return None;
+    }
+
+    let mut runs: Vec<(Range<usize>, bool)> = Vec::new();
+    for (i, c) in surface.char_indices() {
+        let kanji = is_kanji(c);
+        match runs.last_mut() {
+            Some((range, run_kanji)) if *run_kanji == kanji => range.end = i + c.len_utf8(),
+            _ => runs.push((i..i + c.len_utf8(), kanji)),
+        }
+    }
+
+    let mut parts = Vec::with_capacity(runs.len());
+    if align_runs(surface, &runs, &reading, 0, &mut parts) {
+        Some(parts)
+    } else {
+        None
+    }
+}
+
+/// Backtracking step of `align`, matching `runs` against `reading[pos..]`
+fn align_runs(
+    surface: &str,
+    runs: &[(Range<usize>, bool)],
+    reading: &[char],
+    pos: usize,
+    parts: &mut Vec<(Range<usize>, Option<String>)>,
+) -> bool {
+    let Some(((range, kanji), rest)) = runs.split_first() else {
+        return pos == reading.len();
+    };
+
+    if !kanji {
+        let mut end = pos;
+        for c in surface[range.clone()].chars() {
+            if reading.get(end) != Some(&kana::katakana_to_hiragana_char(c)) {
+                return false;
+            }
+            end += 1;
+        }
+        parts.push((range.clone(), None));
+        if align_runs(surface, rest, reading, end, parts) {
+            return true;
+        }
+        parts.pop();
+        return false;
+    }
+
+    let min_end = if rest.is_empty() {
+        reading.len()
+    } else {
+        pos + 1
+    };
+    for end in min_end.max(pos + 1)..=reading.len() {
+        parts.push((
+            range.clone(),
+            Some(reading[pos..end].iter().collect::<String>()),
+        ));
+        if align_runs(surface, rest, reading, end, parts) {
+            return true;
+        }
+        parts.pop();
+    }
+    false
+}
+
+/// Check whether a character is a kanji, including the iteration mark "々"
+fn is_kanji(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FFFF}'
+        | '々'
+        | '〆'
+        | 'ヶ')
+}
+
+/// Escape text for HTML content
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aligned(surface: &str, reading: &str) -> Option<Vec<(String, Option<String>)>> {
+        align(surface, reading).map(|parts| {
+            parts
+                .into_iter()
+                .map(|(range, reading)| (surface[range].to_string(), reading))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_align() {
+        let part =
+            |base: &str, reading: Option<&str>| (base.to_string(), reading.map(|r| r.to_string()));
+        assert_eq!(
+            aligned("食べる", "たべる"),
+            Some(vec![part("食", Some("た")), part("べる", None)])
+        );
+        assert_eq!(
+            aligned("取り扱い", "とりあつかい"),
+            Some(vec![
+                part("取", Some("と")),
+                part("り", None),
+                part("扱", Some("あつか")),
+                part("い", None),
+            ])
+        );
+        assert_eq!(
+            aligned("お茶", "おちゃ"),
+            Some(vec![part("お", None), part("茶", Some("ちゃ"))])
+        );
+        assert_eq!(
+            aligned("ラーメン屋", "らーめんや"),
+            Some(vec![part("ラーメン", None), part("屋", Some("や"))])
+        );
+        assert_eq!(
+            aligned("東京", "とうきょう"),
+            Some(vec![part("東京", Some("とうきょう"))])
+        );
+        // The reading does not fit the surface
+        assert_eq!(aligned("W杯", "わーるどはい"), None);
+        assert_eq!(aligned("食べる", "たべ"), None);
+    }
+
+    #[test]
+    fn test_to_ruby_html() {
+        let segments = vec![
+            Ruby {
+                span: 0..6,
+                base: "漢字".to_string(),
+                reading: Some("かんじ".to_string()),
+            },
+            Ruby {
+                span: 6..9,
+                base: "<b>".to_string(),
+                reading: None,
+            },
+        ];
+        assert_eq!(
+            to_ruby_html(&segments),
+            "<ruby>漢字<rt>かんじ</rt></ruby>&lt;b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_furigana() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let text = "  東京で美味しいラーメンを食べた。Rust";
+        let segments = furigana(&tokenizer, text).unwrap();
+
+        // Segments cover the input exactly
+        let mut end = 0;
+        for segment in &segments {
+            assert_eq!(segment.span.start, end);
+            assert_eq!(&text[segment.span.clone()], segment.base);
+            end = segment.span.end;
+        }
+        assert_eq!(end, text.len());
+
+        let annotated: Vec<(&str, &str)> = segments
+            .iter()
+            .filter_map(|s| s.reading.as_deref().map(|r| (s.base.as_str(), r)))
+            .collect();
+        assert_eq!(
+            annotated,
+            vec![("東京", "とうきょう"), ("美味", "おい"), ("食", "た")]
+        );
+        assert_eq!(segments[0].base, "  ");
+        assert_eq!(segments.last().unwrap().base, "べた。Rust");
+    }
+}
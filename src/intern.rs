@@ -1,11 +1,15 @@
-/// String interning module for common morphological values
-/// 
-/// This module provides static references to frequently used strings to eliminate
-/// repeated allocations during tokenization. Based on analysis of the codebase:
-/// - "*" appears 133 times as placeholder for missing morphological data
-/// - "" appears 39 times for empty values  
-/// - Character categories appear 100+ times during unknown word processing
-/// - BOS/EOS markers used once per sentence but created frequently
+//! String interning module for common morphological values
+//!
+//! This module provides static references to frequently used strings to eliminate
+//! repeated allocations during tokenization. Based on analysis of the codebase:
+//! - "*" appears 133 times as placeholder for missing morphological data
+//! - "" appears 39 times for empty values
+//! - Character categories appear 100+ times during unknown word processing
+//! - BOS/EOS markers used once per sentence but created frequently
+//!
+//! Values outside the static table that still come from a small vocabulary,
+//! such as the part-of-speech strings of the loaded dictionary, can be interned
+//! at runtime with `intern_pooled`.
 
 /// Tier 1: Critical placeholders and sentinels (highest frequency)
 pub const ASTERISK: &str = "*";
@@ -30,12 +34,35 @@ pub const POS_NOUN_COMPOUND: &str = "名詞,複合,*,*";
 pub const POS_NOUN_PROPER: &str = "名詞,固有名詞";
 pub const POS_PARTICLE: &str = "助詞";
 pub const POS_NOUN: &str = "名詞";
+pub const POS_SYMBOL_SPACE: &str = "記号,空白,*,*";
 
 /// Tier 2: Common separators and formatting
 pub const COMMA_SPACE: &str = ", ";
 pub const PIPE_SPACE: &str = " | ";
 
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::sync::{Mutex, PoisonError};
+
+use fxhash::FxHashSet;
+use once_cell::sync::Lazy;
+
+/// Maximum number of distinct strings interned at runtime by `intern_pooled`
+///
+/// Pooled strings are leaked, so the pool is capped to bound memory if callers
+/// feed it unbounded vocabularies; strings beyond the cap are allocated as usual.
+pub const POOL_CAPACITY: usize = 4096;
+
+/// Strings longer than this are never pooled
+pub const POOL_MAX_LEN: usize = 64;
+
+/// Process-wide pool of strings interned at runtime
+static POOL: Lazy<Mutex<FxHashSet<&'static str>>> = Lazy::new(Default::default);
+
+thread_local! {
+    /// Per-thread view of `POOL`, so lookups of known strings don't take the lock
+    static LOCAL_POOL: RefCell<FxHashSet<&'static str>> = RefCell::default();
+}
 
 /// Helper function to get interned string if available, otherwise clone
 /// This provides a migration path for gradually adopting string interning
@@ -46,7 +73,7 @@ pub fn intern_or_clone(s: &str) -> String {
         "" => EMPTY.to_string(),
         "__BOS__" => BOS_SURFACE.to_string(),
         "__EOS__" => EOS_SURFACE.to_string(),
-        
+
         // Character categories
         "DEFAULT" => CHAR_CATEGORY_DEFAULT.to_string(),
         "KANJI" => CHAR_CATEGORY_KANJI.to_string(),
@@ -56,7 +83,7 @@ pub fn intern_or_clone(s: &str) -> String {
         "KANJINUMERIC" => CHAR_CATEGORY_KANJINUMERIC.to_string(),
         "SYMBOL" => CHAR_CATEGORY_SYMBOL.to_string(),
         "ALPHA" => CHAR_CATEGORY_ALPHA.to_string(),
-        
+
         // Part-of-speech patterns
         "名詞,一般,*,*,*,*" => POS_NOUN_GENERAL.to_string(),
         "名詞,一般" => POS_NOUN_GENERAL_PARTIAL.to_string(),
@@ -64,11 +91,12 @@ pub fn intern_or_clone(s: &str) -> String {
         "名詞,固有名詞" => POS_NOUN_PROPER.to_string(),
         "助詞" => POS_PARTICLE.to_string(),
         "名詞" => POS_NOUN.to_string(),
-        
+        "記号,空白,*,*" => POS_SYMBOL_SPACE.to_string(),
+
         // Separators
         ", " => COMMA_SPACE.to_string(),
         " | " => PIPE_SPACE.to_string(),
-        
+
         // Not found in intern table, clone as usual
         _ => s.to_string(),
     }
@@ -83,7 +111,7 @@ pub fn intern_ref(s: &str) -> Option<&'static str> {
         "" => Some(EMPTY),
         "__BOS__" => Some(BOS_SURFACE),
         "__EOS__" => Some(EOS_SURFACE),
-        
+
         // Character categories
         "DEFAULT" => Some(CHAR_CATEGORY_DEFAULT),
         "KANJI" => Some(CHAR_CATEGORY_KANJI),
@@ -93,7 +121,7 @@ pub fn intern_ref(s: &str) -> Option<&'static str> {
         "KANJINUMERIC" => Some(CHAR_CATEGORY_KANJINUMERIC),
         "SYMBOL" => Some(CHAR_CATEGORY_SYMBOL),
         "ALPHA" => Some(CHAR_CATEGORY_ALPHA),
-        
+
         // Part-of-speech patterns
         "名詞,一般,*,*,*,*" => Some(POS_NOUN_GENERAL),
         "名詞,一般" => Some(POS_NOUN_GENERAL_PARTIAL),
@@ -101,11 +129,12 @@ pub fn intern_ref(s: &str) -> Option<&'static str> {
         "名詞,固有名詞" => Some(POS_NOUN_PROPER),
         "助詞" => Some(POS_PARTICLE),
         "名詞" => Some(POS_NOUN),
-        
+        "記号,空白,*,*" => Some(POS_SYMBOL_SPACE),
+
         // Separators
         ", " => Some(COMMA_SPACE),
         " | " => Some(PIPE_SPACE),
-        
+
         // Not found in intern table
         _ => None,
     }
@@ -120,7 +149,7 @@ pub fn intern_or_cow(s: &str) -> Cow<'static, str> {
         "" => Cow::Borrowed(EMPTY),
         "__BOS__" => Cow::Borrowed(BOS_SURFACE),
         "__EOS__" => Cow::Borrowed(EOS_SURFACE),
-        
+
         // Character categories - zero-copy static references
         "DEFAULT" => Cow::Borrowed(CHAR_CATEGORY_DEFAULT),
         "KANJI" => Cow::Borrowed(CHAR_CATEGORY_KANJI),
@@ -130,7 +159,7 @@ pub fn intern_or_cow(s: &str) -> Cow<'static, str> {
         "KANJINUMERIC" => Cow::Borrowed(CHAR_CATEGORY_KANJINUMERIC),
         "SYMBOL" => Cow::Borrowed(CHAR_CATEGORY_SYMBOL),
         "ALPHA" => Cow::Borrowed(CHAR_CATEGORY_ALPHA),
-        
+
         // Part-of-speech patterns - zero-copy static references
         "名詞,一般,*,*,*,*" => Cow::Borrowed(POS_NOUN_GENERAL),
         "名詞,一般" => Cow::Borrowed(POS_NOUN_GENERAL_PARTIAL),
@@ -138,16 +167,72 @@ pub fn intern_or_cow(s: &str) -> Cow<'static, str> {
         "名詞,固有名詞" => Cow::Borrowed(POS_NOUN_PROPER),
         "助詞" => Cow::Borrowed(POS_PARTICLE),
         "名詞" => Cow::Borrowed(POS_NOUN),
-        
+        "記号,空白,*,*" => Cow::Borrowed(POS_SYMBOL_SPACE),
+
         // Separators - zero-copy static references
         ", " => Cow::Borrowed(COMMA_SPACE),
         " | " => Cow::Borrowed(PIPE_SPACE),
-        
+
         // Not found in intern table - must clone (owned)
         _ => Cow::Owned(s.to_string()),
     }
 }
 
+/// Get a static reference to a string, interning it at runtime if needed
+///
+/// Meant for fields drawn from a small vocabulary that the static table can't
+/// know in advance, such as the part-of-speech and inflection values of the
+/// loaded dictionary. The first occurrence of a string is copied into a
+/// process-wide pool and every later occurrence borrows it, on any thread.
+/// Falls back to an owned copy for strings longer than `POOL_MAX_LEN` or once
+/// the pool holds `POOL_CAPACITY` strings.
+pub fn intern_pooled(s: &str) -> Cow<'static, str> {
+    if let Some(interned) = intern_ref(s) {
+        return Cow::Borrowed(interned);
+    }
+    if s.len() > POOL_MAX_LEN {
+        return Cow::Owned(s.to_string());
+    }
+    LOCAL_POOL.with(|local| {
+        if let Some(&interned) = local.borrow().get(s) {
+            return Cow::Borrowed(interned);
+        }
+        let interned = {
+            let mut pool = POOL.lock().unwrap_or_else(PoisonError::into_inner);
+            match pool.get(s) {
+                Some(&interned) => interned,
+                None if pool.len() < POOL_CAPACITY => {
+                    let interned: &'static str = Box::leak(s.into());
+                    pool.insert(interned);
+                    interned
+                }
+                None => return Cow::Owned(s.to_string()),
+            }
+        };
+        local.borrow_mut().insert(interned);
+        Cow::Borrowed(interned)
+    })
+}
+
+/// Get the approximate heap memory of the process-wide string pool
+///
+/// Counts the pooled strings and the pool's table; the per-thread views of
+/// the pool only hold references and are left out.
+pub fn pool_memory() -> usize {
+    let pool = POOL.lock().unwrap_or_else(PoisonError::into_inner);
+    pool.capacity() * (std::mem::size_of::<&str>() + 1)
+        + pool.iter().map(|s| s.len()).sum::<usize>()
+}
+
+/// Like `intern_or_cow`, but takes ownership so strings missing from the
+/// intern table are moved instead of copied
+pub fn intern_string(s: String) -> Cow<'static, str> {
+    match intern_ref(&s) {
+        Some(interned) => Cow::Borrowed(interned),
+        None => Cow::Owned(s),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,7 +252,7 @@ mod tests {
         assert_eq!(intern_or_clone(""), "");
         assert_eq!(intern_or_clone("DEFAULT"), "DEFAULT");
         assert_eq!(intern_or_clone("名詞,一般,*,*,*,*"), "名詞,一般,*,*,*,*");
-        
+
         // Test non-interned values
         assert_eq!(intern_or_clone("random_string"), "random_string");
     }
@@ -178,7 +263,7 @@ mod tests {
         assert!(intern_ref("*").is_some());
         assert!(intern_ref("").is_some());
         assert!(intern_ref("DEFAULT").is_some());
-        
+
         // Test non-interned values return None
         assert!(intern_ref("random_string").is_none());
     }
@@ -186,42 +271,60 @@ mod tests {
     #[test]
     fn test_character_categories() {
         let categories = [
-            "DEFAULT", "KANJI", "HIRAGANA", "KATAKANA", 
-            "NUMERIC", "KANJINUMERIC", "SYMBOL", "ALPHA"
+            "DEFAULT",
+            "KANJI",
+            "HIRAGANA",
+            "KATAKANA",
+            "NUMERIC",
+            "KANJINUMERIC",
+            "SYMBOL",
+            "ALPHA",
         ];
-        
+
         for category in &categories {
-            assert!(intern_ref(category).is_some(), "Category {} not interned", category);
+            assert!(
+                intern_ref(category).is_some(),
+                "Category {} not interned",
+                category
+            );
         }
     }
 
     #[test]
     fn test_pos_patterns() {
         let patterns = [
-            "名詞,一般,*,*,*,*", "名詞,一般", "名詞,複合,*,*", 
-            "名詞,固有名詞", "助詞", "名詞"
+            "名詞,一般,*,*,*,*",
+            "名詞,一般",
+            "名詞,複合,*,*",
+            "名詞,固有名詞",
+            "助詞",
+            "名詞",
         ];
-        
+
         for pattern in &patterns {
-            assert!(intern_ref(pattern).is_some(), "POS pattern {} not interned", pattern);
+            assert!(
+                intern_ref(pattern).is_some(),
+                "POS pattern {} not interned",
+                pattern
+            );
         }
     }
 
     #[test]
     fn test_intern_or_cow() {
         use std::borrow::Cow;
-        
+
         // Test interned values return Borrowed
         match intern_or_cow("*") {
             Cow::Borrowed(s) => assert_eq!(s, "*"),
             Cow::Owned(_) => panic!("Should be borrowed"),
         }
-        
+
         match intern_or_cow("DEFAULT") {
             Cow::Borrowed(s) => assert_eq!(s, "DEFAULT"),
             Cow::Owned(_) => panic!("Should be borrowed"),
         }
-        
+
         // Test non-interned values return Owned
         match intern_or_cow("random_string") {
             Cow::Owned(s) => assert_eq!(s, "random_string"),
@@ -238,10 +341,10 @@ mod tests {
                 assert_eq!(s, "*");
                 // Verify it's the same as our constant (content equality is sufficient)
                 assert_eq!(s, ASTERISK);
-            },
+            }
             Cow::Owned(_) => panic!("Asterisk should be borrowed"),
         }
-        
+
         // Test that non-interned strings are owned
         let cow_random = intern_or_cow("random_string");
         match cow_random {
@@ -249,4 +352,46 @@ mod tests {
             Cow::Borrowed(_) => panic!("Random string should be owned"),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_intern_pooled() {
+        // Strings in the static table are borrowed from it
+        assert!(matches!(intern_pooled("*"), Cow::Borrowed(ASTERISK)));
+
+        // Other strings are pooled on first use and shared afterwards
+        let first = intern_pooled("動詞,自立,*,*");
+        let second = intern_pooled("動詞,自立,*,*");
+        match (&first, &second) {
+            (Cow::Borrowed(a), Cow::Borrowed(b)) => assert!(std::ptr::eq(*a, *b)),
+            _ => panic!("Pooled strings should be borrowed"),
+        }
+
+        // Other threads see the same pooled string
+        let other = std::thread::spawn(|| match intern_pooled("動詞,自立,*,*") {
+            Cow::Borrowed(s) => s as *const str as *const u8 as usize,
+            Cow::Owned(_) => 0,
+        })
+        .join()
+        .unwrap();
+        assert_eq!(other, first.as_ptr() as usize);
+
+        // Long strings are never pooled
+        let long = "長".repeat(POOL_MAX_LEN);
+        assert!(matches!(intern_pooled(&long), Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_intern_string() {
+        assert!(matches!(
+            intern_string("*".to_string()),
+            Cow::Borrowed(ASTERISK)
+        ));
+
+        let owned = "random_string".to_string();
+        let ptr = owned.as_ptr();
+        match intern_string(owned) {
+            Cow::Owned(s) => assert_eq!(s.as_ptr(), ptr),
+            Cow::Borrowed(_) => panic!("Should be moved, not interned"),
+        }
+    }
+}
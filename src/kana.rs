@@ -0,0 +1,118 @@
+//! Katakana and hiragana conversion
+//!
+//! IPADIC readings are written in katakana, while furigana and most input
+//! methods use hiragana. The functions here map between the two scripts one
+//! character at a time; characters without a counterpart, such as the long
+//! vowel mark "ー" or "ヷ", are left unchanged.
+//!
+//! The katakana tables shared by the width conversion of
+//! `WidthNormalizeCharFilter` and the sort keys of `Token::sort_key` live
+//! here as well.
+
+/// Full-width forms of the half-width katakana block U+FF61..U+FF9F, in order
+pub(crate) const HALFWIDTH_KATAKANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+/// Katakana taking the voiced sound mark, and their voiced forms
+pub(crate) const VOICEABLE: &str = "ウカキクケコサシスセソタチツテトハヒフヘホ";
+pub(crate) const VOICED: &str = "ヴガギグゲゴザジズゼゾダヂヅデドバビブベボ";
+
+/// Katakana taking the semi-voiced sound mark, and their semi-voiced forms
+pub(crate) const SEMI_VOICEABLE: &str = "ハヒフヘホ";
+pub(crate) const SEMI_VOICED: &str = "パピプペポ";
+
+/// Small katakana, and their normal-sized forms
+const SMALL: &str = "ァィゥェォヵヶッャュョヮ";
+const NORMAL_SIZED: &str = "アイウエオカケツヤユヨワ";
+
+/// Convert a hiragana character to katakana, leaving other characters as is
+pub fn hiragana_to_katakana_char(c: char) -> char {
+    match c {
+        '\u{3041}'..='\u{3096}' | '\u{309D}'..='\u{309E}' => {
+            char::from_u32(c as u32 + 0x60).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+/// Convert a katakana character to hiragana, leaving other characters as is
+pub fn katakana_to_hiragana_char(c: char) -> char {
+    match c {
+        '\u{30A1}'..='\u{30F6}' | '\u{30FD}'..='\u{30FE}' => {
+            char::from_u32(c as u32 - 0x60).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+/// Convert hiragana in a string to katakana
+///
+/// # Arguments
+/// * `text` - Text to convert
+///
+/// # Returns
+/// * The text with every hiragana character replaced by its katakana form
+pub fn hiragana_to_katakana(text: &str) -> String {
+    text.chars().map(hiragana_to_katakana_char).collect()
+}
+
+/// Convert katakana in a string to hiragana
+///
+/// # Arguments
+/// * `text` - Text to convert
+///
+/// # Returns
+/// * The text with every katakana character replaced by its hiragana form
+pub fn katakana_to_hiragana(text: &str) -> String {
+    text.chars().map(katakana_to_hiragana_char).collect()
+}
+
+/// Get the character at a position of a string
+pub(crate) fn nth_char(chars: &str, index: usize) -> char {
+    chars.chars().nth(index).unwrap_or_default()
+}
+
+/// Map a character of `from` to the character at the same position of `to`
+pub(crate) fn map_char(c: char, from: &str, to: &str) -> Option<char> {
+    from.chars()
+        .position(|candidate| candidate == c)
+        .map(|index| nth_char(to, index))
+}
+
+/// Fold voiced, semi-voiced and small katakana to their plain forms
+pub(crate) fn fold_katakana(c: char) -> char {
+    map_char(c, VOICED, VOICEABLE)
+        .or_else(|| map_char(c, SEMI_VOICED, SEMI_VOICEABLE))
+        .or_else(|| map_char(c, SMALL, NORMAL_SIZED))
+        .unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_katakana_to_hiragana() {
+        assert_eq!(katakana_to_hiragana("スモモ"), "すもも");
+        assert_eq!(katakana_to_hiragana("ヴァイオリン"), "ゔぁいおりん");
+        assert_eq!(katakana_to_hiragana("ヶ月"), "ゖ月");
+        assert_eq!(katakana_to_hiragana("ラーメン"), "らーめん");
+        assert_eq!(katakana_to_hiragana("ヽヾ"), "ゝゞ");
+        assert_eq!(katakana_to_hiragana("ヷABC*"), "ヷABC*");
+    }
+
+    #[test]
+    fn test_hiragana_to_katakana() {
+        assert_eq!(hiragana_to_katakana("すもも"), "スモモ");
+        assert_eq!(hiragana_to_katakana("ゔぁ"), "ヴァ");
+        assert_eq!(hiragana_to_katakana("らーめん123"), "ラーメン123");
+
+        let text = "ぁあいうゔゕゖゝゞ";
+        assert_eq!(katakana_to_hiragana(&hiragana_to_katakana(text)), text);
+    }
+
+    #[test]
+    fn test_fold_katakana() {
+        let folded: String = "ヴァガパッヶヮーン".chars().map(fold_katakana).collect();
+        assert_eq!(folded, "ウアカハツケワーン");
+    }
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use std::sync::Arc;
@@ -7,6 +8,7 @@ use crate::error::RunomeError;
 use crate::intern;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NodeType {
     SysDict,
     UserDict,
@@ -67,6 +69,14 @@ pub trait LatticeNode: std::fmt::Debug {
     /// Returns None for nodes without morphological data (BOS, EOS, etc.)
     fn morph_id(&self) -> Option<usize>;
 
+    /// Get the ID of the dictionary entry this node was created from
+    ///
+    /// Unlike `morph_id`, it is also set for dictionary words copied into
+    /// `UnknownNode`s, whose IDs don't take part in tie-breaking.
+    fn word_id(&self) -> Option<usize> {
+        self.morph_id()
+    }
+
     fn part_of_speech(&self) -> &str;
 
     fn inflection_type(&self) -> &str;
@@ -82,7 +92,7 @@ pub trait LatticeNode: std::fmt::Debug {
 
 /// Node backed by a dictionary entry reference (zero-copy for dictionary words)
 #[derive(Debug)]
-pub struct Node<'a> {
+pub struct DictNode<'a> {
     /// Reference to dictionary entry (avoids copying morphological data)
     dict_entry: &'a DictEntry,
     node_type: NodeType,
@@ -95,8 +105,8 @@ pub struct Node<'a> {
     index: usize,
 }
 
-impl<'a> Node<'a> {
-    /// Create a new Node from a dictionary entry reference
+impl<'a> DictNode<'a> {
+    /// Create a new DictNode from a dictionary entry reference
     pub fn new(dict_entry: &'a DictEntry, node_type: NodeType) -> Self {
         Self {
             dict_entry,
@@ -115,7 +125,7 @@ impl<'a> Node<'a> {
     }
 }
 
-impl<'a> LatticeNode for Node<'a> {
+impl<'a> LatticeNode for DictNode<'a> {
     fn surface(&self) -> &str {
         &self.dict_entry.surface
     }
@@ -210,20 +220,25 @@ impl<'a> LatticeNode for Node<'a> {
 }
 
 /// Node for unknown words that owns its morphological data
+///
+/// Fields drawn from a small vocabulary (`*`, part-of-speech and inflection
+/// values) borrow interned strings, so only per-word fields allocate.
 #[derive(Debug)]
 pub struct UnknownNode {
-    /// Morphological data (owned since it's constructed dynamically)
-    surface: String,
+    /// Morphological data (owned or interned since it's constructed dynamically)
+    surface: Cow<'static, str>,
     left_id: u16,
     right_id: u16,
     cost: i16,
-    part_of_speech: String,
-    inflection_type: String,
-    inflection_form: String,
-    base_form: String,
-    reading: String,
-    phonetic: String,
+    part_of_speech: Cow<'static, str>,
+    inflection_type: Cow<'static, str>,
+    inflection_form: Cow<'static, str>,
+    base_form: Cow<'static, str>,
+    reading: Cow<'static, str>,
+    phonetic: Cow<'static, str>,
     node_type: NodeType,
+    /// ID of the dictionary entry the node was copied from, if any
+    word_id: Option<usize>,
 
     /// Viterbi algorithm fields
     min_cost: i32,
@@ -250,17 +265,18 @@ impl UnknownNode {
         node_type: NodeType,
     ) -> Self {
         Self {
-            surface,
+            surface: intern::intern_string(surface),
             left_id,
             right_id,
             cost,
-            part_of_speech,
-            inflection_type,
-            inflection_form,
-            base_form,
-            reading,
-            phonetic,
+            part_of_speech: intern::intern_string(part_of_speech),
+            inflection_type: intern::intern_string(inflection_type),
+            inflection_form: intern::intern_string(inflection_form),
+            base_form: intern::intern_string(base_form),
+            reading: intern::intern_string(reading),
+            phonetic: intern::intern_string(phonetic),
             node_type,
+            word_id: None,
             min_cost: i32::MAX,
             back_pos: -1,
             back_index: -1,
@@ -286,17 +302,18 @@ impl UnknownNode {
         node_type: NodeType,
     ) -> Self {
         Self {
-            surface: intern::intern_or_clone(surface),
+            surface: intern::intern_or_cow(surface),
             left_id,
             right_id,
             cost,
-            part_of_speech: intern::intern_or_clone(part_of_speech),
-            inflection_type: intern::intern_or_clone(inflection_type),
-            inflection_form: intern::intern_or_clone(inflection_form),
-            base_form: intern::intern_or_clone(base_form),
-            reading: intern::intern_or_clone(reading),
-            phonetic: intern::intern_or_clone(phonetic),
+            part_of_speech: intern::intern_pooled(part_of_speech),
+            inflection_type: intern::intern_pooled(inflection_type),
+            inflection_form: intern::intern_pooled(inflection_form),
+            base_form: intern::intern_or_cow(base_form),
+            reading: intern::intern_or_cow(reading),
+            phonetic: intern::intern_or_cow(phonetic),
             node_type,
+            word_id: None,
             min_cost: i32::MAX,
             back_pos: -1,
             back_index: -1,
@@ -316,23 +333,56 @@ impl UnknownNode {
         base_form: Option<&str>,
         node_type: NodeType,
     ) -> Self {
-        let base_form_string = match base_form {
-            Some(bf) => intern::intern_or_clone(bf),
-            None => intern::ASTERISK.to_string(),
+        let base_form = match base_form {
+            Some(bf) => intern::intern_or_cow(bf),
+            None => Cow::Borrowed(intern::ASTERISK),
         };
 
+        Self {
+            surface: intern::intern_string(surface),
+            left_id,
+            right_id,
+            cost,
+            part_of_speech: intern::intern_pooled(part_of_speech),
+            inflection_type: Cow::Borrowed(intern::ASTERISK),
+            inflection_form: Cow::Borrowed(intern::ASTERISK),
+            base_form,
+            reading: Cow::Borrowed(intern::ASTERISK),
+            phonetic: Cow::Borrowed(intern::ASTERISK),
+            node_type,
+            word_id: None,
+            min_cost: i32::MAX,
+            back_pos: -1,
+            back_index: -1,
+            pos: 0,
+            index: 0,
+        }
+    }
+
+    /// Create an UnknownNode carrying only what the path search needs
+    ///
+    /// The morphological fields are left as "*", so the node is cheap to build
+    /// when only surfaces are wanted, as in wakati mode.
+    pub fn surface_only(
+        surface: Cow<'static, str>,
+        left_id: u16,
+        right_id: u16,
+        cost: i16,
+        node_type: NodeType,
+    ) -> Self {
         Self {
             surface,
             left_id,
             right_id,
             cost,
-            part_of_speech: intern::intern_or_clone(part_of_speech),
-            inflection_type: intern::ASTERISK.to_string(),
-            inflection_form: intern::ASTERISK.to_string(),
-            base_form: base_form_string,
-            reading: intern::ASTERISK.to_string(),
-            phonetic: intern::ASTERISK.to_string(),
+            part_of_speech: Cow::Borrowed(intern::ASTERISK),
+            inflection_type: Cow::Borrowed(intern::ASTERISK),
+            inflection_form: Cow::Borrowed(intern::ASTERISK),
+            base_form: Cow::Borrowed(intern::ASTERISK),
+            reading: Cow::Borrowed(intern::ASTERISK),
+            phonetic: Cow::Borrowed(intern::ASTERISK),
             node_type,
+            word_id: None,
             min_cost: i32::MAX,
             back_pos: -1,
             back_index: -1,
@@ -340,6 +390,12 @@ impl UnknownNode {
             index: 0,
         }
     }
+
+    /// Record the ID of the dictionary entry the node was copied from
+    pub fn with_word_id(mut self, word_id: usize) -> Self {
+        self.word_id = Some(word_id);
+        self
+    }
 }
 
 impl LatticeNode for UnknownNode {
@@ -411,6 +467,10 @@ impl LatticeNode for UnknownNode {
         None // Unknown nodes don't have morphological IDs
     }
 
+    fn word_id(&self) -> Option<usize> {
+        self.word_id
+    }
+
     fn part_of_speech(&self) -> &str {
         &self.part_of_speech
     }
@@ -437,8 +497,11 @@ impl LatticeNode for UnknownNode {
 }
 
 /// Beginning-of-sentence node
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug)]
 pub struct BOS {
+    /// Context id used on both sides in connection costs
+    context_id: u16,
     /// Viterbi algorithm fields
     min_cost: i32,
     back_pos: i32,
@@ -448,9 +511,15 @@ pub struct BOS {
 }
 
 impl BOS {
-    /// Create a new BOS node
+    /// Create a new BOS node with context id 0
     pub fn new() -> Self {
+        Self::with_context_id(0)
+    }
+
+    /// Create a new BOS node with the given context id
+    pub fn with_context_id(context_id: u16) -> Self {
         Self {
+            context_id,
             min_cost: 0, // BOS starts with cost 0
             back_pos: -1,
             back_index: -1,
@@ -472,11 +541,11 @@ impl LatticeNode for BOS {
     }
 
     fn left_id(&self) -> u16 {
-        0 // BOS has no left context
+        self.context_id
     }
 
     fn right_id(&self) -> u16 {
-        0 // BOS connects to any following node
+        self.context_id
     }
 
     fn cost(&self) -> i16 {
@@ -561,8 +630,11 @@ impl LatticeNode for BOS {
 }
 
 /// End-of-sentence node
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug)]
 pub struct EOS {
+    /// Context id used on both sides in connection costs
+    context_id: u16,
     /// Viterbi algorithm fields
     min_cost: i32,
     back_pos: i32,
@@ -572,9 +644,15 @@ pub struct EOS {
 }
 
 impl EOS {
-    /// Create a new EOS node at the specified position
+    /// Create a new EOS node with context id 0 at the specified position
     pub fn new(end_pos: usize) -> Self {
+        Self::with_context_id(end_pos, 0)
+    }
+
+    /// Create a new EOS node with the given context id at the specified position
+    pub fn with_context_id(end_pos: usize, context_id: u16) -> Self {
         Self {
+            context_id,
             min_cost: i32::MAX,
             back_pos: -1,
             back_index: -1,
@@ -590,11 +668,11 @@ impl LatticeNode for EOS {
     }
 
     fn left_id(&self) -> u16 {
-        0 // EOS accepts connections from any preceding node
+        self.context_id
     }
 
     fn right_id(&self) -> u16 {
-        0 // EOS has no right context
+        self.context_id
     }
 
     fn cost(&self) -> i16 {
@@ -678,6 +756,171 @@ impl LatticeNode for EOS {
     }
 }
 
+/// Lattice node stored by value in the lattice vectors
+///
+/// The Viterbi search calls the `LatticeNode` methods of this enum, which
+/// dispatch with a `match` on the variant instead of through a vtable.
+#[derive(Debug)]
+pub enum Node<'a> {
+    /// Word borrowing its dictionary entry
+    Dict(DictNode<'a>),
+    /// Word owning its morphological data, unknown or copied from a dictionary
+    Unknown(UnknownNode),
+    /// Beginning of sentence
+    Bos(BOS),
+    /// End of sentence
+    Eos(EOS),
+}
+
+/// Evaluate an expression with the node inside any variant
+macro_rules! with_node {
+    ($node:expr, $inner:ident => $body:expr) => {
+        match $node {
+            Node::Dict($inner) => $body,
+            Node::Unknown($inner) => $body,
+            Node::Bos($inner) => $body,
+            Node::Eos($inner) => $body,
+        }
+    };
+}
+
+impl<'a> LatticeNode for Node<'a> {
+    #[inline]
+    fn surface(&self) -> &str {
+        with_node!(self, node => node.surface())
+    }
+
+    #[inline]
+    fn left_id(&self) -> u16 {
+        with_node!(self, node => node.left_id())
+    }
+
+    #[inline]
+    fn right_id(&self) -> u16 {
+        with_node!(self, node => node.right_id())
+    }
+
+    #[inline]
+    fn cost(&self) -> i16 {
+        with_node!(self, node => node.cost())
+    }
+
+    #[inline]
+    fn min_cost(&self) -> i32 {
+        with_node!(self, node => node.min_cost())
+    }
+
+    #[inline]
+    fn set_min_cost(&mut self, cost: i32) {
+        with_node!(self, node => node.set_min_cost(cost))
+    }
+
+    #[inline]
+    fn back_pos(&self) -> i32 {
+        with_node!(self, node => node.back_pos())
+    }
+
+    #[inline]
+    fn set_back_pos(&mut self, pos: i32) {
+        with_node!(self, node => node.set_back_pos(pos))
+    }
+
+    #[inline]
+    fn back_index(&self) -> i32 {
+        with_node!(self, node => node.back_index())
+    }
+
+    #[inline]
+    fn set_back_index(&mut self, index: i32) {
+        with_node!(self, node => node.set_back_index(index))
+    }
+
+    #[inline]
+    fn pos(&self) -> usize {
+        with_node!(self, node => node.pos())
+    }
+
+    #[inline]
+    fn set_pos(&mut self, pos: usize) {
+        with_node!(self, node => node.set_pos(pos))
+    }
+
+    #[inline]
+    fn index(&self) -> usize {
+        with_node!(self, node => node.index())
+    }
+
+    #[inline]
+    fn set_index(&mut self, index: usize) {
+        with_node!(self, node => node.set_index(index))
+    }
+
+    fn node_type(&self) -> NodeType {
+        with_node!(self, node => node.node_type())
+    }
+
+    fn surface_len(&self) -> usize {
+        with_node!(self, node => node.surface_len())
+    }
+
+    #[inline]
+    fn morph_id(&self) -> Option<usize> {
+        with_node!(self, node => node.morph_id())
+    }
+
+    fn word_id(&self) -> Option<usize> {
+        with_node!(self, node => node.word_id())
+    }
+
+    fn part_of_speech(&self) -> &str {
+        with_node!(self, node => node.part_of_speech())
+    }
+
+    fn inflection_type(&self) -> &str {
+        with_node!(self, node => node.inflection_type())
+    }
+
+    fn inflection_form(&self) -> &str {
+        with_node!(self, node => node.inflection_form())
+    }
+
+    fn base_form(&self) -> &str {
+        with_node!(self, node => node.base_form())
+    }
+
+    fn reading(&self) -> &str {
+        with_node!(self, node => node.reading())
+    }
+
+    fn phonetic(&self) -> &str {
+        with_node!(self, node => node.phonetic())
+    }
+}
+
+impl<'a> From<DictNode<'a>> for Node<'a> {
+    fn from(node: DictNode<'a>) -> Self {
+        Node::Dict(node)
+    }
+}
+
+impl From<UnknownNode> for Node<'_> {
+    fn from(node: UnknownNode) -> Self {
+        Node::Unknown(node)
+    }
+}
+
+impl From<BOS> for Node<'_> {
+    fn from(node: BOS) -> Self {
+        Node::Bos(node)
+    }
+}
+
+impl From<EOS> for Node<'_> {
+    fn from(node: EOS) -> Self {
+        Node::Eos(node)
+    }
+}
+
 /// Lattice structure for Viterbi algorithm-based morphological analysis
 /// Ultra-compact end node representation that stores critical data inline
 /// This eliminates expensive indirection and bounds checking in hot paths
@@ -697,7 +940,7 @@ pub struct CompactEndNode {
 
 impl CompactEndNode {
     /// Create from a lattice node, caching critical data for fast access
-    fn from_node(node: &dyn LatticeNode, pos: usize, index: usize) -> Self {
+    fn from_node(node: &Node<'_>, pos: usize, index: usize) -> Self {
         Self {
             pos: pos as u16,
             index: index as u16,
@@ -709,6 +952,7 @@ impl CompactEndNode {
 }
 
 /// Legacy node reference for compatibility
+#[cfg(test)]
 #[derive(Debug, Clone)]
 pub struct NodeRef {
     pub pos: usize,
@@ -725,6 +969,9 @@ struct CostCacheKey {
 /// Fast hasher for small integer keys
 type FastHasher = BuildHasherDefault<fxhash::FxHasher>;
 
+/// Path from BOS to EOS with its total cost, as returned by `Lattice::nbest`
+pub type ScoredPath<'a> = (Vec<&'a Node<'a>>, i32);
+
 /// Connection cost cache for frequently accessed cost lookups
 struct ConnectionCostCache {
     cache: HashMap<CostCacheKey, i16, FastHasher>,
@@ -764,15 +1011,11 @@ impl ConnectionCostCache {
         self.cache.insert(key, cost);
         Ok(cost)
     }
-
-    fn clear(&mut self) {
-        self.cache.clear();
-    }
 }
 
 pub struct Lattice<'a> {
     /// Start nodes at each position - snodes[pos][index]
-    snodes: Vec<Vec<Box<dyn LatticeNode + 'a>>>,
+    snodes: Vec<Vec<Node<'a>>>,
     /// Ultra-optimized end nodes with inlined critical data (eliminates indirection)
     enodes: Vec<Vec<CompactEndNode>>,
     /// Current position pointer
@@ -809,14 +1052,15 @@ impl<'a> Lattice<'a> {
             enodes.push(Vec::new());
         }
 
-        // Position 0: BOS node in snodes
-        let mut bos = Box::new(BOS::new()) as Box<dyn LatticeNode + 'a>;
+        // Position 0: BOS node in snodes, with the dictionary's boundary context id
+        let (bos_context_id, _) = dic.boundary_context_ids();
+        let mut bos = Node::Bos(BOS::with_context_id(bos_context_id));
         bos.set_pos(0);
         bos.set_index(0);
         snodes[0].push(bos);
 
         // Position 1: BOS node also appears in enodes[1] for connections
-        let bos_compact = CompactEndNode::from_node(snodes[0][0].as_ref(), 0, 0);
+        let bos_compact = CompactEndNode::from_node(&snodes[0][0], 0, 0);
         enodes[1].push(bos_compact);
 
         Self {
@@ -830,11 +1074,11 @@ impl<'a> Lattice<'a> {
     }
 
     /// Get a node by reference - helper method for efficient node access
-    fn get_node(&self, node_ref: &NodeRef) -> Option<&dyn LatticeNode> {
+    #[cfg(test)]
+    fn get_node(&self, node_ref: &NodeRef) -> Option<&Node<'a>> {
         self.snodes
             .get(node_ref.pos)
             .and_then(|nodes| nodes.get(node_ref.index))
-            .map(|node| node.as_ref())
     }
 
     /// Ensure lattice capacity for a given position (optimized growth)
@@ -863,7 +1107,7 @@ impl<'a> Lattice<'a> {
     }
 
     /// Get reference to start nodes at the specified position
-    pub fn start_nodes(&self, pos: usize) -> Option<&Vec<Box<dyn LatticeNode + 'a>>> {
+    pub fn start_nodes(&self, pos: usize) -> Option<&Vec<Node<'a>>> {
         self.snodes.get(pos)
     }
 
@@ -886,7 +1130,8 @@ impl<'a> Lattice<'a> {
 
         // Cache for future use (limit cache size to prevent memory bloat)
         if self.surface_len_cache.len() < 5000 {
-            self.surface_len_cache.insert(intern::intern_or_clone(surface), len);
+            self.surface_len_cache
+                .insert(intern::intern_or_clone(surface), len);
         }
 
         len
@@ -935,18 +1180,20 @@ impl<'a> Lattice<'a> {
     /// to reach this node from all possible predecessor nodes at the current position.
     ///
     /// # Arguments
-    /// * `mut node` - The node to add (will be mutated to set Viterbi fields)
+    /// * `node` - The node to add, e.g. a `DictNode` or `UnknownNode` (its Viterbi fields are set here)
     ///
     /// # Returns
     /// * `Ok(())` if the node was successfully added
     /// * `Err(RunomeError)` if cost calculation or dictionary access fails
+    ///
     /// Ultra-optimized add method with multiple performance optimizations:
     /// - Inlined critical data (no indirection)
     /// - Connection cost caching
     /// - Surface length caching
     /// - Hot path specialization for single predecessor
     /// - Optimized memory access patterns
-    pub fn add(&mut self, mut node: Box<dyn LatticeNode + 'a>) -> Result<(), RunomeError> {
+    pub fn add(&mut self, node: impl Into<Node<'a>>) -> Result<(), RunomeError> {
+        let mut node = node.into();
         // Initialize Viterbi cost calculation
         let mut min_cost = node.min_cost().saturating_sub(node.cost() as i32);
         let mut best_compact_node: Option<&CompactEndNode> = None;
@@ -1011,10 +1258,9 @@ impl<'a> Lattice<'a> {
                         best_compact_node,
                         enode.morph_id,
                         best_compact_node.and_then(|n| n.morph_id),
-                    ) {
-                        if enode_id < best_id {
-                            best_compact_node = Some(enode);
-                        }
+                    ) && enode_id < best_id
+                    {
+                        best_compact_node = Some(enode);
                     }
                 }
             }
@@ -1049,15 +1295,11 @@ impl<'a> Lattice<'a> {
         // Optimized lattice expansion
         self.ensure_capacity(end_pos);
 
+        // Create ultra-compact end node with inlined critical data (major optimization!)
+        let compact_end_node = CompactEndNode::from_node(&node, self.p, node_index);
+
         // Add to start nodes
         self.snodes[self.p].push(node);
-
-        // Create ultra-compact end node with inlined critical data (major optimization!)
-        let compact_end_node = CompactEndNode::from_node(
-            self.snodes[self.p].last().unwrap().as_ref(),
-            self.p,
-            node_index,
-        );
         self.enodes[end_pos].push(compact_end_node);
 
         Ok(())
@@ -1097,7 +1339,8 @@ impl<'a> Lattice<'a> {
     /// * `Err(RunomeError)` if cost calculation fails
     pub fn end(&mut self) -> Result<(), RunomeError> {
         // Python: eos = EOS(self.p)
-        let eos = Box::new(EOS::new(self.p)) as Box<dyn LatticeNode + 'a>;
+        let (_, eos_context_id) = self.dic.boundary_context_ids();
+        let eos = EOS::with_context_id(self.p, eos_context_id);
 
         // Python: self.add(eos) - use the same add() method as all other nodes
         self.add(eos)?;
@@ -1115,13 +1358,13 @@ impl<'a> Lattice<'a> {
     /// analysis - it returns the best segmentation of the input text.
     ///
     /// # Returns
-    /// * `Ok(Vec<&dyn LatticeNode>)` - Vector of nodes representing optimal path from BOS to EOS
+    /// * `Ok(Vec<&Node>)` - Vector of nodes representing optimal path from BOS to EOS
     /// * `Err(RunomeError)` - Error if lattice is invalid or no path exists
     ///
     /// # Path Structure
     /// The returned path always starts with BOS and ends with EOS:
     /// `[BOS, word1, word2, ..., wordN, EOS]`
-    pub fn backward(&self) -> Result<Vec<&dyn LatticeNode>, RunomeError> {
+    pub fn backward(&self) -> Result<Vec<&Node<'a>>, RunomeError> {
         // Validate that lattice is properly finalized with EOS
         if self.snodes.is_empty() {
             return Err(RunomeError::DictValidationError {
@@ -1161,7 +1404,7 @@ impl<'a> Lattice<'a> {
                 });
             }
 
-            let current_node = self.snodes[current_pos][current_index].as_ref();
+            let current_node = &self.snodes[current_pos][current_index];
             path.push(current_node);
 
             // Check if we've reached BOS (back_pos = -1)
@@ -1216,6 +1459,188 @@ impl<'a> Lattice<'a> {
 
         Ok(path)
     }
+
+    /// Find the `n` lowest-cost paths through the finalized lattice
+    ///
+    /// Paths are searched backwards from EOS with A*, using the minimum cost
+    /// of each node computed during construction as the (exact) estimate of
+    /// the rest of the path, so they are found in order of increasing cost.
+    /// The first path has the cost of the path returned by `backward()`, but
+    /// may differ from it when several paths share that cost. Of nodes that
+    /// only differ in their dictionary entry index, one is used, so no two
+    /// paths yield the same tokens.
+    ///
+    /// # Arguments
+    /// * `n` - Maximum number of paths to return
+    ///
+    /// # Returns
+    /// * `Ok(Vec<ScoredPath>)` - Paths from BOS to EOS with their total costs
+    /// * `Err(RunomeError)` - Error if the lattice is not finalized or a cost lookup fails
+    pub fn nbest(&self, n: usize) -> Result<Vec<ScoredPath<'_>>, RunomeError> {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+
+        let last_pos = self.snodes.len().saturating_sub(1);
+        let eos = match self.snodes.get(last_pos).and_then(|nodes| nodes.first()) {
+            Some(eos) if eos.surface() == "__EOS__" => eos,
+            _ => {
+                return Err(RunomeError::DictValidationError {
+                    reason: "Lattice is not finalized with EOS".to_string(),
+                });
+            }
+        };
+
+        // Partial paths from some node to EOS: (pos, index, cost after the
+        // node, parent towards EOS)
+        let mut partials: Vec<(usize, usize, i64, Option<usize>)> = vec![(last_pos, 0, 0, None)];
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((eos.min_cost() as i64, 0)));
+
+        let mut paths = Vec::new();
+        while paths.len() < n {
+            let Some(Reverse((total, id))) = queue.pop() else {
+                break;
+            };
+            let (pos, index, after, _) = partials[id];
+            let node = &self.snodes[pos][index];
+
+            if node.back_pos() == -1 {
+                // Reached BOS: the parents lead to EOS
+                let mut path = Vec::new();
+                let mut current = Some(id);
+                while let Some(id) = current {
+                    let (pos, index, _, parent) = partials[id];
+                    path.push(&self.snodes[pos][index]);
+                    current = parent;
+                }
+                paths.push((path, total as i32));
+                continue;
+            }
+
+            let after = after + node.cost() as i64;
+            let enodes = self.enodes.get(pos).map_or(&[][..], Vec::as_slice);
+            for (i, enode) in enodes.iter().enumerate() {
+                if enodes[..i]
+                    .iter()
+                    .any(|other| self.interchangeable(other, enode))
+                {
+                    continue;
+                }
+                let connection = self.dic.get_trans_cost(enode.right_id, node.left_id())? as i64;
+                partials.push((
+                    enode.pos as usize,
+                    enode.index as usize,
+                    after + connection,
+                    Some(id),
+                ));
+                queue.push(Reverse((
+                    enode.min_cost as i64 + after + connection,
+                    partials.len() - 1,
+                )));
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// Whether two end nodes yield the same tokens at the same cost, as with
+    /// the duplicated entries of some dictionaries
+    fn interchangeable(&self, a: &CompactEndNode, b: &CompactEndNode) -> bool {
+        if a.pos != b.pos {
+            return false;
+        }
+        let a = &self.snodes[a.pos as usize][a.index as usize];
+        let b = &self.snodes[b.pos as usize][b.index as usize];
+        a.surface() == b.surface()
+            && a.left_id() == b.left_id()
+            && a.right_id() == b.right_id()
+            && a.cost() == b.cost()
+            && a.node_type() == b.node_type()
+            && a.part_of_speech() == b.part_of_speech()
+            && a.inflection_type() == b.inflection_type()
+            && a.inflection_form() == b.inflection_form()
+            && a.base_form() == b.base_form()
+            && a.reading() == b.reading()
+            && a.phonetic() == b.phonetic()
+    }
+
+    /// Export the lattice as a Graphviz DOT graph
+    ///
+    /// Every candidate node is drawn with its surface, part of speech, word cost
+    /// and minimum path cost; edges carry the connection cost. If the lattice
+    /// has been finalized with `end()`, the best path found by `backward()` is
+    /// highlighted in red. Render with e.g. `dot -Tsvg lattice.dot -o lattice.svg`.
+    ///
+    /// # Returns
+    /// * `Ok(String)` - DOT source of the lattice
+    /// * `Err(RunomeError)` - Error if a connection cost lookup fails
+    pub fn to_dot(&self) -> Result<String, RunomeError> {
+        let best_path: Vec<(usize, usize)> = self
+            .backward()
+            .map(|path| path.iter().map(|node| (node.pos(), node.index())).collect())
+            .unwrap_or_default();
+        let on_best_path = |pos: usize, index: usize| best_path.contains(&(pos, index));
+
+        let mut dot = String::from(
+            "digraph lattice {\n  rankdir=LR;\n  node [shape=box, fontname=\"sans-serif\"];\n",
+        );
+
+        for (pos, nodes) in self.snodes.iter().enumerate() {
+            for (index, node) in nodes.iter().enumerate() {
+                let pos_label = node.part_of_speech().split(',').next().unwrap_or("");
+                let label = format!(
+                    "{}\\n{}\\ncost={} min={}",
+                    dot_escape(node.surface()),
+                    dot_escape(pos_label),
+                    node.cost(),
+                    node.min_cost()
+                );
+                let style = if on_best_path(pos, index) {
+                    ", color=red, fontcolor=red, penwidth=2"
+                } else {
+                    ""
+                };
+                dot.push_str(&format!(
+                    "  n{}_{} [label=\"{}\"{}];\n",
+                    pos, index, label, style
+                ));
+            }
+        }
+
+        // Connect each node with every node ending where it starts
+        for (pos, nodes) in self.snodes.iter().enumerate().skip(1) {
+            let Some(end_nodes) = self.enodes.get(pos) else {
+                continue;
+            };
+            for (index, node) in nodes.iter().enumerate() {
+                for enode in end_nodes {
+                    let (from_pos, from_index) = (enode.pos as usize, enode.index as usize);
+                    let connection_cost =
+                        self.dic.get_trans_cost(enode.right_id, node.left_id())?;
+                    let is_best = on_best_path(pos, index)
+                        && node.back_pos() == from_pos as i32
+                        && node.back_index() == from_index as i32;
+                    let style = if is_best {
+                        ", color=red, fontcolor=red, penwidth=2"
+                    } else {
+                        ""
+                    };
+                    dot.push_str(&format!(
+                        "  n{}_{} -> n{}_{} [label=\"{}\"{}];\n",
+                        from_pos, from_index, pos, index, connection_cost, style
+                    ));
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        Ok(dot)
+    }
+}
+
+/// Escape a string for use inside a quoted DOT label
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
 }
 
 impl<'a> std::fmt::Debug for Lattice<'a> {
@@ -1254,7 +1679,7 @@ mod tests {
     #[test]
     fn test_node_creation() {
         let dict_entry = create_test_dict_entry();
-        let node = Node::new(&dict_entry, NodeType::SysDict);
+        let node = DictNode::new(&dict_entry, NodeType::SysDict);
 
         assert_eq!(node.surface(), "テスト");
         assert_eq!(node.left_id(), 100);
@@ -1297,6 +1722,53 @@ mod tests {
         assert_eq!(unknown.base_form(), "未知語");
     }
 
+    #[test]
+    fn test_unknown_node_interning() {
+        let node = UnknownNode::from_dict_entry(
+            "走っ",
+            10,
+            20,
+            30,
+            "動詞,自立,*,*",
+            "五段・ラ行",
+            "連用タ接続",
+            "走る",
+            "ハシッ",
+            "ハシッ",
+            NodeType::SysDict,
+        );
+        // Part-of-speech and inflection values are pooled, per-word fields owned
+        assert!(matches!(node.part_of_speech, Cow::Borrowed(_)));
+        assert!(matches!(node.inflection_type, Cow::Borrowed(_)));
+        assert!(matches!(node.inflection_form, Cow::Borrowed(_)));
+        assert!(matches!(node.reading, Cow::Owned(_)));
+        assert_eq!(node.inflection_type(), "五段・ラ行");
+
+        let unknown = UnknownNode::for_unknown_word(
+            "ABC".to_string(),
+            10,
+            20,
+            30,
+            "名詞,固有名詞,組織,*",
+            None,
+            NodeType::Unknown,
+        );
+        for field in [
+            &unknown.part_of_speech,
+            &unknown.inflection_type,
+            &unknown.inflection_form,
+            &unknown.base_form,
+            &unknown.reading,
+            &unknown.phonetic,
+        ] {
+            assert!(
+                matches!(field, Cow::Borrowed(_)),
+                "{:?} should be interned",
+                field
+            );
+        }
+    }
+
     #[test]
     fn test_bos_node() {
         let bos = BOS::new();
@@ -1327,7 +1799,7 @@ mod tests {
     #[test]
     fn test_viterbi_field_updates() {
         let dict_entry = create_test_dict_entry();
-        let mut node = Node::new(&dict_entry, NodeType::SysDict);
+        let mut node = DictNode::new(&dict_entry, NodeType::SysDict);
 
         // Test updating Viterbi fields
         node.set_min_cost(1000);
@@ -1343,12 +1815,39 @@ mod tests {
         assert_eq!(node.index(), 3);
     }
 
+    #[test]
+    fn test_node_enum_dispatch() {
+        let dict_entry = create_test_dict_entry();
+        let mut node = Node::from(DictNode::new(&dict_entry, NodeType::SysDict));
+        assert!(matches!(node, Node::Dict(_)));
+        assert_eq!(node.surface(), "テスト");
+        assert_eq!(node.morph_id(), Some(0));
+        node.set_min_cost(1000);
+        assert_eq!(node.min_cost(), 1000);
+
+        let node = Node::from(UnknownNode::for_unknown_word(
+            "未知語".to_string(),
+            300,
+            400,
+            500,
+            "名詞,一般,*,*,*,*",
+            None,
+            NodeType::Unknown,
+        ));
+        assert!(matches!(node, Node::Unknown(_)));
+        assert_eq!(node.left_id(), 300);
+        assert_eq!(node.morph_id(), None);
+
+        assert_eq!(Node::from(BOS::new()).surface(), "__BOS__");
+        assert_eq!(Node::from(EOS::new(3)).pos(), 3);
+    }
+
     #[test]
     fn test_node_types() {
         let dict_entry = create_test_dict_entry();
 
-        let sys_node = Node::new(&dict_entry, NodeType::SysDict);
-        let user_node = Node::new(&dict_entry, NodeType::UserDict);
+        let sys_node = DictNode::new(&dict_entry, NodeType::SysDict);
+        let user_node = DictNode::new(&dict_entry, NodeType::UserDict);
 
         assert_eq!(sys_node.node_type(), NodeType::SysDict);
         assert_eq!(user_node.node_type(), NodeType::UserDict);
@@ -1370,7 +1869,7 @@ mod tests {
             phonetic: "".to_string(),
             morph_id: 1,
         };
-        let node_ascii = Node::new(&dict_entry_ascii, NodeType::SysDict);
+        let node_ascii = DictNode::new(&dict_entry_ascii, NodeType::SysDict);
         assert_eq!(node_ascii.surface_len(), 4);
 
         // Test Japanese (multi-byte UTF-8)
@@ -1387,7 +1886,7 @@ mod tests {
             phonetic: "".to_string(),
             morph_id: 2,
         };
-        let node_jp = Node::new(&dict_entry_jp, NodeType::SysDict);
+        let node_jp = DictNode::new(&dict_entry_jp, NodeType::SysDict);
         assert_eq!(node_jp.surface_len(), 5); // 5 characters, not bytes
     }
 
@@ -1523,7 +2022,7 @@ mod tests {
         let mut lattice = Lattice::new(10, dic);
 
         // Create an unknown node to add (avoids lifetime issues)
-        let node = Box::new(UnknownNode::new(
+        let node = UnknownNode::new(
             "テスト".to_string(),
             100,
             200,
@@ -1535,7 +2034,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         // Add the node to the lattice
         let result = lattice.add(node);
@@ -1569,7 +2068,7 @@ mod tests {
         let mut lattice = Lattice::new(10, dic);
 
         // Create multiple unknown nodes with different surface forms
-        let node1 = Box::new(UnknownNode::new(
+        let node1 = UnknownNode::new(
             "テスト1".to_string(),
             100,
             200,
@@ -1581,9 +2080,9 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
-        let node2 = Box::new(UnknownNode::new(
+        let node2 = UnknownNode::new(
             "テスト2".to_string(),
             101,
             201,
@@ -1595,7 +2094,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         // Add both nodes
         assert!(
@@ -1628,7 +2127,7 @@ mod tests {
         let mut lattice = Lattice::new(10, dic);
 
         // Create an unknown node with cost = 150
-        let node = Box::new(UnknownNode::new(
+        let node = UnknownNode::new(
             "テスト".to_string(),
             100,
             200,
@@ -1640,7 +2139,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         // Add the node
         assert!(lattice.add(node).is_ok(), "Adding node should succeed");
@@ -1664,7 +2163,7 @@ mod tests {
         let mut lattice = Lattice::new(10, dic);
 
         // Create an unknown node
-        let unknown_node = Box::new(UnknownNode::new(
+        let unknown_node = UnknownNode::new(
             "未知語".to_string(),
             300,
             400,
@@ -1676,7 +2175,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         // Add the unknown node
         let result = lattice.add(unknown_node);
@@ -1708,7 +2207,7 @@ mod tests {
         let mut lattice = Lattice::new(2, dic); // Small lattice
 
         // Create an unknown node with long surface (will extend beyond initial size)
-        let node = Box::new(UnknownNode::new(
+        let node = UnknownNode::new(
             "とても長い表面形".to_string(), // 7 characters
             100,
             200,
@@ -1720,7 +2219,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         // Add the node (should trigger lattice expansion)
         let result = lattice.add(node);
@@ -1761,7 +2260,7 @@ mod tests {
         let mut lattice = Lattice::new(10, dic);
 
         // Add first node at position 1
-        let node1 = Box::new(UnknownNode::new(
+        let node1 = UnknownNode::new(
             "テスト".to_string(),
             100,
             200,
@@ -1773,14 +2272,14 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
         assert!(lattice.add(node1).is_ok());
 
         // Move to next position
         lattice.forward();
 
         // Add second node that should connect to the first
-        let node2 = Box::new(UnknownNode::new(
+        let node2 = UnknownNode::new(
             "語".to_string(), // 1 character
             101,
             201,
@@ -1792,7 +2291,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
         assert!(lattice.add(node2).is_ok());
 
         // Verify the second node has optimal predecessor
@@ -1850,7 +2349,7 @@ mod tests {
         let entries = entries_result.unwrap();
 
         for entry in &entries {
-            let node = Box::new(Node::new(entry, NodeType::SysDict)) as Box<dyn LatticeNode>;
+            let node = DictNode::new(entry, NodeType::SysDict);
             let add_result = lattice.add(node);
             assert!(add_result.is_ok(), "Adding node should succeed");
         }
@@ -1874,7 +2373,7 @@ mod tests {
             let substring_entries = substring_entries_result.unwrap();
 
             for entry in &substring_entries {
-                let node = Box::new(Node::new(entry, NodeType::SysDict)) as Box<dyn LatticeNode>;
+                let node = DictNode::new(entry, NodeType::SysDict);
                 let add_result = lattice.add(node);
                 assert!(add_result.is_ok(), "Adding substring node should succeed");
             }
@@ -1905,7 +2404,7 @@ mod tests {
             let final_entries = final_entries_result.unwrap();
 
             for entry in &final_entries {
-                let node = Box::new(Node::new(entry, NodeType::SysDict)) as Box<dyn LatticeNode>;
+                let node = DictNode::new(entry, NodeType::SysDict);
                 let add_result = lattice.add(node);
                 assert!(add_result.is_ok(), "Adding final node should succeed");
             }
@@ -1941,7 +2440,7 @@ mod tests {
         let mut lattice = Lattice::new(5, dic);
 
         // Add an unknown node first
-        let node = Box::new(UnknownNode::new(
+        let node = UnknownNode::new(
             "テスト".to_string(),
             100,
             200,
@@ -1953,7 +2452,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
         assert!(lattice.add(node).is_ok());
 
         // Move forward to simulate processing
@@ -1994,7 +2493,7 @@ mod tests {
         let mut lattice = Lattice::new(5, dic);
 
         // Add an unknown node with known cost = 150
-        let node = Box::new(UnknownNode::new(
+        let node = UnknownNode::new(
             "テスト".to_string(),
             100,
             200,
@@ -2006,7 +2505,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
         assert!(lattice.add(node).is_ok());
 
         // Move to end position
@@ -2033,13 +2532,63 @@ mod tests {
         );
     }
 
+    // Mock dictionary with dedicated boundary context ids, whose connection
+    // cost encodes the ids so tests can tell which ones were used
+    struct BoundaryMockDictionary;
+
+    impl crate::dictionary::Dictionary for BoundaryMockDictionary {
+        fn lookup(&self, _surface: &str) -> Result<Vec<&DictEntry>, crate::error::RunomeError> {
+            Ok(Vec::new())
+        }
+
+        fn get_trans_cost(
+            &self,
+            left_id: u16,
+            right_id: u16,
+        ) -> Result<i16, crate::error::RunomeError> {
+            Ok((left_id * 1000 + right_id) as i16)
+        }
+
+        fn boundary_context_ids(&self) -> (u16, u16) {
+            (3, 7)
+        }
+    }
+
+    #[test]
+    fn test_boundary_context_ids() {
+        let mut lattice = Lattice::new(5, Arc::new(BoundaryMockDictionary));
+        assert_eq!(lattice.start_nodes(0).unwrap()[0].right_id(), 3);
+
+        let node = UnknownNode::new(
+            "テスト".to_string(),
+            1,
+            2,
+            150,
+            "名詞,一般,*,*,*,*".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            "テスト".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            NodeType::Unknown,
+        );
+        assert!(lattice.add(node).is_ok());
+        lattice.forward();
+        assert!(lattice.end().is_ok());
+
+        let eos_node = &lattice.start_nodes(lattice.position()).unwrap()[0];
+        assert_eq!(eos_node.left_id(), 7);
+        // BOS(3) -> テスト(left 1): 3001, +150, テスト(right 2) -> EOS(7): 2007
+        assert_eq!(eos_node.min_cost(), 3001 + 150 + 2007);
+    }
+
     #[test]
     fn test_end_method_truncation() {
         let dic = create_mock_dictionary();
         let mut lattice = Lattice::new(10, dic); // Large lattice
 
         // Add a short unknown node
-        let node = Box::new(UnknownNode::new(
+        let node = UnknownNode::new(
             "短".to_string(), // 1 character
             100,
             200,
@@ -2051,7 +2600,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
         assert!(lattice.add(node).is_ok());
 
         // Move forward
@@ -2085,7 +2634,7 @@ mod tests {
         assert_eq!(lattice.position(), 1);
 
         // Add an unknown node to create end nodes (3-character surface)
-        let node = Box::new(UnknownNode::new(
+        let node = UnknownNode::new(
             "テスト".to_string(), // 3 characters
             100,
             200,
@@ -2097,7 +2646,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
         assert!(lattice.add(node).is_ok());
 
         // Forward should move to next position with end nodes
@@ -2147,7 +2696,7 @@ mod tests {
         let entries = entries_result.unwrap();
 
         for entry in &entries {
-            let node = Box::new(Node::new(entry, NodeType::SysDict)) as Box<dyn LatticeNode>;
+            let node = DictNode::new(entry, NodeType::SysDict);
             let add_result = lattice.add(node);
             assert!(add_result.is_ok(), "Adding node should succeed");
         }
@@ -2209,7 +2758,7 @@ mod tests {
             let substring_entries = substring_entries_result.unwrap();
 
             for entry in &substring_entries {
-                let node = Box::new(Node::new(entry, NodeType::SysDict)) as Box<dyn LatticeNode>;
+                let node = DictNode::new(entry, NodeType::SysDict);
                 let add_result = lattice.add(node);
                 assert!(add_result.is_ok(), "Adding substring node should succeed");
             }
@@ -2263,7 +2812,7 @@ mod tests {
             let final_entries = final_entries_result.unwrap();
 
             for entry in &final_entries {
-                let node = Box::new(Node::new(entry, NodeType::SysDict)) as Box<dyn LatticeNode>;
+                let node = DictNode::new(entry, NodeType::SysDict);
                 let add_result = lattice.add(node);
                 assert!(add_result.is_ok(), "Adding final node should succeed");
             }
@@ -2316,15 +2865,15 @@ mod tests {
         );
 
         // Python test: self.assertTrue(isinstance(lattice.enodes[5][0], EOS))
-        if let Some(end_nodes_5) = lattice.end_nodes(final_pos + 1) {
-            if !end_nodes_5.is_empty() {
-                let node_ref = NodeRef {
-                    pos: end_nodes_5[0].pos as usize,
-                    index: end_nodes_5[0].index as usize,
-                };
-                if let Some(node) = lattice.get_node(&node_ref) {
-                    assert_eq!(node.surface(), "__EOS__", "EOS should appear in end nodes");
-                }
+        if let Some(end_nodes_5) = lattice.end_nodes(final_pos + 1)
+            && !end_nodes_5.is_empty()
+        {
+            let node_ref = NodeRef {
+                pos: end_nodes_5[0].pos as usize,
+                index: end_nodes_5[0].index as usize,
+            };
+            if let Some(node) = lattice.get_node(&node_ref) {
+                assert_eq!(node.surface(), "__EOS__", "EOS should appear in end nodes");
             }
         }
     }
@@ -2335,7 +2884,7 @@ mod tests {
         let mut lattice = Lattice::new(3, dic);
 
         // Add a simple chain: BOS -> node1 -> EOS
-        let node1 = Box::new(UnknownNode::new(
+        let node1 = UnknownNode::new(
             "テスト".to_string(),
             100,
             200,
@@ -2347,7 +2896,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         assert!(lattice.add(node1).is_ok());
         lattice.forward();
@@ -2370,6 +2919,46 @@ mod tests {
         assert_eq!(path[2].surface(), "__EOS__", "Third node should be EOS");
     }
 
+    #[test]
+    fn test_to_dot() {
+        let dic = create_mock_dictionary();
+        let mut lattice = Lattice::new(3, dic);
+
+        let make_node = |surface: &str, cost: i16| {
+            UnknownNode::new(
+                surface.to_string(),
+                100,
+                200,
+                cost,
+                "名詞,一般,*,*,*,*".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                surface.to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                NodeType::Unknown,
+            )
+        };
+        assert!(lattice.add(make_node("テスト", 150)).is_ok());
+        assert!(lattice.add(make_node("テ\"ト", 5000)).is_ok());
+        lattice.forward();
+        assert!(lattice.end().is_ok());
+
+        let dot = lattice.to_dot().unwrap();
+        assert!(dot.starts_with("digraph lattice {"));
+        assert!(dot.ends_with("}\n"));
+
+        // Best path nodes and edges are highlighted, the alternative is not
+        assert!(dot.contains(
+            "n1_0 [label=\"テスト\\n名詞\\ncost=150 min=250\", color=red, fontcolor=red, penwidth=2];"
+        ));
+        assert!(dot.contains("n1_1 [label=\"テ\\\"ト\\n名詞\\ncost=5000 min=5100\"];"));
+        assert!(dot.contains("n0_0 -> n1_0 [label=\"100\", color=red"));
+        assert!(dot.contains("n0_0 -> n1_1 [label=\"100\"];"));
+        assert!(dot.contains("n1_0 -> n4_0 [label=\"100\", color=red"));
+        assert!(dot.contains("n1_1 -> n4_0 [label=\"100\"];"));
+    }
+
     #[test]
     fn test_backward_method_empty_lattice() {
         let dic = create_mock_dictionary();
@@ -2389,7 +2978,7 @@ mod tests {
         let mut lattice = Lattice::new(3, dic);
 
         // Add a node but don't call end()
-        let node = Box::new(UnknownNode::new(
+        let node = UnknownNode::new(
             "テスト".to_string(),
             100,
             200,
@@ -2401,7 +2990,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         assert!(lattice.add(node).is_ok());
 
@@ -2416,7 +3005,7 @@ mod tests {
         let mut lattice = Lattice::new(5, dic);
 
         // Add multiple nodes: BOS -> node1 -> node2 -> EOS
-        let node1 = Box::new(UnknownNode::new(
+        let node1 = UnknownNode::new(
             "日本".to_string(),
             100,
             200,
@@ -2428,12 +3017,12 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         assert!(lattice.add(node1).is_ok());
         lattice.forward();
 
-        let node2 = Box::new(UnknownNode::new(
+        let node2 = UnknownNode::new(
             "語".to_string(),
             200,
             300,
@@ -2445,7 +3034,7 @@ mod tests {
             "*".to_string(),
             "*".to_string(),
             NodeType::Unknown,
-        )) as Box<dyn LatticeNode>;
+        );
 
         assert!(lattice.add(node2).is_ok());
         lattice.forward();
@@ -2507,7 +3096,7 @@ mod tests {
 
             // Add all entries to lattice
             for entry in &entries {
-                let node = Box::new(Node::new(entry, NodeType::SysDict)) as Box<dyn LatticeNode>;
+                let node = DictNode::new(entry, NodeType::SysDict);
                 let add_result = lattice.add(node);
                 assert!(add_result.is_ok(), "Adding node should succeed");
             }
@@ -2588,7 +3177,7 @@ mod tests {
 
             // Python test: for e in entries: lattice.add(SurfaceNode(e))
             for entry in &entries {
-                let node = Box::new(Node::new(entry, NodeType::SysDict)) as Box<dyn LatticeNode>;
+                let node = DictNode::new(entry, NodeType::SysDict);
                 let add_result = lattice.add(node);
                 assert!(add_result.is_ok(), "Adding node should succeed");
             }
@@ -1,30 +1,68 @@
 pub mod analyzer;
 pub mod charfilter;
+pub mod config;
+pub mod corpus;
 pub mod dict_builder;
 pub mod dictionary;
 pub mod error;
+pub mod eval;
+pub mod formatter;
+pub mod furigana;
+#[doc(hidden)]
 pub mod intern;
-pub mod lattice;
+pub mod kana;
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+pub(crate) mod lattice;
+pub mod prelude;
+pub mod rescorer;
+pub mod stats;
+pub mod subword;
 pub mod tokenfilter;
 pub mod tokenizer;
 
 #[cfg(feature = "python")]
 pub mod python_bindings;
 
+#[cfg(feature = "wasm")]
+pub mod wasm_bindings;
+
 #[cfg(test)]
 pub mod tokenizer_tests;
 
-pub use analyzer::{Analyzer, AnalyzerBuilder};
-pub use charfilter::{CharFilter, RegexReplaceCharFilter, UnicodeNormalizeCharFilter};
-pub use dict_builder::DictionaryBuilder;
-pub use dictionary::{Dictionary, DictionaryResource, Matcher, RAMDictionary};
-pub use error::{Result, RunomeError};
-pub use lattice::{BOS, EOS, Lattice, LatticeNode, Node, NodeType, UnknownNode};
+pub use analyzer::{Analyzer, AnalyzerBuilder, index_terms};
+pub use charfilter::{
+    CharFilter, CharWidth, OffsetMapping, RegexReplaceCharFilter, UnicodeNormalizeCharFilter,
+    WidthNormalizeCharFilter,
+};
+pub use config::{GlobalConfig, configure};
+pub use corpus::{CancellationToken, CorpusOutput, CorpusProgress, CorpusRunner};
+pub use dict_builder::{BuildProgress, BuildStage, DictionaryBuilder, DictionaryExporter};
+pub use dictionary::{Dictionary, MatcherBackend, MemoryStats};
+pub use error::{ErrorCode, Result, RunomeError};
+pub use eval::{EvalReport, Evaluator};
+pub use formatter::{
+    ChasenFormatter, ConlluFormatter, Formatter, JanomeFormatter, MecabFormatter, WakatiWriter,
+};
+pub use furigana::{Ruby, furigana, to_ruby_html};
+pub use lattice::NodeType;
+pub use rescorer::{Candidate, DEFAULT_COST_FACTOR, DEFAULT_THETA, Rescorer};
+pub use stats::{Collocation, NgramStats};
+pub use subword::{BpeModel, SubwordFallback};
 pub use tokenfilter::{
-    CompoundNounFilter, ExtractAttributeFilter, LowerCaseFilter, POSKeepFilter, POSStopFilter,
-    TokenCountFilter, TokenFilter, UpperCaseFilter,
+    CompoundNounFilter, DedupFilter, ExtractAttributeFilter, LowerCaseFilter, POSKeepFilter,
+    POSStopFilter, PiiKind, PiiMaskFilter, RomajiFilter, TokenCountFilter, TokenFilter,
+    UpperCaseFilter, WordKeepFilter, WordStopFilter,
 };
-pub use tokenizer::{Token, TokenizeResult, Tokenizer};
+pub use tokenizer::{
+    CandidateOverflow, PhaseTimings, SlowCallReport, SpacePolicy, Token, TokenizeOptions,
+    TokenizeResult, Tokenizer, TokenizerMetrics, UnknownDecision,
+};
+
+// Internal building blocks, exported for experimentation only and exempt from semver
+#[cfg(feature = "unstable")]
+pub use dictionary::{DictionaryResource, DoubleArray, FstData, Matcher, RAMDictionary, loader};
+#[cfg(feature = "unstable")]
+pub use lattice::{BOS, DictNode, EOS, Lattice, LatticeNode, Node, UnknownNode};
 
 #[cfg(feature = "python")]
 pub use python_bindings::*;
@@ -0,0 +1,39 @@
+//! Stable public API of runome
+//!
+//! `use runome::prelude::*;` brings in everything needed to tokenize and analyze
+//! text. Items re-exported here follow semantic versioning: they are only
+//! changed incompatibly in a new major (or, before 1.0, minor) release.
+//!
+//! Everything else is outside the stability guarantee:
+//! - internals such as the lattice, string interning and dictionary loaders are
+//!   `#[doc(hidden)]` and may change in any release;
+//! - experimental APIs are only exported with the `unstable` cargo feature.
+//!
+//! # Example
+//! ```rust,no_run
+//! use runome::prelude::*;
+//!
+//! let tokenizer = Tokenizer::new(None, None).unwrap();
+//! for result in tokenizer.tokenize("すもももももももものうち", None, None) {
+//!     if let Ok(TokenizeResult::Token(token)) = result {
+//!         println!("{}", token);
+//!     }
+//! }
+//! ```
+
+pub use crate::analyzer::{Analyzer, AnalyzerBuilder};
+pub use crate::charfilter::{
+    CharFilter, OffsetMapping, RegexReplaceCharFilter, UnicodeNormalizeCharFilter,
+};
+pub use crate::dictionary::{Dictionary, SystemDictionary, UserDictFormat, UserDictionary};
+pub use crate::error::{Result, RunomeError};
+pub use crate::formatter::{
+    ChasenFormatter, Formatter, JanomeFormatter, MecabFormatter, WakatiWriter,
+};
+pub use crate::lattice::NodeType;
+pub use crate::tokenfilter::{
+    CompoundNounFilter, DedupFilter, ExtractAttributeFilter, LowerCaseFilter, POSKeepFilter,
+    POSStopFilter, PiiKind, PiiMaskFilter, RomajiFilter, TokenCountFilter, TokenFilter,
+    UpperCaseFilter, WordKeepFilter, WordStopFilter,
+};
+pub use crate::tokenizer::{SpacePolicy, Token, TokenizeOptions, TokenizeResult, Tokenizer};
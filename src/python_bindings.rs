@@ -1,14 +1,29 @@
-use pyo3::exceptions::{PyException, PyNotImplementedError};
+use pyo3::exceptions::{PyException, PyNotImplementedError, PyTypeError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyTuple};
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::charfilter::{CharFilter, RegexReplaceCharFilter, UnicodeNormalizeCharFilter};
+use crate::charfilter::{
+    CharFilter, CharWidth, RegexReplaceCharFilter, UnicodeNormalizeCharFilter,
+    WidthNormalizeCharFilter,
+};
+use crate::dictionary::{CompiledUserDictionary, SystemDictionary, UserDictFormat, UserDictionary};
 use crate::error::RunomeError;
+use crate::formatter::{ChasenFormatter, ConlluFormatter, Formatter, MecabFormatter, WakatiWriter};
+use crate::rescorer::{DEFAULT_COST_FACTOR, DEFAULT_THETA};
 use crate::tokenfilter::{
-    CompoundNounFilter, ExtractAttributeFilter, LowerCaseFilter, POSKeepFilter, POSStopFilter,
-    TokenCountFilter, TokenFilter, UpperCaseFilter,
+    CompoundNounFilter, DedupFilter, ExtractAttributeFilter, LowerCaseFilter, POSKeepFilter,
+    POSStopFilter, PiiKind, PiiMaskFilter, RomajiFilter, TokenCountFilter, TokenFilter,
+    UpperCaseFilter, WordKeepFilter, WordStopFilter,
+};
+use crate::tokenizer::{
+    TextChunkIterator, Token as RustToken, TokenizeOptions, TokenizeResult,
+    Tokenizer as RustTokenizer,
 };
-use crate::tokenizer::{Token as RustToken, TokenizeResult, Tokenizer as RustTokenizer};
 
 /// Python wrapper for RunomeError
 impl From<RunomeError> for PyErr {
@@ -62,6 +77,12 @@ impl PyToken {
         self.inner.reading().to_string()
     }
 
+    /// reading property converted to hiragana
+    #[getter]
+    fn reading_hiragana(&self) -> String {
+        self.inner.reading_hiragana()
+    }
+
     /// phonetic property
     #[getter]
     fn phonetic(&self) -> String {
@@ -74,6 +95,18 @@ impl PyToken {
         format!("{:?}", self.inner.node_type())
     }
 
+    /// ID of the dictionary entry, None for unknown words
+    #[getter]
+    fn word_id(&self) -> Option<usize> {
+        self.inner.word_id()
+    }
+
+    /// Word cost of the lattice node, without connection costs
+    #[getter]
+    fn word_cost(&self) -> Option<i16> {
+        self.inner.word_cost()
+    }
+
     /// String representation matching Janome format
     fn __str__(&self) -> String {
         format!("{}", self.inner)
@@ -87,19 +120,77 @@ impl PyToken {
             self.inner.part_of_speech()
         )
     }
+
+    /// Tokens are equal when all their attributes are, wherever they occur
+    fn __eq__(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.key().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Length of the surface in characters
+    fn __len__(&self) -> usize {
+        self.inner.surface().chars().count()
+    }
+
+    /// All attributes as a dict, for serialization such as `json.dumps`
+    fn as_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("surface", self.inner.surface())?;
+        dict.set_item("part_of_speech", self.inner.part_of_speech())?;
+        dict.set_item("infl_type", self.inner.infl_type())?;
+        dict.set_item("infl_form", self.inner.infl_form())?;
+        dict.set_item("base_form", self.inner.base_form())?;
+        dict.set_item("reading", self.inner.reading())?;
+        dict.set_item("phonetic", self.inner.phonetic())?;
+        dict.set_item("node_type", self.node_type())?;
+        dict.set_item("word_id", self.inner.word_id())?;
+        dict.set_item("word_cost", self.inner.word_cost())?;
+        Ok(dict)
+    }
 }
 
 impl PyToken {
     fn from_rust_token(token: RustToken) -> Self {
         PyToken { inner: token }
     }
+
+    /// Attributes compared by `__eq__` and `__hash__`, as returned by `as_dict`
+    fn key(&self) -> ([&str; 7], String, Option<usize>, Option<i16>) {
+        (
+            [
+                self.inner.surface(),
+                self.inner.part_of_speech(),
+                self.inner.infl_type(),
+                self.inner.infl_form(),
+                self.inner.base_form(),
+                self.inner.reading(),
+                self.inner.phonetic(),
+            ],
+            self.node_type(),
+            self.inner.word_id(),
+            self.inner.word_cost(),
+        )
+    }
 }
 
+/// Number of results tokenized per release of the GIL
+const TOKEN_BATCH_SIZE: usize = 256;
+
 /// Python iterator for tokenization results
+///
+/// Tokens are produced on demand, like the generator returned by Janome, so
+/// memory use does not grow with the length of the input. They are tokenized
+/// in small batches with the GIL released, so other Python threads keep
+/// running while a long document is tokenized.
 #[pyclass(name = "TokenIterator")]
 pub struct PyTokenIterator {
-    results: Vec<TokenizeResult>,
-    index: usize,
+    results: TextChunkIterator<RustTokenizer, String>,
+    pending: VecDeque<Result<TokenizeResult, RunomeError>>,
 }
 
 #[pymethods]
@@ -108,36 +199,147 @@ impl PyTokenIterator {
         slf
     }
 
-    fn __next__(&mut self) -> PyResult<Option<PyObject>> {
-        if self.index >= self.results.len() {
+    fn __next__(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        if self.pending.is_empty() {
+            let (results, pending) = (&mut self.results, &mut self.pending);
+            py.allow_threads(|| pending.extend(results.take(TOKEN_BATCH_SIZE)));
+        }
+        let Some(result) = self.pending.pop_front() else {
             return Ok(None);
+        };
+        let result =
+            result.map_err(|e| PyException::new_err(format!("Tokenization failed: {:?}", e)))?;
+
+        match result {
+            TokenizeResult::Token(token) => {
+                // Return PyToken object - Rust tokenizer decided this should be a token
+                #[allow(deprecated)]
+                Ok(Some(PyToken::from_rust_token(token).into_py(py)))
+            }
+            TokenizeResult::Surface(surface) => {
+                // Return surface string - Rust tokenizer decided this should be wakati mode
+                #[allow(deprecated)]
+                Ok(Some(surface.into_py(py)))
+            }
+            TokenizeResult::Skipped { span } => {
+                // Return (start, end) byte offsets of the untokenized span
+                #[allow(deprecated)]
+                Ok(Some((span.start, span.end).into_py(py)))
+            }
+            TokenizeResult::Bos { position } =>
+            {
+                #[allow(deprecated)]
+                Ok(Some(("BOS", position).into_py(py)))
+            }
+            TokenizeResult::Eos { position } =>
+            {
+                #[allow(deprecated)]
+                Ok(Some(("EOS", position).into_py(py)))
+            }
         }
+    }
+}
 
-        let result = &self.results[self.index];
-        self.index += 1;
+/// Load the user dictionary passed as `udic` to the Tokenizer
+///
+/// A directory is loaded as a compiled user dictionary, ignoring `udic_enc`
+/// and `udic_type`; any other path is parsed as a CSV file.
+fn load_user_dictionary(udic: &Path, udic_enc: &str, udic_type: &str) -> PyResult<UserDictionary> {
+    let connections = SystemDictionary::instance()
+        .map_err(|e| PyException::new_err(format!("Failed to load system dictionary: {:?}", e)))?
+        .get_connection_matrix();
+
+    if udic.is_dir() {
+        return CompiledUserDictionary::load(udic)
+            .and_then(|compiled| compiled.into_user_dictionary(connections))
+            .map_err(|e| {
+                PyException::new_err(format!("Failed to load compiled user dictionary: {:?}", e))
+            });
+    }
 
-        Python::with_gil(|py| {
-            match result {
-                TokenizeResult::Token(token) => {
-                    // Return PyToken object - Rust tokenizer decided this should be a token
-                    #[allow(deprecated)]
-                    Ok(Some(PyToken::from_rust_token(token.clone()).into_py(py)))
-                }
-                TokenizeResult::Surface(surface) => {
-                    // Return surface string - Rust tokenizer decided this should be wakati mode
-                    #[allow(deprecated)]
-                    Ok(Some(surface.clone().into_py(py)))
-                }
-            }
-        })
+    let dict_format = user_dict_format(udic_type)?;
+    let encoding = japanese_encoding(udic_enc)?;
+    UserDictionary::new_with_encoding(udic, dict_format, encoding, connections)
+        .map_err(|e| PyException::new_err(format!("Failed to load user dictionary: {:?}", e)))
+}
+
+/// Parse the `udic_type` of a CSV user dictionary
+fn user_dict_format(udic_type: &str) -> PyResult<UserDictFormat> {
+    match udic_type {
+        "ipadic" => Ok(UserDictFormat::Ipadic),
+        "simpledic" => Ok(UserDictFormat::Simpledic),
+        _ => Err(PyException::new_err(format!(
+            "Unsupported user dictionary type: {}. Use 'ipadic' or 'simpledic'",
+            udic_type
+        ))),
     }
 }
 
+/// Resolve the `udic_enc` of a CSV user dictionary or the encoding of bytes input
+///
+/// Accepts WHATWG labels ('euc-jp', 'shift_jis', 'sjis', ...) as well as the
+/// Python codec names Janome users pass ('utf8', 'euc_jp', 'cp932', ...), but
+/// only for the encodings Japanese text is commonly stored in.
+fn japanese_encoding(label: &str) -> PyResult<&'static encoding_rs::Encoding> {
+    let normalized = label.trim().to_ascii_lowercase().replace('_', "-");
+    let label_name = match normalized.as_str() {
+        "utf8" => "utf-8",
+        "cp932" | "932" | "ms932" | "mskanji" | "ms-kanji" => "windows-31j",
+        "eucjp" | "ujis" | "u-jis" => "euc-jp",
+        "shiftjis" | "s-jis" => "shift_jis",
+        label => label,
+    };
+    encoding_rs::Encoding::for_label(label_name.as_bytes())
+        .filter(|encoding| {
+            [encoding_rs::UTF_8, encoding_rs::EUC_JP, encoding_rs::SHIFT_JIS].contains(encoding)
+        })
+        .ok_or_else(|| {
+            PyException::new_err(format!(
+                "Unsupported encoding: {}. Supported encodings are 'utf8', 'euc-jp', and 'shift_jis'",
+                label
+            ))
+        })
+}
+
+/// Get the text of a `str`, or decode `bytes` with the given encoding
+///
+/// Bytes that are invalid in the encoding are an error rather than replaced,
+/// like `bytes.decode` with `errors='strict'`.
+fn decode_text(text: &Bound<'_, PyAny>, encoding: Option<&str>) -> PyResult<String> {
+    let Ok(bytes) = text.downcast::<PyBytes>() else {
+        if encoding.is_some() {
+            return Err(PyTypeError::new_err(
+                "encoding is only supported for bytes input",
+            ));
+        }
+        return text.extract();
+    };
+    let encoding = japanese_encoding(encoding.unwrap_or("utf8"))?;
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes.as_bytes())
+        .map(|text| text.into_owned())
+        .ok_or_else(|| PyException::new_err(format!("Input is not valid {}", encoding.name())))
+}
+
 /// Python Tokenizer class - mirrors Janome Tokenizer exactly
-#[pyclass(name = "Tokenizer")]
+#[pyclass(name = "Tokenizer", module = "runome.runome")]
 #[derive(Clone)]
 pub struct PyTokenizer {
     inner: RustTokenizer,
+    /// Constructor arguments, kept to rebuild the tokenizer when unpickling
+    args: TokenizerArgs,
+}
+
+/// Arguments a Python Tokenizer was created with
+#[derive(Clone)]
+struct TokenizerArgs {
+    udic: PathBuf,
+    udic_enc: String,
+    udic_type: String,
+    max_unknown_length: usize,
+    wakati: bool,
+    theta: f64,
+    cost_factor: f64,
 }
 
 #[pymethods]
@@ -145,69 +347,33 @@ impl PyTokenizer {
     /// Initialize Tokenizer with Janome-compatible parameters
     ///
     /// Args:
-    ///     udic (str): User dictionary file path (CSV format) or directory path to compiled dictionary data (default: '')
-    ///     udic_enc (str): Character encoding for user dictionary (default: 'utf8')
-    ///     udic_type (str): User dictionary type - 'ipadic' or 'simpledic' (default: 'ipadic')
+    ///     udic (str | os.PathLike): User dictionary file path (CSV format) or directory path to compiled dictionary data (default: '')
+    ///     udic_enc (str): Character encoding of a CSV user dictionary - 'utf8', 'euc-jp' or 'shift_jis',
+    ///         also accepting Python codec names like 'euc_jp' or 'cp932' (default: 'utf8')
+    ///     udic_type (str): User dictionary type of a CSV user dictionary - 'ipadic' or 'simpledic' (default: 'ipadic')
     ///     max_unknown_length (int): Maximum unknown word length (default: 1024)
     ///     wakati (bool): Wakati mode flag (default: False)
+    ///     theta (float): Scale of the costs in the probabilities of nbest candidates,
+    ///         like MeCab's --theta (default: 0.75)
+    ///     cost_factor (float): cost-factor of the dicrc the system dictionary was
+    ///         compiled with, undone in the probabilities of nbest candidates (default: 800)
     #[new]
-    #[pyo3(signature = (udic = "", *, udic_enc = "utf8", udic_type = "ipadic", max_unknown_length = 1024, wakati = false))]
+    #[pyo3(signature = (udic = PathBuf::new(), *, udic_enc = "utf8", udic_type = "ipadic", max_unknown_length = 1024, wakati = false, theta = DEFAULT_THETA, cost_factor = DEFAULT_COST_FACTOR))]
     fn new(
-        udic: &str,
+        udic: PathBuf,
         udic_enc: &str,
         udic_type: &str,
         max_unknown_length: usize,
         wakati: bool,
+        theta: f64,
+        cost_factor: f64,
     ) -> PyResult<Self> {
-        let tokenizer = if udic.is_empty() {
+        let tokenizer = if udic.as_os_str().is_empty() {
             // No user dictionary
             RustTokenizer::new(Some(max_unknown_length), Some(wakati))
                 .map_err(|e| PyException::new_err(format!("Failed to create tokenizer: {:?}", e)))?
         } else {
-            // Convert udic_type string to enum
-            let dict_format = match udic_type {
-                "ipadic" => crate::dictionary::user_dict::UserDictFormat::Ipadic,
-                "simpledic" => crate::dictionary::user_dict::UserDictFormat::Simpledic,
-                _ => {
-                    return Err(PyException::new_err(format!(
-                        "Unsupported user dictionary type: {}. Use 'ipadic' or 'simpledic'",
-                        udic_type
-                    )));
-                }
-            };
-
-            // Convert encoding string to Rust encoding
-            let encoding = match udic_enc {
-                "utf8" | "utf-8" => encoding_rs::UTF_8,
-                "euc-jp" => encoding_rs::EUC_JP,
-                "shift_jis" | "sjis" => encoding_rs::SHIFT_JIS,
-                _ => {
-                    return Err(PyException::new_err(format!(
-                        "Unsupported encoding: {}. Supported encodings are 'utf8', 'euc-jp', and 'shift_jis'",
-                        udic_enc
-                    )));
-                }
-            };
-
-            // Load user dictionary
-            let user_dict = {
-                use std::path::Path;
-                let connections = crate::dictionary::system_dict::SystemDictionary::instance()
-                    .map_err(|e| {
-                        PyException::new_err(format!("Failed to load system dictionary: {:?}", e))
-                    })?
-                    .get_connection_matrix();
-
-                crate::dictionary::user_dict::UserDictionary::new_with_encoding(
-                    Path::new(udic),
-                    dict_format,
-                    encoding,
-                    connections,
-                )
-                .map_err(|e| {
-                    PyException::new_err(format!("Failed to load user dictionary: {:?}", e))
-                })?
-            };
+            let user_dict = load_user_dictionary(&udic, udic_enc, udic_type)?;
 
             // Create tokenizer with user dictionary
             RustTokenizer::with_user_dict(
@@ -221,9 +387,75 @@ impl PyTokenizer {
                     e
                 ))
             })?
+        }
+        .with_theta(theta)
+        .with_cost_factor(cost_factor);
+
+        let args = TokenizerArgs {
+            udic,
+            udic_enc: udic_enc.to_string(),
+            udic_type: udic_type.to_string(),
+            max_unknown_length,
+            wakati,
+            theta,
+            cost_factor,
+        };
+        Ok(PyTokenizer {
+            inner: tokenizer,
+            args,
+        })
+    }
+
+    /// Pickle support: the constructor arguments the tokenizer is rebuilt from
+    ///
+    /// The user dictionary path is made absolute, so workers running in
+    /// another directory (multiprocessing, joblib, Spark, Dask) find it. The
+    /// file must still be readable at that path in the worker.
+    fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let args = &self.args;
+        let udic = if args.udic.as_os_str().is_empty() {
+            args.udic.clone()
+        } else {
+            std::path::absolute(&args.udic)?
         };
+        let state = PyDict::new(py);
+        state.set_item("udic", udic)?;
+        state.set_item("udic_enc", &args.udic_enc)?;
+        state.set_item("udic_type", &args.udic_type)?;
+        state.set_item("max_unknown_length", args.max_unknown_length)?;
+        state.set_item("wakati", args.wakati)?;
+        state.set_item("theta", args.theta)?;
+        state.set_item("cost_factor", args.cost_factor)?;
+        Ok(state)
+    }
 
-        Ok(PyTokenizer { inner: tokenizer })
+    /// Pickle support: rebuild the tokenizer from the state of `__getstate__`
+    fn __setstate__(&mut self, state: &Bound<'_, PyDict>) -> PyResult<()> {
+        fn get<'py, T: FromPyObject<'py>>(state: &Bound<'py, PyDict>, key: &str) -> PyResult<T> {
+            state
+                .get_item(key)?
+                .ok_or_else(|| {
+                    PyException::new_err(format!("Invalid Tokenizer state: missing '{}'", key))
+                })?
+                .extract()
+        }
+        *self = Self::new(
+            get(state, "udic")?,
+            &get::<String>(state, "udic_enc")?,
+            &get::<String>(state, "udic_type")?,
+            get(state, "max_unknown_length")?,
+            get(state, "wakati")?,
+            // Missing from states pickled before theta and cost_factor were added
+            match state.get_item("theta")? {
+                Some(theta) => theta.extract()?,
+                None => DEFAULT_THETA,
+            },
+            match state.get_item("cost_factor")? {
+                Some(cost_factor) => cost_factor.extract()?,
+                None => DEFAULT_COST_FACTOR,
+            },
+        )?;
+        Ok(())
     }
 
     /// Get version info to verify we're using the right code
@@ -234,41 +466,119 @@ impl PyTokenizer {
     /// Tokenize text with Janome-compatible parameters
     ///
     /// Args:
-    ///     text (str): Input text to tokenize
+    ///     text (str | bytes): Input text to tokenize
     ///     wakati (bool): Override wakati mode (default: None)
     ///     baseform_unk (bool): Set base form for unknown words (default: True)
+    ///     dotfile (str): Write the lattice of inputs under 1024 characters as
+    ///         Graphviz DOT to this file (default: '', no file)
+    ///     encoding (str): Encoding of bytes input - 'utf8', 'euc-jp', or
+    ///         'shift_jis' (default: None, meaning 'utf8')
     ///
     /// Returns:
     ///     Iterator yielding Token objects (wakati=False) or strings (wakati=True)
-    #[pyo3(signature = (text, wakati = None, baseform_unk = true))]
+    #[pyo3(signature = (text, wakati = None, baseform_unk = true, dotfile = PathBuf::new(), *, encoding = None))]
     fn tokenize(
         &self,
-        text: &str,
+        text: &Bound<'_, PyAny>,
         wakati: Option<bool>,
         baseform_unk: bool,
+        dotfile: PathBuf,
+        encoding: Option<&str>,
     ) -> PyResult<PyTokenIterator> {
-        // Let the Rust tokenizer handle wakati precedence
-        let results: Result<Vec<_>, _> = self
-            .inner
-            .tokenize(text, wakati, Some(baseform_unk))
-            .collect();
+        let text = decode_text(text, encoding)?;
+        Ok(self.tokenize_text(text, wakati, baseform_unk, dotfile))
+    }
 
-        let token_results =
-            results.map_err(|e| PyException::new_err(format!("Tokenization failed: {:?}", e)))?;
+    /// Tokenize many texts in parallel with the GIL released
+    ///
+    /// Tokens are always returned in full, ignoring wakati mode.
+    fn tokenize_batch(&self, py: Python, texts: Vec<String>) -> PyResult<Vec<Vec<PyToken>>> {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let batch = py.allow_threads(|| self.inner.tokenize_batch(&texts))?;
+        Ok(batch
+            .into_iter()
+            .map(|tokens| tokens.into_iter().map(PyToken::from_rust_token).collect())
+            .collect())
+    }
 
-        Ok(PyTokenIterator {
-            results: token_results,
-            index: 0,
-        })
+    /// Find the N best segmentations of text with the GIL released
+    ///
+    /// Returns (tokens, cost, probability) tuples in order of increasing cost
+    #[pyo3(signature = (text, n = 10))]
+    fn nbest(&self, py: Python, text: &str, n: usize) -> PyResult<Vec<(Vec<PyToken>, i32, f64)>> {
+        let candidates = py.allow_threads(|| self.inner.nbest(text, n))?;
+        Ok(candidates
+            .into_iter()
+            .map(|candidate| {
+                let tokens = candidate
+                    .tokens
+                    .into_iter()
+                    .map(PyToken::from_rust_token)
+                    .collect();
+                (tokens, candidate.cost, candidate.probability)
+            })
+            .collect())
+    }
+
+    /// Split text into a list of surfaces with the GIL released
+    fn wakati(&self, py: Python, text: &str) -> PyResult<Vec<String>> {
+        let mut surfaces = Vec::new();
+        py.allow_threads(|| {
+            for result in self.inner.tokenize(text, Some(true), None) {
+                match result? {
+                    TokenizeResult::Surface(surface) => surfaces.push(surface),
+                    TokenizeResult::Token(token) => surfaces.push(token.surface().to_string()),
+                    TokenizeResult::Skipped { .. }
+                    | TokenizeResult::Bos { .. }
+                    | TokenizeResult::Eos { .. } => {}
+                }
+            }
+            Ok::<_, RunomeError>(())
+        })?;
+        Ok(surfaces)
+    }
+}
+
+impl PyTokenizer {
+    /// Tokenize text into a Python iterator
+    fn tokenize_text(
+        &self,
+        text: String,
+        wakati: Option<bool>,
+        baseform_unk: bool,
+        dotfile: PathBuf,
+    ) -> PyTokenIterator {
+        // Let the Rust tokenizer handle wakati precedence
+        let mut options = TokenizeOptions::new().with_baseform_unk(baseform_unk);
+        options.wakati = wakati;
+        if !dotfile.as_os_str().is_empty() {
+            options = options.with_dotfile(dotfile);
+        }
+        PyTokenIterator {
+            results: self.inner.tokenize_owned_with(text, &options),
+            pending: VecDeque::new(),
+        }
     }
 }
 
 /// Python CharFilter base class - mirrors Janome CharFilter
+///
+/// Subclass it in Python and override `apply` to implement a filter; any
+/// other callable taking and returning a string also works as a CharFilter.
 #[pyclass(name = "CharFilter", subclass)]
 pub struct PyCharFilter;
 
 #[pymethods]
 impl PyCharFilter {
+    /// Create the base of a CharFilter subclass
+    ///
+    /// Arguments are accepted for the `__init__` of Python subclasses.
+    #[new]
+    #[pyo3(signature = (*_args, **_kwargs))]
+    fn new(_args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>) -> Self {
+        PyCharFilter
+    }
+
     /// Apply the filter to input text
     fn apply(&self, _text: &str) -> PyResult<String> {
         Err(PyNotImplementedError::new_err(
@@ -276,9 +586,10 @@ impl PyCharFilter {
         ))
     }
 
-    /// Callable interface for Python compatibility
-    fn __call__(&self, text: &str) -> PyResult<String> {
-        self.apply(text)
+    /// Callable interface for Python compatibility, dispatching to the
+    /// `apply` of the subclass
+    fn __call__(slf: &Bound<'_, Self>, text: &str) -> PyResult<PyObject> {
+        slf.call_method1("apply", (text,)).map(Bound::unbind)
     }
 }
 
@@ -293,11 +604,11 @@ impl PyRegexReplaceCharFilter {
     /// Create a new RegexReplaceCharFilter
     ///
     /// Args:
-    ///     pattern (str): Regular expression pattern to match
-    ///     replacement (str): Replacement string
+    ///     pat (str): Regular expression pattern to match
+    ///     repl (str): Replacement string
     #[new]
-    fn new(pattern: &str, replacement: &str) -> PyResult<(Self, PyCharFilter)> {
-        let inner = RegexReplaceCharFilter::new(pattern, replacement)
+    fn new(pat: &str, repl: &str) -> PyResult<(Self, PyCharFilter)> {
+        let inner = RegexReplaceCharFilter::new(pat, repl)
             .map_err(|e| PyException::new_err(format!("Invalid regex pattern: {:?}", e)))?;
         Ok((PyRegexReplaceCharFilter { inner }, PyCharFilter))
     }
@@ -348,6 +659,51 @@ impl PyUnicodeNormalizeCharFilter {
     }
 }
 
+/// Python wrapper for WidthNormalizeCharFilter
+#[pyclass(name = "WidthNormalizeCharFilter", extends = PyCharFilter)]
+pub struct PyWidthNormalizeCharFilter {
+    inner: WidthNormalizeCharFilter,
+}
+
+#[pymethods]
+impl PyWidthNormalizeCharFilter {
+    /// Create a new WidthNormalizeCharFilter
+    ///
+    /// Args:
+    ///     katakana (str | None): Width to convert katakana to - 'full', 'half', or None to keep it (default: 'full')
+    ///     ascii (str | None): Width to convert ASCII letters, digits and symbols to - 'full', 'half',
+    ///         or None to keep them (default: 'half')
+    #[new]
+    #[pyo3(signature = (katakana = Some("full"), ascii = Some("half")))]
+    fn new(katakana: Option<&str>, ascii: Option<&str>) -> PyResult<(Self, PyCharFilter)> {
+        fn width(value: Option<&str>) -> PyResult<Option<CharWidth>> {
+            match value {
+                None => Ok(None),
+                Some("full") => Ok(Some(CharWidth::Full)),
+                Some("half") => Ok(Some(CharWidth::Half)),
+                Some(other) => Err(PyException::new_err(format!(
+                    "Invalid width: '{}' (expected 'full', 'half' or None)",
+                    other
+                ))),
+            }
+        }
+        let inner = WidthNormalizeCharFilter::new(width(katakana)?, width(ascii)?);
+        Ok((PyWidthNormalizeCharFilter { inner }, PyCharFilter))
+    }
+
+    /// Convert the width of katakana and ASCII characters in text
+    fn apply(&self, text: &str) -> PyResult<String> {
+        self.inner
+            .apply(text)
+            .map_err(|e| PyException::new_err(format!("CharFilter error: {:?}", e)))
+    }
+
+    /// Callable interface
+    fn __call__(&self, text: &str) -> PyResult<String> {
+        self.apply(text)
+    }
+}
+
 /// Enum to handle different TokenFilter output types
 #[derive(Clone)]
 pub enum PyTokenFilterOutput {
@@ -394,11 +750,23 @@ impl PyTokenFilterIterator {
 }
 
 /// Python TokenFilter base class - mirrors Janome TokenFilter
+///
+/// Subclass it in Python and override `apply` to implement a filter; any
+/// other callable taking and returning an iterable also works as a TokenFilter.
 #[pyclass(name = "TokenFilter", subclass)]
 pub struct PyTokenFilter;
 
 #[pymethods]
 impl PyTokenFilter {
+    /// Create the base of a TokenFilter subclass
+    ///
+    /// Arguments are accepted for the `__init__` of Python subclasses.
+    #[new]
+    #[pyo3(signature = (*_args, **_kwargs))]
+    fn new(_args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>) -> Self {
+        PyTokenFilter
+    }
+
     /// Apply the filter to token iterator
     fn apply(&self, _tokens: &Bound<'_, PyAny>) -> PyResult<PyObject> {
         Err(PyNotImplementedError::new_err(
@@ -406,9 +774,10 @@ impl PyTokenFilter {
         ))
     }
 
-    /// Callable interface for Python compatibility
-    fn __call__(&self, tokens: &Bound<'_, PyAny>) -> PyResult<PyObject> {
-        self.apply(tokens)
+    /// Callable interface for Python compatibility, dispatching to the
+    /// `apply` of the subclass
+    fn __call__(slf: &Bound<'_, Self>, tokens: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        slf.call_method1("apply", (tokens,)).map(Bound::unbind)
     }
 }
 
@@ -522,6 +891,45 @@ impl PyUpperCaseFilter {
     }
 }
 
+/// Python wrapper for RomajiFilter
+#[pyclass(name = "RomajiFilter", extends = PyTokenFilter)]
+pub struct PyRomajiFilter {
+    inner: RomajiFilter,
+}
+
+#[pymethods]
+impl PyRomajiFilter {
+    /// Create a new RomajiFilter
+    #[new]
+    fn new() -> (Self, PyTokenFilter) {
+        (
+            PyRomajiFilter {
+                inner: RomajiFilter,
+            },
+            PyTokenFilter,
+        )
+    }
+
+    /// Replace token readings with Hepburn romaji
+    fn apply(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        let rust_tokens = extract_tokens_from_iterator(py, tokens)?;
+
+        let filtered: Vec<RustToken> = self.inner.apply(rust_tokens.into_iter()).collect();
+
+        let results: Vec<PyTokenFilterOutput> = filtered
+            .into_iter()
+            .map(|t| PyTokenFilterOutput::Token(PyToken::from_rust_token(t)))
+            .collect();
+
+        Ok(PyTokenFilterIterator { results, index: 0 })
+    }
+
+    /// Callable interface
+    fn __call__(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        self.apply(py, tokens)
+    }
+}
+
 /// Python wrapper for POSStopFilter
 #[pyclass(name = "POSStopFilter", extends = PyTokenFilter)]
 pub struct PyPOSStopFilter {
@@ -645,6 +1053,211 @@ impl PyCompoundNounFilter {
     }
 }
 
+/// Python wrapper for DedupFilter
+#[pyclass(name = "DedupFilter", extends = PyTokenFilter)]
+pub struct PyDedupFilter {
+    inner: DedupFilter,
+}
+
+#[pymethods]
+impl PyDedupFilter {
+    /// Create a new DedupFilter
+    ///
+    /// Args:
+    ///     attr (str): Attribute to compare, 'surface' or 'base_form' (default: 'surface')
+    #[new]
+    #[pyo3(signature = (attr = "surface"))]
+    fn new(attr: &str) -> PyResult<(Self, PyTokenFilter)> {
+        let inner = DedupFilter::new(attr.to_string())
+            .map_err(|e| PyException::new_err(format!("Invalid attribute: {:?}", e)))?;
+        Ok((PyDedupFilter { inner }, PyTokenFilter))
+    }
+
+    /// Apply deduplication of consecutive identical tokens
+    fn apply(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        let rust_tokens = extract_tokens_from_iterator(py, tokens)?;
+
+        let filtered: Vec<RustToken> = self.inner.apply(rust_tokens.into_iter()).collect();
+
+        let results: Vec<PyTokenFilterOutput> = filtered
+            .into_iter()
+            .map(|t| PyTokenFilterOutput::Token(PyToken::from_rust_token(t)))
+            .collect();
+
+        Ok(PyTokenFilterIterator { results, index: 0 })
+    }
+
+    /// Callable interface
+    fn __call__(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        self.apply(py, tokens)
+    }
+}
+
+/// Collect an iterable of Python strings into a word set
+fn extract_word_set(words: &Bound<'_, PyAny>) -> PyResult<std::collections::HashSet<String>> {
+    words
+        .try_iter()?
+        .map(|word| word?.extract::<String>())
+        .collect()
+}
+
+/// Python wrapper for WordStopFilter
+#[pyclass(name = "WordStopFilter", extends = PyTokenFilter)]
+pub struct PyWordStopFilter {
+    inner: WordStopFilter,
+}
+
+#[pymethods]
+impl PyWordStopFilter {
+    /// Create a new WordStopFilter
+    ///
+    /// Args:
+    ///     words (Iterable[str], optional): Words to remove
+    ///     path (str, optional): Path to a newline-delimited word list file
+    ///     attr (str): Attribute to match, 'surface' or 'base_form' (default: 'surface')
+    #[new]
+    #[pyo3(signature = (words = None, path = None, attr = "surface"))]
+    fn new(
+        words: Option<&Bound<'_, PyAny>>,
+        path: Option<&str>,
+        attr: &str,
+    ) -> PyResult<(Self, PyTokenFilter)> {
+        let inner = match (words, path) {
+            (Some(words), None) => WordStopFilter::new(extract_word_set(words)?, attr.to_string())?,
+            (None, Some(path)) => {
+                WordStopFilter::from_file(std::path::Path::new(path), attr.to_string())?
+            }
+            _ => {
+                return Err(PyException::new_err(
+                    "Exactly one of 'words' or 'path' must be given",
+                ));
+            }
+        };
+        Ok((PyWordStopFilter { inner }, PyTokenFilter))
+    }
+
+    /// Apply word list filtering
+    fn apply(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        let rust_tokens = extract_tokens_from_iterator(py, tokens)?;
+
+        let filtered: Vec<RustToken> = self.inner.apply(rust_tokens.into_iter()).collect();
+
+        let results: Vec<PyTokenFilterOutput> = filtered
+            .into_iter()
+            .map(|t| PyTokenFilterOutput::Token(PyToken::from_rust_token(t)))
+            .collect();
+
+        Ok(PyTokenFilterIterator { results, index: 0 })
+    }
+
+    /// Callable interface
+    fn __call__(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        self.apply(py, tokens)
+    }
+}
+
+/// Python wrapper for PiiMaskFilter
+#[pyclass(name = "PiiMaskFilter", extends = PyTokenFilter)]
+pub struct PyPiiMaskFilter {
+    inner: PiiMaskFilter,
+}
+
+#[pymethods]
+impl PyPiiMaskFilter {
+    /// Create a new PiiMaskFilter
+    ///
+    /// Args:
+    ///     kinds (list[str], optional): Kinds to mask, any of 'email', 'phone' and
+    ///         'postal_code' (default: all)
+    #[new]
+    #[pyo3(signature = (kinds = None))]
+    fn new(kinds: Option<Vec<String>>) -> PyResult<(Self, PyTokenFilter)> {
+        let mut inner = PiiMaskFilter::new();
+        if let Some(kinds) = kinds {
+            let kinds = kinds
+                .iter()
+                .map(|kind| PiiKind::from_name(kind))
+                .collect::<Result<Vec<_>, _>>()?;
+            inner = inner.with_kinds(kinds);
+        }
+        Ok((PyPiiMaskFilter { inner }, PyTokenFilter))
+    }
+
+    /// Apply masking of personally identifiable information
+    fn apply(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        let rust_tokens = extract_tokens_from_iterator(py, tokens)?;
+
+        let filtered: Vec<RustToken> = self.inner.apply(rust_tokens.into_iter()).collect();
+
+        let results: Vec<PyTokenFilterOutput> = filtered
+            .into_iter()
+            .map(|t| PyTokenFilterOutput::Token(PyToken::from_rust_token(t)))
+            .collect();
+
+        Ok(PyTokenFilterIterator { results, index: 0 })
+    }
+
+    /// Callable interface
+    fn __call__(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        self.apply(py, tokens)
+    }
+}
+
+/// Python wrapper for WordKeepFilter
+#[pyclass(name = "WordKeepFilter", extends = PyTokenFilter)]
+pub struct PyWordKeepFilter {
+    inner: WordKeepFilter,
+}
+
+#[pymethods]
+impl PyWordKeepFilter {
+    /// Create a new WordKeepFilter
+    ///
+    /// Args:
+    ///     words (Iterable[str], optional): Words to keep
+    ///     path (str, optional): Path to a newline-delimited word list file
+    ///     attr (str): Attribute to match, 'surface' or 'base_form' (default: 'surface')
+    #[new]
+    #[pyo3(signature = (words = None, path = None, attr = "surface"))]
+    fn new(
+        words: Option<&Bound<'_, PyAny>>,
+        path: Option<&str>,
+        attr: &str,
+    ) -> PyResult<(Self, PyTokenFilter)> {
+        let inner = match (words, path) {
+            (Some(words), None) => WordKeepFilter::new(extract_word_set(words)?, attr.to_string())?,
+            (None, Some(path)) => {
+                WordKeepFilter::from_file(std::path::Path::new(path), attr.to_string())?
+            }
+            _ => {
+                return Err(PyException::new_err(
+                    "Exactly one of 'words' or 'path' must be given",
+                ));
+            }
+        };
+        Ok((PyWordKeepFilter { inner }, PyTokenFilter))
+    }
+
+    /// Apply word list filtering
+    fn apply(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        let rust_tokens = extract_tokens_from_iterator(py, tokens)?;
+
+        let filtered: Vec<RustToken> = self.inner.apply(rust_tokens.into_iter()).collect();
+
+        let results: Vec<PyTokenFilterOutput> = filtered
+            .into_iter()
+            .map(|t| PyTokenFilterOutput::Token(PyToken::from_rust_token(t)))
+            .collect();
+
+        Ok(PyTokenFilterIterator { results, index: 0 })
+    }
+
+    /// Callable interface
+    fn __call__(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<PyTokenFilterIterator> {
+        self.apply(py, tokens)
+    }
+}
+
 /// Python wrapper for ExtractAttributeFilter (terminal filter)
 #[pyclass(name = "ExtractAttributeFilter", extends = PyTokenFilter)]
 pub struct PyExtractAttributeFilter {
@@ -656,11 +1269,11 @@ impl PyExtractAttributeFilter {
     /// Create a new ExtractAttributeFilter
     ///
     /// Args:
-    ///     attr (str): Attribute to extract (default: 'surface')
+    ///     att (str): Attribute to extract (default: 'surface')
     #[new]
-    #[pyo3(signature = (attr = "surface"))]
-    fn new(attr: &str) -> PyResult<(Self, PyTokenFilter)> {
-        let inner = ExtractAttributeFilter::new(attr.to_string())
+    #[pyo3(signature = (att = "surface"))]
+    fn new(att: &str) -> PyResult<(Self, PyTokenFilter)> {
+        let inner = ExtractAttributeFilter::new(att.to_string())
             .map_err(|e| PyException::new_err(format!("Invalid attribute: {:?}", e)))?;
         Ok((PyExtractAttributeFilter { inner }, PyTokenFilter))
     }
@@ -696,12 +1309,12 @@ impl PyTokenCountFilter {
     /// Create a new TokenCountFilter
     ///
     /// Args:
-    ///     attr (str): Attribute to count (default: 'surface')
+    ///     att (str): Attribute to count (default: 'surface')
     ///     sorted (bool): Whether to sort results by count (default: False)
     #[new]
-    #[pyo3(signature = (attr = "surface", sorted = false))]
-    fn new(attr: &str, sorted: bool) -> PyResult<(Self, PyTokenFilter)> {
-        let inner = TokenCountFilter::new(attr.to_string(), sorted)
+    #[pyo3(signature = (att = "surface", sorted = false))]
+    fn new(att: &str, sorted: bool) -> PyResult<(Self, PyTokenFilter)> {
+        let inner = TokenCountFilter::new(att.to_string(), sorted)
             .map_err(|e| PyException::new_err(format!("Invalid attribute: {:?}", e)))?;
         Ok((PyTokenCountFilter { inner }, PyTokenFilter))
     }
@@ -786,7 +1399,15 @@ impl PyAnalyzer {
             t
         } else {
             // Create default tokenizer
-            PyTokenizer::new("", "utf8", "ipadic", 1024, false)?
+            PyTokenizer::new(
+                PathBuf::new(),
+                "utf8",
+                "ipadic",
+                1024,
+                false,
+                DEFAULT_THETA,
+                DEFAULT_COST_FACTOR,
+            )?
         };
 
         // Wrap char filters
@@ -823,7 +1444,9 @@ impl PyAnalyzer {
         }
 
         // Stage 2: Tokenize the preprocessed text
-        let tokens = self.tokenizer.tokenize(&processed_text, None, true)?;
+        let tokens = self
+            .tokenizer
+            .tokenize_text(processed_text, None, true, PathBuf::new());
         #[allow(deprecated)]
         let mut current_iter = tokens.into_py(py);
 
@@ -836,6 +1459,148 @@ impl PyAnalyzer {
     }
 }
 
+/// Python wrapper for ChasenFormatter
+#[pyclass(name = "ChasenFormatter")]
+pub struct PyChasenFormatter {
+    inner: ChasenFormatter,
+}
+
+#[pymethods]
+impl PyChasenFormatter {
+    /// Create a new ChasenFormatter
+    #[new]
+    fn new() -> Self {
+        PyChasenFormatter {
+            inner: ChasenFormatter::new(),
+        }
+    }
+
+    /// Format the tokens of one sentence in ChaSen format, ending with an EOS line
+    fn format(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<String> {
+        let rust_tokens = extract_tokens_from_iterator(py, tokens)?;
+        Ok(self.inner.format(&rust_tokens))
+    }
+}
+
+/// Python wrapper for MecabFormatter
+#[pyclass(name = "MecabFormatter")]
+pub struct PyMecabFormatter {
+    inner: MecabFormatter,
+}
+
+#[pymethods]
+impl PyMecabFormatter {
+    /// Create a new MecabFormatter
+    #[new]
+    fn new() -> Self {
+        PyMecabFormatter {
+            inner: MecabFormatter::new(),
+        }
+    }
+
+    /// Format the tokens of one sentence in MeCab's default format, ending with an EOS line
+    fn format(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<String> {
+        let rust_tokens = extract_tokens_from_iterator(py, tokens)?;
+        Ok(self.inner.format(&rust_tokens))
+    }
+}
+
+/// Python wrapper for ConlluFormatter
+#[pyclass(name = "ConlluFormatter")]
+pub struct PyConlluFormatter {
+    inner: ConlluFormatter,
+}
+
+#[pymethods]
+impl PyConlluFormatter {
+    /// Create a new ConlluFormatter
+    #[new]
+    fn new() -> Self {
+        PyConlluFormatter {
+            inner: ConlluFormatter::new(),
+        }
+    }
+
+    /// Format the tokens of one sentence as CoNLL-U, ending with a blank line
+    fn format(&self, py: Python, tokens: &Bound<'_, PyAny>) -> PyResult<String> {
+        let rust_tokens = extract_tokens_from_iterator(py, tokens)?;
+        Ok(self.inner.format(&rust_tokens))
+    }
+}
+
+/// Python wrapper for WakatiWriter
+#[pyclass(name = "WakatiWriter")]
+pub struct PyWakatiWriter {
+    inner: WakatiWriter,
+}
+
+#[pymethods]
+impl PyWakatiWriter {
+    /// Create a new WakatiWriter
+    #[new]
+    #[pyo3(signature = (separator = " "))]
+    fn new(separator: &str) -> Self {
+        PyWakatiWriter {
+            inner: WakatiWriter::with_separator(separator),
+        }
+    }
+
+    /// Format one sentence of Token objects or surface strings, ending with a newline
+    fn format(&self, tokens: &Bound<'_, PyAny>) -> PyResult<String> {
+        let mut surfaces = Vec::new();
+        for item in tokens.try_iter()? {
+            let item = item?;
+            if let Ok(py_token) = item.extract::<PyToken>() {
+                surfaces.push(py_token.inner.surface().to_string());
+            } else if let Ok(surface) = item.extract::<String>() {
+                surfaces.push(surface);
+            } else {
+                return Err(PyException::new_err(
+                    "WakatiWriter expects Token objects or strings",
+                ));
+            }
+        }
+        Ok(self.inner.format_surfaces(&surfaces))
+    }
+}
+
+/// Load the system dictionary into the process before forking workers
+///
+/// Call this in the parent process of a preforking server (e.g. gunicorn with
+/// `--preload`). Workers inherit the loaded dictionary and share its memory
+/// with the parent instead of loading their own copy. The FST is memory-mapped,
+/// so it is also shared between independent processes.
+#[pyfunction]
+fn preload_dictionary(py: Python<'_>) -> PyResult<()> {
+    py.allow_threads(SystemDictionary::preload_shared)
+        .map(|_| ())
+        .map_err(|e| PyException::new_err(format!("Failed to load dictionary: {:?}", e)))
+}
+
+/// Compile a CSV user dictionary into a directory loadable as `udic`
+///
+/// Loading a compiled dictionary skips parsing the CSV and building the FST,
+/// which speeds up the creation of tokenizers with large user dictionaries.
+///
+/// Args:
+///     udic (str | os.PathLike): User dictionary file path (CSV format)
+///     output_dir (str | os.PathLike): Directory to write the compiled dictionary to
+///     udic_enc (str): Character encoding of the CSV file (default: 'utf8')
+///     udic_type (str): User dictionary type - 'ipadic' or 'simpledic' (default: 'ipadic')
+#[pyfunction]
+#[pyo3(signature = (udic, output_dir, *, udic_enc = "utf8", udic_type = "ipadic"))]
+fn compile_user_dictionary(
+    udic: PathBuf,
+    output_dir: PathBuf,
+    udic_enc: &str,
+    udic_type: &str,
+) -> PyResult<()> {
+    let dict_format = user_dict_format(udic_type)?;
+    let encoding = japanese_encoding(udic_enc)?;
+    CompiledUserDictionary::compile_with_encoding(&udic, dict_format, encoding, &output_dir)
+        .map_err(|e| PyException::new_err(format!("Failed to compile user dictionary: {:?}", e)))
+}
+
 /// Python module definition
 #[pymodule]
 fn runome(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -848,14 +1613,20 @@ fn runome(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyCharFilter>()?;
     m.add_class::<PyRegexReplaceCharFilter>()?;
     m.add_class::<PyUnicodeNormalizeCharFilter>()?;
+    m.add_class::<PyWidthNormalizeCharFilter>()?;
 
     // TokenFilter classes
     m.add_class::<PyTokenFilter>()?;
     m.add_class::<PyLowerCaseFilter>()?;
     m.add_class::<PyUpperCaseFilter>()?;
+    m.add_class::<PyRomajiFilter>()?;
     m.add_class::<PyPOSStopFilter>()?;
     m.add_class::<PyPOSKeepFilter>()?;
     m.add_class::<PyCompoundNounFilter>()?;
+    m.add_class::<PyDedupFilter>()?;
+    m.add_class::<PyWordStopFilter>()?;
+    m.add_class::<PyWordKeepFilter>()?;
+    m.add_class::<PyPiiMaskFilter>()?;
     m.add_class::<PyExtractAttributeFilter>()?;
     m.add_class::<PyTokenCountFilter>()?;
     m.add_class::<PyTokenFilterIterator>()?;
@@ -863,5 +1634,17 @@ fn runome(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Analyzer
     m.add_class::<PyAnalyzer>()?;
 
+    // Formatters
+    m.add_class::<PyChasenFormatter>()?;
+    m.add_class::<PyMecabFormatter>()?;
+    m.add_class::<PyConlluFormatter>()?;
+    m.add_class::<PyWakatiWriter>()?;
+
+    // Dictionary preloading for multi-process deployments
+    m.add_function(wrap_pyfunction!(preload_dictionary, m)?)?;
+
+    // Precompiled user dictionaries
+    m.add_function(wrap_pyfunction!(compile_user_dictionary, m)?)?;
+
     Ok(())
 }
@@ -0,0 +1,118 @@
+//! Reranking of candidate segmentations by an external model
+//!
+//! The lattice cost model only looks at adjacent morphemes. A `Rescorer`
+//! sees the N best segmentations of a lattice as whole token sequences, so
+//! a model with more context, such as a neural language model, can choose
+//! among them. See `Tokenizer::with_rescorer`.
+
+use crate::error::RunomeError;
+use crate::tokenizer::Token;
+
+/// A candidate segmentation of a text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Tokens of the segmentation, with spans into the tokenized input
+    pub tokens: Vec<Token>,
+    /// Total word and connection cost of the path in the lattice
+    pub cost: i32,
+    /// Probability of the candidate among the candidates of the same lattice,
+    /// proportional to `exp(-theta * cost / cost_factor)` with the theta and
+    /// cost factor of the tokenizer. 1.0 for the single path of
+    /// `Tokenizer::tokenize_with_cost`.
+    pub probability: f64,
+}
+
+/// Default cost units per unit of log probability, as in MeCab
+///
+/// MeCab dictionaries are compiled with costs scaled by the `cost-factor` of
+/// dicrc, 800 for mecab-ipadic, so candidate probabilities undo that scaling
+/// before applying theta. Set the factor of other dictionaries with
+/// `Tokenizer::with_cost_factor`.
+pub const DEFAULT_COST_FACTOR: f64 = 800.0;
+
+/// Default theta, as MeCab's `--theta`
+pub const DEFAULT_THETA: f64 = 0.75;
+
+/// Set the probabilities of candidates from their costs
+///
+/// Higher theta concentrates the probability on the lowest-cost candidates;
+/// theta 0 makes all candidates equally likely.
+pub(crate) fn assign_probabilities(candidates: &mut [Candidate], theta: f64, cost_factor: f64) {
+    let Some(min_cost) = candidates.iter().map(|c| c.cost).min() else {
+        return;
+    };
+    // Relative to the best candidate, so the weights don't underflow
+    let weights: Vec<f64> = candidates
+        .iter()
+        .map(|c| (-theta * (c.cost - min_cost) as f64 / cost_factor).exp())
+        .collect();
+    let total: f64 = weights.iter().sum();
+    for (candidate, weight) in candidates.iter_mut().zip(weights) {
+        candidate.probability = weight / total;
+    }
+}
+
+/// Chooses among the N best segmentations of a text
+///
+/// Implementations wrap an external model, e.g. an ONNX language model
+/// loaded behind a feature flag of the application. Tokenization calls
+/// `rescore` once per lattice, i.e. per chunk of the input between spaces
+/// handled by a `SpacePolicy`, with the candidates in order of increasing
+/// lattice cost.
+///
+/// # Example
+/// ```rust
+/// use runome::{Candidate, Rescorer, RunomeError};
+///
+/// /// Prefers segmentations with fewer tokens, then lower cost
+/// struct FewestTokens;
+///
+/// impl Rescorer for FewestTokens {
+///     fn rescore(&self, _text: &str, candidates: &[Candidate]) -> Result<Vec<f64>, RunomeError> {
+///         Ok(candidates
+///             .iter()
+///             .map(|c| c.tokens.len() as f64 * 1e6 + c.cost as f64)
+///             .collect())
+///     }
+/// }
+/// ```
+pub trait Rescorer: Send + Sync {
+    /// Score candidate segmentations of a text
+    ///
+    /// # Arguments
+    /// * `text` - The text the candidates segment
+    /// * `candidates` - Candidates in order of increasing lattice cost
+    ///
+    /// # Returns
+    /// * `Ok(Vec<f64>)` - One score per candidate; the lowest wins, and ties
+    ///   go to the candidate with the lower lattice cost
+    /// * `Err(RunomeError)` - Error if the model fails; tokenization fails with it
+    fn rescore(&self, text: &str, candidates: &[Candidate]) -> Result<Vec<f64>, RunomeError>;
+}
+
+/// Pick the candidate with the lowest score
+pub(crate) fn best_candidate(
+    rescorer: &dyn Rescorer,
+    text: &str,
+    mut candidates: Vec<Candidate>,
+) -> Result<Candidate, RunomeError> {
+    let scores = rescorer.rescore(text, &candidates)?;
+    if scores.len() != candidates.len() {
+        return Err(RunomeError::InvalidTokenizerConfig {
+            reason: format!(
+                "Rescorer returned {} scores for {} candidates",
+                scores.len(),
+                candidates.len()
+            ),
+        });
+    }
+    let best = scores
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+        .ok_or_else(|| RunomeError::DictValidationError {
+            reason: "No candidate path found in lattice".to_string(),
+        })?;
+    Ok(candidates.swap_remove(best))
+}
@@ -0,0 +1,321 @@
+//! N-gram and collocation statistics
+//!
+//! `NgramStats` counts token n-grams over any number of token streams, such
+//! as the sentences or documents of a corpus. Tokens are compared by one
+//! attribute, such as the surface, or the base form so that inflected forms
+//! count together, and part-of-speech filters restrict counting to the words
+//! of interest. Collocations are ranked by pointwise mutual information
+//! (PMI) of the n-gram against its words.
+
+use std::collections::HashMap;
+
+use crate::error::RunomeError;
+use crate::tokenfilter::{token_attribute, validate_token_attribute};
+use crate::tokenizer::Token;
+
+/// A counted n-gram with its association score
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collocation {
+    /// Words of the n-gram, in text order
+    pub words: Vec<String>,
+    /// Number of occurrences
+    pub count: usize,
+    /// Pointwise mutual information in bits
+    pub pmi: f64,
+}
+
+/// Token n-gram frequency counter
+///
+/// Tokens excluded by the part-of-speech filters are not counted and break
+/// n-grams, so with a noun filter "東京の駅" yields no "東京 駅" bigram.
+/// N-grams never span two calls to `add_tokens`.
+///
+/// # Example
+/// ```rust,no_run
+/// use runome::{NgramStats, Tokenizer};
+///
+/// let tokenizer = Tokenizer::new(None, None).unwrap();
+/// let mut stats = NgramStats::new(2, "base_form")
+///     .unwrap()
+///     .with_keep_pos(vec!["名詞".to_string()]);
+/// for sentence in ["東京都庁に行く", "東京都庁は新宿にある"] {
+///     stats.add_tokens(tokenizer.tokenize_to_vec(sentence).unwrap());
+/// }
+/// assert_eq!(stats.count(&["東京", "都庁"]), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NgramStats {
+    n: usize,
+    attribute: String,
+    keep_pos: Vec<String>,
+    stop_pos: Vec<String>,
+    unigrams: HashMap<String, usize>,
+    ngrams: HashMap<Vec<String>, usize>,
+    total_unigrams: usize,
+    total_ngrams: usize,
+}
+
+impl NgramStats {
+    /// Create a counter for n-grams of the given length
+    ///
+    /// # Arguments
+    /// * `n` - Number of tokens per n-gram, at least 1
+    /// * `attribute` - Token attribute compared, as accepted by `ExtractAttributeFilter`
+    ///
+    /// # Returns
+    /// * `Ok(NgramStats)` - Empty counter
+    /// * `Err(RunomeError)` - Error if `n` is 0 or the attribute is invalid
+    pub fn new(n: usize, attribute: &str) -> Result<Self, RunomeError> {
+        if n == 0 {
+            return Err(RunomeError::InvalidArgument {
+                reason: "N-gram length must be at least 1".to_string(),
+            });
+        }
+        validate_token_attribute(attribute)?;
+        Ok(Self {
+            n,
+            attribute: attribute.to_string(),
+            keep_pos: Vec::new(),
+            stop_pos: Vec::new(),
+            unigrams: HashMap::new(),
+            ngrams: HashMap::new(),
+            total_unigrams: 0,
+            total_ngrams: 0,
+        })
+    }
+
+    /// Only count tokens whose part of speech starts with one of the prefixes
+    ///
+    /// An empty list, the default, keeps every token.
+    pub fn with_keep_pos(mut self, pos_list: Vec<String>) -> Self {
+        self.keep_pos = pos_list;
+        self
+    }
+
+    /// Skip tokens whose part of speech starts with one of the prefixes
+    pub fn with_stop_pos(mut self, pos_list: Vec<String>) -> Self {
+        self.stop_pos = pos_list;
+        self
+    }
+
+    /// Count the n-grams of a token stream
+    ///
+    /// # Arguments
+    /// * `tokens` - Tokens of one sentence or document
+    pub fn add_tokens<I>(&mut self, tokens: I)
+    where
+        I: IntoIterator<Item = Token>,
+    {
+        let mut window: Vec<String> = Vec::with_capacity(self.n);
+        for token in tokens {
+            if !self.is_counted(&token) {
+                window.clear();
+                continue;
+            }
+            let value = token_attribute(&token, &self.attribute);
+            *self.unigrams.entry(value.clone()).or_insert(0) += 1;
+            self.total_unigrams += 1;
+
+            if window.len() == self.n {
+                window.remove(0);
+            }
+            window.push(value);
+            if window.len() == self.n {
+                *self.ngrams.entry(window.clone()).or_insert(0) += 1;
+                self.total_ngrams += 1;
+            }
+        }
+    }
+
+    /// Get the number of occurrences of an n-gram
+    ///
+    /// # Arguments
+    /// * `ngram` - Attribute values of the n-gram's tokens
+    ///
+    /// # Returns
+    /// * The count, 0 if the n-gram was not seen or has the wrong length
+    pub fn count(&self, ngram: &[&str]) -> usize {
+        let key: Vec<String> = ngram.iter().map(|word| word.to_string()).collect();
+        self.ngrams.get(&key).copied().unwrap_or(0)
+    }
+
+    /// Get the number of occurrences of a single counted token value
+    pub fn word_count(&self, word: &str) -> usize {
+        self.unigrams.get(word).copied().unwrap_or(0)
+    }
+
+    /// Get the total number of n-grams counted, including repeats
+    pub fn total(&self) -> usize {
+        self.total_ngrams
+    }
+
+    /// Get the n-grams with their counts
+    ///
+    /// # Returns
+    /// * Pairs sorted by descending count, ties in lexicographic order
+    pub fn ngrams(&self) -> Vec<(Vec<String>, usize)> {
+        let mut ngrams: Vec<(Vec<String>, usize)> = self
+            .ngrams
+            .iter()
+            .map(|(ngram, count)| (ngram.clone(), *count))
+            .collect();
+        ngrams.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ngrams
+    }
+
+    /// Rank n-grams as collocations
+    ///
+    /// PMI favors n-grams whose words rarely occur apart, which makes it
+    /// unreliable for rare n-grams; `min_count` excludes them.
+    ///
+    /// # Arguments
+    /// * `min_count` - Minimum number of occurrences of an n-gram
+    ///
+    /// # Returns
+    /// * Collocations sorted by descending PMI, then descending count
+    pub fn collocations(&self, min_count: usize) -> Vec<Collocation> {
+        let mut collocations: Vec<Collocation> = self
+            .ngrams
+            .iter()
+            .filter(|(_, count)| **count >= min_count)
+            .map(|(words, count)| Collocation {
+                words: words.clone(),
+                count: *count,
+                pmi: self.pmi(words, *count),
+            })
+            .collect();
+        collocations.sort_by(|a, b| {
+            b.pmi
+                .total_cmp(&a.pmi)
+                .then_with(|| b.count.cmp(&a.count))
+                .then_with(|| a.words.cmp(&b.words))
+        });
+        collocations
+    }
+
+    /// log2(P(w1..wn) / (P(w1) * ... * P(wn)))
+    fn pmi(&self, words: &[String], count: usize) -> f64 {
+        let joint = (count as f64 / self.total_ngrams as f64).log2();
+        let independent: f64 = words
+            .iter()
+            .map(|word| (self.unigrams[word] as f64 / self.total_unigrams as f64).log2())
+            .sum();
+        joint - independent
+    }
+
+    fn is_counted(&self, token: &Token) -> bool {
+        let pos = token.part_of_speech();
+        (self.keep_pos.is_empty() || self.keep_pos.iter().any(|p| pos.starts_with(p.as_str())))
+            && !self.stop_pos.iter().any(|p| pos.starts_with(p.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::NodeType;
+
+    fn token(surface: &str, pos: &str) -> Token {
+        Token::new(
+            surface.to_string(),
+            pos.to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            surface.to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            NodeType::SysDict,
+        )
+    }
+
+    fn sentence(words: &[(&str, &str)]) -> Vec<Token> {
+        words.iter().map(|(s, pos)| token(s, pos)).collect()
+    }
+
+    #[test]
+    fn test_ngram_counts() {
+        let mut stats = NgramStats::new(2, "surface").unwrap();
+        stats.add_tokens(sentence(&[
+            ("機械", "名詞,一般"),
+            ("学習", "名詞,サ変接続"),
+            ("の", "助詞,連体化"),
+            ("機械", "名詞,一般"),
+            ("学習", "名詞,サ変接続"),
+        ]));
+        // No bigram across calls
+        stats.add_tokens(sentence(&[("学習", "名詞,サ変接続")]));
+
+        assert_eq!(stats.count(&["機械", "学習"]), 2);
+        assert_eq!(stats.count(&["学習", "の"]), 1);
+        assert_eq!(stats.count(&["学習", "学習"]), 0);
+        assert_eq!(stats.count(&["機械"]), 0);
+        assert_eq!(stats.word_count("学習"), 3);
+        assert_eq!(stats.total(), 4);
+        assert_eq!(
+            stats.ngrams()[0],
+            (vec!["機械".to_string(), "学習".to_string()], 2)
+        );
+
+        let mut unigrams = NgramStats::new(1, "surface").unwrap();
+        unigrams.add_tokens(sentence(&[("a", "名詞"), ("a", "名詞")]));
+        assert_eq!(unigrams.count(&["a"]), 2);
+    }
+
+    #[test]
+    fn test_pos_filters() {
+        let words = [
+            ("東京", "名詞,固有名詞"),
+            ("の", "助詞,連体化"),
+            ("駅", "名詞,一般"),
+            ("前", "名詞,副詞可能"),
+        ];
+
+        let mut nouns = NgramStats::new(2, "surface")
+            .unwrap()
+            .with_keep_pos(vec!["名詞".to_string()]);
+        nouns.add_tokens(sentence(&words));
+        assert_eq!(nouns.count(&["東京", "駅"]), 0);
+        assert_eq!(nouns.count(&["駅", "前"]), 1);
+        assert_eq!(nouns.word_count("の"), 0);
+
+        let mut no_adverbial = NgramStats::new(2, "surface")
+            .unwrap()
+            .with_stop_pos(vec!["名詞,副詞可能".to_string()]);
+        no_adverbial.add_tokens(sentence(&words));
+        assert_eq!(no_adverbial.total(), 2);
+        assert_eq!(no_adverbial.count(&["駅", "前"]), 0);
+    }
+
+    #[test]
+    fn test_collocations() {
+        let mut stats = NgramStats::new(2, "surface").unwrap();
+        for _ in 0..3 {
+            stats.add_tokens(sentence(&[
+                ("自然", "名詞"),
+                ("言語", "名詞"),
+                ("の", "助詞"),
+                ("処理", "名詞"),
+            ]));
+        }
+        stats.add_tokens(sentence(&[("の", "助詞"), ("言語", "名詞")]));
+
+        let collocations = stats.collocations(2);
+        assert_eq!(collocations.len(), 3);
+        assert!(collocations.windows(2).all(|w| w[0].pmi >= w[1].pmi));
+        let natural_language = collocations
+            .iter()
+            .find(|c| c.words == ["自然", "言語"])
+            .unwrap();
+        assert_eq!(natural_language.count, 3);
+        // P(自然 言語) = 3/10, P(自然) = 3/14, P(言語) = 4/14
+        let expected = (0.3f64 / (3.0 / 14.0 * 4.0 / 14.0)).log2();
+        assert!((natural_language.pmi - expected).abs() < 1e-9);
+        assert!(stats.collocations(4).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(NgramStats::new(0, "surface").is_err());
+        assert!(NgramStats::new(2, "unknown").is_err());
+    }
+}
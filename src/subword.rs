@@ -0,0 +1,218 @@
+//! Subword segmentation of long unknown words
+//!
+//! Texts dominated by out-of-vocabulary words, such as romaji slang or source
+//! code, produce long unknown words that are of little use as index terms.
+//! A `SubwordFallback` set with `Tokenizer::with_subword_fallback` splits
+//! them into smaller units, either fixed-size character n-grams or pieces of
+//! a byte-pair encoding (BPE) model.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::{PathContext, RunomeError};
+
+/// Segmenter applied to long unknown words
+///
+/// # Example
+/// ```rust
+/// use runome::subword::{BpeModel, SubwordFallback};
+///
+/// let ngram = SubwordFallback::char_ngram(3).unwrap();
+/// assert_eq!(ngram.split("abcdefgh"), vec!["abc", "def", "gh"]);
+///
+/// let model = BpeModel::from_merges("t h\nth e\nr e").unwrap();
+/// let bpe = SubwordFallback::bpe(model);
+/// assert_eq!(bpe.split("therethe"), vec!["the", "re", "the"]);
+/// ```
+#[derive(Debug, Clone)]
+pub enum SubwordFallback {
+    /// Consecutive runs of `n` characters; the last one may be shorter
+    CharNgram(usize),
+    /// Pieces of a byte-pair encoding model
+    Bpe(Arc<BpeModel>),
+}
+
+impl SubwordFallback {
+    /// Split into character n-grams
+    ///
+    /// # Arguments
+    /// * `n` - Number of characters per piece
+    ///
+    /// # Returns
+    /// * `Ok(SubwordFallback)` - The segmenter
+    /// * `Err(RunomeError)` - Error if `n` is zero
+    pub fn char_ngram(n: usize) -> Result<Self, RunomeError> {
+        if n == 0 {
+            return Err(RunomeError::InvalidTokenizerConfig {
+                reason: "Character n-gram length must be at least 1".to_string(),
+            });
+        }
+        Ok(Self::CharNgram(n))
+    }
+
+    /// Split with a byte-pair encoding model
+    pub fn bpe(model: BpeModel) -> Self {
+        Self::Bpe(Arc::new(model))
+    }
+
+    /// Split a word into pieces that concatenate back to it
+    pub fn split<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        match self {
+            Self::CharNgram(n) => {
+                let mut pieces = Vec::new();
+                let mut rest = word;
+                while !rest.is_empty() {
+                    let end = rest.char_indices().nth(*n).map_or(rest.len(), |(i, _)| i);
+                    let (piece, tail) = rest.split_at(end);
+                    pieces.push(piece);
+                    rest = tail;
+                }
+                pieces
+            }
+            Self::Bpe(model) => model.segment(word),
+        }
+    }
+}
+
+/// Byte-pair encoding model given by its merge rules
+///
+/// Words are split into characters, then the adjacent pair whose merge rule
+/// comes first is merged until no rule applies, as in the original BPE
+/// algorithm.
+#[derive(Debug, Clone, Default)]
+pub struct BpeModel {
+    /// Rank of the merge rule for each pair of pieces
+    ranks: HashMap<String, HashMap<String, usize>>,
+}
+
+impl BpeModel {
+    /// Parse merge rules, one `left right` pair per line in order of priority
+    ///
+    /// This is the `merges.txt` format written by common BPE trainers. Empty
+    /// lines and a leading `#version` line are skipped.
+    ///
+    /// # Arguments
+    /// * `merges` - Text of the merge rules
+    ///
+    /// # Returns
+    /// * `Ok(BpeModel)` - The model
+    /// * `Err(RunomeError)` - Error if a line is not a pair of pieces
+    pub fn from_merges(merges: &str) -> Result<Self, RunomeError> {
+        let mut ranks: HashMap<String, HashMap<String, usize>> = HashMap::new();
+        let mut rank = 0;
+        for (line_number, line) in merges.lines().enumerate() {
+            if line.trim().is_empty() || (line_number == 0 && line.starts_with("#version")) {
+                continue;
+            }
+            let mut pieces = line.split_whitespace();
+            let (Some(left), Some(right), None) = (pieces.next(), pieces.next(), pieces.next())
+            else {
+                return Err(RunomeError::InvalidTokenizerConfig {
+                    reason: format!(
+                        "Invalid BPE merge rule on line {}: {:?}",
+                        line_number + 1,
+                        line
+                    ),
+                });
+            };
+            ranks
+                .entry(left.to_string())
+                .or_default()
+                .entry(right.to_string())
+                .or_insert(rank);
+            rank += 1;
+        }
+        Ok(Self { ranks })
+    }
+
+    /// Read merge rules from a file, see `from_merges`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, RunomeError> {
+        let path = path.as_ref();
+        Self::from_merges(&std::fs::read_to_string(path).with_path(path)?).with_path(path)
+    }
+
+    /// Number of merge rules
+    pub fn len(&self) -> usize {
+        self.ranks.values().map(HashMap::len).sum()
+    }
+
+    /// Check if the model has no merge rules
+    pub fn is_empty(&self) -> bool {
+        self.ranks.is_empty()
+    }
+
+    /// Split a word into the pieces of the model
+    pub fn segment<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        // Byte offsets where the pieces start, followed by the end of the word
+        let mut bounds: Vec<usize> = word.char_indices().map(|(i, _)| i).collect();
+        bounds.push(word.len());
+
+        loop {
+            let best = (0..bounds.len().saturating_sub(2))
+                .filter_map(|i| {
+                    let left = &word[bounds[i]..bounds[i + 1]];
+                    let right = &word[bounds[i + 1]..bounds[i + 2]];
+                    self.ranks.get(left)?.get(right).map(|&rank| (rank, i))
+                })
+                .min();
+            match best {
+                Some((_, i)) => {
+                    bounds.remove(i + 1);
+                }
+                None => break,
+            }
+        }
+
+        bounds.windows(2).map(|w| &word[w[0]..w[1]]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_ngram() {
+        let ngram = SubwordFallback::char_ngram(2).unwrap();
+        assert_eq!(ngram.split("ｗｗｗｗｗ"), vec!["ｗｗ", "ｗｗ", "ｗ"]);
+        assert_eq!(ngram.split("ab"), vec!["ab"]);
+        assert!(ngram.split("").is_empty());
+        assert!(SubwordFallback::char_ngram(0).is_err());
+    }
+
+    #[test]
+    fn test_bpe_segment() {
+        let model = BpeModel::from_merges(
+            "#version: 0.2\n\
+             l o\n\
+             lo l\n\
+             ? !\n\
+             lol ?!\n\
+             \n\
+             w w\n",
+        )
+        .unwrap();
+        assert_eq!(model.len(), 5);
+
+        assert_eq!(model.segment("lol?!lol"), vec!["lol?!", "lol"]);
+        assert_eq!(model.segment("wwwww"), vec!["ww", "ww", "w"]);
+        assert_eq!(model.segment("xyz"), vec!["x", "y", "z"]);
+        assert_eq!(model.segment("草"), vec!["草"]);
+        assert!(model.segment("").is_empty());
+
+        // Earlier rules win over later ones
+        let model = BpeModel::from_merges("b c\na b").unwrap();
+        assert_eq!(model.segment("abc"), vec!["a", "bc"]);
+    }
+
+    #[test]
+    fn test_bpe_invalid_rule() {
+        match BpeModel::from_merges("a b\nabc\n") {
+            Err(RunomeError::InvalidTokenizerConfig { reason }) => {
+                assert!(reason.contains("line 2"))
+            }
+            other => panic!("Expected InvalidTokenizerConfig, got {:?}", other),
+        }
+    }
+}
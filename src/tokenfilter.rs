@@ -1,5 +1,9 @@
+use crate::error::PathContext;
+use crate::kana::hiragana_to_katakana_char;
 use crate::{RunomeError, Token};
-use std::collections::HashMap;
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 
 /// Core trait for token filtering operations
 /// Mirrors Python Janome's TokenFilter abstract base class
@@ -57,6 +61,7 @@ impl TokenFilter for LowerCaseFilter {
                 token.phonetic().to_string(),
                 token.node_type(),
             )
+            .with_span_of(&token)
         });
         Box::new(iter)
     }
@@ -98,6 +103,278 @@ impl TokenFilter for UpperCaseFilter {
                 token.phonetic().to_string(),
                 token.node_type(),
             )
+            .with_span_of(&token)
+        });
+        Box::new(iter)
+    }
+}
+
+/// Converts token readings to romaji (Hepburn)
+///
+/// The `reading` field is replaced by its romanization; other fields are kept.
+/// Readings that are not kana, such as the "*" of unknown words, are left
+/// unchanged. To keep the reading and get romaji alongside it, use
+/// `ExtractAttributeFilter` with the "romaji" attribute, or
+/// `RomajiFilter::to_romaji`.
+///
+/// Long vowels are spelled out rather than written with macrons: "ラーメン"
+/// becomes "raamen" and "トウキョウ" becomes "toukyou". A syllabic n before a
+/// vowel or "y" is followed by an apostrophe ("キンエン" becomes "kin'en").
+///
+/// # Example
+/// ```rust
+/// use runome::RomajiFilter;
+/// assert_eq!(RomajiFilter::to_romaji("シャシン"), "shashin");
+/// assert_eq!(RomajiFilter::to_romaji("マッチャ"), "matcha");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RomajiFilter;
+
+/// Unit of kana text before romanization
+enum Syllable {
+    Romaji(String),
+    /// Small tsu, doubling the consonant that follows
+    Sokuon,
+    /// Syllabic n
+    N,
+    /// Prolonged sound mark, repeating the vowel before it
+    LongVowel,
+    Other(char),
+}
+
+impl RomajiFilter {
+    /// Create a new RomajiFilter
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Romanize katakana or hiragana text
+    ///
+    /// Characters other than kana are copied unchanged.
+    ///
+    /// # Arguments
+    /// * `kana` - Text to romanize, e.g. a token reading
+    ///
+    /// # Returns
+    /// The text in Hepburn romaji
+    pub fn to_romaji(kana: &str) -> String {
+        let chars: Vec<char> = kana.chars().map(hiragana_to_katakana_char).collect();
+        let mut syllables = Vec::with_capacity(chars.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            i += 1;
+            let base = match c {
+                'ッ' => {
+                    syllables.push(Syllable::Sokuon);
+                    continue;
+                }
+                'ン' => {
+                    syllables.push(Syllable::N);
+                    continue;
+                }
+                'ー' => {
+                    syllables.push(Syllable::LongVowel);
+                    continue;
+                }
+                _ => match Self::kana_romaji(c) {
+                    Some(base) => base,
+                    None => {
+                        syllables.push(Syllable::Other(c));
+                        continue;
+                    }
+                },
+            };
+            match chars.get(i).and_then(|&next| Self::combine(c, base, next)) {
+                Some(combined) => {
+                    syllables.push(Syllable::Romaji(combined));
+                    i += 1;
+                }
+                None => syllables.push(Syllable::Romaji(base.to_string())),
+            }
+        }
+
+        let mut romaji = String::with_capacity(kana.len());
+        for (index, syllable) in syllables.iter().enumerate() {
+            let next = match syllables.get(index + 1) {
+                Some(Syllable::Romaji(next)) => next.as_str(),
+                _ => "",
+            };
+            match syllable {
+                Syllable::Romaji(text) => romaji.push_str(text),
+                Syllable::Sokuon => {
+                    if next.starts_with("ch") {
+                        romaji.push('t');
+                    } else if let Some(first) = next.chars().next().filter(|c| !is_vowel(*c)) {
+                        romaji.push(first);
+                    }
+                }
+                Syllable::N => {
+                    romaji.push('n');
+                    if next.starts_with(|c: char| is_vowel(c) || c == 'y') {
+                        romaji.push('\'');
+                    }
+                }
+                Syllable::LongVowel => {
+                    if let Some(vowel) = romaji.chars().last().filter(|c| is_vowel(*c)) {
+                        romaji.push(vowel);
+                    }
+                }
+                Syllable::Other(c) => romaji.push(*c),
+            }
+        }
+        romaji
+    }
+
+    /// Romanize a syllable followed by a small kana, e.g. "キャ" or "ファ"
+    fn combine(c: char, base: &str, next: char) -> Option<String> {
+        let stem = match base {
+            "shi" => "sh",
+            "chi" => "ch",
+            "ji" => "j",
+            "tsu" => "ts",
+            "fu" => "f",
+            "vu" => "v",
+            "u" => "w",
+            "ku" => "kw",
+            "gu" => "gw",
+            _ => &base[..base.len() - 1],
+        };
+        match next {
+            'ャ' | 'ュ' | 'ョ' if base.ends_with('i') || matches!(c, 'テ' | 'デ' | 'フ' | 'ヴ') =>
+            {
+                let vowel = &Self::kana_romaji(next)?[1..];
+                if matches!(stem, "sh" | "ch" | "j") {
+                    Some(format!("{}{}", stem, vowel))
+                } else {
+                    Some(format!("{}y{}", stem, vowel))
+                }
+            }
+            'ェ' if c == 'イ' => Some("ye".to_string()),
+            'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ'
+                if matches!(
+                    c,
+                    'ウ' | 'ク'
+                        | 'グ'
+                        | 'ス'
+                        | 'ズ'
+                        | 'シ'
+                        | 'ジ'
+                        | 'チ'
+                        | 'ツ'
+                        | 'テ'
+                        | 'デ'
+                        | 'ト'
+                        | 'ド'
+                        | 'フ'
+                        | 'ヴ'
+                ) =>
+            {
+                Some(format!("{}{}", stem, Self::kana_romaji(next)?))
+            }
+            _ => None,
+        }
+    }
+
+    fn kana_romaji(c: char) -> Option<&'static str> {
+        let romaji = match c {
+            'ア' | 'ァ' => "a",
+            'イ' | 'ィ' | 'ヰ' => "i",
+            'ウ' | 'ゥ' => "u",
+            'エ' | 'ェ' | 'ヱ' => "e",
+            'オ' | 'ォ' | 'ヲ' => "o",
+            'カ' | 'ヵ' => "ka",
+            'キ' => "ki",
+            'ク' => "ku",
+            'ケ' | 'ヶ' => "ke",
+            'コ' => "ko",
+            'ガ' => "ga",
+            'ギ' => "gi",
+            'グ' => "gu",
+            'ゲ' => "ge",
+            'ゴ' => "go",
+            'サ' => "sa",
+            'シ' => "shi",
+            'ス' => "su",
+            'セ' => "se",
+            'ソ' => "so",
+            'ザ' => "za",
+            'ジ' | 'ヂ' => "ji",
+            'ズ' | 'ヅ' => "zu",
+            'ゼ' => "ze",
+            'ゾ' => "zo",
+            'タ' => "ta",
+            'チ' => "chi",
+            'ツ' => "tsu",
+            'テ' => "te",
+            'ト' => "to",
+            'ダ' => "da",
+            'デ' => "de",
+            'ド' => "do",
+            'ナ' => "na",
+            'ニ' => "ni",
+            'ヌ' => "nu",
+            'ネ' => "ne",
+            'ノ' => "no",
+            'ハ' => "ha",
+            'ヒ' => "hi",
+            'フ' => "fu",
+            'ヘ' => "he",
+            'ホ' => "ho",
+            'バ' => "ba",
+            'ビ' => "bi",
+            'ブ' => "bu",
+            'ベ' => "be",
+            'ボ' => "bo",
+            'パ' => "pa",
+            'ピ' => "pi",
+            'プ' => "pu",
+            'ペ' => "pe",
+            'ポ' => "po",
+            'マ' => "ma",
+            'ミ' => "mi",
+            'ム' => "mu",
+            'メ' => "me",
+            'モ' => "mo",
+            'ヤ' | 'ャ' => "ya",
+            'ユ' | 'ュ' => "yu",
+            'ヨ' | 'ョ' => "yo",
+            'ラ' => "ra",
+            'リ' => "ri",
+            'ル' => "ru",
+            'レ' => "re",
+            'ロ' => "ro",
+            'ワ' | 'ヮ' => "wa",
+            'ヴ' => "vu",
+            _ => return None,
+        };
+        Some(romaji)
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+impl TokenFilter for RomajiFilter {
+    type Output = Token;
+
+    fn apply<I>(&self, tokens: I) -> Box<dyn Iterator<Item = Token>>
+    where
+        I: Iterator<Item = Token> + 'static,
+    {
+        let iter = tokens.map(|token| {
+            Token::new(
+                token.surface().to_string(),
+                token.part_of_speech().to_string(),
+                token.infl_type().to_string(),
+                token.infl_form().to_string(),
+                token.base_form().to_string(),
+                Self::to_romaji(token.reading()),
+                token.phonetic().to_string(),
+                token.node_type(),
+            )
+            .with_span_of(&token)
         });
         Box::new(iter)
     }
@@ -185,6 +462,195 @@ impl TokenFilter for POSKeepFilter {
     }
 }
 
+/// Default Japanese stopword list, one word per line
+#[cfg(feature = "default-stopwords")]
+const DEFAULT_JAPANESE_STOPWORDS: &str = include_str!("stopwords_ja.txt");
+
+/// Parse a newline-delimited word list, skipping blank lines and `#` comments
+fn parse_word_list(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Validate the attribute a word list filter matches on
+fn validate_word_attribute(attribute: &str) -> Result<(), RunomeError> {
+    match attribute {
+        "surface" | "base_form" => Ok(()),
+        _ => Err(RunomeError::InvalidArgument {
+            reason: format!(
+                "Invalid attribute '{}'. Valid attributes are: surface, base_form",
+                attribute
+            ),
+        }),
+    }
+}
+
+/// Removes tokens found in a word list
+///
+/// Tokens are matched by `surface` or `base_form` against a set of words,
+/// given directly or read from a newline-delimited file where blank lines and
+/// lines starting with `#` are ignored. Matching on `base_form` also removes
+/// inflected forms of a listed word.
+///
+/// # Example
+/// ```rust
+/// use runome::WordStopFilter;
+/// use std::collections::HashSet;
+/// let words: HashSet<String> = ["の", "は"].iter().map(|w| w.to_string()).collect();
+/// let filter = WordStopFilter::new(words, "surface".to_string()).unwrap();
+/// // Removes the particles "の" and "は"
+/// ```
+#[derive(Debug, Clone)]
+pub struct WordStopFilter {
+    words: HashSet<String>,
+    attribute: String,
+}
+
+impl WordStopFilter {
+    /// Create a new WordStopFilter with the specified words to remove
+    ///
+    /// # Arguments
+    /// * `words` - The words to remove
+    /// * `attribute` - The token attribute to match: surface or base_form
+    ///
+    /// # Returns
+    /// * `Ok(WordStopFilter)` if the attribute is valid
+    /// * `Err(RunomeError)` if the attribute is invalid
+    pub fn new(words: HashSet<String>, attribute: String) -> Result<Self, RunomeError> {
+        validate_word_attribute(&attribute)?;
+        Ok(Self { words, attribute })
+    }
+
+    /// Create a new WordStopFilter from a newline-delimited word list file
+    ///
+    /// # Arguments
+    /// * `path` - Path to a UTF-8 file with one word per line
+    /// * `attribute` - The token attribute to match: surface or base_form
+    ///
+    /// # Returns
+    /// * `Ok(WordStopFilter)` if the file is read and the attribute is valid
+    /// * `Err(RunomeError)` if the file cannot be read or the attribute is invalid
+    pub fn from_file(path: &Path, attribute: String) -> Result<Self, RunomeError> {
+        let content = std::fs::read_to_string(path).with_path(path)?;
+        Self::new(parse_word_list(&content), attribute)
+    }
+
+    /// Create a new WordStopFilter with the bundled Japanese stopword list
+    ///
+    /// The list holds base forms of function words, so `base_form` is the
+    /// natural attribute to match on.
+    #[cfg(feature = "default-stopwords")]
+    pub fn default_japanese(attribute: String) -> Result<Self, RunomeError> {
+        Self::new(parse_word_list(DEFAULT_JAPANESE_STOPWORDS), attribute)
+    }
+
+    /// Default Japanese stopwords matched by base form, which needs no validation
+    #[cfg(feature = "default-stopwords")]
+    pub(crate) fn default_japanese_base_forms() -> Self {
+        Self {
+            words: parse_word_list(DEFAULT_JAPANESE_STOPWORDS),
+            attribute: "base_form".to_string(),
+        }
+    }
+}
+
+impl TokenFilter for WordStopFilter {
+    type Output = Token;
+
+    fn apply<I>(&self, tokens: I) -> Box<dyn Iterator<Item = Token>>
+    where
+        I: Iterator<Item = Token> + 'static,
+    {
+        let words = self.words.clone();
+        let by_surface = self.attribute == "surface";
+        let iter = tokens.filter(move |token| {
+            let key = if by_surface {
+                token.surface()
+            } else {
+                token.base_form()
+            };
+            // Keep tokens that are NOT in the word list
+            !words.contains(key)
+        });
+        Box::new(iter)
+    }
+}
+
+/// Keeps only tokens found in a word list
+///
+/// Inverse of WordStopFilter, with the same matching rules and word list
+/// file format.
+///
+/// # Example
+/// ```rust
+/// use runome::WordKeepFilter;
+/// use std::collections::HashSet;
+/// let words: HashSet<String> = ["東京", "大阪"].iter().map(|w| w.to_string()).collect();
+/// let filter = WordKeepFilter::new(words, "surface".to_string()).unwrap();
+/// // Keeps only "東京" and "大阪"
+/// ```
+#[derive(Debug, Clone)]
+pub struct WordKeepFilter {
+    words: HashSet<String>,
+    attribute: String,
+}
+
+impl WordKeepFilter {
+    /// Create a new WordKeepFilter with the specified words to keep
+    ///
+    /// # Arguments
+    /// * `words` - The words to keep
+    /// * `attribute` - The token attribute to match: surface or base_form
+    ///
+    /// # Returns
+    /// * `Ok(WordKeepFilter)` if the attribute is valid
+    /// * `Err(RunomeError)` if the attribute is invalid
+    pub fn new(words: HashSet<String>, attribute: String) -> Result<Self, RunomeError> {
+        validate_word_attribute(&attribute)?;
+        Ok(Self { words, attribute })
+    }
+
+    /// Create a new WordKeepFilter from a newline-delimited word list file
+    ///
+    /// # Arguments
+    /// * `path` - Path to a UTF-8 file with one word per line
+    /// * `attribute` - The token attribute to match: surface or base_form
+    ///
+    /// # Returns
+    /// * `Ok(WordKeepFilter)` if the file is read and the attribute is valid
+    /// * `Err(RunomeError)` if the file cannot be read or the attribute is invalid
+    pub fn from_file(path: &Path, attribute: String) -> Result<Self, RunomeError> {
+        let content = std::fs::read_to_string(path).with_path(path)?;
+        Self::new(parse_word_list(&content), attribute)
+    }
+}
+
+impl TokenFilter for WordKeepFilter {
+    type Output = Token;
+
+    fn apply<I>(&self, tokens: I) -> Box<dyn Iterator<Item = Token>>
+    where
+        I: Iterator<Item = Token> + 'static,
+    {
+        let words = self.words.clone();
+        let by_surface = self.attribute == "surface";
+        let iter = tokens.filter(move |token| {
+            let key = if by_surface {
+                token.surface()
+            } else {
+                token.base_form()
+            };
+            // Keep tokens that ARE in the word list
+            words.contains(key)
+        });
+        Box::new(iter)
+    }
+}
+
 /// Combines contiguous noun tokens into compound nouns
 ///
 /// This filter detects sequences of adjacent tokens with part-of-speech tags
@@ -242,7 +708,7 @@ where
         let reading = format!("{}{}", first.reading(), second.reading());
         let phonetic = format!("{}{}", first.phonetic(), second.phonetic());
 
-        Token::new(
+        let compound = Token::new(
             surface,
             "名詞,複合,*,*".to_string(), // Compound noun POS
             "*".to_string(),
@@ -251,7 +717,15 @@ where
             reading,
             phonetic,
             first.node_type(),
-        )
+        );
+
+        // The compound covers both spans when they are known
+        match (first.span(), second.span()) {
+            (Some(first_span), Some(second_span)) => {
+                compound.with_span(first_span.start..second_span.end)
+            }
+            _ => compound,
+        }
     }
 }
 
@@ -266,14 +740,11 @@ where
         if let Some(current) = self.pending.take() {
             if Self::is_noun(&current) {
                 // Look ahead to see if next token is also a noun
-                if let Some(next_token) = self.tokens.peek() {
-                    if Self::is_noun(next_token) {
-                        // Combine current with next token
-                        let next = self.tokens.next().unwrap();
-                        let combined = Self::combine_tokens(current, next);
-                        self.pending = Some(combined);
-                        return self.next(); // Recursively process the combined token
-                    }
+                if let Some(next) = self.tokens.next_if(Self::is_noun) {
+                    // Combine current with next token
+                    let combined = Self::combine_tokens(current, next);
+                    self.pending = Some(combined);
+                    return self.next(); // Recursively process the combined token
                 }
             }
             return Some(current);
@@ -289,13 +760,294 @@ where
     }
 }
 
+/// Collapses immediately repeated tokens into one
+///
+/// A token is dropped when its key attribute (`surface` or `base_form`) equals
+/// that of the token right before it, so runs like "ｗ ｗ ｗ" left by aggressive
+/// normalization of SNS text count once. The first token of each run is kept
+/// unchanged; non-adjacent repeats are not affected.
+///
+/// # Example
+/// ```rust
+/// use runome::DedupFilter;
+/// let filter = DedupFilter::new("surface".to_string()).unwrap();
+/// // "すごい すごい すごい ね" -> "すごい ね"
+/// ```
+#[derive(Debug, Clone)]
+pub struct DedupFilter {
+    attribute: String,
+}
+
+impl DedupFilter {
+    /// Create a new DedupFilter comparing the specified attribute
+    ///
+    /// # Arguments
+    /// * `attribute` - The token attribute to compare: surface or base_form
+    ///
+    /// # Returns
+    /// * `Ok(DedupFilter)` if the attribute is valid
+    /// * `Err(RunomeError)` if the attribute is invalid
+    pub fn new(attribute: String) -> Result<Self, RunomeError> {
+        match attribute.as_str() {
+            "surface" | "base_form" => Ok(Self { attribute }),
+            _ => Err(RunomeError::InvalidArgument {
+                reason: format!(
+                    "Invalid attribute '{}'. Valid attributes are: surface, base_form",
+                    attribute
+                ),
+            }),
+        }
+    }
+}
+
+impl TokenFilter for DedupFilter {
+    type Output = Token;
+
+    fn apply<I>(&self, tokens: I) -> Box<dyn Iterator<Item = Token>>
+    where
+        I: Iterator<Item = Token> + 'static,
+    {
+        let by_surface = self.attribute == "surface";
+        let mut previous: Option<String> = None;
+        let iter = tokens.filter(move |token| {
+            let key = if by_surface {
+                token.surface()
+            } else {
+                token.base_form()
+            };
+            if previous.as_deref() == Some(key) {
+                return false;
+            }
+            previous = Some(key.to_string());
+            true
+        });
+        Box::new(iter)
+    }
+}
+
+/// Kinds of personally identifiable information detected by `PiiMaskFilter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PiiKind {
+    /// Email addresses, e.g. `taro@example.com`
+    Email,
+    /// Japanese phone numbers, e.g. `03-1234-5678`, `09012345678` or `+81-90-1234-5678`
+    Phone,
+    /// Japanese postal codes, e.g. `〒100-0001`
+    PostalCode,
+}
+
+impl PiiKind {
+    /// All kinds, in the order they are matched
+    pub const ALL: [PiiKind; 3] = [PiiKind::Email, PiiKind::Phone, PiiKind::PostalCode];
+
+    /// Surface that replaces a masked value
+    pub fn placeholder(&self) -> &'static str {
+        match self {
+            PiiKind::Email => "<EMAIL>",
+            PiiKind::Phone => "<PHONE>",
+            PiiKind::PostalCode => "<POSTAL_CODE>",
+        }
+    }
+
+    /// Parse a kind from its name: email, phone or postal_code
+    ///
+    /// # Arguments
+    /// * `name` - The kind name
+    ///
+    /// # Returns
+    /// * `Ok(PiiKind)` if the name is valid
+    /// * `Err(RunomeError)` if the name is invalid
+    pub fn from_name(name: &str) -> Result<Self, RunomeError> {
+        match name {
+            "email" => Ok(PiiKind::Email),
+            "phone" => Ok(PiiKind::Phone),
+            "postal_code" => Ok(PiiKind::PostalCode),
+            _ => Err(RunomeError::InvalidArgument {
+                reason: format!(
+                    "Invalid PII kind '{}'. Valid kinds are: email, phone, postal_code",
+                    name
+                ),
+            }),
+        }
+    }
+}
+
+/// Part-of-speech of tokens replaced by `PiiMaskFilter`
+const PII_MASK_POS: &str = "名詞,固有名詞,一般,*";
+
+/// Patterns of all PII kinds, one named group per kind, in `PiiKind::ALL` order
+static PII_PATTERN: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?P<email>[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,})",
+        r"|(?P<phone>(?:\+81-?|0)\d{1,4}-\d{1,4}-\d{3,4}|0\d{9,10})",
+        r"|(?P<postal_code>〒?\d{3}-\d{4})",
+    ))
+    .expect("PII pattern is valid")
+});
+
+/// Masks email addresses, phone numbers and postal codes
+///
+/// The tokenizer splits such values into several tokens (`03`, `-`, `1234`,
+/// ...), so they are matched against the joined surfaces of consecutive
+/// alphanumeric and symbol tokens. The tokens of a match are replaced by a
+/// single token whose surface and base form are the placeholder of its kind
+/// (e.g. `<PHONE>`) and whose span covers the original tokens, so offsets
+/// into the input stay valid.
+///
+/// Only ASCII forms are detected; apply `UnicodeNormalizeCharFilter` first to
+/// also catch full-width digits and symbols.
+///
+/// # Example
+/// ```rust
+/// use runome::PiiMaskFilter;
+/// let filter = PiiMaskFilter::new();
+/// // "03" + "-" + "1234" + "-" + "5678" -> "<PHONE>"
+/// ```
+#[derive(Debug, Clone)]
+pub struct PiiMaskFilter {
+    kinds: Vec<PiiKind>,
+}
+
+impl Default for PiiMaskFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PiiMaskFilter {
+    /// Create a new PiiMaskFilter masking all kinds of PII
+    pub fn new() -> Self {
+        Self {
+            kinds: PiiKind::ALL.to_vec(),
+        }
+    }
+
+    /// Only mask the given kinds of PII
+    ///
+    /// # Arguments
+    /// * `kinds` - The kinds to mask
+    pub fn with_kinds(mut self, kinds: Vec<PiiKind>) -> Self {
+        self.kinds = kinds;
+        self
+    }
+
+    /// Characters a PII value can consist of
+    fn is_candidate(token: &Token) -> bool {
+        let surface = token.surface();
+        !surface.is_empty()
+            && surface
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "@._%+-〒".contains(c))
+    }
+
+    /// Check whether the characters next to a match, read outwards, continue
+    /// it, so that the match is only part of a longer value
+    fn continues_value(mut chars: impl Iterator<Item = char>) -> bool {
+        match chars.next() {
+            Some(c) if c.is_ascii_alphanumeric() => true,
+            Some('-') => chars.next().is_some_and(|c| c.is_ascii_alphanumeric()),
+            _ => false,
+        }
+    }
+
+    /// Mask the values found in a run of candidate tokens
+    fn mask_run(&self, run: Vec<Token>, output: &mut VecDeque<Token>) {
+        let text: String = run.iter().map(Token::surface).collect();
+        let mut ends = Vec::with_capacity(run.len());
+        let mut end = 0;
+        for token in &run {
+            end += token.surface().len();
+            ends.push(end);
+        }
+
+        let mut run = run.into_iter().enumerate().peekable();
+        for captures in PII_PATTERN.captures_iter(&text) {
+            let Some((kind, m)) = PiiKind::ALL
+                .iter()
+                .zip(["email", "phone", "postal_code"])
+                .find_map(|(kind, group)| captures.name(group).map(|m| (*kind, m)))
+            else {
+                continue;
+            };
+            let embedded = Self::continues_value(text[..m.start()].chars().rev())
+                || Self::continues_value(text[m.end()..].chars());
+            if embedded || !self.kinds.contains(&kind) {
+                continue;
+            }
+
+            // Mask every token overlapping the match
+            let first = ends.partition_point(|end| *end <= m.start());
+            let last = ends.partition_point(|end| *end < m.end());
+            if run.peek().is_none_or(|(index, _)| *index > first) {
+                continue;
+            }
+            while let Some((_, token)) = run.next_if(|(index, _)| *index < first) {
+                output.push_back(token);
+            }
+            let covered: Vec<Token> = run
+                .by_ref()
+                .take(last - first + 1)
+                .map(|(_, token)| token)
+                .collect();
+            output.push_back(Self::masked_token(kind, &covered));
+        }
+        output.extend(run.map(|(_, token)| token));
+    }
+
+    fn masked_token(kind: PiiKind, covered: &[Token]) -> Token {
+        let placeholder = kind.placeholder().to_string();
+        let masked = Token::new(
+            placeholder.clone(),
+            PII_MASK_POS.to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            placeholder,
+            "*".to_string(),
+            "*".to_string(),
+            covered[0].node_type(),
+        );
+        match (covered[0].span(), covered[covered.len() - 1].span()) {
+            (Some(first), Some(last)) => masked.with_span(first.start..last.end),
+            _ => masked,
+        }
+    }
+}
+
+impl TokenFilter for PiiMaskFilter {
+    type Output = Token;
+
+    fn apply<I>(&self, tokens: I) -> Box<dyn Iterator<Item = Token>>
+    where
+        I: Iterator<Item = Token> + 'static,
+    {
+        let filter = self.clone();
+        let mut tokens = tokens.peekable();
+        let mut output = VecDeque::new();
+        Box::new(std::iter::from_fn(move || {
+            while output.is_empty() {
+                let token = tokens.next()?;
+                if !Self::is_candidate(&token) {
+                    return Some(token);
+                }
+                let mut run = vec![token];
+                while let Some(token) = tokens.next_if(Self::is_candidate) {
+                    run.push(token);
+                }
+                filter.mask_run(run, &mut output);
+            }
+            output.pop_front()
+        }))
+    }
+}
+
 /// Extracts specific token attributes as strings (terminal filter)
 ///
 /// This is a terminal filter that extracts a specific attribute from each token
 /// and returns it as a string. The output type changes from Token to String,
 /// so this filter cannot be followed by other token filters.
 ///
-/// Valid attributes: surface, part_of_speech, infl_type, infl_form, base_form, reading, phonetic
+/// Valid attributes: surface, part_of_speech, infl_type, infl_form, base_form, reading,
+/// phonetic, and romaji, the reading converted by `RomajiFilter::to_romaji`
 ///
 /// # Example
 /// ```rust
@@ -318,17 +1070,8 @@ impl ExtractAttributeFilter {
     /// * `Ok(ExtractAttributeFilter)` if the attribute is valid
     /// * `Err(RunomeError)` if the attribute is invalid
     pub fn new(attribute: String) -> Result<Self, RunomeError> {
-        // Validate attribute name
-        match attribute.as_str() {
-            "surface" | "part_of_speech" | "infl_type" | "infl_form" | "base_form" | "reading"
-            | "phonetic" => Ok(Self { attribute }),
-            _ => Err(RunomeError::DictValidationError {
-                reason: format!(
-                    "Invalid attribute '{}'. Valid attributes are: surface, part_of_speech, infl_type, infl_form, base_form, reading, phonetic",
-                    attribute
-                ),
-            }),
-        }
+        validate_token_attribute(&attribute)?;
+        Ok(Self { attribute })
     }
 }
 
@@ -340,28 +1083,50 @@ impl TokenFilter for ExtractAttributeFilter {
         I: Iterator<Item = Token> + 'static,
     {
         let attr = self.attribute.clone();
-        let iter = tokens.map(move |token| {
-            match attr.as_str() {
-                "surface" => token.surface().to_string(),
-                "part_of_speech" => token.part_of_speech().to_string(),
-                "infl_type" => token.infl_type().to_string(),
-                "infl_form" => token.infl_form().to_string(),
-                "base_form" => token.base_form().to_string(),
-                "reading" => token.reading().to_string(),
-                "phonetic" => token.phonetic().to_string(),
-                _ => String::new(), // Should not happen due to validation
-            }
-        });
+        let iter = tokens.map(move |token| token_attribute(&token, &attr));
         Box::new(iter)
     }
 }
 
+/// Validate the name of a token attribute
+pub(crate) fn validate_token_attribute(attribute: &str) -> Result<(), RunomeError> {
+    match attribute {
+        "surface" | "part_of_speech" | "infl_type" | "infl_form" | "base_form" | "reading"
+        | "phonetic" | "romaji" => Ok(()),
+        _ => Err(RunomeError::InvalidArgument {
+            reason: format!(
+                "Invalid attribute '{}'. Valid attributes are: surface, part_of_speech, infl_type, infl_form, base_form, reading, phonetic, romaji",
+                attribute
+            ),
+        }),
+    }
+}
+
+/// Get the value of a token attribute validated by `validate_token_attribute`
+pub(crate) fn token_attribute(token: &Token, attribute: &str) -> String {
+    match attribute {
+        "surface" => token.surface().to_string(),
+        "part_of_speech" => token.part_of_speech().to_string(),
+        "infl_type" => token.infl_type().to_string(),
+        "infl_form" => token.infl_form().to_string(),
+        "base_form" => token.base_form().to_string(),
+        "reading" => token.reading().to_string(),
+        "phonetic" => token.phonetic().to_string(),
+        "romaji" => RomajiFilter::to_romaji(token.reading()),
+        _ => String::new(), // Should not happen due to validation
+    }
+}
+
 /// Counts token frequencies (terminal filter)
 ///
 /// This is a terminal filter that counts the frequency of the specified token
 /// attribute and returns an iterator over (attribute_value, count) pairs.
 /// The output type changes from Token to (String, usize).
 ///
+/// Pairs are returned in order of first occurrence, like Janome's
+/// `TokenCountFilter`. When sorted, pairs are ordered by descending count and
+/// pairs with equal counts keep their first-occurrence order.
+///
 /// # Example
 /// ```rust
 /// use runome::TokenCountFilter;
@@ -385,17 +1150,8 @@ impl TokenCountFilter {
     /// * `Ok(TokenCountFilter)` if the attribute is valid
     /// * `Err(RunomeError)` if the attribute is invalid
     pub fn new(attribute: String, sorted: bool) -> Result<Self, RunomeError> {
-        // Validate attribute name (same as ExtractAttributeFilter)
-        match attribute.as_str() {
-            "surface" | "part_of_speech" | "infl_type" | "infl_form" | "base_form" | "reading"
-            | "phonetic" => Ok(Self { attribute, sorted }),
-            _ => Err(RunomeError::DictValidationError {
-                reason: format!(
-                    "Invalid attribute '{}'. Valid attributes are: surface, part_of_speech, infl_type, infl_form, base_form, reading, phonetic",
-                    attribute
-                ),
-            }),
-        }
+        validate_token_attribute(&attribute)?;
+        Ok(Self { attribute, sorted })
     }
 }
 
@@ -409,30 +1165,24 @@ impl TokenFilter for TokenCountFilter {
         let attr = self.attribute.clone();
         let sorted = self.sorted;
 
-        // Collect all tokens and count frequencies
-        let tokens: Vec<Token> = tokens.collect();
-        let mut counts: HashMap<String, usize> = HashMap::new();
+        // Count frequencies, remembering the first-occurrence order of each value
+        let mut result: Vec<(String, usize)> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
 
         for token in tokens {
-            let value = match attr.as_str() {
-                "surface" => token.surface().to_string(),
-                "part_of_speech" => token.part_of_speech().to_string(),
-                "infl_type" => token.infl_type().to_string(),
-                "infl_form" => token.infl_form().to_string(),
-                "base_form" => token.base_form().to_string(),
-                "reading" => token.reading().to_string(),
-                "phonetic" => token.phonetic().to_string(),
-                _ => String::new(), // Should not happen due to validation
-            };
-            *counts.entry(value).or_insert(0) += 1;
+            let value = token_attribute(&token, &attr);
+            match positions.get(&value) {
+                Some(&index) => result[index].1 += 1,
+                None => {
+                    positions.insert(value.clone(), result.len());
+                    result.push((value, 1));
+                }
+            }
         }
 
-        // Convert to vector of pairs
-        let mut result: Vec<(String, usize)> = counts.into_iter().collect();
-
         if sorted {
-            // Sort by frequency descending, then by key ascending for stability
-            result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            // Stable sort by frequency descending, as Python's sorted(reverse=True)
+            result.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
         }
 
         Box::new(result.into_iter())
@@ -486,6 +1236,64 @@ mod tests {
         assert_eq!(results[0].base_form(), "PYTHON");
     }
 
+    #[test]
+    fn test_romaji_conversion() {
+        let cases = [
+            ("スモモ", "sumomo"),
+            ("シャシン", "shashin"),
+            ("チョコレート", "chokoreeto"),
+            ("ジュウ", "juu"),
+            ("キョウト", "kyouto"),
+            ("ガッコウ", "gakkou"),
+            ("マッチャ", "matcha"),
+            ("キンエン", "kin'en"),
+            ("コンヤ", "kon'ya"),
+            ("シンブン", "shinbun"),
+            ("ファイル", "fairu"),
+            ("ティー", "tii"),
+            ("ウィキ", "wiki"),
+            ("ヴァイオリン", "vaiorin"),
+            ("ツヅキ", "tsuzuki"),
+            ("ヂャ", "ja"),
+            ("ひらがな", "hiragana"),
+            ("*", "*"),
+            ("", ""),
+        ];
+        for (kana, expected) in cases {
+            assert_eq!(RomajiFilter::to_romaji(kana), expected, "{}", kana);
+        }
+    }
+
+    #[test]
+    fn test_romaji_filter() {
+        let mut token = create_test_token("東京", "名詞,固有名詞", "東京");
+        token = Token::new(
+            token.surface().to_string(),
+            token.part_of_speech().to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            token.base_form().to_string(),
+            "トウキョウ".to_string(),
+            "トーキョー".to_string(),
+            NodeType::SysDict,
+        )
+        .with_span(0..6);
+        let unknown = create_test_token("Python", "名詞,固有名詞", "Python");
+
+        let results: Vec<Token> = RomajiFilter::new()
+            .apply(vec![token.clone(), unknown].into_iter())
+            .collect();
+        assert_eq!(results[0].reading(), "toukyou");
+        assert_eq!(results[0].phonetic(), "トーキョー");
+        assert_eq!(results[0].span(), Some(0..6));
+        assert_eq!(results[1].reading(), "*");
+
+        // As an extra attribute, keeping the reading
+        let filter = ExtractAttributeFilter::new("romaji".to_string()).unwrap();
+        let romaji: Vec<String> = filter.apply(vec![token].into_iter()).collect();
+        assert_eq!(romaji, vec!["toukyou"]);
+    }
+
     #[test]
     fn test_pos_stop_filter() {
         let filter = POSStopFilter::new(vec!["助詞".to_string(), "記号".to_string()]);
@@ -540,6 +1348,200 @@ mod tests {
         assert_eq!(results[2].part_of_speech(), "名詞,複合,*,*");
     }
 
+    /// Create tokens with consecutive spans, as produced from their joined surfaces
+    fn create_spanned_tokens(surfaces: &[&str]) -> Vec<Token> {
+        let mut start = 0;
+        surfaces
+            .iter()
+            .map(|surface| {
+                let end = start + surface.len();
+                let token = create_test_token(surface, "名詞,一般", surface).with_span(start..end);
+                start = end;
+                token
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pii_mask_filter() {
+        let surfaces = [
+            "電話",
+            "03",
+            "-",
+            "1234",
+            "-",
+            "5678",
+            "、",
+            "メール",
+            "taro",
+            ".",
+            "yamada",
+            "@",
+            "example",
+            ".",
+            "co",
+            ".",
+            "jp",
+            "、",
+            "〒",
+            "100",
+            "-",
+            "0001",
+            "、",
+            "1",
+            "03",
+            "-",
+            "1234",
+            "-",
+            "5678",
+        ];
+        let text: String = surfaces.concat();
+
+        let results: Vec<Token> = PiiMaskFilter::new()
+            .apply(create_spanned_tokens(&surfaces).into_iter())
+            .collect();
+        let masked: Vec<&str> = results.iter().map(|t| t.surface()).collect();
+        assert_eq!(
+            masked,
+            vec![
+                "電話",
+                "<PHONE>",
+                "、",
+                "メール",
+                "<EMAIL>",
+                "、",
+                "<POSTAL_CODE>",
+                "、",
+                // Digits before the match make it part of a longer number
+                "1",
+                "03",
+                "-",
+                "1234",
+                "-",
+                "5678",
+            ]
+        );
+        assert_eq!(results[1].part_of_speech(), "名詞,固有名詞,一般,*");
+        assert_eq!(results[1].base_form(), "<PHONE>");
+        assert_eq!(&text[results[1].span().unwrap()], "03-1234-5678");
+        assert_eq!(
+            &text[results[4].span().unwrap()],
+            "taro.yamada@example.co.jp"
+        );
+        assert_eq!(&text[results[6].span().unwrap()], "〒100-0001");
+
+        // Only the selected kinds are masked
+        let results: Vec<Token> = PiiMaskFilter::new()
+            .with_kinds(vec![PiiKind::PostalCode])
+            .apply(create_spanned_tokens(&surfaces).into_iter())
+            .collect();
+        assert_eq!(results.len(), surfaces.len() - 3);
+        assert_eq!(results[1].surface(), "03");
+        assert!(PiiKind::from_name("address").is_err());
+
+        // Tokens with an empty surface are never absorbed into a value
+        let surfaces = ["", "03", "-", "1234", "-", "5678", ""];
+        let results: Vec<Token> = PiiMaskFilter::new()
+            .apply(create_spanned_tokens(&surfaces).into_iter())
+            .collect();
+        let masked: Vec<&str> = results.iter().map(|t| t.surface()).collect();
+        assert_eq!(masked, vec!["", "<PHONE>", ""]);
+    }
+
+    #[test]
+    fn test_dedup_filter() {
+        let tokens = vec![
+            create_test_token("すごい", "形容詞,自立,*,*", "すごい"),
+            create_test_token("すごい", "形容詞,自立,*,*", "すごい"),
+            create_test_token("すごく", "形容詞,自立,*,*", "すごい"),
+            create_test_token("ね", "助詞,終助詞,*,*", "ね"),
+            create_test_token("すごい", "形容詞,自立,*,*", "すごい"),
+        ];
+
+        let filter = DedupFilter::new("surface".to_string()).unwrap();
+        let surfaces: Vec<String> = filter
+            .apply(tokens.clone().into_iter())
+            .map(|t| t.surface().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["すごい", "すごく", "ね", "すごい"]);
+
+        let filter = DedupFilter::new("base_form".to_string()).unwrap();
+        let surfaces: Vec<String> = filter
+            .apply(tokens.into_iter())
+            .map(|t| t.surface().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["すごい", "ね", "すごい"]);
+
+        assert!(DedupFilter::new("reading".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_word_stop_and_keep_filters() {
+        let tokens = vec![
+            create_test_token("走っ", "動詞,自立,*,*", "走る"),
+            create_test_token("て", "助詞,接続助詞,*,*", "て"),
+            create_test_token("いる", "動詞,非自立,*,*", "いる"),
+        ];
+        let words: HashSet<String> = ["走る", "て"].iter().map(|w| w.to_string()).collect();
+
+        let filter = WordStopFilter::new(words.clone(), "surface".to_string()).unwrap();
+        let surfaces: Vec<String> = filter
+            .apply(tokens.clone().into_iter())
+            .map(|t| t.surface().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["走っ", "いる"]);
+
+        let filter = WordKeepFilter::new(words.clone(), "base_form".to_string()).unwrap();
+        let surfaces: Vec<String> = filter
+            .apply(tokens.into_iter())
+            .map(|t| t.surface().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["走っ", "て"]);
+
+        assert!(WordStopFilter::new(words.clone(), "reading".to_string()).is_err());
+        assert!(WordKeepFilter::new(words, "reading".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_word_stop_filter_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stopwords.txt");
+        std::fs::write(&path, "# particles\nの\n\n  は  \n").unwrap();
+
+        let filter = WordStopFilter::from_file(&path, "surface".to_string()).unwrap();
+        let tokens = vec![
+            create_test_token("私", "名詞,代名詞", "私"),
+            create_test_token("は", "助詞,係助詞", "は"),
+            create_test_token("# particles", "記号,一般", "# particles"),
+        ];
+        let surfaces: Vec<String> = filter
+            .apply(tokens.into_iter())
+            .map(|t| t.surface().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["私", "# particles"]);
+
+        assert!(
+            WordKeepFilter::from_file(&dir.path().join("missing.txt"), "surface".to_string())
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "default-stopwords")]
+    #[test]
+    fn test_default_japanese_stopwords() {
+        let filter = WordStopFilter::default_japanese("base_form".to_string()).unwrap();
+        let tokens = vec![
+            create_test_token("猫", "名詞,一般", "猫"),
+            create_test_token("が", "助詞,格助詞", "が"),
+            create_test_token("い", "動詞,自立", "いる"),
+        ];
+        let surfaces: Vec<String> = filter
+            .apply(tokens.into_iter())
+            .map(|t| t.surface().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["猫"]);
+    }
+
     #[test]
     fn test_extract_attribute_filter_surface() {
         let filter = ExtractAttributeFilter::new("surface".to_string()).unwrap();
@@ -595,6 +1597,36 @@ mod tests {
         assert_eq!(results[1], ("の".to_string(), 1));
     }
 
+    #[test]
+    fn test_token_count_filter_order() {
+        let tokens = vec![
+            create_test_token("すもも", "名詞", "すもも"),
+            create_test_token("も", "助詞", "も"),
+            create_test_token("もも", "名詞", "もも"),
+            create_test_token("も", "助詞", "も"),
+            create_test_token("もも", "名詞", "もも"),
+            create_test_token("の", "助詞", "の"),
+        ];
+
+        // Unsorted: first-occurrence order
+        let filter = TokenCountFilter::new("surface".to_string(), false).unwrap();
+        let results: Vec<(String, usize)> = filter.apply(tokens.clone().into_iter()).collect();
+        let expected = [("すもも", 1), ("も", 2), ("もも", 2), ("の", 1)];
+        assert_eq!(
+            results,
+            expected.map(|(term, count)| (term.to_string(), count))
+        );
+
+        // Sorted: ties keep first-occurrence order
+        let filter = TokenCountFilter::new("surface".to_string(), true).unwrap();
+        let results: Vec<(String, usize)> = filter.apply(tokens.into_iter()).collect();
+        let expected = [("も", 2), ("もも", 2), ("すもも", 1), ("の", 1)];
+        assert_eq!(
+            results,
+            expected.map(|(term, count)| (term.to_string(), count))
+        );
+    }
+
     #[test]
     fn test_compound_noun_filter_japanese_text() {
         // Test equivalent to Python TestTokenFilter.test_compound_noun_filter()
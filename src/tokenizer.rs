@@ -1,20 +1,95 @@
-use std::borrow::Cow;
+use std::borrow::{Borrow, Cow};
 use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
-use crate::dictionary::{Dictionary, SystemDictionary, UserDictionary};
-use crate::error::RunomeError;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::charfilter::{OffsetMapping, UnicodeNormalizeCharFilter};
+use crate::dictionary::{DictEntry, Dictionary, MemoryStats, SystemDictionary, UserDictionary};
+use crate::error::{PathContext, RunomeError};
 use crate::intern;
-use crate::lattice::{Lattice, LatticeNode, NodeType};
+use crate::kana;
+use crate::lattice::{Lattice, LatticeNode, Node, NodeType};
+use crate::rescorer::{self, Candidate, Rescorer};
+use crate::subword::SubwordFallback;
 
 /// Constants matching Python Janome tokenizer
 const MAX_CHUNK_SIZE: usize = 1024;
 const CHUNK_SIZE: usize = 500;
 
+/// Unknown word category of emoji sequences, used when char.def defines it
+const EMOJI_CATEGORY: &str = "EMOJI";
+/// Category of keycap sequences by dictionaries without `EMOJI`
+const DEFAULT_CATEGORY: &str = "DEFAULT";
+
+/// Order dictionary matches by surface length, keeping the lookup order of
+/// matches of the same length
+fn shortest_first(entries: Result<Vec<&DictEntry>, RunomeError>) -> Vec<&DictEntry> {
+    let mut entries = entries.unwrap_or_default();
+    entries.sort_by_key(|entry| entry.surface.len());
+    entries
+}
+
+/// Byte position `count` characters after `pos`, moving by at least one
+/// character to avoid looping forever
+fn advance_chars(text: &str, pos: usize, count: usize) -> usize {
+    text[pos..]
+        .char_indices()
+        .nth(count.max(1))
+        .map_or(text.len(), |(i, _)| pos + i)
+}
+
+/// Whether a character can start an emoji sequence
+fn is_pictographic(c: char) -> bool {
+    matches!(
+        c,
+        '\u{a9}'
+            | '\u{ae}'
+            | '\u{203c}'
+            | '\u{2049}'
+            | '\u{2122}'
+            | '\u{2139}'
+            | '\u{2190}'..='\u{21ff}'
+            | '\u{2300}'..='\u{23ff}'
+            | '\u{24c2}'
+            | '\u{25a0}'..='\u{27bf}'
+            | '\u{2900}'..='\u{297f}'
+            | '\u{2b00}'..='\u{2bff}'
+            | '\u{3030}'
+            | '\u{303d}'
+            | '\u{3297}'
+            | '\u{3299}'
+            | '\u{1f000}'..='\u{1faff}'
+    )
+}
+
+/// Byte ranges of the emoji sequences of the text that span several code
+/// points: ZWJ sequences, skin tone modifiers, flags and keycaps
+///
+/// Each is one extended grapheme cluster.
+fn emoji_clusters(text: &str) -> Vec<Range<usize>> {
+    if !text.chars().any(|c| is_pictographic(c) || c == '\u{20e3}') {
+        return Vec::new();
+    }
+    text.grapheme_indices(true)
+        .filter(|(_, cluster)| {
+            let mut chars = cluster.chars();
+            let first = chars.next().unwrap_or_default();
+            chars.next().is_some() && (is_pictographic(first) || cluster.contains('\u{20e3}'))
+        })
+        .map(|(start, cluster)| start..start + cluster.len())
+        .collect()
+}
+
 /// Token struct containing all morphological information
 /// Mirrors the Python Token class with complete compatibility
 /// Uses Cow<str> for zero-copy optimization when strings can reference static/interned data
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token {
     surface: Cow<'static, str>,
     part_of_speech: Cow<'static, str>,
@@ -24,27 +99,59 @@ pub struct Token {
     reading: Cow<'static, str>,
     phonetic: Cow<'static, str>,
     node_type: NodeType,
+    span: Option<Range<usize>>,
+    /// Lattice node the token was created from, `None` for tokens created directly
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    node: Option<NodeInfo>,
+}
+
+/// Connection IDs and costs of the lattice node a token comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct NodeInfo {
+    left_id: u16,
+    right_id: u16,
+    cost: i16,
+    /// ID of the dictionary entry, unset for unknown words
+    word_id: Option<usize>,
+}
+
+impl NodeInfo {
+    fn of(node: &dyn LatticeNode) -> Self {
+        Self {
+            left_id: node.left_id(),
+            right_id: node.right_id(),
+            cost: node.cost(),
+            word_id: node.word_id(),
+        }
+    }
 }
 
 impl Token {
     /// Create a Token from a dictionary node with full morphological information
-    /// Uses zero-copy optimization for interned strings
-    pub fn from_dict_node(node: &dyn LatticeNode) -> Self {
+    /// Part-of-speech and inflection fields come from a small vocabulary and are
+    /// pooled, so only the per-word fields allocate
+    pub(crate) fn from_dict_node(node: &dyn LatticeNode) -> Self {
         Self {
             surface: intern::intern_or_cow(node.surface()),
-            part_of_speech: intern::intern_or_cow(node.part_of_speech()),
-            infl_type: intern::intern_or_cow(node.inflection_type()),
-            infl_form: intern::intern_or_cow(node.inflection_form()),
+            part_of_speech: intern::intern_pooled(node.part_of_speech()),
+            infl_type: intern::intern_pooled(node.inflection_type()),
+            infl_form: intern::intern_pooled(node.inflection_form()),
             base_form: intern::intern_or_cow(node.base_form()),
             reading: intern::intern_or_cow(node.reading()),
             phonetic: intern::intern_or_cow(node.phonetic()),
             node_type: node.node_type(),
+            span: None,
+            node: Some(NodeInfo::of(node)),
         }
     }
 
     /// Create a Token from an unknown word node
     /// Uses zero-copy optimization for interned strings (especially asterisks)
-    pub fn from_unknown_node(node: &dyn LatticeNode, baseform_unk: bool) -> Self {
+    pub(crate) fn from_unknown_node(node: &dyn LatticeNode, baseform_unk: bool) -> Self {
         let base_form = if baseform_unk {
             intern::intern_or_cow(node.surface())
         } else {
@@ -53,19 +160,22 @@ impl Token {
 
         Self {
             surface: intern::intern_or_cow(node.surface()),
-            part_of_speech: intern::intern_or_cow(node.part_of_speech()),
+            part_of_speech: intern::intern_pooled(node.part_of_speech()),
             infl_type: Cow::Borrowed(intern::ASTERISK),
             infl_form: Cow::Borrowed(intern::ASTERISK),
             base_form,
             reading: intern::intern_or_cow(node.reading()),
             phonetic: intern::intern_or_cow(node.phonetic()),
             node_type: node.node_type(),
+            span: None,
+            node: Some(NodeInfo::of(node)),
         }
     }
 
     /// Create a Token with explicit field values
     /// Used by TokenFilters to create modified tokens
-    /// Converts String parameters to Cow<str> with interning optimization
+    /// Converts String parameters to Cow<str> with interning optimization,
+    /// moving the strings that aren't interned rather than copying them
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         surface: String,
@@ -78,17 +188,31 @@ impl Token {
         node_type: NodeType,
     ) -> Self {
         Self {
-            surface: intern::intern_or_cow(&surface),
-            part_of_speech: intern::intern_or_cow(&part_of_speech),
-            infl_type: intern::intern_or_cow(&infl_type),
-            infl_form: intern::intern_or_cow(&infl_form),
-            base_form: intern::intern_or_cow(&base_form),
-            reading: intern::intern_or_cow(&reading),
-            phonetic: intern::intern_or_cow(&phonetic),
+            surface: intern::intern_string(surface),
+            part_of_speech: intern::intern_pooled(&part_of_speech),
+            infl_type: intern::intern_pooled(&infl_type),
+            infl_form: intern::intern_pooled(&infl_form),
+            base_form: intern::intern_string(base_form),
+            reading: intern::intern_string(reading),
+            phonetic: intern::intern_string(phonetic),
             node_type,
+            span: None,
+            node: None,
         }
     }
 
+    /// Set the byte range of the token in the input text
+    pub fn with_span(mut self, span: Range<usize>) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Copy the span of another token, e.g. one this token was derived from
+    pub(crate) fn with_span_of(mut self, other: &Token) -> Self {
+        self.span = other.span.clone();
+        self
+    }
+
     // Accessor methods matching Python Token class
 
     pub fn surface(&self) -> &str {
@@ -115,6 +239,14 @@ impl Token {
         &self.reading
     }
 
+    /// Get the reading in hiragana
+    ///
+    /// IPADIC readings are katakana; this converts them for display such as
+    /// furigana. Returns "*" for tokens without a reading.
+    pub fn reading_hiragana(&self) -> String {
+        kana::katakana_to_hiragana(&self.reading)
+    }
+
     pub fn phonetic(&self) -> &str {
         &self.phonetic
     }
@@ -122,14 +254,176 @@ impl Token {
     pub fn node_type(&self) -> NodeType {
         self.node_type.clone()
     }
-}
 
-impl fmt::Display for Token {
-    /// Format Token to match Python Janome output exactly:
-    /// "surface\tpart_of_speech,infl_type,infl_form,base_form,reading,phonetic"
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    /// Get the byte range of the token in the input text
+    ///
+    /// Set for tokens produced by the tokenizer; for tokens produced by an
+    /// Analyzer, the range refers to the text before char filters were applied.
+    /// `None` for tokens created directly with `Token::new`.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+
+    /// Get the ID of the dictionary entry the token was created from
+    ///
+    /// Morpheme IDs are numbered separately in the system and user
+    /// dictionaries; see `node_type`. `None` for unknown words and tokens
+    /// created directly with `Token::new`.
+    pub fn word_id(&self) -> Option<usize> {
+        self.node?.word_id
+    }
+
+    /// Get the word cost of the lattice node the token was created from
+    ///
+    /// Like MeCab's `wcost`, this is the cost of the word itself, without
+    /// connection costs. `None` for tokens created directly with `Token::new`.
+    pub fn word_cost(&self) -> Option<i16> {
+        self.node.map(|node| node.cost)
+    }
+
+    /// Get the dictionary entry the token was created from
+    ///
+    /// The entry is rebuilt from the token, with the connection IDs, cost and
+    /// morpheme ID of the lattice node; with `Tokenizer::with_user_dict_cost_bias`,
+    /// user dictionary costs include the bias. `None` for unknown words and
+    /// tokens created directly with `Token::new`.
+    pub fn dict_entry(&self) -> Option<DictEntry> {
+        let node = self.node?;
+        let morph_id = node.word_id?;
+        Some(DictEntry {
+            surface: self.surface.to_string(),
+            left_id: node.left_id,
+            right_id: node.right_id,
+            cost: node.cost,
+            part_of_speech: self.part_of_speech.to_string(),
+            inflection_type: self.infl_type.to_string(),
+            inflection_form: self.infl_form.to_string(),
+            base_form: self.base_form.to_string(),
+            reading: self.reading.to_string(),
+            phonetic: self.phonetic.to_string(),
+            morph_id,
+        })
+    }
+
+    /// Format the token byte-for-byte like Janome's `str(token)`
+    ///
+    /// The layout is `surface\tpart_of_speech,infl_type,infl_form,base_form,reading,phonetic`
+    /// with fields written verbatim, so unset fields keep Janome's `*`. Only
+    /// the Janome fields are included; runome-specific data such as the node
+    /// type or span is never part of this string. `Display` uses the same
+    /// format and is covered by the parity fixture `tests/janome_parity.txt`.
+    pub fn to_janome_string(&self) -> String {
+        // Writing to a String cannot fail
+        let mut output = String::new();
+        let _ = self.write_janome(&mut output);
+        output
+    }
+
+    /// Create an unknown token for a run of spaces, like those of ASCII spaces
+    fn space(surface: &str, span: Range<usize>, baseform_unk: bool) -> Self {
+        let surface = intern::intern_or_cow(surface);
+        let base_form = if baseform_unk {
+            surface.clone()
+        } else {
+            Cow::Borrowed(intern::ASTERISK)
+        };
+        Self {
+            surface,
+            part_of_speech: Cow::Borrowed(intern::POS_SYMBOL_SPACE),
+            infl_type: Cow::Borrowed(intern::ASTERISK),
+            infl_form: Cow::Borrowed(intern::ASTERISK),
+            base_form,
+            reading: Cow::Borrowed(intern::ASTERISK),
+            phonetic: Cow::Borrowed(intern::ASTERISK),
+            node_type: NodeType::Unknown,
+            span: Some(span),
+            node: None,
+        }
+    }
+
+    /// Copy of this unknown word token for one of its subwords
+    fn subword(&self, piece: &str, span: Range<usize>) -> Self {
+        let base_form = if self.base_form == self.surface {
+            Cow::Owned(piece.to_string())
+        } else {
+            self.base_form.clone()
+        };
+        Self {
+            surface: Cow::Owned(piece.to_string()),
+            base_form,
+            span: Some(span),
+            ..self.clone()
+        }
+    }
+
+    /// Join an adjacent space token to this token, before or after it
+    fn merge_space(&mut self, space: Token, before: bool) {
+        let join = |field: &str| {
+            if before {
+                format!("{}{}", space.surface, field)
+            } else {
+                format!("{}{}", field, space.surface)
+            }
+        };
+        if self.base_form == self.surface {
+            self.base_form = Cow::Owned(join(&self.base_form));
+        }
+        self.surface = Cow::Owned(join(&self.surface));
+        if let (Some(span), Some(space_span)) = (self.span.as_mut(), space.span) {
+            *span = span.start.min(space_span.start)..span.end.max(space_span.end);
+        }
+    }
+
+    /// Collation key for sorting tokens in dictionary (五十音) order
+    ///
+    /// Built from the reading, or from the surface when the reading is unset
+    /// (`*`), converted to katakana. Keys compare first on the kana with
+    /// voicing marks and small kana folded (ガ = カ, ッ = ツ) and the long vowel
+    /// mark ー replaced by the vowel it extends, then on the unfolded kana, so
+    /// カ < ガ < キ. Characters other than kana are kept as is and compare by
+    /// code point, so Latin letters sort before kana and kanji after.
+    ///
+    /// # Example
+    /// ```rust
+    /// use runome::{NodeType, Token};
+    /// let token = |reading: &str| {
+    ///     let s = |v: &str| v.to_string();
+    ///     Token::new(s("x"), s("名詞"), s("*"), s("*"), s("x"), s(reading), s(reading), NodeType::SysDict)
+    /// };
+    /// let mut tokens = vec![token("ガッコウ"), token("カキ"), token("カッコウ")];
+    /// tokens.sort_by_key(Token::sort_key);
+    /// let readings: Vec<&str> = tokens.iter().map(|t| t.reading()).collect();
+    /// assert_eq!(readings, vec!["カキ", "カッコウ", "ガッコウ"]);
+    /// ```
+    pub fn sort_key(&self) -> String {
+        let source = if self.reading == "*" {
+            &self.surface
+        } else {
+            &self.reading
+        };
+
+        // Expand ー to the preceding vowel, on katakana
+        let mut kana = String::with_capacity(source.len());
+        let mut previous = None;
+        for c in source.chars().map(kana::hiragana_to_katakana_char) {
+            let c = match (c, previous.and_then(katakana_vowel)) {
+                ('ー', Some(vowel)) => vowel,
+                _ => c,
+            };
+            kana.push(c);
+            previous = Some(c);
+        }
+
+        let mut key: String = kana.chars().map(kana::fold_katakana).collect();
+        // U+0000 sorts before any character, so shorter primary keys come first
+        key.push('\0');
+        key.push_str(&kana);
+        key
+    }
+
+    fn write_janome(&self, out: &mut impl fmt::Write) -> fmt::Result {
         write!(
-            f,
+            out,
             "{}\t{},{},{},{},{},{}",
             self.surface,
             self.part_of_speech,
@@ -142,12 +436,50 @@ impl fmt::Display for Token {
     }
 }
 
+/// Vowel of a katakana character, used to expand the long vowel mark
+fn katakana_vowel(c: char) -> Option<char> {
+    const VOWELS: [(char, &str); 5] = [
+        ('ア', "アァカガサザタダナハバパマヤャラワヮヵ"),
+        ('イ', "イィキギシジチヂニヒビピミリヰ"),
+        ('ウ', "ウゥクグスズツヅッヌフブプムユュルヴ"),
+        ('エ', "エェケゲセゼテデネヘベペメレヱヶ"),
+        ('オ', "オォコゴソゾトドノホボポモヨョロヲ"),
+    ];
+    VOWELS
+        .iter()
+        .find(|(_, row)| row.contains(c))
+        .map(|(vowel, _)| *vowel)
+}
+
+impl fmt::Display for Token {
+    /// Format Token to match Python Janome output exactly (see `Token::to_janome_string`)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_janome(f)
+    }
+}
+
 /// Enum representing the result of tokenization
 /// Either a full Token with morphological info or just the surface string (wakati mode)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TokenizeResult {
     Token(Token),
     Surface(String),
+    /// Byte range of the input that was not tokenized
+    /// Only emitted when enabled with `Tokenizer::with_skipped_spans`
+    Skipped {
+        span: Range<usize>,
+    },
+    /// Byte offset of the input where a sentence starts
+    /// Only emitted when enabled with `Tokenizer::with_boundary_markers`
+    Bos {
+        position: usize,
+    },
+    /// Byte offset of the input where a sentence ends
+    /// Only emitted when enabled with `Tokenizer::with_boundary_markers`
+    Eos {
+        position: usize,
+    },
 }
 
 impl fmt::Display for TokenizeResult {
@@ -155,65 +487,463 @@ impl fmt::Display for TokenizeResult {
         match self {
             TokenizeResult::Token(token) => write!(f, "{}", token),
             TokenizeResult::Surface(surface) => write!(f, "{}", surface),
+            TokenizeResult::Skipped { span } => write!(f, "<skipped {}..{}>", span.start, span.end),
+            TokenizeResult::Bos { position } => write!(f, "<bos {}>", position),
+            TokenizeResult::Eos { position } => write!(f, "<eos {}>", position),
+        }
+    }
+}
+
+/// Unknown-word processing decision for one category at one position
+///
+/// Returned by `Tokenizer::explain_unknown_words` to debug why unknown-word
+/// candidates were (or were not) added to the lattice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownDecision {
+    /// Byte offset of the position in the input text
+    pub position: usize,
+    /// Character at the position
+    pub character: char,
+    /// Character category considered
+    pub category: String,
+    /// True if a dictionary word starts at the position
+    pub dictionary_matched: bool,
+    /// True if unknown-word candidates were generated for the category
+    pub invoked: bool,
+    /// True if the category groups consecutive characters of the same category
+    pub grouping: bool,
+    /// Surface of the unknown-word candidate, if invoked
+    pub surface: Option<String>,
+}
+
+/// Time spent in each phase of a tokenize call, summed over its chunks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    /// Finding chunk boundaries in the input
+    pub chunking: Duration,
+    /// Dictionary lookup, unknown-word processing and forward pass
+    pub lattice: Duration,
+    /// Backward pass selecting the best path
+    pub viterbi: Duration,
+    /// Converting the best path into tokens
+    pub tokens: Duration,
+}
+
+/// Report passed to the slow-call hook for a tokenize call over the threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowCallReport {
+    /// Length of the input text in characters
+    pub input_chars: usize,
+    /// Number of chunks the input was split into
+    pub chunks: usize,
+    /// Time spent tokenizing, excluding time spent by the caller between items
+    pub elapsed: Duration,
+    /// Breakdown of `elapsed` by phase
+    pub phases: PhaseTimings,
+    /// False if the call ended before the end of the input: it timed out,
+    /// failed, or its iterator was dropped early
+    pub completed: bool,
+}
+
+/// Runtime counters of a tokenizer, see `Tokenizer::with_metrics`
+///
+/// A snapshot of the counters since the tokenizer was created or its metrics
+/// were last reset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenizerMetrics {
+    /// Tokens and wakati surfaces produced, excluding skipped spans
+    pub tokens: u64,
+    /// Tokens of words not found in the dictionaries
+    ///
+    /// Spaces tokenized by a `SpacePolicy` other than `Dictionary` are not
+    /// counted.
+    pub unknown_tokens: u64,
+    /// Chunks the inputs were split into
+    pub chunks: u64,
+    /// Time spent tokenizing chunks
+    pub elapsed: Duration,
+}
+
+impl TokenizerMetrics {
+    /// Get the fraction of tokens that are unknown words, 0 without tokens
+    pub fn unknown_ratio(&self) -> f64 {
+        ratio(self.unknown_tokens, self.tokens)
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// Counters behind `TokenizerMetrics`, shared by clones of a tokenizer
+#[derive(Debug, Default)]
+struct MetricsCounters {
+    tokens: AtomicU64,
+    unknown_tokens: AtomicU64,
+    chunks: AtomicU64,
+    elapsed_nanos: AtomicU64,
+}
+
+impl MetricsCounters {
+    fn add(counter: &AtomicU64, value: usize) {
+        counter.fetch_add(value as u64, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> TokenizerMetrics {
+        TokenizerMetrics {
+            tokens: self.tokens.load(Ordering::Relaxed),
+            unknown_tokens: self.unknown_tokens.load(Ordering::Relaxed),
+            chunks: self.chunks.load(Ordering::Relaxed),
+            elapsed: Duration::from_nanos(self.elapsed_nanos.load(Ordering::Relaxed)),
         }
     }
+
+    fn reset(&self) {
+        for counter in [
+            &self.tokens,
+            &self.unknown_tokens,
+            &self.chunks,
+            &self.elapsed_nanos,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Callback invoked with the report of a slow tokenize call
+#[cfg(feature = "slow-call-hook")]
+type SlowCallCallback = Arc<dyn Fn(&SlowCallReport) + Send + Sync>;
+
+/// Records the time spent in a tokenization phase, see `PhaseTimings`
+type PhaseRecorder<'r> = dyn FnMut(fn(&mut PhaseTimings) -> &mut Duration) + 'r;
+
+/// Handling of space characters that a policy applies to
+///
+/// Set for the ideographic space (U+3000) and the no-break space (U+00A0)
+/// with `Tokenizer::with_wide_space_policy`, and for ASCII whitespace with
+/// `Tokenizer::with_ascii_space_policy`. With any policy other than
+/// `Dictionary`, the text between space runs is analyzed separately, so a
+/// run always separates the words around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpacePolicy {
+    /// Analyze spaces with the dictionary like any other character, as
+    /// Janome does (default)
+    #[default]
+    Dictionary,
+    /// Leave spaces out of the output
+    Drop,
+    /// Emit each run of spaces as one `記号,空白` unknown token, like ASCII spaces
+    Token,
+    /// Append each run of spaces to the preceding unknown word, or else
+    /// prepend it to the following one; otherwise, or if dropped spaces of
+    /// the other policy separate them, emit it as a `記号,空白` token
+    Merge,
+}
+
+/// What to do when more candidates than allowed start at a lattice position
+///
+/// Set with `Tokenizer::with_candidate_limit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CandidateOverflow {
+    /// Keep the first candidates, shorter dictionary matches first, and leave
+    /// out the rest (default)
+    #[default]
+    Truncate,
+    /// Fail with `RunomeError::CandidateLimitExceeded`
+    Error,
+}
+
+/// Per-call tokenization options for `Tokenizer::tokenize_with`
+///
+/// The options only cover settings of the streaming, chunked tokenization
+/// of `tokenize_with`. N-best analysis and span constraints are separate
+/// calls, `Tokenizer::nbest` and `Tokenizer::tokenize_constrained`, since
+/// they analyze the whole text as a single lattice and return candidates or
+/// a complete token list rather than a stream. Token filters are applied by
+/// an `Analyzer` built around the tokenizer.
+///
+/// # Example
+/// ```rust,no_run
+/// use runome::{TokenizeOptions, Tokenizer};
+/// use std::time::Duration;
+///
+/// let tokenizer = Tokenizer::new(None, None).unwrap();
+/// let options = TokenizeOptions::new()
+///     .with_wakati(true)
+///     .with_timeout(Duration::from_secs(1));
+/// for result in tokenizer.tokenize_with("すもももももももものうち", &options) {
+///     println!("{}", result.unwrap());
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct TokenizeOptions {
+    /// Override wakati mode for this call; ignored if the tokenizer was created with wakati=true
+    pub wakati: Option<bool>,
+    /// Set base form for unknown words (default: true)
+    pub baseform_unk: Option<bool>,
+    /// Stop with `RunomeError::TokenizeTimeout` if tokenization takes longer than this
+    pub timeout: Option<Duration>,
+    /// Write the lattice of short inputs as Graphviz DOT to this file
+    pub dotfile: Option<PathBuf>,
+}
+
+impl TokenizeOptions {
+    /// Create options with all settings left at their defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override wakati mode for this call
+    pub fn with_wakati(mut self, wakati: bool) -> Self {
+        self.wakati = Some(wakati);
+        self
+    }
+
+    /// Set whether unknown words get their surface as base form
+    pub fn with_baseform_unk(mut self, baseform_unk: bool) -> Self {
+        self.baseform_unk = Some(baseform_unk);
+        self
+    }
+
+    /// Set a time limit for tokenizing the whole text
+    ///
+    /// The limit is checked between chunks, so a single chunk in progress is
+    /// always completed. Tokens produced before the limit are still returned.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Write the lattice of the input to a Graphviz DOT file, like Janome's
+    /// `dotfile` argument
+    ///
+    /// As in Janome, the file is only written for inputs shorter than one
+    /// chunk (1024 characters); longer inputs are tokenized without it. The
+    /// file is written before the first token is returned, and a write error
+    /// is reported as the first item of the iteration.
+    pub fn with_dotfile(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dotfile = Some(path.into());
+        self
+    }
 }
 
 /// Iterator for streaming tokenization results
-pub struct TextChunkIterator<'a> {
-    tokenizer: &'a Tokenizer,
-    text: &'a str,
+///
+/// Generic over how the tokenizer and the input are held, so the same
+/// iterator serves borrowed (`tokenize`) and owned (`tokenize_owned`) calls.
+pub struct TextChunkIterator<T: Borrow<Tokenizer>, S: AsRef<str>> {
+    tokenizer: T,
+    input: S,
+    /// NFKC-normalized input and the mapping of its offsets back to the input,
+    /// if normalization changed it
+    normalized: Option<(String, OffsetMapping)>,
+    /// Byte range of the input that is tokenized (surrounding whitespace is trimmed)
+    offset: usize,
+    end: usize,
     processed: usize,
     current_tokens: std::vec::IntoIter<TokenizeResult>,
     wakati: bool,
     baseform_unk: bool,
+    leading_skipped: Option<Range<usize>>,
+    trailing_skipped: Option<Range<usize>>,
+    timeout: Option<(Duration, Instant)>,
+    /// File to write the lattice to before tokenizing the first chunk
+    dotfile: Option<PathBuf>,
+    /// Phase timings accumulated for the slow-call hook, if one is set
+    timings: Option<PhaseTimings>,
+    chunks: usize,
+    /// Total best-path cost of the lattices of the chunks tokenized so far
+    cost: i32,
+}
+
+impl<T: Borrow<Tokenizer>, S: AsRef<str>> TextChunkIterator<T, S> {
+    /// Text being analyzed: the input, or its normalized form
+    fn text(&self) -> &str {
+        match &self.normalized {
+            Some((text, _)) => text,
+            None => self.input.as_ref(),
+        }
+    }
+
+    /// Map the span of a result in the analyzed text back to the input
+    fn to_input_span(&self, result: TokenizeResult) -> TokenizeResult {
+        let Some((_, mapping)) = &self.normalized else {
+            return result;
+        };
+        match result {
+            TokenizeResult::Token(token) => match token.span() {
+                Some(span) => TokenizeResult::Token(token.with_span(mapping.to_original(span))),
+                None => TokenizeResult::Token(token),
+            },
+            TokenizeResult::Skipped { span } => TokenizeResult::Skipped {
+                span: mapping.to_original(span),
+            },
+            TokenizeResult::Bos { position } => TokenizeResult::Bos {
+                position: mapping.to_original_start(position),
+            },
+            TokenizeResult::Eos { position } => TokenizeResult::Eos {
+                position: mapping.to_original_end(position),
+            },
+            surface => surface,
+        }
+    }
+
+    /// Invoke the slow-call hook when the call ends, at most once
+    ///
+    /// Called when the whole input has been tokenized, on a timeout or
+    /// error, and when the iterator is dropped before the end.
+    #[cfg(feature = "slow-call-hook")]
+    fn report_slow_call(&mut self) {
+        let Some(phases) = self.timings.take() else {
+            return;
+        };
+        let Some((threshold, callback)) = self.tokenizer.borrow().slow_call_hook.as_ref() else {
+            return;
+        };
+        let elapsed = phases.chunking + phases.lattice + phases.viterbi + phases.tokens;
+        if elapsed > *threshold {
+            callback(&SlowCallReport {
+                input_chars: self.text()[self.offset..self.end].chars().count(),
+                chunks: self.chunks,
+                elapsed,
+                phases,
+                completed: self.processed == self.end - self.offset,
+            });
+        }
+    }
+
+    #[cfg(not(feature = "slow-call-hook"))]
+    fn report_slow_call(&mut self) {}
+}
+
+#[cfg(feature = "slow-call-hook")]
+impl<T: Borrow<Tokenizer>, S: AsRef<str>> Drop for TextChunkIterator<T, S> {
+    fn drop(&mut self) {
+        self.report_slow_call();
+    }
 }
 
-impl<'a> Iterator for TextChunkIterator<'a> {
+impl<T: Borrow<Tokenizer>, S: AsRef<str>> Iterator for TextChunkIterator<T, S> {
     type Item = Result<TokenizeResult, RunomeError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        // Report leading whitespace removed before tokenization
+        if let Some(span) = self.leading_skipped.take() {
+            return Some(Ok(self.to_input_span(TokenizeResult::Skipped { span })));
+        }
+
         // Return next token from current batch
         if let Some(token) = self.current_tokens.next() {
-            return Some(Ok(token));
+            return Some(Ok(self.to_input_span(token)));
         }
 
         // Process next chunk if available
-        if self.processed < self.text.len() {
-            match self.tokenizer.tokenize_partial(
-                &self.text[self.processed..],
+        let text_len = self.end - self.offset;
+        if self.processed < text_len {
+            if let Some((timeout, started)) = self.timeout
+                && started.elapsed() > timeout
+            {
+                // Stop the iteration after reporting the timeout
+                self.report_slow_call();
+                self.processed = text_len;
+                self.trailing_skipped = None;
+                return Some(Err(RunomeError::TokenizeTimeout { timeout }));
+            }
+
+            let start = self.offset + self.processed;
+            if let Some(path) = self.dotfile.take()
+                && let Err(e) = self.tokenizer.borrow().write_dotfile(
+                    &self.text()[start..self.end],
+                    self.baseform_unk,
+                    &path,
+                )
+            {
+                self.report_slow_call();
+                return Some(Err(e));
+            }
+            let text = match &self.normalized {
+                Some((text, _)) => text.as_str(),
+                None => self.input.as_ref(),
+            };
+            match self.tokenizer.borrow().tokenize_partial(
+                &text[start..self.end],
+                start,
                 self.wakati,
                 self.baseform_unk,
+                self.timings.as_mut(),
             ) {
-                Ok((tokens, pos)) => {
+                Ok((tokens, pos, cost)) => {
                     self.processed += pos;
+                    self.chunks += 1;
+                    self.cost = self.cost.saturating_add(cost);
+                    if self.processed == text_len {
+                        self.report_slow_call();
+                    }
                     self.current_tokens = tokens.into_iter();
-                    self.current_tokens.next().map(Ok)
+                    let token = self.current_tokens.next()?;
+                    Some(Ok(self.to_input_span(token)))
+                }
+                Err(e) => {
+                    self.report_slow_call();
+                    Some(Err(e))
                 }
-                Err(e) => Some(Err(e)),
             }
         } else {
-            None
+            // Report trailing whitespace once all chunks are exhausted
+            let span = self.trailing_skipped.take()?;
+            Some(Ok(self.to_input_span(TokenizeResult::Skipped { span })))
         }
     }
 }
 
 /// Main Tokenizer struct providing Japanese morphological analysis
 /// Mirrors the Python Janome Tokenizer class API
+///
+/// A `Tokenizer` is `Send + Sync` and cloning it only copies `Arc`s to the
+/// dictionaries and hooks, so it can be shared by reference across threads or
+/// cloned into each one. Tokenization reads the dictionaries without locking.
 #[derive(Clone)]
 pub struct Tokenizer {
     sys_dic: Arc<SystemDictionary>,
     user_dic: Option<Arc<UserDictionary>>,
     max_unknown_length: usize,
+    max_word_length: usize,
     wakati: bool,
+    emit_skipped: bool,
+    user_dict_cost_bias: i16,
+    wide_space_policy: SpacePolicy,
+    ascii_space_policy: SpacePolicy,
+    /// NFKC-normalize input before analysis
+    nfkc: bool,
+    /// Maximum number of candidates starting at one lattice position
+    candidate_limit: Option<(usize, CandidateOverflow)>,
+    /// Number of candidates and the rescorer choosing among them
+    rescorer: Option<(usize, Arc<dyn Rescorer>)>,
+    /// Scale of the costs in candidate probabilities
+    theta: f64,
+    /// Cost units per unit of log probability of the dictionary
+    cost_factor: f64,
+    /// Emit BOS and EOS marker tokens around the tokens of each chunk
+    boundary_markers: bool,
+    /// Minimum length in characters of the unknown words split into subwords
+    subword_fallback: Option<(usize, SubwordFallback)>,
+    /// Runtime counters, if enabled
+    metrics: Option<Arc<MetricsCounters>>,
+    #[cfg(feature = "slow-call-hook")]
+    slow_call_hook: Option<(Duration, SlowCallCallback)>,
 }
 
 impl Tokenizer {
     /// Create a new Tokenizer instance
     ///
     /// # Arguments
-    /// * `max_unknown_length` - Maximum length for unknown words (default: 1024)
-    /// * `wakati` - If true, only return surface forms (default: false)
+    /// * `max_unknown_length` - Maximum length for unknown words (default: 1024, see `GlobalConfig`)
+    /// * `wakati` - If true, only return surface forms (default: false, see `GlobalConfig`)
     ///
     /// # Returns
     /// * `Ok(Tokenizer)` - Successfully created tokenizer
@@ -224,11 +954,29 @@ impl Tokenizer {
     ) -> Result<Self, RunomeError> {
         let sys_dic = SystemDictionary::instance()?;
 
+        let max_word_length = sys_dic.max_surface_length();
+
         Ok(Self {
             sys_dic,
             user_dic: None,
-            max_unknown_length: max_unknown_length.unwrap_or(1024),
-            wakati: wakati.unwrap_or(false),
+            max_unknown_length: max_unknown_length
+                .unwrap_or(crate::config::global_config().default_max_unknown_length),
+            max_word_length,
+            wakati: wakati.unwrap_or(crate::config::global_config().default_wakati),
+            emit_skipped: false,
+            user_dict_cost_bias: 0,
+            wide_space_policy: SpacePolicy::Dictionary,
+            ascii_space_policy: SpacePolicy::Dictionary,
+            nfkc: false,
+            candidate_limit: None,
+            rescorer: None,
+            theta: rescorer::DEFAULT_THETA,
+            cost_factor: rescorer::DEFAULT_COST_FACTOR,
+            boundary_markers: false,
+            subword_fallback: None,
+            metrics: None,
+            #[cfg(feature = "slow-call-hook")]
+            slow_call_hook: None,
         })
     }
 
@@ -236,8 +984,8 @@ impl Tokenizer {
     ///
     /// # Arguments
     /// * `user_dic` - User dictionary to use for custom entries
-    /// * `max_unknown_length` - Maximum length for unknown words (default: 1024)
-    /// * `wakati` - If true, only return surface forms (default: false)
+    /// * `max_unknown_length` - Maximum length for unknown words (default: 1024, see `GlobalConfig`)
+    /// * `wakati` - If true, only return surface forms (default: false, see `GlobalConfig`)
     ///
     /// # Returns
     /// * `Ok(Tokenizer)` - Successfully created tokenizer
@@ -249,522 +997,3518 @@ impl Tokenizer {
     ) -> Result<Self, RunomeError> {
         let sys_dic = SystemDictionary::instance()?;
 
+        let max_word_length = sys_dic
+            .max_surface_length()
+            .max(user_dic.max_surface_length());
+
         Ok(Self {
             sys_dic,
             user_dic: Some(user_dic),
-            max_unknown_length: max_unknown_length.unwrap_or(1024),
-            wakati: wakati.unwrap_or(false),
+            max_unknown_length: max_unknown_length
+                .unwrap_or(crate::config::global_config().default_max_unknown_length),
+            max_word_length,
+            wakati: wakati.unwrap_or(crate::config::global_config().default_wakati),
+            emit_skipped: false,
+            user_dict_cost_bias: 0,
+            wide_space_policy: SpacePolicy::Dictionary,
+            ascii_space_policy: SpacePolicy::Dictionary,
+            nfkc: false,
+            candidate_limit: None,
+            rescorer: None,
+            theta: rescorer::DEFAULT_THETA,
+            cost_factor: rescorer::DEFAULT_COST_FACTOR,
+            boundary_markers: false,
+            subword_fallback: None,
+            metrics: None,
+            #[cfg(feature = "slow-call-hook")]
+            slow_call_hook: None,
         })
     }
 
-    /// Tokenize input text into morphological units
+    /// Create a new Tokenizer instance using the system dictionary at the given path
     ///
     /// # Arguments
-    /// * `text` - Input Japanese text to tokenize
-    /// * `wakati` - Override wakati mode for this call (optional)
-    /// * `baseform_unk` - Set base form for unknown words (default: true)
+    /// * `sysdic_dir` - Path to directory containing system dictionary data
+    /// * `max_unknown_length` - Maximum length for unknown words (default: 1024, see `GlobalConfig`)
+    /// * `wakati` - If true, only return surface forms (default: false, see `GlobalConfig`)
     ///
     /// # Returns
-    /// Iterator yielding `TokenizeResult` items (either Token or Surface string)
-    pub fn tokenize<'a>(
-        &'a self,
-        text: &'a str,
+    /// * `Ok(Tokenizer)` - Successfully created tokenizer
+    /// * `Err(RunomeError)` - Error if the dictionary cannot be loaded from `sysdic_dir`
+    pub fn with_sysdic(
+        sysdic_dir: &Path,
+        max_unknown_length: Option<usize>,
         wakati: Option<bool>,
-        baseform_unk: Option<bool>,
-    ) -> impl Iterator<Item = Result<TokenizeResult, RunomeError>> + 'a {
-        // If tokenizer was initialized with wakati=True, always use wakati mode
-        // regardless of the parameter passed to tokenize()
-        let wakati_mode = if self.wakati {
-            true
-        } else {
-            wakati.unwrap_or(false)
-        };
-        let baseform_unk_mode = baseform_unk.unwrap_or(true);
+    ) -> Result<Self, RunomeError> {
+        let sys_dic = SystemDictionary::instance_at(sysdic_dir)?;
+        Self::with_system_dict(sys_dic, max_unknown_length, wakati)
+    }
+
+    /// Create a new Tokenizer instance backed by a specific system dictionary
+    ///
+    /// Use this to tokenize with a dictionary other than the shared singleton,
+    /// e.g. one created with `SystemDictionary::new_degraded`.
+    ///
+    /// # Arguments
+    /// * `sys_dic` - System dictionary to use
+    /// * `max_unknown_length` - Maximum length for unknown words (default: 1024, see `GlobalConfig`)
+    /// * `wakati` - If true, only return surface forms (default: false, see `GlobalConfig`)
+    ///
+    /// # Returns
+    /// * `Ok(Tokenizer)` - Successfully created tokenizer
+    /// * `Err(RunomeError)` - Error if dictionary initialization fails
+    pub fn with_system_dict(
+        sys_dic: Arc<SystemDictionary>,
+        max_unknown_length: Option<usize>,
+        wakati: Option<bool>,
+    ) -> Result<Self, RunomeError> {
+        let max_word_length = sys_dic.max_surface_length();
 
-        self.tokenize_stream(text, wakati_mode, baseform_unk_mode)
+        Ok(Self {
+            sys_dic,
+            user_dic: None,
+            max_unknown_length: max_unknown_length
+                .unwrap_or(crate::config::global_config().default_max_unknown_length),
+            max_word_length,
+            wakati: wakati.unwrap_or(crate::config::global_config().default_wakati),
+            emit_skipped: false,
+            user_dict_cost_bias: 0,
+            wide_space_policy: SpacePolicy::Dictionary,
+            ascii_space_policy: SpacePolicy::Dictionary,
+            nfkc: false,
+            candidate_limit: None,
+            rescorer: None,
+            theta: rescorer::DEFAULT_THETA,
+            cost_factor: rescorer::DEFAULT_COST_FACTOR,
+            boundary_markers: false,
+            subword_fallback: None,
+            metrics: None,
+            #[cfg(feature = "slow-call-hook")]
+            slow_call_hook: None,
+        })
     }
 
-    /// Get the wakati mode setting for this tokenizer
-    pub fn wakati(&self) -> bool {
-        self.wakati
+    /// Set the maximum length in characters of dictionary matches
+    ///
+    /// Defaults to the longest surface form in the system and user dictionaries.
+    /// Lowering it speeds up lookup at the cost of never matching longer entries.
+    ///
+    /// # Arguments
+    /// * `max_word_length` - Maximum number of characters to look up per position
+    pub fn with_max_word_length(mut self, max_word_length: usize) -> Self {
+        self.max_word_length = max_word_length;
+        self
     }
 
-    /// Create a streaming iterator for tokenization
-    fn tokenize_stream<'a>(
-        &'a self,
-        text: &'a str,
-        wakati: bool,
-        baseform_unk: bool,
-    ) -> TextChunkIterator<'a> {
-        TextChunkIterator {
-            tokenizer: self,
-            text: text.trim(),
-            processed: 0,
-            current_tokens: Vec::new().into_iter(),
-            wakati,
-            baseform_unk,
-        }
+    /// Set the maximum length in characters of unknown words
+    ///
+    /// Runs of characters of a grouping category, such as katakana or
+    /// alphabet, are cut into unknown words of at most this length, and the
+    /// per-category lengths of char.def are capped by it. Defaults to
+    /// `GlobalConfig::default_max_unknown_length`.
+    ///
+    /// # Arguments
+    /// * `max_unknown_length` - Maximum number of characters per unknown word, at least 1
+    pub fn with_max_unknown_length(mut self, max_unknown_length: usize) -> Self {
+        self.max_unknown_length = max_unknown_length.max(1);
+        self
     }
 
-    /// Process a partial chunk of text through the tokenization pipeline
-    /// This is the core tokenization method implementing Phase 2 functionality
-    fn tokenize_partial(
-        &self,
-        text: &str,
-        wakati: bool,
-        baseform_unk: bool,
-    ) -> Result<(Vec<TokenizeResult>, usize), RunomeError> {
-        if text.is_empty() {
-            return Ok((Vec::new(), 0));
-        }
+    /// Limit the number of candidates starting at each lattice position
+    ///
+    /// Inputs matching many dictionary entries at one position, e.g. long runs
+    /// of characters with many homographs, can otherwise make the lattice grow
+    /// without bound. Candidates are considered in lookup order: user
+    /// dictionary matches, then system dictionary matches, each shortest
+    /// first, then unknown words.
+    /// Together with `with_max_word_length`, this bounds the lattice memory per
+    /// character. No limit is set by default.
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of candidates per position, at least 1
+    /// * `overflow` - Whether to drop the extra candidates or fail
+    pub fn with_candidate_limit(mut self, limit: usize, overflow: CandidateOverflow) -> Self {
+        self.candidate_limit = Some((limit.max(1), overflow));
+        self
+    }
 
-        // Determine chunk size, respecting splitting logic and character boundaries
-        let mut chunk_end = text.len();
-        let mut char_count = 0;
+    /// Report input that is not tokenized as `TokenizeResult::Skipped` spans
+    ///
+    /// Leading and trailing whitespace is trimmed before tokenization, as in
+    /// Janome. When enabled, the trimmed ranges are emitted as byte spans of the
+    /// original text so consumers can verify full coverage of the input.
+    ///
+    /// # Arguments
+    /// * `emit_skipped` - If true, emit `Skipped` results for untokenized spans
+    pub fn with_skipped_spans(mut self, emit_skipped: bool) -> Self {
+        self.emit_skipped = emit_skipped;
+        self
+    }
 
-        for (byte_pos, _) in text.char_indices() {
-            char_count += 1;
+    /// Add a cost bias to every user dictionary entry
+    ///
+    /// A negative bias makes user-provided terms win over system dictionary
+    /// splits of the same text without tuning each entry's cost, as MeCab users
+    /// do by lowering costs in their user dictionaries. The biased cost is
+    /// clamped to the `i16` range. Has no effect without a user dictionary.
+    ///
+    /// # Arguments
+    /// * `bias` - Cost added to each user dictionary node (default: 0)
+    pub fn with_user_dict_cost_bias(mut self, bias: i16) -> Self {
+        self.user_dict_cost_bias = bias;
+        self
+    }
 
-            if char_count >= CHUNK_SIZE && char_count < MAX_CHUNK_SIZE {
-                if self.should_split_at_char_pos(text, byte_pos, char_count) {
-                    chunk_end = byte_pos;
-                    break;
-                }
-            }
+    /// Set how the ideographic space (U+3000) and no-break space (U+00A0) are handled
+    ///
+    /// By default they are analyzed with the dictionary as in Janome, where
+    /// IPADIC makes U+3000 a noun and U+00A0 a symbol, unlike ASCII spaces.
+    /// Leading and trailing spaces are trimmed before
+    /// tokenization regardless of the policy. See `SpacePolicy`.
+    ///
+    /// # Arguments
+    /// * `policy` - Handling of U+3000 and U+00A0
+    pub fn with_wide_space_policy(mut self, policy: SpacePolicy) -> Self {
+        self.wide_space_policy = policy;
+        self
+    }
 
-            if char_count >= MAX_CHUNK_SIZE {
-                chunk_end = byte_pos;
-                break;
-            }
-        }
+    /// Set how ASCII whitespace (space, tab, line breaks) is handled
+    ///
+    /// By default ASCII whitespace is analyzed with the dictionary as in
+    /// Janome and MeCab, so user dictionary words containing spaces (e.g.
+    /// "New York") can match. With any other policy the text is split at
+    /// whitespace runs before analysis, and such words no longer match.
+    /// Leading and trailing spaces are trimmed before tokenization
+    /// regardless of the policy. See `SpacePolicy`.
+    ///
+    /// # Arguments
+    /// * `policy` - Handling of ASCII whitespace
+    pub fn with_ascii_space_policy(mut self, policy: SpacePolicy) -> Self {
+        self.ascii_space_policy = policy;
+        self
+    }
 
-        // Process only the chunk we determined
-        let chunk_text = &text[..chunk_end];
+    /// NFKC-normalize input before analysis
+    ///
+    /// Full-width alphanumerics become ASCII and half-width katakana become
+    /// full-width, so they are looked up like their common forms. Token
+    /// surfaces and other fields are those of the normalized text, while
+    /// spans refer to the original input; a span touching characters changed
+    /// by normalization covers all of them. As U+3000 normalizes to an ASCII
+    /// space, `with_ascii_space_policy` applies to it instead of
+    /// `with_wide_space_policy`.
+    ///
+    /// Applies to `tokenize` and the other methods yielding tokens from an
+    /// iterator over the text, but not to `tokenize_constrained`,
+    /// `tokenize_annotated`, `nbest` or the lattice export and explain methods.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether to normalize input (default: false)
+    pub fn with_nfkc_normalization(mut self, enabled: bool) -> Self {
+        self.nfkc = enabled;
+        self
+    }
 
-        // Create lattice for this chunk
-        // Add +1 to lattice size to account for EOS position
-        let lattice_size = chunk_text.chars().count() + 1;
-        let mut lattice = Lattice::new(
-            lattice_size,
-            self.sys_dic.clone() as Arc<dyn crate::dictionary::Dictionary>,
-        );
+    /// Choose segmentations with a rescorer instead of the lattice cost alone
+    ///
+    /// For each lattice, the `n_best` lowest-cost paths are passed to the
+    /// rescorer and the one it scores lowest becomes the tokens. Lattices are
+    /// built per chunk of the input, so the rescorer sees chunks rather than
+    /// the whole text. Each additional candidate costs a path search, so keep
+    /// `n_best` small (e.g. 5 to 20).
+    ///
+    /// # Arguments
+    /// * `n_best` - Number of candidate paths per lattice
+    /// * `rescorer` - Model choosing among the candidates
+    pub fn with_rescorer<R>(mut self, n_best: usize, rescorer: R) -> Self
+    where
+        R: Rescorer + 'static,
+    {
+        self.rescorer = Some((n_best.max(1), Arc::new(rescorer)));
+        self
+    }
 
-        // Add dictionary entries to lattice
-        self.add_dictionary_entries(&mut lattice, chunk_text, baseform_unk)?;
+    /// Emit BOS and EOS marker tokens around the tokens of each sentence
+    ///
+    /// Input is analyzed in chunks ending at sentence punctuation or line
+    /// breaks where possible, and every chunk gets a `TokenizeResult::Bos`
+    /// before its tokens and a `TokenizeResult::Eos` after them, as in
+    /// MeCab's lattice. Markers carry the byte offset of the chunk boundary
+    /// in the input and are not tokens, so formatters and filters never see
+    /// them. They are not emitted in wakati mode and not counted by `metrics`.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether to emit the markers (default: false)
+    pub fn with_boundary_markers(mut self, enabled: bool) -> Self {
+        self.boundary_markers = enabled;
+        self
+    }
 
-        // Process the lattice using Viterbi algorithm
-        // Note: we don't call lattice.forward() here because we've already advanced incrementally
-        lattice.end()?;
-        let path = lattice.backward()?;
+    /// Set the theta scaling costs in candidate probabilities
+    ///
+    /// Like MeCab's `--theta`, the probability of each candidate of `nbest`
+    /// or of a rescorer is proportional to `exp(-theta * cost / cost_factor)`.
+    /// Lower values spread the probability over more candidates, higher
+    /// values concentrate it on the best ones. The segmentation chosen by
+    /// `tokenize` does not depend on theta.
+    ///
+    /// # Arguments
+    /// * `theta` - Scale of the costs (default: `DEFAULT_THETA`); negative
+    ///   and NaN values are treated as 0, making all candidates equally likely
+    pub fn with_theta(mut self, theta: f64) -> Self {
+        self.theta = if theta.is_nan() {
+            0.0
+        } else {
+            theta.clamp(0.0, f64::MAX)
+        };
+        self
+    }
 
-        // Convert path to tokens (excluding BOS and EOS)
-        let tokens = self.path_to_tokens(&path[1..path.len() - 1], wakati, baseform_unk)?;
+    /// Get the theta scaling costs in candidate probabilities
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
 
-        Ok((tokens, chunk_end))
+    /// Set the cost factor the dictionary costs were compiled with
+    ///
+    /// The `cost-factor` of the dicrc of the MeCab dictionary source, used
+    /// to convert costs back to log probabilities in candidate probabilities
+    /// (see `with_theta`). The default, `DEFAULT_COST_FACTOR`, is that of
+    /// mecab-ipadic.
+    ///
+    /// # Arguments
+    /// * `cost_factor` - Cost units per unit of log probability; values that
+    ///   are not positive and finite are ignored
+    pub fn with_cost_factor(mut self, cost_factor: f64) -> Self {
+        if cost_factor.is_finite() && cost_factor > 0.0 {
+            self.cost_factor = cost_factor;
+        }
+        self
     }
 
-    /// Add dictionary entries to the lattice following Python's incremental approach
-    /// This matches Python Janome's tokenize() method exactly
-    fn add_dictionary_entries<'a>(
-        &self,
-        lattice: &mut Lattice<'a>,
-        text: &str,
-        baseform_unk: bool,
-    ) -> Result<(), RunomeError> {
-        let _text_bytes = text.as_bytes();
-        let text_len = text.len();
-        let mut pos = 0;
+    /// Get the cost factor used in candidate probabilities
+    pub fn cost_factor(&self) -> f64 {
+        self.cost_factor
+    }
 
-        // Python-style incremental processing: while pos < len(s):
-        while pos < text_len {
-            let _current_pos = lattice.position();
+    /// Split long unknown words into subwords
+    ///
+    /// Unknown words of at least `min_length` characters are emitted as
+    /// several unknown tokens, one per piece of the segmenter, which keep the
+    /// part of speech of the word and have their own spans. Dictionary words
+    /// are never split. Useful when indexing texts with many out-of-vocabulary
+    /// words such as romaji slang or source code.
+    ///
+    /// # Arguments
+    /// * `min_length` - Minimum length in characters of the words to split
+    /// * `fallback` - Segmenter producing the pieces
+    pub fn with_subword_fallback(mut self, min_length: usize, fallback: SubwordFallback) -> Self {
+        self.subword_fallback = Some((min_length, fallback));
+        self
+    }
 
-            // Extract current character for unknown word processing
-            let current_char = text[pos..].chars().next().unwrap();
-            let mut matched = false;
+    /// Call a hook for tokenize calls that take longer than a threshold
+    ///
+    /// Phase timings are only collected while a hook is set. The hook runs
+    /// once per call, when the whole input has been tokenized or the call
+    /// ends early: on a timeout, on an error, or when the iterator is dropped
+    /// before the end of the input. Reports of calls that ended early have
+    /// `completed` set to false.
+    ///
+    /// # Arguments
+    /// * `threshold` - Minimum tokenization time to report
+    /// * `callback` - Function receiving the report of each slow call
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use runome::Tokenizer;
+    /// use std::time::Duration;
+    ///
+    /// let tokenizer = Tokenizer::new(None, None)
+    ///     .unwrap()
+    ///     .with_slow_call_hook(Duration::from_millis(50), |report| {
+    ///         eprintln!("slow tokenize: {} chars in {:?}", report.input_chars, report.elapsed);
+    ///     });
+    /// ```
+    #[cfg(feature = "slow-call-hook")]
+    pub fn with_slow_call_hook<F>(mut self, threshold: Duration, callback: F) -> Self
+    where
+        F: Fn(&SlowCallReport) + Send + Sync + 'static,
+    {
+        self.slow_call_hook = Some((threshold, Arc::new(callback)));
+        self
+    }
 
-            // 1. DICTIONARY LOOKUP - try all possible substrings starting at current position
-            // We need to work with character-based lengths, not byte-based
-            let remaining_text = &text[pos..];
-            let char_indices: Vec<_> = remaining_text.char_indices().collect();
+    /// Log tokenize calls that take longer than a threshold as warnings
+    ///
+    /// Convenience wrapper around `with_slow_call_hook` logging to the
+    /// `runome::slow` target.
+    ///
+    /// # Arguments
+    /// * `threshold` - Minimum tokenization time to report
+    #[cfg(feature = "slow-call-hook")]
+    pub fn with_slow_call_logging(self, threshold: Duration) -> Self {
+        self.with_slow_call_hook(threshold, |report| {
+            log::warn!(
+                target: "runome::slow",
+                "Slow tokenize call{}: {} chars in {} chunks took {:?} \
+                 (chunking {:?}, lattice {:?}, viterbi {:?}, tokens {:?})",
+                if report.completed { "" } else { " (ended early)" },
+                report.input_chars,
+                report.chunks,
+                report.elapsed,
+                report.phases.chunking,
+                report.phases.lattice,
+                report.phases.viterbi,
+                report.phases.tokens
+            );
+        })
+    }
 
-            for char_len in 1..=std::cmp::min(char_indices.len(), 15) {
-                // Max word length limit
-                // Get substring by character count, not byte count
-                let end_byte = if char_len < char_indices.len() {
-                    char_indices[char_len].0
-                } else {
-                    remaining_text.len()
-                };
-                let substring = &remaining_text[..end_byte];
-
-                // Look up dictionary entries for this substring
-                // 1. Check user dictionary first (higher priority)
-                if let Some(user_dic) = &self.user_dic {
-                    match user_dic.lookup(substring) {
-                        Ok(entries) if !entries.is_empty() => {
-                            matched = true;
-                            for entry in entries {
-                                // Create user dictionary node - optimized with string interning
-                                let user_node = Box::new(crate::lattice::UnknownNode::from_dict_entry(
-                                    &entry.surface,
-                                    entry.left_id,
-                                    entry.right_id,
-                                    entry.cost,
-                                    &entry.part_of_speech,
-                                    &entry.inflection_type,
-                                    &entry.inflection_form,
-                                    &entry.base_form,
-                                    &entry.reading,
-                                    &entry.phonetic,
-                                    NodeType::UserDict,
-                                ));
-                                lattice.add(user_node)?;
-                            }
-                        }
-                        _ => {
-                            // No entries found in user dictionary
-                        }
-                    }
-                }
+    /// Collect runtime metrics of tokenization
+    ///
+    /// Counters are updated once per chunk with relaxed atomics and shared by
+    /// clones of the tokenizer, so a service can read the totals of all its
+    /// threads from any clone.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use runome::Tokenizer;
+    ///
+    /// let tokenizer = Tokenizer::new(None, None).unwrap().with_metrics();
+    /// tokenizer.tokenize_to_vec("すもももももももものうち").unwrap();
+    /// let metrics = tokenizer.metrics().unwrap();
+    /// println!("{} tokens, {:.1}% unknown", metrics.tokens, metrics.unknown_ratio() * 100.0);
+    /// ```
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = Some(Arc::default());
+        self
+    }
 
-                // 2. Check system dictionary (lower priority)
-                match self.sys_dic.lookup(substring) {
-                    Ok(entries) if !entries.is_empty() => {
-                        matched = true;
-                        for entry in entries {
-                            // Create system dictionary node - optimized with string interning
-                            let dict_node = Box::new(crate::lattice::UnknownNode::from_dict_entry(
-                                &entry.surface,
-                                entry.left_id,
-                                entry.right_id,
-                                entry.cost,
-                                &entry.part_of_speech,
-                                &entry.inflection_type,
-                                &entry.inflection_form,
-                                &entry.base_form,
-                                &entry.reading,
-                                &entry.phonetic,
-                                NodeType::SysDict,
-                            ));
-                            lattice.add(dict_node)?;
-                        }
-                    }
-                    _ => {
-                        // No entries found for this substring
-                    }
-                }
-            }
+    /// Get the runtime metrics collected so far
+    ///
+    /// # Returns
+    /// * `Some(TokenizerMetrics)` - Counters since creation or the last reset
+    /// * `None` - Metrics are not enabled, see `with_metrics`
+    pub fn metrics(&self) -> Option<TokenizerMetrics> {
+        self.metrics.as_ref().map(|metrics| metrics.snapshot())
+    }
 
-            // 2. UNKNOWN WORD PROCESSING - Python logic
-            let char_categories = self.sys_dic.get_char_categories_result(current_char)?;
+    /// Reset the runtime metrics to zero, for every clone of the tokenizer
+    pub fn reset_metrics(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.reset();
+        }
+    }
 
-            for category in &char_categories {
-                // Python: if matched and not self.sys_dic.unknown_invoked_always(cate): continue
-                let should_invoke = !matched
-                    || self
-                        .sys_dic
-                        .unknown_invoked_always_result(category)
-                        .unwrap_or(false);
+    /// Get the maximum length in characters of dictionary matches
+    pub fn max_word_length(&self) -> usize {
+        self.max_word_length
+    }
 
-                if should_invoke {
-                    // Get unknown word entries for this category
-                    let unknown_entries = match self.sys_dic.get_unknown_entries_result(category) {
-                        Ok(entries) => entries,
-                        Err(_) => continue,
-                    };
+    /// Get the maximum length in characters of unknown words
+    pub fn max_unknown_length(&self) -> usize {
+        self.max_unknown_length
+    }
 
-                    // Build unknown word following Python's exact logic
-                    let grouped_surface =
-                        self.build_grouped_surface_python_style(text, pos, category)?;
+    /// Get the approximate memory used by the tokenizer
+    ///
+    /// Includes the system and user dictionaries in full, even when shared
+    /// with other tokenizers, and the process-wide string pool. Lattices only
+    /// live for one call and are not counted.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut stats = self.sys_dic.memory_stats();
+        if let Some(user_dic) = &self.user_dic {
+            let user_stats = user_dic.memory_stats();
+            stats.user_dict = user_stats.heap();
+            stats.mapped += user_stats.mapped;
+        }
+        stats.string_pool = intern::pool_memory();
+        stats
+    }
 
-                    // Create unknown word nodes - highly optimized to reduce cloning
-                    let base_form_option = if baseform_unk {
-                        Some(grouped_surface.as_str())
-                    } else {
-                        None
-                    };
+    /// Tokenize input text into morphological units
+    ///
+    /// Thin wrapper around `tokenize_with`.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to tokenize
+    /// * `wakati` - Override wakati mode for this call (optional)
+    /// * `baseform_unk` - Set base form for unknown words (default: true)
+    ///
+    /// # Returns
+    /// Iterator yielding `TokenizeResult` items (either Token or Surface string)
+    pub fn tokenize<'a>(
+        &'a self,
+        text: &'a str,
+        wakati: Option<bool>,
+        baseform_unk: Option<bool>,
+    ) -> impl Iterator<Item = Result<TokenizeResult, RunomeError>> + 'a {
+        let options = TokenizeOptions {
+            wakati,
+            baseform_unk,
+            ..TokenizeOptions::default()
+        };
+        self.tokenize_with(text, &options)
+    }
 
-                    for entry in unknown_entries {
-                        let unknown_node = Box::new(crate::lattice::UnknownNode::for_unknown_word(
-                            grouped_surface.clone(),
-                            entry.left_id,
-                            entry.right_id,
-                            entry.cost,
-                            &entry.part_of_speech,
-                            base_form_option,
-                            NodeType::Unknown,
-                        ));
+    /// Tokenize many documents in parallel
+    ///
+    /// Documents are distributed over the rayon thread pool and share this
+    /// tokenizer's dictionaries. Tokens are always returned in full, ignoring
+    /// wakati mode, and results keep the order of the input.
+    ///
+    /// # Arguments
+    /// * `texts` - Documents to tokenize
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Vec<Token>>)` - Tokens of each document, in input order
+    /// * `Err(RunomeError)` - Error if tokenization of any document fails
+    #[cfg(feature = "parallel")]
+    pub fn tokenize_batch(&self, texts: &[&str]) -> Result<Vec<Vec<Token>>, RunomeError> {
+        use rayon::prelude::*;
+
+        texts
+            .par_iter()
+            .map(|text| {
+                Self::stream_over(self, *text, false, true)
+                    .filter_map(|result| match result {
+                        Ok(TokenizeResult::Token(token)) => Some(Ok(token)),
+                        Ok(_) => None,
+                        Err(e) => Some(Err(e)),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
 
-                        lattice.add(unknown_node)?;
-                    }
-                }
-            }
+    /// Tokenize input text with an iterator that owns all its data
+    ///
+    /// Unlike `tokenize`, the iterator borrows neither the tokenizer nor the
+    /// text: it holds a clone of the tokenizer, which shares the dictionaries,
+    /// and the text itself. It can be returned from functions, stored, or sent
+    /// to another thread, e.g. by service handlers and async tasks. Tokens are
+    /// always returned in full, ignoring wakati mode.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to tokenize
+    ///
+    /// # Returns
+    /// Iterator yielding the tokens of the text
+    pub fn tokenize_owned(
+        &self,
+        text: String,
+    ) -> impl Iterator<Item = Result<Token, RunomeError>> + Send + 'static {
+        Self::stream_over(self.clone(), text, false, true).filter_map(|result| match result {
+            Ok(TokenizeResult::Token(token)) => Some(Ok(token)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
 
-            // 3. CRITICAL: Python-style position advancement
-            // Python: pos += lattice.forward()
-            let advancement = lattice.forward();
-
-            // Convert lattice position advancement to byte position in text
-            // This is the key insight - we need to track byte positions in text
-            // while letting the lattice control the advancement
-            if advancement > 0 {
-                // Find the byte position corresponding to the lattice advancement
-                let mut char_count = 0;
-                for (_i, _) in text[pos..].char_indices() {
-                    if char_count >= advancement {
-                        break;
-                    }
-                    char_count += 1;
-                }
-                // Move to position after the last character
-                if char_count < advancement {
-                    pos = text_len; // End of string
-                } else {
-                    // Find start of next character
-                    pos = text[pos..]
-                        .char_indices()
-                        .nth(advancement)
-                        .map(|(i, _)| pos + i)
-                        .unwrap_or(text_len);
-                }
-            } else {
-                // If no advancement, move by one character to avoid infinite loop
-                pos = text[pos..]
-                    .char_indices()
-                    .nth(1)
-                    .map(|(i, _)| pos + i)
-                    .unwrap_or(text_len);
+    /// Tokenize input text into a vector of tokens
+    ///
+    /// Shorthand for collecting the tokens of `tokenize`. Tokens are always
+    /// returned in full, ignoring wakati mode, and skipped spans are left out.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to tokenize
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Token>)` - Tokens of the text
+    /// * `Err(RunomeError)` - Error if tokenization fails
+    pub fn tokenize_to_vec(&self, text: &str) -> Result<Vec<Token>, RunomeError> {
+        Self::stream_over(self, text, false, true)
+            .filter_map(|result| match result {
+                Ok(TokenizeResult::Token(token)) => Some(Ok(token)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect()
+    }
+
+    /// Append the surfaces of the words of a text to a buffer
+    ///
+    /// Surfaces are separated by `sep`, with no separator before the first
+    /// or after the last, so a buffer can be reused across calls without
+    /// allocating a string per call. Skipped spans are left out. On error,
+    /// the buffer keeps the surfaces appended before it.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to segment
+    /// * `buf` - Buffer the surfaces are appended to
+    /// * `sep` - Separator between surfaces, e.g. `" "`
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of surfaces appended
+    /// * `Err(RunomeError)` - Error if tokenization fails
+    pub fn wakati_into(
+        &self,
+        text: &str,
+        buf: &mut String,
+        sep: &str,
+    ) -> Result<usize, RunomeError> {
+        let mut count = 0;
+        for result in Self::stream_over(self, text, true, true) {
+            let result = result?;
+            let surface = match &result {
+                TokenizeResult::Surface(surface) => surface.as_str(),
+                TokenizeResult::Token(token) => token.surface(),
+                TokenizeResult::Skipped { .. }
+                | TokenizeResult::Bos { .. }
+                | TokenizeResult::Eos { .. } => continue,
+            };
+            if count > 0 {
+                buf.push_str(sep);
             }
+            buf.push_str(surface);
+            count += 1;
         }
+        Ok(count)
+    }
 
-        Ok(())
+    /// Tokenize input text with per-call options
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to tokenize
+    /// * `options` - Options for this call
+    ///
+    /// # Returns
+    /// Iterator yielding `TokenizeResult` items (either Token or Surface string)
+    pub fn tokenize_with<'a>(
+        &'a self,
+        text: &'a str,
+        options: &TokenizeOptions,
+    ) -> impl Iterator<Item = Result<TokenizeResult, RunomeError>> + use<'a> {
+        Self::stream_with_options(self, text, options)
     }
 
-    /// Build grouped surface form following Python Janome's exact logic
-    /// This version works with string byte positions like Python
-    fn build_grouped_surface_python_style(
+    /// Tokenize input text with per-call options, with an iterator that owns
+    /// all its data
+    ///
+    /// The counterpart of `tokenize_with` for `tokenize_owned`: the iterator
+    /// can be stored or sent to another thread, and yields every kind of
+    /// `TokenizeResult`, honoring wakati mode.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to tokenize
+    /// * `options` - Options for this call
+    ///
+    /// # Returns
+    /// Iterator yielding `TokenizeResult` items (either Token or Surface string)
+    pub fn tokenize_owned_with(
         &self,
-        text: &str,
-        start_pos: usize,
-        category: &str,
-    ) -> Result<String, RunomeError> {
-        let category_max_length = self.sys_dic.unknown_length_result(category)?;
-        let length = if self.sys_dic.unknown_grouping_result(category)? {
-            self.max_unknown_length
+        text: String,
+        options: &TokenizeOptions,
+    ) -> TextChunkIterator<Tokenizer, String> {
+        Self::stream_with_options(self.clone(), text, options)
+    }
+
+    fn stream_with_options<T: Borrow<Tokenizer>, S: AsRef<str>>(
+        tokenizer: T,
+        input: S,
+        options: &TokenizeOptions,
+    ) -> TextChunkIterator<T, S> {
+        // If tokenizer was initialized with wakati=True, always use wakati mode
+        // regardless of the parameter passed to tokenize()
+        let wakati_mode = if tokenizer.borrow().wakati {
+            true
         } else {
-            category_max_length
+            options.wakati.unwrap_or(false)
         };
+        let baseform_unk_mode = options.baseform_unk.unwrap_or(true);
 
-        let mut buf = String::new();
-        let char_indices: Vec<_> = text[start_pos..].char_indices().collect();
+        let mut iter = Self::stream_over(tokenizer, input, wakati_mode, baseform_unk_mode);
+        iter.timeout = options.timeout.map(|timeout| (timeout, Instant::now()));
+        iter.dotfile = options.dotfile.clone();
+        iter
+    }
+
+    /// Get the wakati mode setting for this tokenizer
+    pub fn wakati(&self) -> bool {
+        self.wakati
+    }
+
+    /// Create a streaming iterator for tokenization, holding the tokenizer
+    /// and input as given
+    fn stream_over<T: Borrow<Tokenizer>, S: AsRef<str>>(
+        tokenizer: T,
+        input: S,
+        wakati: bool,
+        baseform_unk: bool,
+    ) -> TextChunkIterator<T, S> {
+        let this = tokenizer.borrow();
+        let normalized = this
+            .nfkc
+            .then(|| {
+                UnicodeNormalizeCharFilter::with_default_form()
+                    .normalize_with_offsets(input.as_ref())
+            })
+            .filter(|(text, _)| text != input.as_ref());
+        let text = match &normalized {
+            Some((text, _)) => text.as_str(),
+            None => input.as_ref(),
+        };
+        let start = text.len() - text.trim_start().len();
+        let end = start + text.trim().len();
+
+        let (leading_skipped, trailing_skipped) = if this.emit_skipped {
+            (
+                (start > 0).then_some(0..start),
+                (end < text.len()).then_some(end..text.len()),
+            )
+        } else {
+            (None, None)
+        };
+        let timings = this.slow_call_timings();
+
+        TextChunkIterator {
+            tokenizer,
+            input,
+            normalized,
+            offset: start,
+            end,
+            processed: 0,
+            current_tokens: Vec::new().into_iter(),
+            wakati,
+            baseform_unk,
+            leading_skipped,
+            trailing_skipped,
+            timeout: None,
+            dotfile: None,
+            timings,
+            chunks: 0,
+            cost: 0,
+        }
+    }
+
+    #[cfg(feature = "slow-call-hook")]
+    fn slow_call_timings(&self) -> Option<PhaseTimings> {
+        self.slow_call_hook
+            .as_ref()
+            .map(|_| PhaseTimings::default())
+    }
+
+    #[cfg(not(feature = "slow-call-hook"))]
+    fn slow_call_timings(&self) -> Option<PhaseTimings> {
+        None
+    }
+
+    /// Process a partial chunk of text through the tokenization pipeline
+    /// This is the core tokenization method implementing Phase 2 functionality
+    fn tokenize_partial(
+        &self,
+        text: &str,
+        offset: usize,
+        wakati: bool,
+        baseform_unk: bool,
+        mut timings: Option<&mut PhaseTimings>,
+    ) -> Result<(Vec<TokenizeResult>, usize, i32), RunomeError> {
+        if text.is_empty() {
+            return Ok((Vec::new(), 0, 0));
+        }
+        let started = self.metrics.is_some().then(Instant::now);
+
+        // Only read the clock when timings are collected
+        let mut phase_start = timings.is_some().then(Instant::now);
+        let mut record = |phase: fn(&mut PhaseTimings) -> &mut Duration| {
+            if let (Some(timings), Some(start)) = (timings.as_deref_mut(), phase_start.as_mut()) {
+                let now = Instant::now();
+                *phase(timings) += now - *start;
+                *start = now;
+            }
+        };
+
+        // Determine chunk size, respecting splitting logic and character boundaries
+        let mut chunk_end = text.len();
+        let mut char_count = 0;
+
+        for (byte_pos, _) in text.char_indices() {
+            char_count += 1;
+
+            if (CHUNK_SIZE..MAX_CHUNK_SIZE).contains(&char_count)
+                && self.should_split_at_char_pos(text, byte_pos, char_count)
+            {
+                chunk_end = byte_pos;
+                break;
+            }
+
+            if char_count >= MAX_CHUNK_SIZE {
+                chunk_end = byte_pos;
+                break;
+            }
+        }
+
+        // Process only the chunk we determined
+        let chunk_text = &text[..chunk_end];
+        record(|t| &mut t.chunking);
+        let (tokens, cost) = if self.wide_space_policy == SpacePolicy::Dictionary
+            && self.ascii_space_policy == SpacePolicy::Dictionary
+        {
+            self.tokenize_segment(chunk_text, offset, wakati, baseform_unk, &mut record)?
+        } else {
+            self.tokenize_spaced(chunk_text, offset, wakati, baseform_unk, &mut record)?
+        };
+        log::trace!(
+            "Tokenized chunk of {} chars into {} tokens",
+            chunk_text.chars().count(),
+            tokens.len()
+        );
+        if let (Some(metrics), Some(started)) = (&self.metrics, started) {
+            MetricsCounters::add(&metrics.tokens, tokens.len());
+            MetricsCounters::add(&metrics.chunks, 1);
+            metrics
+                .elapsed_nanos
+                .fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        }
+
+        let tokens = if self.boundary_markers && !wakati {
+            let mut marked = Vec::with_capacity(tokens.len() + 2);
+            marked.push(TokenizeResult::Bos { position: offset });
+            marked.extend(tokens);
+            marked.push(TokenizeResult::Eos {
+                position: offset + chunk_end,
+            });
+            marked
+        } else {
+            tokens
+        };
+
+        Ok((tokens, chunk_end, cost))
+    }
+
+    /// Analyze a segment of text as one lattice
+    ///
+    /// Returns the tokens with the lattice cost of their path.
+    fn tokenize_segment(
+        &self,
+        text: &str,
+        offset: usize,
+        wakati: bool,
+        baseform_unk: bool,
+        record: &mut PhaseRecorder,
+    ) -> Result<(Vec<TokenizeResult>, i32), RunomeError> {
+        // Without a rescorer, which may look at any field, wakati mode only
+        // needs the surfaces of the path
+        let surface_only = wakati && self.rescorer.is_none();
+        let lattice = self
+            .build_lattice(text, baseform_unk, surface_only, &[], None)
+            .map_err(|e| match e {
+                RunomeError::CandidateLimitExceeded { position, limit } => {
+                    RunomeError::CandidateLimitExceeded {
+                        position: offset + position,
+                        limit,
+                    }
+                }
+                e => e,
+            })?;
+        record(|t| &mut t.lattice);
+        if let Some((n_best, rescorer)) = &self.rescorer {
+            let candidates = self.lattice_candidates(&lattice, *n_best, offset, baseform_unk)?;
+            record(|t| &mut t.viterbi);
+            let best = rescorer::best_candidate(rescorer.as_ref(), text, candidates)?;
+            record(|t| &mut t.tokens);
+            if let Some(metrics) = &self.metrics {
+                let unknown = best
+                    .tokens
+                    .iter()
+                    .filter(|token| token.node_type == NodeType::Unknown)
+                    .count();
+                MetricsCounters::add(&metrics.unknown_tokens, unknown);
+            }
+            let tokens = best
+                .tokens
+                .into_iter()
+                .map(|token| {
+                    if wakati {
+                        TokenizeResult::Surface(token.surface.into_owned())
+                    } else {
+                        TokenizeResult::Token(token)
+                    }
+                })
+                .collect();
+            return Ok((tokens, best.cost));
+        }
+        let path = lattice.backward()?;
+        record(|t| &mut t.viterbi);
+        // The minimum cost of EOS is the cost of the whole path
+        let cost = path.last().map_or(0, |eos| eos.min_cost());
+
+        // Convert path to tokens (excluding BOS and EOS)
+        let words = path.get(1..path.len().saturating_sub(1)).ok_or_else(|| {
+            RunomeError::DictValidationError {
+                reason: "Invalid path: missing BOS or EOS node".to_string(),
+            }
+        })?;
+        let tokens = self.path_to_tokens(words, offset, wakati, baseform_unk)?;
+        if let Some(metrics) = &self.metrics {
+            let unknown = words
+                .iter()
+                .filter(|node| node.node_type() == NodeType::Unknown)
+                .map(|node| self.subword_pieces(node).map_or(1, |pieces| pieces.len()))
+                .sum();
+            MetricsCounters::add(&metrics.unknown_tokens, unknown);
+        }
+        record(|t| &mut t.tokens);
+        Ok((tokens, cost))
+    }
+
+    /// Analyze a chunk of text, handling space runs according to their `SpacePolicy`
+    ///
+    /// The text between runs is analyzed as separate segments. Merging only
+    /// looks at tokens of the same chunk. The returned cost is the sum of the
+    /// path costs of the segments.
+    fn tokenize_spaced(
+        &self,
+        text: &str,
+        offset: usize,
+        wakati: bool,
+        baseform_unk: bool,
+        record: &mut PhaseRecorder,
+    ) -> Result<(Vec<TokenizeResult>, i32), RunomeError> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut cost: i32 = 0;
+        // Run of spaces waiting to be merged into the following unknown word
+        let mut pending: Option<Token> = None;
+
+        for (range, policy) in self.split_space_runs(text) {
+            let span = offset + range.start..offset + range.end;
+            let piece = &text[range];
+            match policy {
+                SpacePolicy::Dictionary => {
+                    // Full tokens are needed to merge spaces, even in wakati mode
+                    let (segment, segment_cost) =
+                        self.tokenize_segment(piece, span.start, false, baseform_unk, record)?;
+                    cost = cost.saturating_add(segment_cost);
+                    let mut segment: Vec<Token> = segment
+                        .into_iter()
+                        .filter_map(|result| match result {
+                            TokenizeResult::Token(token) => Some(token),
+                            _ => None,
+                        })
+                        .collect();
+                    // Spaces only merge across no dropped run, so spans stay
+                    // contiguous and match the surfaces
+                    if let Some(space) = pending.take() {
+                        match segment.first_mut() {
+                            Some(first)
+                                if first.node_type == NodeType::Unknown
+                                    && space.span.as_ref().is_some_and(|s| s.end == span.start) =>
+                            {
+                                first.merge_space(space, true)
+                            }
+                            _ => tokens.push(space),
+                        }
+                    }
+                    tokens.append(&mut segment);
+                }
+                SpacePolicy::Drop => {}
+                SpacePolicy::Token => tokens.push(Token::space(piece, span, baseform_unk)),
+                SpacePolicy::Merge => {
+                    let space = Token::space(piece, span.clone(), baseform_unk);
+                    match tokens.last_mut() {
+                        Some(last)
+                            if last.node_type == NodeType::Unknown
+                                && last.span.as_ref().is_some_and(|s| s.end == span.start) =>
+                        {
+                            last.merge_space(space, false)
+                        }
+                        _ => tokens.extend(pending.replace(space)),
+                    }
+                }
+            }
+        }
+        tokens.extend(pending);
+
+        let tokens = tokens
+            .into_iter()
+            .map(|token| {
+                if wakati {
+                    TokenizeResult::Surface(token.surface.into_owned())
+                } else {
+                    TokenizeResult::Token(token)
+                }
+            })
+            .collect();
+        Ok((tokens, cost))
+    }
+
+    /// Split text into runs of characters sharing the same `SpacePolicy`
+    ///
+    /// Characters no policy applies to form runs with `SpacePolicy::Dictionary`.
+    fn split_space_runs(&self, text: &str) -> Vec<(Range<usize>, SpacePolicy)> {
+        let mut runs: Vec<(Range<usize>, SpacePolicy)> = Vec::new();
+        for (pos, c) in text.char_indices() {
+            let policy = match c {
+                '\u{3000}' | '\u{a0}' => self.wide_space_policy,
+                c if c.is_ascii_whitespace() => self.ascii_space_policy,
+                _ => SpacePolicy::Dictionary,
+            };
+            let end = pos + c.len_utf8();
+            match runs.last_mut() {
+                Some((range, last)) if *last == policy => range.end = end,
+                _ => runs.push((pos..end, policy)),
+            }
+        }
+        runs
+    }
+
+    /// Build the lattice for a chunk of text and finalize it with EOS
+    ///
+    /// `fixed_spans` are sorted, non-overlapping byte ranges of the text that
+    /// must each be a single node; see `tokenize_constrained`. With
+    /// `surface_only`, dictionary and unknown word nodes carry only their
+    /// surfaces, connection IDs and costs.
+    fn build_lattice(
+        &self,
+        chunk_text: &str,
+        baseform_unk: bool,
+        surface_only: bool,
+        fixed_spans: &[Range<usize>],
+        decisions: Option<&mut Vec<UnknownDecision>>,
+    ) -> Result<Lattice<'_>, RunomeError> {
+        // Add +1 to lattice size to account for EOS position
+        let lattice_size = chunk_text.chars().count() + 1;
+        let mut lattice = Lattice::new(
+            lattice_size,
+            self.sys_dic.clone() as Arc<dyn crate::dictionary::Dictionary>,
+        );
+
+        // Add dictionary entries to lattice
+        self.add_dictionary_entries(
+            &mut lattice,
+            chunk_text,
+            baseform_unk,
+            surface_only,
+            fixed_spans,
+            decisions,
+        )?;
+
+        // Process the lattice using Viterbi algorithm
+        // Note: we don't call lattice.forward() here because we've already advanced incrementally
+        lattice.end()?;
+        Ok(lattice)
+    }
+
+    /// Find the N best segmentations of a text
+    ///
+    /// The whole text is analyzed as a single lattice, without the chunking
+    /// done by `tokenize`. Candidates are in order of increasing lattice cost;
+    /// the first is the segmentation `tokenize` returns unless several paths
+    /// share the lowest cost. A rescorer set with `with_rescorer` is not applied.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to analyze
+    /// * `n` - Maximum number of candidates
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Candidate>)` - Candidates with their tokens, costs and
+    ///   probabilities among the returned candidates (see `with_theta`)
+    /// * `Err(RunomeError)` - Error if lattice construction fails
+    pub fn nbest(&self, text: &str, n: usize) -> Result<Vec<Candidate>, RunomeError> {
+        let lattice = self.build_lattice(text, true, false, &[], None)?;
+        self.lattice_candidates(&lattice, n, 0, true)
+    }
+
+    /// Tokenize input text keeping the given spans as single tokens
+    ///
+    /// Each span, e.g. a named entity found beforehand, becomes one token: a
+    /// dictionary word if the dictionaries have entries for its exact surface,
+    /// otherwise an unknown word of the categories of its first character.
+    /// No other token crosses a span boundary, like MeCab's boundary
+    /// constraints. The whole text is analyzed as a single lattice, without
+    /// the chunking done by `tokenize`, and neither a rescorer nor the
+    /// subword fallback is applied.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to tokenize
+    /// * `spans` - Non-overlapping byte ranges of `text`, in any order
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Token>)` - Tokens of the text, with spans into it
+    /// * `Err(RunomeError)` - `InvalidSpanConstraint` if a span is empty,
+    ///   overlaps another or isn't on character boundaries of the text, or an
+    ///   error of lattice construction
+    pub fn tokenize_constrained(
+        &self,
+        text: &str,
+        spans: &[Range<usize>],
+    ) -> Result<Vec<Token>, RunomeError> {
+        let mut spans = spans.to_vec();
+        spans.sort_by_key(|span| span.start);
+        for (i, span) in spans.iter().enumerate() {
+            let reason = if span.is_empty() {
+                Some("empty span")
+            } else if text.get(span.clone()).is_none() {
+                Some("not on character boundaries of the text")
+            } else if i > 0 && spans[i - 1].end > span.start {
+                Some("overlaps another span")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                return Err(RunomeError::InvalidSpanConstraint {
+                    start: span.start,
+                    end: span.end,
+                    reason: reason.to_string(),
+                });
+            }
+        }
+
+        let lattice = self.build_lattice(text, true, false, &spans, None)?;
+        let path = lattice.backward()?;
+        let mut start = 0;
+        Ok(path[1..path.len() - 1]
+            .iter()
+            .map(|node| {
+                let token = match node.node_type() {
+                    NodeType::Unknown => Token::from_unknown_node(*node, true),
+                    _ => Token::from_dict_node(*node),
+                };
+                let end = start + node.surface().len();
+                let token = token.with_span(start..end);
+                start = end;
+                token
+            })
+            .collect())
+    }
+
+    /// Tokenize a partially annotated sentence, like MeCab's partial parsing mode
+    ///
+    /// Each line of the input is either raw text or a morpheme annotated as
+    /// `surface\tfeatures`, with features in the format of `Token`'s `Display`:
+    /// `part_of_speech,infl_type,infl_form,base_form,reading,phonetic`, the
+    /// part of speech having four levels. Missing trailing features are `*`.
+    /// The lines are joined without separators into one sentence, which is
+    /// analyzed with `tokenize_constrained` keeping each annotated surface as
+    /// a single token. Annotated morphemes are then returned unchanged, as
+    /// `NodeType::UserDict` tokens; with features left empty or `*`, only the
+    /// boundaries are fixed and the features come from the analysis.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use runome::Tokenizer;
+    ///
+    /// let tokenizer = Tokenizer::new(None, None).unwrap();
+    /// let input = "すもももももも\nもものうち\t名詞,固有名詞,一般,*\n";
+    /// for token in tokenizer.tokenize_annotated(input).unwrap() {
+    ///     println!("{}", token);
+    /// }
+    /// ```
+    ///
+    /// # Arguments
+    /// * `input` - Lines of raw text and annotated morphemes of one sentence
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Token>)` - Tokens of the sentence, with spans into the joined text
+    /// * `Err(RunomeError)` - `AnnotationParseError` for an annotated line
+    ///   with an empty surface or too many features, or an error of analysis
+    pub fn tokenize_annotated(&self, input: &str) -> Result<Vec<Token>, RunomeError> {
+        const FEATURES: usize = 9;
+
+        let mut text = String::new();
+        let mut annotations: Vec<(Range<usize>, Option<Vec<&str>>)> = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            let Some((surface, features)) = line.split_once('\t') else {
+                text.push_str(line);
+                continue;
+            };
+            let error = |reason: &str| RunomeError::AnnotationParseError {
+                line: i + 1,
+                reason: reason.to_string(),
+            };
+            if surface.is_empty() {
+                return Err(error("Empty surface"));
+            }
+            let features = match features.trim() {
+                "" | "*" => None,
+                features => {
+                    let mut features: Vec<&str> = features.split(',').collect();
+                    if features.len() > FEATURES {
+                        return Err(error("More than 9 features"));
+                    }
+                    features.resize(FEATURES, "*");
+                    Some(features)
+                }
+            };
+            annotations.push((text.len()..text.len() + surface.len(), features));
+            text.push_str(surface);
+        }
+
+        let spans: Vec<Range<usize>> = annotations.iter().map(|(span, _)| span.clone()).collect();
+        let mut tokens = self.tokenize_constrained(&text, &spans)?;
+        for token in tokens.iter_mut() {
+            let Some(span) = token.span() else {
+                continue;
+            };
+            let Some((_, Some(features))) = annotations.iter().find(|(s, _)| *s == span) else {
+                continue;
+            };
+            *token = Token::new(
+                text[span.clone()].to_string(),
+                features[..4].join(","),
+                features[4].to_string(),
+                features[5].to_string(),
+                features[6].to_string(),
+                features[7].to_string(),
+                features[8].to_string(),
+                NodeType::UserDict,
+            )
+            .with_span(span);
+        }
+        Ok(tokens)
+    }
+
+    /// Tokenize input text and return the total cost of the chosen path
+    ///
+    /// The text is tokenized as by `tokenize`, and the cost is the sum of the
+    /// word and connection costs of the best path through the lattice of
+    /// each chunk, saturating at the `i32` bounds. With a rescorer, the path
+    /// it chose is counted. Tokens are always returned in full, ignoring
+    /// wakati mode.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to tokenize
+    ///
+    /// # Returns
+    /// * `Ok(Candidate)` - Tokens of the text with the total path cost
+    /// * `Err(RunomeError)` - Error if tokenization fails
+    pub fn tokenize_with_cost(&self, text: &str) -> Result<Candidate, RunomeError> {
+        let mut iter = Self::stream_over(self, text, false, true);
+        let tokens = iter
+            .by_ref()
+            .filter_map(|result| match result {
+                Ok(TokenizeResult::Token(token)) => Some(Ok(token)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Candidate {
+            tokens,
+            cost: iter.cost,
+            probability: 1.0,
+        })
+    }
+
+    /// Convert the N best paths of a finalized lattice to candidates
+    fn lattice_candidates(
+        &self,
+        lattice: &Lattice,
+        n: usize,
+        offset: usize,
+        baseform_unk: bool,
+    ) -> Result<Vec<Candidate>, RunomeError> {
+        let mut candidates = lattice
+            .nbest(n)?
+            .into_iter()
+            .map(|(path, cost)| {
+                let tokens = self
+                    .path_to_tokens(&path[1..path.len() - 1], offset, false, baseform_unk)?
+                    .into_iter()
+                    .filter_map(|result| match result {
+                        TokenizeResult::Token(token) => Some(token),
+                        _ => None,
+                    })
+                    .collect();
+                Ok(Candidate {
+                    tokens,
+                    cost,
+                    probability: 0.0,
+                })
+            })
+            .collect::<Result<Vec<_>, RunomeError>>()?;
+        rescorer::assign_probabilities(&mut candidates, self.theta, self.cost_factor);
+        Ok(candidates)
+    }
+
+    /// Export the candidate lattice of a text as Graphviz DOT
+    ///
+    /// The whole text is analyzed as a single lattice, without the chunking
+    /// done by `tokenize`, and the best path is highlighted. Intended for
+    /// debugging unexpected segmentations of short inputs; see `Lattice::to_dot`.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to analyze
+    ///
+    /// # Returns
+    /// * `Ok(String)` - DOT source of the lattice
+    /// * `Err(RunomeError)` - Error if lattice construction fails
+    pub fn tokenize_with_dot(&self, text: &str) -> Result<String, RunomeError> {
+        let lattice = self.build_lattice(text, true, false, &[], None)?;
+        lattice.to_dot()
+    }
+
+    /// Write the lattice of a text to a DOT file if it fits in one chunk
+    fn write_dotfile(
+        &self,
+        text: &str,
+        baseform_unk: bool,
+        path: &Path,
+    ) -> Result<(), RunomeError> {
+        if text.chars().count() >= MAX_CHUNK_SIZE {
+            log::debug!("Input too long for a dotfile, not writing {:?}", path);
+            return Ok(());
+        }
+        let lattice = self.build_lattice(text, baseform_unk, false, &[], None)?;
+        std::fs::write(path, lattice.to_dot()?).with_path(path)?;
+        Ok(())
+    }
+
+    /// Record the unknown-word processing decisions made for a text
+    ///
+    /// Returns one `UnknownDecision` per character category considered at each
+    /// lattice position, in processing order. The same decisions are logged at
+    /// trace level under the `runome::unknown` target during normal tokenization.
+    /// Like `tokenize_with_dot`, the whole text is analyzed as a single lattice.
+    ///
+    /// # Arguments
+    /// * `text` - Input Japanese text to analyze
+    ///
+    /// # Returns
+    /// * `Ok(Vec<UnknownDecision>)` - Decisions in processing order
+    /// * `Err(RunomeError)` - Error if lattice construction fails
+    pub fn explain_unknown_words(&self, text: &str) -> Result<Vec<UnknownDecision>, RunomeError> {
+        let mut decisions = Vec::new();
+        self.build_lattice(text, true, false, &[], Some(&mut decisions))?;
+        Ok(decisions)
+    }
+
+    /// Add dictionary entries to the lattice following Python's incremental approach
+    /// This matches Python Janome's tokenize() method exactly
+    fn add_dictionary_entries<'a>(
+        &self,
+        lattice: &mut Lattice<'a>,
+        text: &str,
+        baseform_unk: bool,
+        surface_only: bool,
+        fixed_spans: &[Range<usize>],
+        mut decisions: Option<&mut Vec<UnknownDecision>>,
+    ) -> Result<(), RunomeError> {
+        let _text_bytes = text.as_bytes();
+        let text_len = text.len();
+        let mut pos = 0;
+        let mut fixed_spans = fixed_spans.iter().peekable();
+        let emoji_clusters = emoji_clusters(text);
+        let mut emoji_clusters = emoji_clusters.iter().peekable();
+
+        // Python-style incremental processing: while pos < len(s):
+        while pos < text_len {
+            let _current_pos = lattice.position();
+
+            // Fixed spans are single nodes, and no other node may cross into them
+            while fixed_spans.next_if(|span| span.end <= pos).is_some() {}
+            if let Some(span) = fixed_spans.next_if(|span| span.start == pos) {
+                self.add_fixed_span(lattice, &text[span.clone()], baseform_unk)?;
+                pos = advance_chars(text, pos, lattice.forward());
+                continue;
+            }
+            let limit = fixed_spans.peek().map_or(text_len, |span| span.start);
+
+            // Emoji sequences are single nodes too, unless they cross a fixed span
+            while emoji_clusters
+                .next_if(|cluster| cluster.start < pos)
+                .is_some()
+            {}
+            if let Some(cluster) =
+                emoji_clusters.next_if(|cluster| cluster.start == pos && cluster.end <= limit)
+            {
+                self.add_emoji_cluster(
+                    lattice,
+                    &text[cluster.clone()],
+                    pos,
+                    baseform_unk,
+                    decisions.as_deref_mut(),
+                )?;
+                pos = advance_chars(text, pos, lattice.forward());
+                continue;
+            }
+            // Extract current character for unknown word processing
+            let Some(current_char) = text[pos..].chars().next() else {
+                break;
+            };
+
+            // A cluster crossing a fixed span is tokenized character by
+            // character, so the limit is at least the next character boundary
+            let limit = emoji_clusters.peek().map_or(limit, |cluster| {
+                cluster.start.max(pos + current_char.len_utf8()).min(limit)
+            });
+            let mut matched = false;
+            let mut candidates = 0;
+
+            // 1. DICTIONARY LOOKUP - entries of every prefix of the text at the current position
+            // Only the first max_word_length characters can start a dictionary word
+            let remaining_text = &text[pos..];
+            let window_end = remaining_text
+                .char_indices()
+                .nth(self.max_word_length)
+                .map_or(remaining_text.len(), |(i, _)| i);
+            // Lookup is a common prefix search, so one call finds the matches
+            // of every length
+            let substring = &remaining_text[..window_end];
+
+            // 1. Check user dictionary first (higher priority)
+            if let Some(user_dic) = &self.user_dic {
+                for entry in shortest_first(user_dic.lookup(substring)) {
+                    if pos + entry.surface.len() > limit {
+                        break;
+                    }
+                    matched = true;
+                    if !self.admit_candidate(&mut candidates, pos)? {
+                        break;
+                    }
+                    lattice.add(self.entry_node(entry, NodeType::UserDict, surface_only))?;
+                }
+            }
+
+            // 2. Check system dictionary (lower priority)
+            for entry in shortest_first(self.sys_dic.lookup(substring)) {
+                if pos + entry.surface.len() > limit {
+                    break;
+                }
+                matched = true;
+                if !self.admit_candidate(&mut candidates, pos)? {
+                    break;
+                }
+                lattice.add(self.entry_node(entry, NodeType::SysDict, surface_only))?;
+            }
+
+            // 2. UNKNOWN WORD PROCESSING - Python logic
+            let char_categories = self.sys_dic.get_char_categories_result(current_char)?;
+
+            for category in &char_categories {
+                // Python: if matched and not self.sys_dic.unknown_invoked_always(cate): continue
+                let should_invoke = !matched
+                    || self
+                        .sys_dic
+                        .unknown_invoked_always_result(category)
+                        .unwrap_or(false);
+
+                // Get unknown word entries for this category
+                let unknown_entries = match self.sys_dic.get_unknown_entries_result(category) {
+                    Ok(entries) if should_invoke => Some(entries),
+                    _ => None,
+                };
+
+                // Build unknown word following Python's exact logic
+                let grouped_surface = match unknown_entries {
+                    Some(_) => {
+                        // End before the next fixed span, at least one character away
+                        let mut surface =
+                            self.build_grouped_surface_python_style(text, pos, category)?;
+                        surface.truncate(surface.len().min(limit - pos));
+                        Some(surface)
+                    }
+                    None => None,
+                };
+
+                log::trace!(
+                    target: "runome::unknown",
+                    "pos={} char={:?} category={} matched={} invoked={} surface={:?}",
+                    pos,
+                    current_char,
+                    category,
+                    matched,
+                    grouped_surface.is_some(),
+                    grouped_surface
+                );
+                if let Some(decisions) = decisions.as_deref_mut() {
+                    decisions.push(UnknownDecision {
+                        position: pos,
+                        character: current_char,
+                        category: category.to_string(),
+                        dictionary_matched: matched,
+                        invoked: grouped_surface.is_some(),
+                        grouping: self
+                            .sys_dic
+                            .unknown_grouping_result(category)
+                            .unwrap_or(false),
+                        surface: grouped_surface.clone(),
+                    });
+                }
+
+                if let (Some(unknown_entries), Some(grouped_surface)) =
+                    (unknown_entries, grouped_surface)
+                {
+                    // Create unknown word nodes - highly optimized to reduce cloning
+                    let base_form_option = if baseform_unk {
+                        Some(grouped_surface.as_str())
+                    } else {
+                        None
+                    };
+
+                    for entry in unknown_entries {
+                        if !self.admit_candidate(&mut candidates, pos)? {
+                            break;
+                        }
+                        let unknown_node = if surface_only {
+                            crate::lattice::UnknownNode::surface_only(
+                                Cow::Owned(grouped_surface.clone()),
+                                entry.left_id,
+                                entry.right_id,
+                                entry.cost,
+                                NodeType::Unknown,
+                            )
+                        } else {
+                            crate::lattice::UnknownNode::for_unknown_word(
+                                grouped_surface.clone(),
+                                entry.left_id,
+                                entry.right_id,
+                                entry.cost,
+                                &entry.part_of_speech,
+                                base_form_option,
+                                NodeType::Unknown,
+                            )
+                        };
+
+                        lattice.add(unknown_node)?;
+                    }
+                }
+            }
+
+            // 3. CRITICAL: Python-style position advancement
+            // Python: pos += lattice.forward()
+            pos = advance_chars(text, pos, lattice.forward());
+        }
+        Ok(())
+    }
+
+    /// Add the nodes of a fixed span, which must be a single token
+    ///
+    /// Dictionary entries with the exact surface of the span are used if there
+    /// are any; otherwise the span is an unknown word of the categories of its
+    /// first character.
+    fn add_fixed_span(
+        &self,
+        lattice: &mut Lattice<'_>,
+        surface: &str,
+        baseform_unk: bool,
+    ) -> Result<(), RunomeError> {
+        if self.add_exact_entries(lattice, surface)? {
+            return Ok(());
+        }
+
+        let first_char = surface.chars().next().unwrap_or_default();
+        let mut added = false;
+        for category in self.sys_dic.get_char_categories_result(first_char)? {
+            added |= self.add_unknown_span(lattice, surface, baseform_unk, &category)?;
+        }
+        if !added {
+            return Err(RunomeError::DictValidationError {
+                reason: format!("No unknown word entries for {:?}", first_char),
+            });
+        }
+        Ok(())
+    }
+
+    /// Add the nodes of an emoji sequence spanning several code points
+    ///
+    /// Dictionary entries with the exact surface are used if there are any;
+    /// otherwise the sequence is an unknown word of the `EMOJI` category. If
+    /// char.def does not define it, the categories of the first character are
+    /// used instead, or `DEFAULT` for keycaps such as "1\u{fe0f}\u{20e3}".
+    fn add_emoji_cluster(
+        &self,
+        lattice: &mut Lattice<'_>,
+        surface: &str,
+        pos: usize,
+        baseform_unk: bool,
+        decisions: Option<&mut Vec<UnknownDecision>>,
+    ) -> Result<(), RunomeError> {
+        if self.add_exact_entries(lattice, surface)? {
+            return Ok(());
+        }
+
+        let first_char = surface.chars().next().unwrap_or_default();
+        let categories: Vec<Cow<'static, str>> =
+            if self.sys_dic.get_unknown_entries(EMOJI_CATEGORY).is_some() {
+                vec![Cow::Borrowed(EMOJI_CATEGORY)]
+            } else if is_pictographic(first_char) {
+                self.sys_dic.get_char_categories_result(first_char)?
+            } else {
+                vec![Cow::Borrowed(DEFAULT_CATEGORY)]
+            };
+        let mut added = false;
+        for category in &categories {
+            added |= self.add_unknown_span(lattice, surface, baseform_unk, category)?;
+        }
+        if !added {
+            return Err(RunomeError::DictValidationError {
+                reason: format!("No unknown word entries for emoji {:?}", surface),
+            });
+        }
+
+        if let Some(decisions) = decisions {
+            decisions.extend(categories.into_iter().map(|category| UnknownDecision {
+                position: pos,
+                character: first_char,
+                category: category.into_owned(),
+                dictionary_matched: false,
+                invoked: true,
+                grouping: false,
+                surface: Some(surface.to_string()),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Add the unknown word nodes of a category for a whole span
+    ///
+    /// Returns whether any node was added.
+    fn add_unknown_span(
+        &self,
+        lattice: &mut Lattice<'_>,
+        surface: &str,
+        baseform_unk: bool,
+        category: &str,
+    ) -> Result<bool, RunomeError> {
+        let mut added = false;
+        for entry in self.sys_dic.get_unknown_entries_result(category)? {
+            let node = crate::lattice::UnknownNode::for_unknown_word(
+                surface.to_string(),
+                entry.left_id,
+                entry.right_id,
+                entry.cost,
+                &entry.part_of_speech,
+                baseform_unk.then_some(surface),
+                NodeType::Unknown,
+            );
+            lattice.add(node)?;
+            added = true;
+        }
+        Ok(added)
+    }
+
+    /// Add the dictionary entries whose surface is exactly `surface`
+    ///
+    /// Returns whether any entry was found.
+    fn add_exact_entries(
+        &self,
+        lattice: &mut Lattice<'_>,
+        surface: &str,
+    ) -> Result<bool, RunomeError> {
+        let mut added = false;
+        if let Some(user_dic) = &self.user_dic {
+            for entry in user_dic.lookup(surface)? {
+                if entry.surface == surface {
+                    lattice.add(self.entry_node(entry, NodeType::UserDict, false))?;
+                    added = true;
+                }
+            }
+        }
+        for entry in self.sys_dic.lookup(surface)? {
+            if entry.surface == surface {
+                lattice.add(self.entry_node(entry, NodeType::SysDict, false))?;
+                added = true;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Lattice node of a dictionary entry, with the user dictionary cost bias
+    /// applied to user dictionary entries
+    ///
+    /// With `surface_only`, the morphological fields are skipped; see
+    /// `UnknownNode::surface_only`.
+    fn entry_node(
+        &self,
+        entry: &DictEntry,
+        node_type: NodeType,
+        surface_only: bool,
+    ) -> crate::lattice::UnknownNode {
+        let cost = if node_type == NodeType::UserDict {
+            entry.cost.saturating_add(self.user_dict_cost_bias)
+        } else {
+            entry.cost
+        };
+        let node = if surface_only {
+            crate::lattice::UnknownNode::surface_only(
+                intern::intern_or_cow(&entry.surface),
+                entry.left_id,
+                entry.right_id,
+                cost,
+                node_type,
+            )
+        } else {
+            crate::lattice::UnknownNode::from_dict_entry(
+                &entry.surface,
+                entry.left_id,
+                entry.right_id,
+                cost,
+                &entry.part_of_speech,
+                &entry.inflection_type,
+                &entry.inflection_form,
+                &entry.base_form,
+                &entry.reading,
+                &entry.phonetic,
+                node_type,
+            )
+        };
+        node.with_word_id(entry.morph_id)
+    }
+
+    /// Count a candidate starting at `pos` against the candidate limit
+    ///
+    /// Returns whether the candidate is added to the lattice; with
+    /// `CandidateOverflow::Error`, exceeding the limit fails instead.
+    fn admit_candidate(&self, candidates: &mut usize, pos: usize) -> Result<bool, RunomeError> {
+        let Some((limit, overflow)) = self.candidate_limit else {
+            return Ok(true);
+        };
+        if *candidates < limit {
+            *candidates += 1;
+            return Ok(true);
+        }
+        match overflow {
+            CandidateOverflow::Truncate => {
+                if *candidates == limit {
+                    log::debug!("Candidate limit {} reached at byte {}", limit, pos);
+                    *candidates += 1;
+                }
+                Ok(false)
+            }
+            CandidateOverflow::Error => Err(RunomeError::CandidateLimitExceeded {
+                position: pos,
+                limit,
+            }),
+        }
+    }
+
+    /// Build grouped surface form following Python Janome's exact logic
+    /// This version works with string byte positions like Python
+    fn build_grouped_surface_python_style(
+        &self,
+        text: &str,
+        start_pos: usize,
+        category: &str,
+    ) -> Result<String, RunomeError> {
+        let category_max_length = self.sys_dic.unknown_length_result(category)?;
+        // The configured maximum also caps the char.def length
+        let length = if self.sys_dic.unknown_grouping_result(category)? {
+            self.max_unknown_length
+        } else {
+            category_max_length.min(self.max_unknown_length)
+        };
+
+        let mut chars = text[start_pos..].chars();
+        let mut buf = String::new();
+
+        // Add the starting character
+        if let Some(first_char) = chars.next() {
+            buf.push(first_char);
+        }
+
+        // Group consecutive characters following Python's logic
+        let mut count = 1;
+        for c in chars {
+            if count >= length {
+                break;
+            }
+
+            // Python logic: if cate in _cates or any(cate in _compat_cates for _compat_cates in _cates.values())
+            if self.sys_dic.is_char_compatible(c, category) {
+                buf.push(c);
+                count += 1;
+            } else {
+                break;
+            }
+        }
+
+        Ok(buf)
+    }
+
+    /// Convert a path of lattice nodes to tokens
+    fn path_to_tokens(
+        &self,
+        path: &[&Node<'_>],
+        offset: usize,
+        wakati: bool,
+        baseform_unk: bool,
+    ) -> Result<Vec<TokenizeResult>, RunomeError> {
+        let mut tokens = Vec::new();
+        // The path covers the chunk without gaps, so spans follow the surfaces
+        let mut start = offset;
+
+        for node in path {
+            if let Some(pieces) = self.subword_pieces(node) {
+                let token = Token::from_unknown_node(*node, baseform_unk);
+                for piece in pieces {
+                    let end = start + piece.len();
+                    tokens.push(if wakati {
+                        TokenizeResult::Surface(piece.to_string())
+                    } else {
+                        TokenizeResult::Token(token.subword(piece, start..end))
+                    });
+                    start = end;
+                }
+                continue;
+            }
+
+            let end = start + node.surface().len();
+            if wakati {
+                // Wakati mode: return only surface forms
+                tokens.push(TokenizeResult::Surface(intern::intern_or_clone(
+                    node.surface(),
+                )));
+            } else {
+                // Full mode: create Token objects with morphological information
+                let token = match node.node_type() {
+                    NodeType::SysDict => Token::from_dict_node(*node),
+                    NodeType::Unknown => Token::from_unknown_node(*node, baseform_unk),
+                    NodeType::UserDict => Token::from_dict_node(*node), // Treat as dict node for now
+                };
+                tokens.push(TokenizeResult::Token(token.with_span(start..end)));
+            }
+            start = end;
+        }
+
+        Ok(tokens)
+    }
+
+    /// Subword pieces of a node, if it is an unknown word long enough to split
+    fn subword_pieces<'a>(&self, node: &'a Node<'_>) -> Option<Vec<&'a str>> {
+        let (min_length, fallback) = self.subword_fallback.as_ref()?;
+        (node.node_type() == NodeType::Unknown && node.surface_len() >= *min_length)
+            .then(|| fallback.split(node.surface()))
+    }
+
+    /// Determine if text should be split at the given character position
+    /// This version works with character counts instead of byte positions
+    fn should_split_at_char_pos(&self, text: &str, byte_pos: usize, char_count: usize) -> bool {
+        byte_pos >= text.len()
+            || char_count >= MAX_CHUNK_SIZE
+            || (char_count >= CHUNK_SIZE
+                && byte_pos <= text.len()
+                && self.is_splittable(&text[..byte_pos]))
+    }
+
+    /// Check if text can be split at the end (at punctuation or newlines)
+    fn is_splittable(&self, text: &str) -> bool {
+        if let Some(last_char) = text.chars().last() {
+            self.is_punct(last_char) || self.is_newline(text)
+        } else {
+            false
+        }
+    }
+
+    /// Check if character is punctuation (suitable for splitting)
+    fn is_punct(&self, c: char) -> bool {
+        matches!(c, '、' | '。' | ',' | '.' | '？' | '?' | '！' | '!')
+    }
+
+    /// Check if text ends with newlines (suitable for splitting)
+    fn is_newline(&self, text: &str) -> bool {
+        text.ends_with("\n\n") || text.ends_with("\r\n\r\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_creation() {
+        // Test Token creation with minimal data
+        use crate::lattice::UnknownNode;
+
+        let unknown_node = UnknownNode::new(
+            "テスト".to_string(),
+            100,
+            200,
+            150,
+            "名詞,一般,*,*,*,*".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            "テスト".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            NodeType::Unknown,
+        );
+
+        let token = Token::from_unknown_node(&unknown_node, true);
+
+        assert_eq!(token.surface(), "テスト");
+        assert_eq!(token.part_of_speech(), "名詞,一般,*,*,*,*");
+        assert_eq!(token.base_form(), "テスト"); // baseform_unk = true
+        assert_eq!(token.node_type(), NodeType::Unknown);
+    }
+
+    #[test]
+    fn test_token_sort_key() {
+        let token = |surface: &str, reading: &str| {
+            Token::new(
+                surface.to_string(),
+                "名詞,一般,*,*".to_string(),
+                "*".to_string(),
+                "*".to_string(),
+                surface.to_string(),
+                reading.to_string(),
+                reading.to_string(),
+                NodeType::SysDict,
+            )
+        };
+        let mut tokens = [
+            token("学校", "ガッコウ"),
+            token("カーブ", "カーブ"),
+            token("すし", "*"),
+            token("括弧", "カッコ"),
+            token("蚊", "カ"),
+            token("が", "ガ"),
+            token("会", "カイ"),
+            token("ABC", "*"),
+        ];
+        tokens.sort_by_key(Token::sort_key);
+        let surfaces: Vec<&str> = tokens.iter().map(|t| t.surface()).collect();
+        // カーブ is ordered as カアブ; the unset reading of すし falls back to the surface
+        assert_eq!(
+            surfaces,
+            vec!["ABC", "蚊", "が", "カーブ", "会", "括弧", "学校", "すし"]
+        );
+    }
+
+    #[test]
+    fn test_token_display() {
+        use crate::lattice::UnknownNode;
+
+        let unknown_node = UnknownNode::new(
+            "テスト".to_string(),
+            100,
+            200,
+            150,
+            "名詞,一般,*,*,*,*".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            "テスト".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            NodeType::Unknown,
+        );
+
+        let token = Token::from_unknown_node(&unknown_node, true);
+        let formatted = format!("{}", token);
+
+        // Should match Python format: surface\tpart_of_speech,infl_type,infl_form,base_form,reading,phonetic
+        assert_eq!(formatted, "テスト\t名詞,一般,*,*,*,*,*,*,テスト,*,*");
+    }
+
+    #[test]
+    fn test_tokenize_result_display() {
+        let surface_result = TokenizeResult::Surface("テスト".to_string());
+        assert_eq!(format!("{}", surface_result), "テスト");
+
+        use crate::lattice::UnknownNode;
+        let unknown_node = UnknownNode::new(
+            "テスト".to_string(),
+            100,
+            200,
+            150,
+            "名詞,一般,*,*,*,*".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            "テスト".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            NodeType::Unknown,
+        );
+        let token = Token::from_unknown_node(&unknown_node, true);
+        let token_result = TokenizeResult::Token(token);
+
+        assert!(format!("{}", token_result).starts_with("テスト\t"));
+    }
+
+    #[test]
+    fn test_tokenizer_creation() {
+        // Skip test if sysdic directory doesn't exist
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None);
+        assert!(tokenizer.is_ok(), "Tokenizer creation should succeed");
+
+        let tokenizer = tokenizer.unwrap();
+        assert_eq!(tokenizer.max_unknown_length, 1024);
+        assert_eq!(tokenizer.max_word_length, 26);
+        assert!(!tokenizer.wakati);
+    }
+
+    #[test]
+    fn test_tokenizer_custom_params() {
+        // Skip test if sysdic directory doesn't exist
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(Some(2048), Some(true));
+        assert!(tokenizer.is_ok(), "Tokenizer creation should succeed");
+
+        let tokenizer = tokenizer.unwrap();
+        assert_eq!(tokenizer.max_unknown_length, 2048);
+        assert!(tokenizer.wakati);
+    }
+
+    #[test]
+    fn test_candidate_limit() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "すもももももももものうち";
+        let surfaces = |tokenizer: &Tokenizer| -> Result<Vec<String>, RunomeError> {
+            tokenizer
+                .tokenize(text, Some(true), None)
+                .map(|result| result.map(|r| r.to_string()))
+                .collect()
+        };
+
+        // A generous limit leaves the segmentation unchanged
+        let unlimited = Tokenizer::new(None, None).unwrap();
+        let limited = Tokenizer::new(None, None)
+            .unwrap()
+            .with_candidate_limit(100, CandidateOverflow::Error);
+        assert_eq!(surfaces(&limited).unwrap(), surfaces(&unlimited).unwrap());
+
+        // Truncation keeps the shortest matches and still covers the text
+        let truncated = Tokenizer::new(None, None)
+            .unwrap()
+            .with_candidate_limit(1, CandidateOverflow::Truncate);
+        let tokens = surfaces(&truncated).unwrap();
+        assert_eq!(tokens.concat(), text);
+        assert_eq!(tokens[..2], ["す", "も"]);
+
+        let strict = Tokenizer::new(None, None)
+            .unwrap()
+            .with_candidate_limit(1, CandidateOverflow::Error);
+        assert!(matches!(
+            surfaces(&strict),
+            Err(RunomeError::CandidateLimitExceeded {
+                position: 0,
+                limit: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_tokenizer_max_word_length() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap().with_max_word_length(2);
+        assert_eq!(tokenizer.max_word_length(), 2);
+
+        // "すもも" (3 chars) can no longer be matched from the dictionary
+        let surfaces: Vec<String> = tokenizer
+            .tokenize("すもも", None, None)
+            .map(|result| match result.unwrap() {
+                TokenizeResult::Token(token) => token.surface().to_string(),
+                TokenizeResult::Surface(surface) => surface,
+                TokenizeResult::Skipped { span } => panic!("Unexpected skipped span: {:?}", span),
+                marker @ (TokenizeResult::Bos { .. } | TokenizeResult::Eos { .. }) => {
+                    panic!("Unexpected boundary marker: {:?}", marker)
+                }
+            })
+            .collect();
+        assert!(!surfaces.contains(&"すもも".to_string()));
+        assert_eq!(surfaces.concat(), "すもも");
+    }
+
+    /// Tokenize text with many multi-category characters, one token per line
+    fn tokenize_determinism_sample(tokenizer: &Tokenizer) -> String {
+        tokenizer
+            .tokenize("一二三万円の〇〇年、ＡＢＣ１２３とｶﾀｶﾅ☆ーす", None, None)
+            .map(|result| result.unwrap().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_tokenize_deterministic_across_runs() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        // Fresh dictionaries and tokenizers, so every run gets new hash seeds
+        let sys_dic = Arc::new(SystemDictionary::new(&sysdic_path).unwrap());
+        let expected =
+            tokenize_determinism_sample(&Tokenizer::with_system_dict(sys_dic, None, None).unwrap());
+        for _ in 0..2 {
+            let sys_dic = Arc::new(SystemDictionary::new(&sysdic_path).unwrap());
+            let tokenizer = Tokenizer::with_system_dict(sys_dic, None, None).unwrap();
+            assert_eq!(tokenize_determinism_sample(&tokenizer), expected);
+        }
+
+        // Run the same tokenization in a separate process and compare
+        if std::env::var_os("RUNOME_DETERMINISM_CHILD").is_some() {
+            println!("{}", expected);
+            return;
+        }
+        let child = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "tokenizer::tests::test_tokenize_deterministic_across_runs",
+                "--nocapture",
+            ])
+            .env("RUNOME_DETERMINISM_CHILD", "1")
+            .output()
+            .unwrap();
+        assert!(child.status.success());
+        assert!(String::from_utf8_lossy(&child.stdout).contains(&expected));
+    }
+
+    #[test]
+    fn test_tokenize_repetitive_text() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        // Repeated substrings segment the same way at every occurrence
+        let tokenizer = Tokenizer::new(None, Some(true)).unwrap();
+        let surfaces: Vec<String> = tokenizer
+            .tokenize(
+                "すもももももももものうちすもももももももものうち",
+                None,
+                None,
+            )
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        let once = ["すもも", "も", "もも", "も", "もも", "の", "うち"];
+        let expected: Vec<&str> = once.iter().chain(once.iter()).copied().collect();
+        assert_eq!(surfaces, expected);
+    }
+
+    #[test]
+    fn test_tokenizer_with_sysdic() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::with_sysdic(&sysdic_path, None, Some(true)).unwrap();
+        let surfaces: Vec<String> = tokenizer
+            .tokenize("すもももももももものうち", None, None)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        assert_eq!(
+            surfaces,
+            vec!["すもも", "も", "もも", "も", "もも", "の", "うち"]
+        );
+
+        let result =
+            Tokenizer::with_sysdic(std::path::Path::new("/nonexistent/sysdic"), None, None);
+        assert!(matches!(
+            result,
+            Err(RunomeError::DictDirectoryNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tokenize_skipped_spans() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "  すもも \n";
+        let tokenizer = Tokenizer::new(None, Some(true)).unwrap();
+        let results: Vec<_> = tokenizer
+            .tokenize(text, None, None)
+            .map(|result| result.unwrap())
+            .collect();
+        assert_eq!(results.len(), 1);
+
+        let tokenizer = tokenizer.with_skipped_spans(true);
+        let results: Vec<_> = tokenizer
+            .tokenize(text, None, None)
+            .map(|result| result.unwrap())
+            .collect();
+        assert_eq!(results.len(), 3);
+        match (&results[0], &results[1], &results[2]) {
+            (
+                TokenizeResult::Skipped { span: leading },
+                TokenizeResult::Surface(surface),
+                TokenizeResult::Skipped { span: trailing },
+            ) => {
+                assert_eq!(&text[leading.clone()], "  ");
+                assert_eq!(surface, "すもも");
+                assert_eq!(&text[trailing.clone()], " \n");
+            }
+            other => panic!("Unexpected results: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tokenize_with_options() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let options = TokenizeOptions::new().with_wakati(true);
+        let surfaces: Vec<String> = tokenizer
+            .tokenize_with("すもももももももものうち", &options)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        assert_eq!(
+            surfaces,
+            vec!["すもも", "も", "もも", "も", "もも", "の", "うち"]
+        );
+
+        let options = TokenizeOptions::new().with_baseform_unk(false);
+        let result = tokenizer
+            .tokenize_with("😀", &options)
+            .next()
+            .unwrap()
+            .unwrap();
+        match result {
+            TokenizeResult::Token(token) => assert_eq!(token.base_form(), "*"),
+            other => panic!("Expected Token, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tokenize_with_dot() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let dot = tokenizer
+            .tokenize_with_dot("すもももももももものうち")
+            .unwrap();
+
+        assert!(dot.starts_with("digraph lattice {"));
+        assert!(dot.contains("__BOS__"));
+        assert!(dot.contains("__EOS__"));
+        // BOS, 7 tokens and EOS on the best path, plus the 8 edges between them
+        let highlighted_nodes = dot
+            .lines()
+            .filter(|line| !line.contains("->") && line.contains("color=red"))
+            .count();
+        let highlighted_edges = dot
+            .lines()
+            .filter(|line| line.contains("->") && line.contains("color=red"))
+            .count();
+        assert_eq!(highlighted_nodes, 9);
+        assert_eq!(highlighted_edges, 8);
+    }
+
+    #[test]
+    fn test_wide_space_policy() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "東京\u{3000}\u{a0}abc 大阪\u{3000}です";
+        let analyze = |policy: SpacePolicy| -> Vec<(String, String, Range<usize>)> {
+            let tokenizer = Tokenizer::new(None, None)
+                .unwrap()
+                .with_wide_space_policy(policy);
+            tokenizer
+                .tokenize(text, None, None)
+                .map(|result| match result.unwrap() {
+                    TokenizeResult::Token(token) => (
+                        token.surface().to_string(),
+                        token.part_of_speech().to_string(),
+                        token.span().unwrap(),
+                    ),
+                    other => panic!("Expected Token but got {:?}", other),
+                })
+                .collect()
+        };
+        let surfaces = |tokens: &[(String, String, Range<usize>)]| -> Vec<String> {
+            tokens
+                .iter()
+                .map(|(surface, _, _)| surface.clone())
+                .collect()
+        };
+
+        // Default: U+3000 is analyzed as a noun, unlike the ASCII space
+        let tokens = analyze(SpacePolicy::Dictionary);
+        assert_eq!(tokens[1].0, "\u{3000}");
+        assert_eq!(tokens[1].1, "名詞,サ変接続,*,*");
+        assert_eq!(tokens[2].0, "\u{a0}");
+
+        let tokens = analyze(SpacePolicy::Drop);
+        assert_eq!(surfaces(&tokens), vec!["東京", "abc", " ", "大阪", "です"]);
+        assert_eq!(tokens[1].2, 11..14);
+
+        let tokens = analyze(SpacePolicy::Token);
+        assert_eq!(
+            surfaces(&tokens),
+            vec![
+                "東京",
+                "\u{3000}\u{a0}",
+                "abc",
+                " ",
+                "大阪",
+                "\u{3000}",
+                "です"
+            ]
+        );
+        assert_eq!(tokens[1].1, "記号,空白,*,*");
+        assert_eq!(tokens[1].1, tokens[3].1);
+        assert_eq!(tokens[1].2, 6..11);
+
+        // Spaces join the neighboring unknown word "abc"; next to known words they stay tokens
+        let tokens = analyze(SpacePolicy::Merge);
+        assert_eq!(
+            surfaces(&tokens),
+            vec!["東京", "\u{3000}\u{a0}abc", " ", "大阪", "\u{3000}", "です"]
+        );
+        assert_eq!(tokens[1].2, 6..14);
+        assert_eq!(tokens[4].1, "記号,空白,*,*");
+
+        let tokenizer = Tokenizer::new(None, Some(true))
+            .unwrap()
+            .with_wide_space_policy(SpacePolicy::Merge);
+        let surfaces: Vec<String> = tokenizer
+            .tokenize("abc\u{a0}def", None, None)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["abc\u{a0}", "def"]);
+    }
+
+    #[test]
+    fn test_ascii_space_policy() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "東京  abc\tdef 大阪";
+        let analyze = |policy: SpacePolicy| -> Vec<(String, String)> {
+            let tokenizer = Tokenizer::new(None, None)
+                .unwrap()
+                .with_ascii_space_policy(policy);
+            tokenizer
+                .tokenize(text, None, None)
+                .map(|result| match result.unwrap() {
+                    TokenizeResult::Token(token) => (
+                        token.surface().to_string(),
+                        token.part_of_speech().to_string(),
+                    ),
+                    other => panic!("Expected Token but got {:?}", other),
+                })
+                .collect()
+        };
+        let surfaces = |tokens: &[(String, String)]| -> Vec<String> {
+            tokens.iter().map(|(surface, _)| surface.clone()).collect()
+        };
+
+        // Default matches Janome: whitespace runs are SYMBOL unknown words
+        let default = analyze(SpacePolicy::Dictionary);
+        assert_eq!(
+            surfaces(&default),
+            vec!["東京", "  ", "abc", "\t", "def", " ", "大阪"]
+        );
+        assert_eq!(analyze(SpacePolicy::Token), default);
+
+        let tokens = analyze(SpacePolicy::Drop);
+        assert_eq!(surfaces(&tokens), vec!["東京", "abc", "def", "大阪"]);
+
+        let tokens = analyze(SpacePolicy::Merge);
+        assert_eq!(surfaces(&tokens), vec!["東京", "  abc\t", "def ", "大阪"]);
+        assert_eq!(tokens[1].1, "名詞,固有名詞,組織,*");
+
+        // The wide space policy still applies to U+3000 alone
+        let tokenizer = Tokenizer::new(None, Some(true))
+            .unwrap()
+            .with_ascii_space_policy(SpacePolicy::Drop)
+            .with_wide_space_policy(SpacePolicy::Token);
+        let surfaces: Vec<String> = tokenizer
+            .tokenize("東京 \u{3000}大阪", None, None)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["東京", "\u{3000}", "大阪"]);
+    }
+
+    #[test]
+    fn test_space_policy_spans() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let policies = [
+            SpacePolicy::Dictionary,
+            SpacePolicy::Drop,
+            SpacePolicy::Token,
+            SpacePolicy::Merge,
+        ];
+        let texts = [
+            "１２３\u{3000}\tx…",
+            "ヴ！\u{3000}\r\n\u{3000}ヴ",
+            "東京\u{3000}\u{a0}abc 大阪\u{3000}です",
+            "abc \u{3000} def\t\u{3000}\tghi",
+        ];
+        for wide in policies {
+            for ascii in policies {
+                let tokenizer = Tokenizer::new(None, None)
+                    .unwrap()
+                    .with_wide_space_policy(wide)
+                    .with_ascii_space_policy(ascii);
+                for text in texts {
+                    for result in tokenizer.tokenize(text, None, None) {
+                        let TokenizeResult::Token(token) = result.unwrap() else {
+                            continue;
+                        };
+                        // Dropped spaces are in neither the surface nor the span
+                        assert_eq!(
+                            &text[token.span().unwrap()],
+                            token.surface(),
+                            "{:?} with wide {:?}, ASCII {:?}",
+                            text,
+                            wide,
+                            ascii
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_emoji_sequences() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let text = "は👍🏽です👨\u{200d}👩\u{200d}👧🇯🇵🇺🇸と1\u{fe0f}\u{20e3}😀😀";
+        let tokens: Vec<Token> = tokenizer
+            .tokenize(text, None, None)
+            .map(|r| match r.unwrap() {
+                TokenizeResult::Token(token) => token,
+                other => panic!("Expected Token but got {:?}", other),
+            })
+            .collect();
+        let surfaces: Vec<&str> = tokens.iter().map(|t| t.surface()).collect();
+        // Each multi-code-point sequence is one token; single emoji still group
+        assert_eq!(
+            surfaces,
+            vec![
+                "は",
+                "👍🏽",
+                "です",
+                "👨\u{200d}👩\u{200d}👧",
+                "🇯🇵",
+                "🇺🇸",
+                "と",
+                "1\u{fe0f}\u{20e3}",
+                "😀😀"
+            ]
+        );
+        // IPADIC has no EMOJI category, so sequences use the emoji's categories
+        assert_eq!(tokens[3].part_of_speech(), "記号,一般,*,*");
+        assert_eq!(tokens[3].node_type(), NodeType::Unknown);
+        assert_eq!(tokens[7].part_of_speech(), "記号,一般,*,*");
+
+        let decisions = tokenizer.explain_unknown_words("は👍🏽").unwrap();
+        assert_eq!(decisions[1].category, "DEFAULT");
+        assert_eq!(decisions[1].surface.as_deref(), Some("👍🏽"));
+    }
+
+    #[test]
+    fn test_unknown_word_grouping_uses_char_def() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, Some(true)).unwrap();
+        let wakati = |text: &str| -> Vec<String> {
+            tokenizer
+                .tokenize(text, None, None)
+                .map(|r| r.unwrap().to_string())
+                .collect()
+        };
+
+        // DEFAULT characters do not continue words of other categories
+        assert_eq!(wakati("abc😀です"), vec!["abc", "😀", "です"]);
+        assert_eq!(wakati("ラーメン🍜"), vec!["ラーメン", "🍜"]);
+    }
+
+    #[test]
+    fn test_max_unknown_length() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "xyzxyzxyzx";
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        assert_eq!(tokenizer.max_unknown_length(), 1024);
+        assert_eq!(tokenizer.tokenize_to_vec(text).unwrap().len(), 1);
+
+        let tokenizer = tokenizer.with_max_unknown_length(4);
+        assert_eq!(tokenizer.max_unknown_length(), 4);
+        let tokens = tokenizer.tokenize_to_vec(text).unwrap();
+        let surfaces: Vec<&str> = tokens.iter().map(|t| t.surface()).collect();
+        assert_eq!(surfaces, vec!["xyzx", "yzxy", "zx"]);
+        assert!(tokens.iter().all(|t| t.node_type() == NodeType::Unknown));
+
+        // Same limit through the constructor; 0 is raised to 1
+        let tokenizer = Tokenizer::new(Some(4), None).unwrap();
+        assert_eq!(tokenizer.tokenize_to_vec(text).unwrap().len(), 3);
+        let tokenizer = tokenizer.with_max_unknown_length(0);
+        assert_eq!(tokenizer.max_unknown_length(), 1);
+        assert_eq!(tokenizer.tokenize_to_vec("xyz").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_boundary_markers() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "すもももももももものうち。".repeat(CHUNK_SIZE / 10);
+        let plain: Vec<Token> = Tokenizer::new(None, None)
+            .unwrap()
+            .tokenize(&text, None, None)
+            .map(|result| match result.unwrap() {
+                TokenizeResult::Token(token) => token,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        let tokenizer = Tokenizer::new(None, None)
+            .unwrap()
+            .with_boundary_markers(true);
+        let marked: Vec<TokenizeResult> = tokenizer
+            .tokenize(&text, None, None)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        // Markers wrap every chunk, and the tokens are unchanged
+        assert!(matches!(
+            marked.first(),
+            Some(TokenizeResult::Bos { position: 0 })
+        ));
+        assert!(
+            matches!(marked.last(), Some(TokenizeResult::Eos { position }) if *position == text.len())
+        );
+        let words: Vec<Token> = marked
+            .iter()
+            .filter_map(|result| match result {
+                TokenizeResult::Token(token) => Some(token.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(words, plain);
+        let boundaries: Vec<&TokenizeResult> = marked
+            .iter()
+            .filter(|result| !matches!(result, TokenizeResult::Token(_)))
+            .collect();
+        assert!(boundaries.len() > 2);
+        for pair in boundaries.chunks(2) {
+            assert!(matches!(
+                pair,
+                [TokenizeResult::Bos { position: start }, TokenizeResult::Eos { position: end }]
+                    if start < end
+            ));
+        }
+        for window in boundaries.windows(2).skip(1).step_by(2) {
+            // EOS of a chunk and BOS of the next one are at the same position
+            assert!(matches!(
+                window,
+                [TokenizeResult::Eos { position: end }, TokenizeResult::Bos { position: start }]
+                    if end == start
+            ));
+        }
+
+        // Formatters only get the tokens, so no marker lines are printed
+        use crate::formatter::{Formatter, MecabFormatter};
+        let formatter = MecabFormatter::new();
+        assert_eq!(formatter.format(&words), formatter.format(&plain));
+
+        // Not emitted in wakati mode
+        let surfaces: Vec<String> = tokenizer
+            .tokenize("すもも", Some(true), None)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["すもも"]);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        assert_eq!(tokenizer.metrics(), None);
+
+        let tokenizer = tokenizer.with_metrics();
+        let clone = tokenizer.clone();
+        assert_eq!(tokenizer.metrics(), Some(TokenizerMetrics::default()));
+        tokenizer
+            .tokenize_to_vec("すもももももももものうち")
+            .unwrap();
+        let metrics = tokenizer.metrics().unwrap();
+        assert_eq!(metrics.tokens, 7);
+        assert_eq!(metrics.unknown_tokens, 0);
+        assert_eq!(metrics.chunks, 1);
+
+        // Unknown words are counted in wakati mode too, and by every clone
+        let surfaces: Vec<_> = clone
+            .tokenize("Rustで書く", Some(true), None)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(surfaces.len(), 3);
+        let metrics = tokenizer.metrics().unwrap();
+        assert_eq!(metrics.tokens, 10);
+        assert_eq!(metrics.unknown_tokens, 1);
+        assert_eq!(metrics.chunks, 2);
+        assert!((metrics.unknown_ratio() - 0.1).abs() < 1e-9);
+
+        clone.reset_metrics();
+        assert_eq!(tokenizer.metrics(), Some(TokenizerMetrics::default()));
+        assert_eq!(TokenizerMetrics::default().unknown_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_memory_stats() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        tokenizer
+            .tokenize_to_vec("すもももももももものうち")
+            .unwrap();
+        let stats = tokenizer.memory_stats();
+        assert!(stats.entries > 0 && stats.connections > 0 && stats.morpheme_index > 0);
+        assert_eq!(stats.user_dict, 0);
+        assert!(stats.string_pool > 0);
+
+        let sys_dic = SystemDictionary::instance().unwrap();
+        let user_dic = UserDictionary::new(
+            std::path::Path::new("tests/user_ipadic.csv"),
+            crate::dictionary::UserDictFormat::Ipadic,
+            sys_dic.get_connection_matrix(),
+        )
+        .unwrap();
+        let user_stats = user_dic.memory_stats();
+        assert_eq!(user_stats.connections, 0);
+        let tokenizer = Tokenizer::with_user_dict(Arc::new(user_dic), None, None).unwrap();
+        let stats = tokenizer.memory_stats();
+        assert_eq!(stats.user_dict, user_stats.heap());
+        assert!(stats.heap() > stats.entries + stats.user_dict);
+    }
+
+    #[test]
+    fn test_reading_hiragana() {
+        let token = Token::new(
+            "東京".to_string(),
+            "名詞,固有名詞,地域,一般".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            "東京".to_string(),
+            "トウキョウ".to_string(),
+            "トーキョー".to_string(),
+            NodeType::SysDict,
+        );
+        assert_eq!(token.reading_hiragana(), "とうきょう");
+        assert_eq!(token.reading(), "トウキョウ");
+
+        let unknown = Token::new(
+            "Python".to_string(),
+            "名詞,固有名詞,組織,*".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            "Python".to_string(),
+            "*".to_string(),
+            "*".to_string(),
+            NodeType::Unknown,
+        );
+        assert_eq!(unknown.reading_hiragana(), "*");
+    }
+
+    #[test]
+    fn test_tokenize_to_vec_and_wakati_into() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        // Full tokens even in wakati mode
+        let tokenizer = Tokenizer::new(None, Some(true)).unwrap();
+        let tokens = tokenizer.tokenize_to_vec("すもももももも").unwrap();
+        let surfaces: Vec<&str> = tokens.iter().map(|t| t.surface()).collect();
+        assert_eq!(surfaces, vec!["すもも", "も", "もも", "も"]);
+        assert_eq!(tokens[0].part_of_speech(), "名詞,一般,*,*");
+        assert!(tokenizer.tokenize_to_vec("").unwrap().is_empty());
+
+        // Appends to existing content; skipped spans are left out
+        let tokenizer = Tokenizer::new(None, None).unwrap().with_skipped_spans(true);
+        let mut buf = String::from("> ");
+        let count = tokenizer
+            .wakati_into("  すもももももも ", &mut buf, "/")
+            .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(buf, "> すもも/も/もも/も");
+
+        buf.clear();
+        assert_eq!(tokenizer.wakati_into("", &mut buf, "/").unwrap(), 0);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_nfkc_normalization() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "\u{3000}ＰｙｔｈｏｎでｶﾞｲﾄﾞをＡＢＣ１２３";
+        let tokenizer = Tokenizer::new(None, None)
+            .unwrap()
+            .with_skipped_spans(true)
+            .with_nfkc_normalization(true);
+        let results: Vec<TokenizeResult> = tokenizer
+            .tokenize(text, None, None)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        // The leading U+3000 became a space and was trimmed
+        assert!(matches!(&results[0], TokenizeResult::Skipped { span } if *span == (0..3)));
+        let tokens: Vec<&Token> = results[1..]
+            .iter()
+            .map(|result| match result {
+                TokenizeResult::Token(token) => token,
+                other => panic!("Expected Token but got {:?}", other),
+            })
+            .collect();
+        let surfaces: Vec<&str> = tokens.iter().map(|t| t.surface()).collect();
+        assert_eq!(surfaces, vec!["Python", "で", "ガイド", "を", "ABC", "123"]);
+        // Found in the dictionary in its normalized form
+        assert_eq!(tokens[2].node_type(), NodeType::SysDict);
+
+        // Spans slice the original text
+        let originals: Vec<&str> = tokens.iter().map(|t| &text[t.span().unwrap()]).collect();
+        assert_eq!(
+            originals,
+            vec!["Ｐｙｔｈｏｎ", "で", "ｶﾞｲﾄﾞ", "を", "ＡＢＣ", "１２３"]
+        );
+
+        // Unchanged text and wakati mode
+        let surfaces: Vec<String> = tokenizer
+            .tokenize("すもも", Some(true), None)
+            .map(|r| r.unwrap().to_string())
+            .collect();
+        assert_eq!(surfaces, vec!["すもも"]);
+        let mut buf = String::new();
+        tokenizer.wakati_into("ｶﾞｲﾄﾞ", &mut buf, " ").unwrap();
+        assert_eq!(buf, "ガイド");
+    }
+
+    #[test]
+    fn test_explain_unknown_words() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let decisions = tokenizer.explain_unknown_words("すもも1234").unwrap();
+
+        // Dictionary word at the start: HIRAGANA is not invoked
+        let hiragana = decisions
+            .iter()
+            .find(|d| d.position == 0 && d.category == "HIRAGANA")
+            .unwrap();
+        assert_eq!(hiragana.character, 'す');
+        assert!(hiragana.dictionary_matched);
+        assert!(!hiragana.invoked);
+        assert_eq!(hiragana.surface, None);
+
+        // Digits: NUMERIC is invoked and groups the whole number
+        let numeric = decisions
+            .iter()
+            .find(|d| d.position == "すもも".len() && d.category == "NUMERIC")
+            .unwrap();
+        assert_eq!(numeric.character, '1');
+        assert!(numeric.invoked);
+        assert!(numeric.grouping);
+        assert_eq!(numeric.surface.as_deref(), Some("1234"));
+    }
+
+    #[test]
+    fn test_token_spans() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let text = format!("  {}東京へ行く ", "すもももももももものうち。".repeat(40));
+        let mut covered = 2;
+        for result in tokenizer.tokenize(&text, None, None) {
+            let TokenizeResult::Token(token) = result.unwrap() else {
+                panic!("Expected Token");
+            };
+            let span = token.span().unwrap();
+            assert_eq!(span.start, covered, "Spans should be contiguous");
+            assert_eq!(&text[span.clone()], token.surface());
+            covered = span.end;
+        }
+        assert_eq!(covered, text.len() - 1);
+    }
+
+    #[test]
+    fn test_tokenize_with_timeout() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, Some(true)).unwrap();
+        let text = "すもももももももものうち。".repeat(200);
+        let options = TokenizeOptions::new().with_timeout(Duration::ZERO);
+        let results: Vec<_> = tokenizer.tokenize_with(&text, &options).collect();
+
+        // The first chunk is started immediately, then the deadline stops the iteration
+        assert!(matches!(
+            results.last(),
+            Some(Err(RunomeError::TokenizeTimeout { .. }))
+        ));
+        assert!(results[..results.len() - 1].iter().all(|r| r.is_ok()));
+        assert!(results.len() < text.chars().count());
+    }
+
+    #[test]
+    fn test_token_dict_entry() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let tokens: Vec<Token> = tokenizer
+            .tokenize("すもももももももものうちXYZ", None, None)
+            .map(|result| match result.unwrap() {
+                TokenizeResult::Token(token) => token,
+                other => panic!("Unexpected result: {:?}", other),
+            })
+            .collect();
+
+        // Dictionary words give back one of the entries of their surface
+        let entry = tokens[0].dict_entry().unwrap();
+        assert_eq!(entry.surface, "すもも");
+        let entries = tokenizer.sys_dic.lookup("すもも").unwrap();
+        assert!(entries.contains(&&entry));
+
+        assert_eq!(tokens[0].word_id(), Some(entry.morph_id));
+        assert_eq!(tokens[0].word_cost(), Some(entry.cost));
+
+        // Unknown words and tokens built directly have no entry
+        let unknown = tokens.last().unwrap();
+        assert_eq!(unknown.node_type(), NodeType::Unknown);
+        assert_eq!(unknown.dict_entry(), None);
+        assert_eq!(unknown.word_id(), None);
+        assert!(unknown.word_cost().is_some());
+        let s = |v: &str| v.to_string();
+        let token = Token::new(
+            s("すもも"),
+            s("名詞,一般,*,*"),
+            s("*"),
+            s("*"),
+            s("すもも"),
+            s("スモモ"),
+            s("スモモ"),
+            NodeType::SysDict,
+        );
+        assert_eq!(token.dict_entry(), None);
+        assert_eq!(token.word_cost(), None);
+    }
+
+    #[test]
+    fn test_tokenize_with_dotfile() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lattice.gv");
+        let options = TokenizeOptions::new().with_dotfile(&path);
+
+        let text = "すもももももももものうち";
+        let tokens: Vec<_> = tokenizer.tokenize_with(text, &options).collect();
+        assert!(tokens.iter().all(|r| r.is_ok()));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            tokenizer.tokenize_with_dot(text).unwrap()
+        );
+
+        // Inputs of a chunk or more are tokenized without writing the file
+        std::fs::remove_file(&path).unwrap();
+        let text = "あ".repeat(MAX_CHUNK_SIZE);
+        assert!(tokenizer.tokenize_with(&text, &options).all(|r| r.is_ok()));
+        assert!(!path.exists());
+
+        // Write errors are reported before the tokens
+        let options = TokenizeOptions::new().with_dotfile(dir.path().join("missing/lattice.gv"));
+        let mut results = tokenizer.tokenize_with("すもも", &options);
+        assert!(matches!(
+            results.next(),
+            Some(Err(RunomeError::FileIo { .. }))
+        ));
+        assert!(results.all(|r| r.is_ok()));
+    }
+
+    #[test]
+    fn test_subword_fallback() {
+        use crate::subword::{BpeModel, SubwordFallback};
+
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "今日はkusowarotaxyzだった";
+        let tokens = |tokenizer: &Tokenizer| -> Vec<Token> {
+            tokenizer
+                .tokenize(text, None, None)
+                .map(|result| match result.unwrap() {
+                    TokenizeResult::Token(token) => token,
+                    other => panic!("Unexpected result: {:?}", other),
+                })
+                .collect()
+        };
+
+        let plain = tokens(&Tokenizer::new(None, None).unwrap());
+        let unknown = plain
+            .iter()
+            .find(|token| token.surface() == "kusowarotaxyz")
+            .unwrap();
+        assert_eq!(unknown.node_type(), NodeType::Unknown);
+
+        let ngram = Tokenizer::new(None, None)
+            .unwrap()
+            .with_subword_fallback(8, SubwordFallback::char_ngram(4).unwrap());
+        let split = tokens(&ngram);
+        assert_eq!(split.len(), plain.len() + 3);
+        let pieces: Vec<&Token> = split
+            .iter()
+            .filter(|token| token.node_type() == NodeType::Unknown)
+            .collect();
+        let surfaces: Vec<&str> = pieces.iter().map(|token| token.surface()).collect();
+        assert_eq!(surfaces, vec!["kuso", "waro", "taxy", "z"]);
+        for piece in &pieces {
+            assert_eq!(piece.part_of_speech(), unknown.part_of_speech());
+            assert_eq!(piece.base_form(), piece.surface());
+            assert_eq!(&text[piece.span().unwrap()], piece.surface());
+        }
+
+        // Shorter unknown words are kept whole
+        let short = Tokenizer::new(None, None)
+            .unwrap()
+            .with_subword_fallback(20, SubwordFallback::char_ngram(4).unwrap());
+        assert_eq!(tokens(&short), plain);
+
+        let model = BpeModel::from_merges("w a\nr o\nwa ro\nk u\ns o\nku so").unwrap();
+        let bpe = Tokenizer::new(None, None)
+            .unwrap()
+            .with_subword_fallback(8, SubwordFallback::bpe(model));
+        let surfaces: Vec<String> = bpe
+            .tokenize(text, Some(true), None)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        assert_eq!(
+            surfaces,
+            vec![
+                "今日", "は", "kuso", "waro", "t", "a", "x", "y", "z", "だっ", "た"
+            ]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_tokenize_constrained() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
 
-        // Add the starting character
-        if let Some((_, first_char)) = char_indices.first() {
-            buf.push(*first_char);
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let text = "東京都庁に行く";
+        let surfaces = |spans: &[Range<usize>]| -> Vec<String> {
+            tokenizer
+                .tokenize_constrained(text, spans)
+                .unwrap()
+                .iter()
+                .map(|token| token.surface().to_string())
+                .collect()
+        };
+        let unconstrained: Vec<String> = tokenizer
+            .tokenize(text, Some(true), None)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        assert_eq!(surfaces(&[]), unconstrained);
+
+        // A span cutting across dictionary words becomes one unknown word
+        let tokens = tokenizer.tokenize_constrained(text, &[3..12]).unwrap();
+        let fixed = tokens.iter().find(|t| t.span() == Some(3..12)).unwrap();
+        assert_eq!(fixed.surface(), "京都庁");
+        assert_eq!(fixed.node_type(), NodeType::Unknown);
+        assert_eq!(tokens[0].surface(), "東");
+        assert!(tokens.iter().all(|t| {
+            let span = t.span().unwrap();
+            span.end <= 3 || span.start >= 12 || span == (3..12)
+        }));
+
+        // A span matching a dictionary word uses its entry
+        let tokens = tokenizer.tokenize_constrained(text, &[3..9]).unwrap();
+        let fixed = tokens.iter().find(|t| t.span() == Some(3..9)).unwrap();
+        assert_eq!(fixed.surface(), "京都");
+        assert_eq!(fixed.node_type(), NodeType::SysDict);
+
+        assert!(matches!(
+            tokenizer.tokenize_constrained(text, &[0..6, 3..9]),
+            Err(RunomeError::InvalidSpanConstraint { start: 3, .. })
+        ));
+        assert!(tokenizer.tokenize_constrained(text, &[1..3]).is_err());
+        assert!(tokenizer.tokenize_constrained(text, &[3..3]).is_err());
+
+        // A span starting inside an emoji sequence splits it between characters
+        let text = "👨\u{200d}👩\u{200d}👧です";
+        let tokens = tokenizer
+            .tokenize_constrained(text, &[4..text.len()])
+            .unwrap();
+        assert_eq!(tokens[0].surface(), "👨");
+        assert_eq!(tokens.last().unwrap().span(), Some(4..text.len()));
+        for token in &tokens {
+            assert_eq!(&text[token.span().unwrap()], token.surface());
         }
+    }
 
-        // Group consecutive characters following Python's logic
-        for (byte_offset, c) in char_indices.iter().skip(1) {
-            if buf.chars().count() >= length {
-                break;
-            }
+    #[test]
+    fn test_tokenize_annotated() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
 
-            let abs_pos = start_pos + byte_offset;
-            if abs_pos >= text.len() {
-                break;
-            }
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let input = "すもももももも\nもものうち\t名詞,固有名詞,一般,*\n";
+        let tokens = tokenizer.tokenize_annotated(input).unwrap();
+        let last = tokens.last().unwrap();
+        assert_eq!(
+            last.to_string(),
+            "もものうち\t名詞,固有名詞,一般,*,*,*,*,*,*"
+        );
+        assert_eq!(last.node_type(), NodeType::UserDict);
+        assert_eq!(last.span(), Some(21..36));
+        let surfaces: Vec<&str> = tokens.iter().map(|t| t.surface()).collect();
+        assert_eq!(surfaces, ["すもも", "も", "もも", "も", "もものうち"]);
+
+        // Without features only the boundaries are fixed
+        let tokens = tokenizer.tokenize_annotated("東\t\n京都庁に行く").unwrap();
+        assert_eq!(tokens[0].surface(), "東");
+        assert_eq!(tokens[0].node_type(), NodeType::SysDict);
+        assert_eq!(tokens[1].surface(), "京都");
+
+        assert!(matches!(
+            tokenizer.tokenize_annotated("すもも\n\t名詞"),
+            Err(RunomeError::AnnotationParseError { line: 2, .. })
+        ));
+        assert!(
+            tokenizer
+                .tokenize_annotated("すもも\t名詞,一般,*,*,*,*,すもも,スモモ,スモモ,余分")
+                .is_err()
+        );
 
-            // Get character categories for this character
-            let c_categories = self.sys_dic.get_char_categories_result(*c)?;
+        // Emoji sequences next to text that doesn't group with them
+        let tokens = tokenizer
+            .tokenize_annotated("👍🏽\u{3000}①é\u{3000}ー\n👍🏽abc")
+            .unwrap();
+        let text = "👍🏽\u{3000}①é\u{3000}ー👍🏽abc";
+        let surfaces: String = tokens.iter().map(|t| t.surface()).collect();
+        assert_eq!(surfaces, text);
+        for token in &tokens {
+            assert_eq!(&text[token.span().unwrap()], token.surface());
+        }
 
-            // Python logic: if cate in _cates or any(cate in _compat_cates for _compat_cates in _cates.values())
-            let same_category = c_categories.contains(&intern::intern_or_clone(category));
-            let compatible = self.is_compatible_category_python_style(category, &c_categories);
+        // An annotated surface starting inside an emoji sequence of the joined text
+        let tokens = tokenizer
+            .tokenize_annotated("👨\u{200d}\n👩\u{200d}👧です\t名詞,一般,*,*")
+            .unwrap();
+        let last = tokens.last().unwrap();
+        assert_eq!(last.surface(), "👩\u{200d}👧です");
+        assert_eq!(last.node_type(), NodeType::UserDict);
+        assert_eq!(tokens[0].surface(), "👨");
+    }
 
-            if same_category || compatible {
-                buf.push(*c);
-            } else {
-                break;
-            }
+    #[test]
+    fn test_tokenize_with_cost() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
         }
 
-        Ok(buf)
+        let text = "すもももももももものうち";
+        let tokenizer = Tokenizer::new(None, Some(true)).unwrap();
+        let result = tokenizer.tokenize_with_cost(text).unwrap();
+        let best = &tokenizer.nbest(text, 1).unwrap()[0];
+        assert_eq!(result.tokens, best.tokens);
+        assert_eq!(result.cost, best.cost);
+
+        // Chunks are analyzed separately and their costs add up
+        let long_text = format!("{}。", text).repeat(100);
+        let long = tokenizer.tokenize_with_cost(&long_text).unwrap();
+        assert!(long_text.chars().count() > MAX_CHUNK_SIZE);
+        assert_eq!(long.tokens.len(), 800);
+        let sentence_cost = tokenizer
+            .tokenize_with_cost(&format!("{}。", text))
+            .unwrap()
+            .cost;
+        assert!(long.cost > sentence_cost);
     }
 
-    /// Python-style category compatibility checking
-    /// Implements: any(cate in _compat_cates for _compat_cates in _cates.values())
-    fn is_compatible_category_python_style(
-        &self,
-        base_category: &str,
-        char_categories: &[String],
-    ) -> bool {
-        // For now, use simplified compatibility rules
-        // TODO: Implement full compatible categories lookup from char definitions
-        match base_category {
-            "NUMERIC" => char_categories
-                .iter()
-                .any(|cat| cat == "NUMERIC" || cat == "DEFAULT"),
-            "ALPHA" => char_categories
-                .iter()
-                .any(|cat| cat == "ALPHA" || cat == "DEFAULT"),
-            "KATAKANA" => char_categories
-                .iter()
-                .any(|cat| cat == "KATAKANA" || cat == "DEFAULT"),
-            "HIRAGANA" => char_categories
-                .iter()
-                .any(|cat| cat == "HIRAGANA" || cat == "DEFAULT"),
-            "KANJI" => char_categories
-                .iter()
-                .any(|cat| cat == "KANJI" || cat == "DEFAULT"),
-            "SYMBOL" => char_categories
-                .iter()
-                .any(|cat| cat == "SYMBOL" || cat == "DEFAULT"),
-            _ => false,
+    #[test]
+    fn test_theta() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
+
+        let text = "すもももももももものうち";
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        assert_eq!(tokenizer.theta(), crate::rescorer::DEFAULT_THETA);
+        let default = tokenizer.nbest(text, 5).unwrap();
+
+        // Lower theta spreads the probability, without changing the ranking
+        let flat = Tokenizer::new(None, None)
+            .unwrap()
+            .with_theta(0.1)
+            .nbest(text, 5)
+            .unwrap();
+        assert_eq!(
+            flat.iter().map(|c| c.cost).collect::<Vec<_>>(),
+            default.iter().map(|c| c.cost).collect::<Vec<_>>()
+        );
+        assert!(flat[0].probability < default[0].probability);
+
+        let uniform = Tokenizer::new(None, None)
+            .unwrap()
+            .with_theta(-1.0)
+            .nbest(text, 5)
+            .unwrap();
+        assert!(uniform.iter().all(|c| (c.probability - 0.2).abs() < 1e-9));
+
+        let sharp = Tokenizer::new(None, None)
+            .unwrap()
+            .with_theta(f64::INFINITY)
+            .nbest(text, 5)
+            .unwrap();
+        let best = sharp.iter().filter(|c| c.cost == sharp[0].cost).count();
+        assert!((sharp[0].probability - 1.0 / best as f64).abs() < 1e-9);
+
+        // Costs are scaled by theta over the cost factor, 800 by default as in mecab-ipadic
+        assert_eq!(tokenizer.cost_factor(), 800.0);
+        let doubled = Tokenizer::new(None, None)
+            .unwrap()
+            .with_cost_factor(1600.0)
+            .nbest(text, 5)
+            .unwrap();
+        let halved = Tokenizer::new(None, None)
+            .unwrap()
+            .with_theta(crate::rescorer::DEFAULT_THETA / 2.0)
+            .nbest(text, 5)
+            .unwrap();
+        for (a, b) in doubled.iter().zip(&halved) {
+            assert!((a.probability - b.probability).abs() < 1e-9);
         }
+        assert_eq!(tokenizer.clone().with_cost_factor(0.0).cost_factor(), 800.0);
     }
 
-    /// Convert a path of lattice nodes to tokens
-    fn path_to_tokens(
-        &self,
-        path: &[&dyn LatticeNode],
-        wakati: bool,
-        baseform_unk: bool,
-    ) -> Result<Vec<TokenizeResult>, RunomeError> {
-        let mut tokens = Vec::new();
+    #[test]
+    fn test_nbest_and_rescorer() {
+        use crate::rescorer::{Candidate, Rescorer};
 
-        for node in path {
-            if wakati {
-                // Wakati mode: return only surface forms
-                tokens.push(TokenizeResult::Surface(intern::intern_or_clone(node.surface())));
-            } else {
-                // Full mode: create Token objects with morphological information
-                let token = match node.node_type() {
-                    NodeType::SysDict => Token::from_dict_node(*node),
-                    NodeType::Unknown => Token::from_unknown_node(*node, baseform_unk),
-                    NodeType::UserDict => Token::from_dict_node(*node), // Treat as dict node for now
-                };
-                tokens.push(TokenizeResult::Token(token));
-            }
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
         }
 
-        Ok(tokens)
-    }
+        let text = "すもももももももものうち";
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let best: Vec<Token> = tokenizer
+            .tokenize(text, None, None)
+            .filter_map(|result| match result.unwrap() {
+                TokenizeResult::Token(token) => Some(token),
+                _ => None,
+            })
+            .collect();
+
+        let candidates = tokenizer.nbest(text, 10).unwrap();
+        assert_eq!(candidates.len(), 10);
+        assert_eq!(candidates[0].tokens, best);
+        for (i, candidate) in candidates.iter().enumerate() {
+            let surfaces: String = candidate.tokens.iter().map(|t| t.surface()).collect();
+            assert_eq!(surfaces, text);
+            assert!(candidates[..i].iter().all(|c| c.tokens != candidate.tokens));
+            if i > 0 {
+                assert!(candidates[i - 1].cost <= candidate.cost);
+            }
+        }
 
-    /// Determine if text should be split at the given character position
-    /// This version works with character counts instead of byte positions
-    fn should_split_at_char_pos(&self, text: &str, byte_pos: usize, char_count: usize) -> bool {
-        byte_pos >= text.len()
-            || char_count >= MAX_CHUNK_SIZE
-            || (char_count >= CHUNK_SIZE
-                && byte_pos <= text.len()
-                && self.is_splittable(&text[..byte_pos]))
-    }
+        let total: f64 = candidates.iter().map(|c| c.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(
+            candidates
+                .windows(2)
+                .all(|w| w[0].probability >= w[1].probability)
+        );
 
-    /// Check if text can be split at the end (at punctuation or newlines)
-    fn is_splittable(&self, text: &str) -> bool {
-        if let Some(last_char) = text.chars().last() {
-            self.is_punct(last_char) || self.is_newline(text)
-        } else {
-            false
+        /// Prefers the segmentation with the most tokens
+        struct MostTokens;
+        impl Rescorer for MostTokens {
+            fn rescore(&self, _: &str, candidates: &[Candidate]) -> Result<Vec<f64>, RunomeError> {
+                Ok(candidates
+                    .iter()
+                    .map(|c| -(c.tokens.len() as f64))
+                    .collect())
+            }
+        }
+        let most = candidates.iter().map(|c| c.tokens.len()).max().unwrap();
+        let expected = candidates.iter().find(|c| c.tokens.len() == most).unwrap();
+        let rescored = Tokenizer::new(None, None)
+            .unwrap()
+            .with_rescorer(10, MostTokens);
+        let surfaces: Vec<String> = rescored
+            .tokenize(text, Some(true), None)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        let expected_surfaces: Vec<&str> = expected.tokens.iter().map(|t| t.surface()).collect();
+        assert_eq!(surfaces, expected_surfaces);
+        assert_ne!(expected.tokens, best);
+
+        /// Returns the wrong number of scores
+        struct Broken;
+        impl Rescorer for Broken {
+            fn rescore(&self, _: &str, _: &[Candidate]) -> Result<Vec<f64>, RunomeError> {
+                Ok(Vec::new())
+            }
         }
+        let broken = Tokenizer::new(None, None).unwrap().with_rescorer(5, Broken);
+        assert!(
+            broken
+                .tokenize(text, None, None)
+                .any(|result| result.is_err())
+        );
     }
 
-    /// Check if character is punctuation (suitable for splitting)
-    fn is_punct(&self, c: char) -> bool {
-        matches!(c, '、' | '。' | ',' | '.' | '？' | '?' | '！' | '!')
-    }
+    #[test]
+    fn test_tokenize_owned() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
 
-    /// Check if text ends with newlines (suitable for splitting)
-    fn is_newline(&self, text: &str) -> bool {
-        text.ends_with("\n\n") || text.ends_with("\r\n\r\n")
+        let text = "  すもももももももものうち。東京駅に行く ";
+        let tokenizer = Tokenizer::new(None, Some(true))
+            .unwrap()
+            .with_skipped_spans(true);
+        let expected: Vec<Token> = Tokenizer::new(None, None)
+            .unwrap()
+            .tokenize(text, None, None)
+            .filter_map(|result| match result.unwrap() {
+                TokenizeResult::Token(token) => Some(token),
+                _ => None,
+            })
+            .collect();
+
+        // The iterator outlives the tokenizer and the text, and moves to another thread
+        let iter = tokenizer.tokenize_owned(text.to_string());
+        drop(tokenizer);
+        let tokens = std::thread::spawn(move || iter.collect::<Result<Vec<Token>, _>>())
+            .join()
+            .unwrap()
+            .unwrap();
+        assert_eq!(tokens, expected);
+        assert_eq!(tokens[0].span(), Some(2..11));
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_token_creation() {
-        // Test Token creation with minimal data
-        use crate::lattice::UnknownNode;
-
-        let unknown_node = UnknownNode::new(
-            "テスト".to_string(),
-            100,
-            200,
-            150,
-            "名詞,一般,*,*,*,*".to_string(),
-            "*".to_string(),
-            "*".to_string(),
-            "テスト".to_string(),
-            "*".to_string(),
-            "*".to_string(),
-            NodeType::Unknown,
-        );
+    fn test_token_fields_interned() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
 
-        let token = Token::from_unknown_node(&unknown_node, true);
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        for result in tokenizer.tokenize("走った犬と ABC", None, None) {
+            let TokenizeResult::Token(token) = result.unwrap() else {
+                panic!("Expected token");
+            };
+            // Part-of-speech and inflection fields never allocate per token
+            for field in [&token.part_of_speech, &token.infl_type, &token.infl_form] {
+                assert!(
+                    matches!(field, Cow::Borrowed(_)),
+                    "{:?} of {:?} should be interned",
+                    field,
+                    token.surface
+                );
+            }
+        }
 
-        assert_eq!(token.surface(), "テスト");
-        assert_eq!(token.part_of_speech(), "名詞,一般,*,*,*,*");
-        assert_eq!(token.base_form(), "テスト"); // baseform_unk = true
-        assert_eq!(token.node_type(), NodeType::Unknown);
+        // Pooled values are shared between tokens
+        let first = tokenizer
+            .tokenize("犬", None, None)
+            .next()
+            .unwrap()
+            .unwrap();
+        let second = tokenizer
+            .tokenize("犬", None, None)
+            .next()
+            .unwrap()
+            .unwrap();
+        match (first, second) {
+            (TokenizeResult::Token(a), TokenizeResult::Token(b)) => {
+                assert!(std::ptr::eq(a.part_of_speech(), b.part_of_speech()));
+            }
+            _ => panic!("Expected tokens"),
+        }
     }
 
     #[test]
-    fn test_token_display() {
-        use crate::lattice::UnknownNode;
-
-        let unknown_node = UnknownNode::new(
-            "テスト".to_string(),
-            100,
-            200,
-            150,
-            "名詞,一般,*,*,*,*".to_string(),
-            "*".to_string(),
-            "*".to_string(),
-            "テスト".to_string(),
-            "*".to_string(),
-            "*".to_string(),
-            NodeType::Unknown,
-        );
+    fn test_tokenizer_shared_across_threads() {
+        fn assert_shareable<T: Send + Sync + Clone + 'static>() {}
+        assert_shareable::<Tokenizer>();
 
-        let token = Token::from_unknown_node(&unknown_node, true);
-        let formatted = format!("{}", token);
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
 
-        // Should match Python format: surface\tpart_of_speech,infl_type,infl_form,base_form,reading,phonetic
-        assert_eq!(formatted, "テスト\t名詞,一般,*,*,*,*,*,*,テスト,*,*");
+        let text = "すもももももももものうち";
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let expected: Vec<Token> = tokenizer
+            .tokenize(text, None, None)
+            .map(|result| match result.unwrap() {
+                TokenizeResult::Token(token) => token,
+                other => panic!("Expected token, got {:?}", other),
+            })
+            .collect();
+
+        // Clones share the dictionaries
+        let clone = tokenizer.clone();
+        assert!(Arc::ptr_eq(&clone.sys_dic, &tokenizer.sys_dic));
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|i| {
+                    let shared = &tokenizer;
+                    let owned = clone.clone();
+                    scope.spawn(move || {
+                        let tokenizer = if i % 2 == 0 { shared } else { &owned };
+                        tokenizer
+                            .tokenize(text, None, None)
+                            .map(|result| match result.unwrap() {
+                                TokenizeResult::Token(token) => token,
+                                other => panic!("Expected token, got {:?}", other),
+                            })
+                            .collect::<Vec<Token>>()
+                    })
+                })
+                .collect();
+            for handle in handles {
+                assert_eq!(handle.join().unwrap(), expected);
+            }
+        });
     }
 
+    #[cfg(feature = "parallel")]
     #[test]
-    fn test_tokenize_result_display() {
-        let surface_result = TokenizeResult::Surface("テスト".to_string());
-        assert_eq!(format!("{}", surface_result), "テスト");
-
-        use crate::lattice::UnknownNode;
-        let unknown_node = UnknownNode::new(
-            "テスト".to_string(),
-            100,
-            200,
-            150,
-            "名詞,一般,*,*,*,*".to_string(),
-            "*".to_string(),
-            "*".to_string(),
-            "テスト".to_string(),
-            "*".to_string(),
-            "*".to_string(),
-            NodeType::Unknown,
-        );
-        let token = Token::from_unknown_node(&unknown_node, true);
-        let token_result = TokenizeResult::Token(token);
+    fn test_tokenize_batch() {
+        let sysdic_path = std::path::PathBuf::from("sysdic");
+        if !sysdic_path.exists() {
+            eprintln!(
+                "Skipping test: sysdic directory not found at {:?}",
+                sysdic_path
+            );
+            return;
+        }
 
-        assert!(format!("{}", token_result).starts_with("テスト\t"));
+        // Wakati mode is ignored by batch tokenization
+        let batch_tokenizer = Tokenizer::new(None, Some(true)).unwrap();
+        let tokenizer = Tokenizer::new(None, None).unwrap();
+        let texts = ["すもももももももものうち", "", "東京駅に行く"];
+        let batch = batch_tokenizer.tokenize_batch(&texts).unwrap();
+
+        assert_eq!(batch.len(), texts.len());
+        for (text, tokens) in texts.iter().zip(&batch) {
+            let expected: Vec<Token> = tokenizer
+                .tokenize(text, Some(false), None)
+                .filter_map(|result| match result.unwrap() {
+                    TokenizeResult::Token(token) => Some(token),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(tokens, &expected);
+        }
     }
 
+    #[cfg(feature = "slow-call-hook")]
     #[test]
-    fn test_tokenizer_creation() {
-        // Skip test if sysdic directory doesn't exist
+    fn test_slow_call_hook() {
         let sysdic_path = std::path::PathBuf::from("sysdic");
         if !sysdic_path.exists() {
             eprintln!(
@@ -774,17 +4518,69 @@ mod tests {
             return;
         }
 
-        let tokenizer = Tokenizer::new(None, None);
-        assert!(tokenizer.is_ok(), "Tokenizer creation should succeed");
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let tokenizer = Tokenizer::new(None, Some(true))
+            .unwrap()
+            .with_slow_call_hook(Duration::ZERO, move |report| {
+                sink.lock().unwrap().push(report.clone());
+            });
+
+        let text = "すもももももももものうち。".repeat(100);
+        assert!(tokenizer.tokenize(&text, None, None).all(|r| r.is_ok()));
+        {
+            let report = &reports.lock().unwrap()[0];
+            assert!(report.completed);
+            assert_eq!(report.input_chars, text.chars().count());
+            assert!(report.chunks > 1);
+            let phases = report.phases;
+            assert_eq!(
+                report.elapsed,
+                phases.chunking + phases.lattice + phases.viterbi + phases.tokens
+            );
+            assert!(phases.lattice > Duration::ZERO);
+        }
 
-        let tokenizer = tokenizer.unwrap();
-        assert_eq!(tokenizer.max_unknown_length, 1024);
-        assert!(!tokenizer.wakati);
+        // An iterator dropped before the end of the input is reported when dropped
+        let mut results = tokenizer.tokenize(&text, None, None);
+        assert!(results.next().unwrap().is_ok());
+        assert_eq!(reports.lock().unwrap().len(), 1);
+        drop(results);
+        {
+            let reports = reports.lock().unwrap();
+            assert_eq!(reports.len(), 2);
+            assert!(!reports[1].completed);
+            assert_eq!(reports[1].chunks, 1);
+        }
+
+        // A call stopped by a timeout is reported once, when the timeout is hit
+        let options = TokenizeOptions::new().with_timeout(Duration::from_millis(200));
+        let mut results = tokenizer.tokenize_with(&text, &options);
+        assert!(results.next().unwrap().is_ok());
+        std::thread::sleep(Duration::from_millis(300));
+        let rest: Vec<_> = results.by_ref().collect();
+        assert!(matches!(
+            rest.last(),
+            Some(Err(RunomeError::TokenizeTimeout { .. }))
+        ));
+        assert_eq!(reports.lock().unwrap().len(), 3);
+        drop(results);
+        {
+            let reports = reports.lock().unwrap();
+            assert_eq!(reports.len(), 3);
+            assert!(!reports[2].completed);
+            assert_eq!(reports[2].chunks, 1);
+        }
+
+        // A threshold above the call duration is never reported
+        let quiet = Tokenizer::new(None, None)
+            .unwrap()
+            .with_slow_call_hook(Duration::from_secs(3600), |_| panic!("unexpected report"));
+        assert!(quiet.tokenize("すもも", None, None).all(|r| r.is_ok()));
     }
 
     #[test]
-    fn test_tokenizer_custom_params() {
-        // Skip test if sysdic directory doesn't exist
+    fn test_tokenize_degraded_dictionary() {
         let sysdic_path = std::path::PathBuf::from("sysdic");
         if !sysdic_path.exists() {
             eprintln!(
@@ -794,12 +4590,52 @@ mod tests {
             return;
         }
 
-        let tokenizer = Tokenizer::new(Some(2048), Some(true));
-        assert!(tokenizer.is_ok(), "Tokenizer creation should succeed");
+        // Dictionary without char_defs.bin and unknowns.bin
+        let temp_dir = tempfile::tempdir().unwrap();
+        for filename in [
+            "entries.bin",
+            "connections.bin",
+            "dic.fst",
+            "morpheme_index.bin",
+        ] {
+            std::fs::copy(sysdic_path.join(filename), temp_dir.path().join(filename)).unwrap();
+        }
 
-        let tokenizer = tokenizer.unwrap();
-        assert_eq!(tokenizer.max_unknown_length, 2048);
-        assert!(tokenizer.wakati);
+        assert!(SystemDictionary::new(temp_dir.path()).is_err());
+        let sys_dic = Arc::new(SystemDictionary::new_degraded(temp_dir.path()).unwrap());
+        assert!(sys_dic.is_degraded());
+
+        let tokenizer = Tokenizer::with_system_dict(sys_dic, None, None).unwrap();
+        let tokens: Vec<Token> = tokenizer
+            .tokenize("すもももももももものうち😀😀", None, None)
+            .map(|r| match r.unwrap() {
+                TokenizeResult::Token(token) => token,
+                TokenizeResult::Surface(s) => panic!("Unexpected surface: {}", s),
+                TokenizeResult::Skipped { span } => panic!("Unexpected skipped span: {:?}", span),
+                marker @ (TokenizeResult::Bos { .. } | TokenizeResult::Eos { .. }) => {
+                    panic!("Unexpected boundary marker: {:?}", marker)
+                }
+            })
+            .collect();
+
+        let surfaces: Vec<&str> = tokens.iter().map(|t| t.surface()).collect();
+        assert_eq!(
+            surfaces,
+            vec![
+                "すもも",
+                "も",
+                "もも",
+                "も",
+                "もも",
+                "の",
+                "うち",
+                "😀",
+                "😀"
+            ]
+        );
+        // Characters without dictionary entries become single-char unknowns
+        assert_eq!(tokens[7].node_type(), NodeType::Unknown);
+        assert_eq!(tokens[8].node_type(), NodeType::Unknown);
     }
 
     #[test]
@@ -980,6 +4816,18 @@ mod tests {
                                     surface, text
                                 );
                             }
+                            TokenizeResult::Skipped { span } => {
+                                panic!(
+                                    "Expected Token but got Skipped {:?} for test case '{}'",
+                                    span, text
+                                );
+                            }
+                            marker @ (TokenizeResult::Bos { .. } | TokenizeResult::Eos { .. }) => {
+                                panic!(
+                                    "Expected Token but got {:?} for test case '{}'",
+                                    marker, text
+                                );
+                            }
                         }
                     }
                 }
@@ -52,6 +52,10 @@ pub mod segmentation_tests {
             TokenizeResult::Surface(_) => {
                 panic!("Expected Token but got Surface for '{}'", expected_surface);
             }
+            TokenizeResult::Skipped { span } => panic!("Unexpected skipped span: {:?}", span),
+            marker @ (TokenizeResult::Bos { .. } | TokenizeResult::Eos { .. }) => {
+                panic!("Unexpected boundary marker: {:?}", marker)
+            }
         }
     }
 
@@ -122,6 +126,49 @@ pub mod segmentation_tests {
         );
     }
 
+    #[test]
+    fn test_tokenize_inflected_words() {
+        // Inflection, reading and phonetic come from the dictionary entries of
+        // conjugated verbs, adjectives and auxiliaries
+        let tokenizer = Tokenizer::new(None, None);
+        if tokenizer.is_err() {
+            eprintln!("Skipping test: SystemDictionary not available");
+            return;
+        }
+        let tokenizer = tokenizer.unwrap();
+
+        let tokens: Vec<_> = tokenizer
+            .tokenize("寒かったので窓を閉めました", None, None)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(tokens.len(), 8);
+
+        check_token(
+            &tokens[0],
+            "寒かっ",
+            "形容詞,自立,*,*,形容詞・アウオ段,連用タ接続,寒い,サムカッ,サムカッ",
+            NodeType::SysDict,
+        );
+        check_token(
+            &tokens[1],
+            "た",
+            "助動詞,*,*,*,特殊・タ,基本形,た,タ,タ",
+            NodeType::SysDict,
+        );
+        check_token(
+            &tokens[5],
+            "閉め",
+            "動詞,自立,*,*,一段,連用形,閉める,シメ,シメ",
+            NodeType::SysDict,
+        );
+        check_token(
+            &tokens[6],
+            "まし",
+            "助動詞,*,*,*,特殊・マス,連用形,ます,マシ,マシ",
+            NodeType::SysDict,
+        );
+    }
+
     #[test]
     fn test_tokenize_mixed_known_unknown() {
         // Equivalent to Python's TestTokenizer.test_tokenize2()
@@ -563,6 +610,10 @@ pub mod segmentation_tests {
             .map(|token| match token {
                 TokenizeResult::Surface(surface) => surface.as_str(),
                 TokenizeResult::Token(_) => panic!("Expected Surface but got Token in wakati mode"),
+                TokenizeResult::Skipped { span } => panic!("Unexpected skipped span: {:?}", span),
+                marker @ (TokenizeResult::Bos { .. } | TokenizeResult::Eos { .. }) => {
+                    panic!("Unexpected boundary marker: {:?}", marker)
+                }
             })
             .collect();
 
@@ -616,6 +667,10 @@ pub mod segmentation_tests {
                         i
                     );
                 }
+                TokenizeResult::Skipped { span } => panic!("Unexpected skipped span: {:?}", span),
+                marker @ (TokenizeResult::Bos { .. } | TokenizeResult::Eos { .. }) => {
+                    panic!("Unexpected boundary marker: {:?}", marker)
+                }
             }
         }
 
@@ -637,6 +692,10 @@ pub mod segmentation_tests {
                         i
                     );
                 }
+                TokenizeResult::Skipped { span } => panic!("Unexpected skipped span: {:?}", span),
+                marker @ (TokenizeResult::Bos { .. } | TokenizeResult::Eos { .. }) => {
+                    panic!("Unexpected boundary marker: {:?}", marker)
+                }
             }
         }
     }
@@ -965,6 +1024,10 @@ pub mod segmentation_tests {
             .map(|token| match token {
                 TokenizeResult::Surface(surface) => surface.as_str(),
                 TokenizeResult::Token(_) => panic!("Expected Surface but got Token in wakati mode"),
+                TokenizeResult::Skipped { span } => panic!("Unexpected skipped span: {:?}", span),
+                marker @ (TokenizeResult::Bos { .. } | TokenizeResult::Eos { .. }) => {
+                    panic!("Unexpected boundary marker: {:?}", marker)
+                }
             })
             .collect();
 
@@ -985,6 +1048,56 @@ pub mod segmentation_tests {
         assert_eq!(surfaces[13], "。");
     }
 
+    #[test]
+    fn test_tokenize_with_user_dict_cost_bias() {
+        use crate::dictionary::{UserDictFormat, UserDictionary};
+        use std::io::Write;
+        use std::sync::Arc;
+        use tempfile::NamedTempFile;
+
+        let tokenizer = Tokenizer::new(None, None);
+        if tokenizer.is_err() {
+            eprintln!("Skipping test: SystemDictionary not available");
+            return;
+        }
+
+        // A user entry too costly to beat the system split 東京/駅
+        let csv_content =
+            "東京駅,1285,1285,30000,名詞,固有名詞,一般,*,*,*,東京駅,トウキョウエキ,トーキョーエキ";
+        let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        temp_file
+            .write_all(csv_content.as_bytes())
+            .expect("Failed to write to temp file");
+
+        let sys_dict = crate::dictionary::SystemDictionary::instance().unwrap();
+        let user_dict = Arc::new(
+            UserDictionary::new(
+                temp_file.path(),
+                UserDictFormat::Ipadic,
+                sys_dict.get_connection_matrix(),
+            )
+            .unwrap(),
+        );
+
+        let surfaces = |tokenizer: &Tokenizer| -> Vec<String> {
+            tokenizer
+                .tokenize("東京駅に行く", Some(true), None)
+                .map(|result| match result.unwrap() {
+                    TokenizeResult::Surface(surface) => surface,
+                    other => panic!("Expected Surface but got {:?}", other),
+                })
+                .collect()
+        };
+
+        let tokenizer = Tokenizer::with_user_dict(user_dict.clone(), None, None).unwrap();
+        assert_eq!(surfaces(&tokenizer), vec!["東京", "駅", "に", "行く"]);
+
+        let tokenizer = Tokenizer::with_user_dict(user_dict, None, None)
+            .unwrap()
+            .with_user_dict_cost_bias(-30000);
+        assert_eq!(surfaces(&tokenizer), vec!["東京駅", "に", "行く"]);
+    }
+
     #[test]
     fn test_tokenize_large_text() {
         // Equivalent to Python's TestTokenizer.test_tokenize_large_text()
@@ -1022,4 +1135,268 @@ pub mod segmentation_tests {
             tokens.len()
         );
     }
+
+    #[test]
+    fn test_wakati_matches_token_surfaces() {
+        // Wakati mode builds surface-only lattice nodes; the path must not change
+        use crate::dictionary::{UserDictFormat, UserDictionary};
+        use std::io::Write;
+        use std::sync::Arc;
+        use tempfile::NamedTempFile;
+
+        if Tokenizer::new(None, None).is_err() {
+            eprintln!("Skipping test: SystemDictionary not available");
+            return;
+        }
+        let text = match std::fs::read_to_string("tests/text_lemon.txt") {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Skipping test: Could not read text_lemon.txt: {}", e);
+                return;
+            }
+        };
+
+        let csv_content = "\
+東京スカイツリー,1288,1288,4569,名詞,固有名詞,一般,*,*,*,東京スカイツリー,トウキョウスカイツリー,トウキョウスカイツリー
+檸檬,1288,1288,3000,名詞,固有名詞,一般,*,*,*,檸檬,レモン,レモン";
+        let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        temp_file
+            .write_all(csv_content.as_bytes())
+            .expect("Failed to write to temp file");
+        let sys_dict = crate::dictionary::SystemDictionary::instance().unwrap();
+        let user_dict = UserDictionary::new(
+            temp_file.path(),
+            UserDictFormat::Ipadic,
+            sys_dict.get_connection_matrix(),
+        )
+        .unwrap();
+        let tokenizer = Tokenizer::with_user_dict(Arc::new(user_dict), None, None)
+            .unwrap()
+            .with_user_dict_cost_bias(-500);
+
+        let text = format!("東京スカイツリーで檸檬を買った。\n{}", text);
+        let surfaces: Vec<String> = tokenizer
+            .tokenize_to_vec(&text)
+            .unwrap()
+            .iter()
+            .map(|token| token.surface().to_string())
+            .collect();
+        let wakati: Vec<String> = tokenizer
+            .tokenize(&text, Some(true), None)
+            .map(|result| result.unwrap().to_string())
+            .collect();
+        assert_eq!(wakati, surfaces);
+        assert_eq!(wakati[0], "東京スカイツリー");
+    }
+}
+
+/// Janome parity tests - `Display` output must match Janome's `str(token)` byte-for-byte
+pub mod janome_parity_tests {
+    use super::*;
+    use crate::formatter::{Formatter, JanomeFormatter};
+
+    /// Janome output for a fixture corpus, see the header of the file
+    const PARITY_FIXTURE: &str = include_str!("../tests/janome_parity.txt");
+
+    /// Parse the fixture into (input, expected lines) blocks
+    fn parse_fixture() -> Vec<(&'static str, Vec<&'static str>)> {
+        let mut blocks = Vec::new();
+        let mut current: Option<(&str, Vec<&str>)> = None;
+        for line in PARITY_FIXTURE.lines().filter(|line| !line.starts_with('#')) {
+            if line.is_empty() {
+                blocks.extend(current.take());
+            } else if let Some((_, expected)) = current.as_mut() {
+                expected.push(line);
+            } else {
+                current = Some((line, Vec::new()));
+            }
+        }
+        blocks.extend(current);
+        blocks
+    }
+
+    #[test]
+    fn test_janome_string_parity() {
+        let tokenizer = Tokenizer::new(None, None);
+        if tokenizer.is_err() {
+            eprintln!("Skipping test: SystemDictionary not available");
+            return;
+        }
+        let tokenizer = tokenizer.unwrap();
+
+        let blocks = parse_fixture();
+        assert!(!blocks.is_empty(), "Parity fixture is empty");
+
+        for (text, expected) in blocks {
+            let tokens: Vec<_> = tokenizer
+                .tokenize(text, None, None)
+                .map(|result| match result.unwrap() {
+                    TokenizeResult::Token(token) => token,
+                    other => panic!("Unexpected result {:?} for '{}'", other, text),
+                })
+                .collect();
+
+            let actual: Vec<String> = tokens.iter().map(|token| token.to_string()).collect();
+            assert_eq!(actual, expected, "Janome parity mismatch for '{}'", text);
+
+            let mut expected_output = expected.join("\n");
+            expected_output.push('\n');
+            assert_eq!(JanomeFormatter::new().format(&tokens), expected_output);
+            assert!(
+                tokens
+                    .iter()
+                    .all(|token| token.to_janome_string() == token.to_string())
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod regression_snapshot_tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    /// Set to regenerate the snapshot instead of comparing against it
+    const UPDATE_ENV: &str = "RUNOME_UPDATE_SNAPSHOTS";
+
+    fn regression_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/regression")
+    }
+
+    /// Read the corpus sentences, skipping comments and blank lines
+    fn read_sentences() -> Vec<String> {
+        std::fs::read_to_string(regression_dir().join("sentences.txt"))
+            .expect("Failed to read regression corpus")
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Parse the snapshot into (input, expected lines) blocks. Only the
+    /// leading header is comments, since tokens may start with '#'
+    fn parse_snapshot(content: &str) -> HashMap<&str, Vec<&str>> {
+        let mut blocks = HashMap::new();
+        let mut current: Option<(&str, Vec<&str>)> = None;
+        for line in content.lines().skip_while(|line| line.starts_with('#')) {
+            if line.is_empty() {
+                blocks.extend(current.take());
+            } else if let Some((_, expected)) = current.as_mut() {
+                expected.push(line);
+            } else {
+                current = Some((line, Vec::new()));
+            }
+        }
+        blocks.extend(current);
+        blocks
+    }
+
+    /// Render a line diff of expected and actual tokens, based on their LCS
+    fn render_diff(expected: &[&str], actual: &[String]) -> String {
+        let (n, m) = (expected.len(), actual.len());
+        let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if expected[i] == actual[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut output = String::new();
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && expected[i] == actual[j] {
+                output.push_str(&format!("   {}\n", expected[i]));
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                output.push_str(&format!(" + {}\n", actual[j]));
+                j += 1;
+            } else {
+                output.push_str(&format!(" - {}\n", expected[i]));
+                i += 1;
+            }
+        }
+        output
+    }
+
+    #[test]
+    fn test_regression_snapshot() {
+        let tokenizer = Tokenizer::new(None, None);
+        if tokenizer.is_err() {
+            eprintln!("Skipping test: SystemDictionary not available");
+            return;
+        }
+        let tokenizer = tokenizer.unwrap();
+
+        let sentences = read_sentences();
+        let actual: Vec<Vec<String>> = sentences
+            .iter()
+            .map(|text| {
+                tokenizer
+                    .tokenize(text, None, None)
+                    .map(|result| result.unwrap().to_string())
+                    .collect()
+            })
+            .collect();
+
+        let snapshot_path = regression_dir().join("snapshot.txt");
+        if std::env::var_os(UPDATE_ENV).is_some() {
+            let mut content = String::from(
+                "# Tokenizer regression snapshot, generated from sentences.txt\n\
+                 #\n\
+                 # Each block is an input sentence followed by its tokens in Janome's\n\
+                 # `str(token)` format. Do not edit by hand; see sentences.txt.\n",
+            );
+            for (text, tokens) in sentences.iter().zip(&actual) {
+                content.push('\n');
+                content.push_str(text);
+                content.push('\n');
+                for token in tokens {
+                    content.push_str(token);
+                    content.push('\n');
+                }
+            }
+            std::fs::write(&snapshot_path, content).expect("Failed to write snapshot");
+            return;
+        }
+
+        let content = std::fs::read_to_string(&snapshot_path).unwrap_or_default();
+        let snapshot = parse_snapshot(&content);
+        let mut failures = String::new();
+        let mut failed = 0;
+        for (text, tokens) in sentences.iter().zip(&actual) {
+            match snapshot.get(text.as_str()) {
+                Some(expected) if expected == tokens => {}
+                Some(expected) => {
+                    failed += 1;
+                    failures.push_str(&format!("\n{}\n{}", text, render_diff(expected, tokens)));
+                }
+                None => {
+                    failed += 1;
+                    failures.push_str(&format!("\n{}\n (missing from snapshot)\n", text));
+                }
+            }
+        }
+        assert!(
+            failed == 0,
+            "{} of {} sentences differ from {:?}; rerun with {}=1 to accept:\n{}",
+            failed,
+            sentences.len(),
+            snapshot_path,
+            UPDATE_ENV,
+            failures
+        );
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let expected = ["a", "b", "c"];
+        let actual = ["a".to_string(), "x".to_string(), "c".to_string()];
+        assert_eq!(render_diff(&expected, &actual), "   a\n + x\n - b\n   c\n");
+    }
 }
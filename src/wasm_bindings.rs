@@ -0,0 +1,179 @@
+//! WebAssembly bindings for running runome in the browser
+//!
+//! Enabled by the `wasm` feature. The system dictionary is passed in as an
+//! `ArrayBuffer` holding a bundle created with `runome-build-dict bundle`,
+//! so no filesystem is needed:
+//!
+//! ```js
+//! const dict = await (await fetch("sysdic.bundle")).arrayBuffer();
+//! const tokenizer = new Tokenizer(dict);
+//! for (const token of tokenizer.tokenize("すもももももももものうち")) {
+//!   console.log(token.surface, token.partOfSpeech);
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use wasm_bindgen::prelude::*;
+
+use crate::dictionary::SystemDictionary;
+use crate::error::RunomeError;
+use crate::tokenizer::{Token, TokenizeResult, Tokenizer};
+
+fn to_js_error(error: RunomeError) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+/// JavaScript Tokenizer class
+#[wasm_bindgen(js_name = Tokenizer)]
+pub struct WasmTokenizer {
+    inner: Tokenizer,
+}
+
+#[wasm_bindgen(js_class = Tokenizer)]
+impl WasmTokenizer {
+    /// Create a tokenizer from a dictionary bundle
+    ///
+    /// # Arguments
+    /// * `dict` - Bundle bytes created with `runome-build-dict bundle`
+    /// * `max_unknown_length` - Maximum length for unknown words (default: 1024)
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        dict: &js_sys::ArrayBuffer,
+        max_unknown_length: Option<usize>,
+    ) -> Result<WasmTokenizer, JsError> {
+        let bundle = js_sys::Uint8Array::new(dict).to_vec();
+        let sys_dic = SystemDictionary::from_bundle(&bundle).map_err(to_js_error)?;
+        let inner = Tokenizer::with_system_dict(Arc::new(sys_dic), max_unknown_length, Some(false))
+            .map_err(to_js_error)?;
+        Ok(Self { inner })
+    }
+
+    /// Tokenize text into tokens with full morphological information
+    pub fn tokenize(&self, text: &str) -> Result<Vec<WasmToken>, JsError> {
+        let mut offsets = Utf16Offsets::new(text);
+        let mut tokens = Vec::new();
+        for result in self.inner.tokenize(text, Some(false), None) {
+            if let TokenizeResult::Token(token) = result.map_err(to_js_error)? {
+                let span = token
+                    .span()
+                    .map(|span| (offsets.at(span.start), offsets.at(span.end)));
+                tokens.push(WasmToken { inner: token, span });
+            }
+        }
+        Ok(tokens)
+    }
+
+    /// Split text into surface forms only
+    pub fn wakati(&self, text: &str) -> Result<Vec<String>, JsError> {
+        self.inner
+            .tokenize(text, Some(true), None)
+            .filter_map(|result| match result {
+                Ok(TokenizeResult::Surface(surface)) => Some(Ok(surface)),
+                Ok(_) => None,
+                Err(e) => Some(Err(to_js_error(e))),
+            })
+            .collect()
+    }
+}
+
+/// JavaScript Token class
+#[wasm_bindgen(js_name = Token)]
+pub struct WasmToken {
+    inner: Token,
+    /// UTF-16 offsets of the token, as JavaScript strings index them
+    span: Option<(usize, usize)>,
+}
+
+#[wasm_bindgen(js_class = Token)]
+impl WasmToken {
+    #[wasm_bindgen(getter)]
+    pub fn surface(&self) -> String {
+        self.inner.surface().to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = partOfSpeech)]
+    pub fn part_of_speech(&self) -> String {
+        self.inner.part_of_speech().to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = inflType)]
+    pub fn infl_type(&self) -> String {
+        self.inner.infl_type().to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = inflForm)]
+    pub fn infl_form(&self) -> String {
+        self.inner.infl_form().to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = baseForm)]
+    pub fn base_form(&self) -> String {
+        self.inner.base_form().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn reading(&self) -> String {
+        self.inner.reading().to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = readingHiragana)]
+    pub fn reading_hiragana(&self) -> String {
+        self.inner.reading_hiragana()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn phonetic(&self) -> String {
+        self.inner.phonetic().to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = nodeType)]
+    pub fn node_type(&self) -> String {
+        format!("{:?}", self.inner.node_type())
+    }
+
+    /// Start offset of the token in the input string
+    #[wasm_bindgen(getter)]
+    pub fn start(&self) -> Option<usize> {
+        self.span.map(|(start, _)| start)
+    }
+
+    /// End offset of the token in the input string
+    #[wasm_bindgen(getter)]
+    pub fn end(&self) -> Option<usize> {
+        self.span.map(|(_, end)| end)
+    }
+
+    /// Format the token like Janome's `str(token)`
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_janome_string(&self) -> String {
+        self.inner.to_janome_string()
+    }
+}
+
+/// Converts increasing byte offsets of a text to UTF-16 offsets
+struct Utf16Offsets<'a> {
+    text: &'a str,
+    byte: usize,
+    utf16: usize,
+}
+
+impl<'a> Utf16Offsets<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            byte: 0,
+            utf16: 0,
+        }
+    }
+
+    fn at(&mut self, byte: usize) -> usize {
+        if byte < self.byte {
+            self.byte = 0;
+            self.utf16 = 0;
+        }
+        self.utf16 += self.text[self.byte..byte].encode_utf16().count();
+        self.byte = byte;
+        self.utf16
+    }
+}
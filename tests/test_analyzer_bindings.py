@@ -32,6 +32,21 @@ def test_charfilters():
     assert filter.__call__("2024年です") == "2024 yearです"
 
 
+def test_width_normalize_charfilter():
+    """Test WidthNormalizeCharFilter bindings"""
+    from runome.charfilter import WidthNormalizeCharFilter
+
+    filter = WidthNormalizeCharFilter()
+    assert filter("ﾒｶﾞﾊﾞｲﾄＲＡＭ") == "メガバイトRAM"
+    assert filter("㍻") == "㍻"  # Not NFKC
+
+    filter = WidthNormalizeCharFilter(katakana="half", ascii=None)
+    assert filter("メガバイトＲＡＭ") == "ﾒｶﾞﾊﾞｲﾄＲＡＭ"
+
+    with pytest.raises(Exception):
+        WidthNormalizeCharFilter(katakana="narrow")
+
+
 def test_tokenfilters_basic():
     """Test basic TokenFilter bindings"""
     from runome.tokenizer import Tokenizer
@@ -51,6 +66,13 @@ def test_tokenfilters_basic():
     filtered = list(filter(tokens))
     assert any(t.surface == "TEST" for t in filtered)
 
+    # Test RomajiFilter
+    from runome.tokenfilter import RomajiFilter
+
+    filter = RomajiFilter()
+    filtered = list(filter(tokenizer.tokenize("写真")))
+    assert filtered[0].reading == "shashin"
+
     # Test CompoundNounFilter
     tokens = list(tokenizer.tokenize("形態素解析器"))
     filter = CompoundNounFilter()
@@ -83,6 +105,75 @@ def test_pos_filters():
     assert all("動詞" not in t.part_of_speech for t in filtered)
 
 
+def test_dedup_filter():
+    """Test DedupFilter collapsing consecutive identical tokens"""
+    from runome.tokenizer import Tokenizer
+    from runome.tokenfilter import DedupFilter
+
+    tokenizer = Tokenizer()
+    tokens = list(tokenizer.tokenize("すごいすごいすごいね"))
+
+    filtered = list(DedupFilter()(tokens))
+    assert [t.surface for t in filtered] == ["すごい", "ね"]
+
+    filtered = list(DedupFilter("base_form")(tokens))
+    assert [t.surface for t in filtered] == ["すごい", "ね"]
+
+    try:
+        DedupFilter("reading")
+        assert False, "Should raise an exception for an invalid attribute"
+    except Exception:
+        pass
+
+
+def test_word_stop_and_keep_filters(tmp_path):
+    """Test WordStopFilter and WordKeepFilter with word sets and files"""
+    from runome.tokenizer import Tokenizer
+    from runome.tokenfilter import WordStopFilter, WordKeepFilter
+
+    tokenizer = Tokenizer()
+    tokens = list(tokenizer.tokenize("東京駅で降りた"))
+
+    filtered = list(WordStopFilter({"で", "た"})(tokens))
+    assert [t.surface for t in filtered] == ["東京", "駅", "降り"]
+
+    filtered = list(WordKeepFilter(["降りる"], attr="base_form")(tokens))
+    assert [t.surface for t in filtered] == ["降り"]
+
+    path = tmp_path / "stopwords.txt"
+    path.write_text("# comment\n東京\n駅\n", encoding="utf-8")
+    filtered = list(WordStopFilter(path=str(path))(tokens))
+    assert [t.surface for t in filtered] == ["で", "降り", "た"]
+
+    try:
+        WordStopFilter()
+        assert False, "Should raise an exception without words or path"
+    except Exception:
+        pass
+
+
+def test_pii_mask_filter():
+    """Test PiiMaskFilter replacing PII with placeholders"""
+    from runome.tokenizer import Tokenizer
+    from runome.tokenfilter import PiiMaskFilter
+
+    tokenizer = Tokenizer()
+    text = "連絡先は03-1234-5678です"
+    tokens = list(tokenizer.tokenize(text))
+
+    filtered = list(PiiMaskFilter()(tokens))
+    assert [t.surface for t in filtered] == ["連絡", "先", "は", "<PHONE>", "です"]
+
+    filtered = list(PiiMaskFilter(kinds=["email"])(tokens))
+    assert [t.surface for t in filtered] == [t.surface for t in tokens]
+
+    try:
+        PiiMaskFilter(kinds=["address"])
+        assert False, "Should raise an exception for an invalid kind"
+    except Exception:
+        pass
+
+
 def test_terminal_filters():
     """Test terminal TokenFilters that change output type"""
     from runome.tokenizer import Tokenizer
@@ -287,6 +378,66 @@ def test_filter_chaining():
     assert "降りる" in surfaces
 
 
+def test_janome_keyword_arguments():
+    """Test constructor keyword arguments named as in Janome"""
+    from runome.analyzer import Analyzer
+    from runome.charfilter import RegexReplaceCharFilter, UnicodeNormalizeCharFilter
+    from runome.tokenfilter import ExtractAttributeFilter, TokenCountFilter
+
+    analyzer = Analyzer(
+        char_filters=[
+            UnicodeNormalizeCharFilter(form="NFKC"),
+            RegexReplaceCharFilter(pat="蛇の目", repl="janome"),
+        ],
+        token_filters=[ExtractAttributeFilter(att="surface")],
+    )
+    assert list(analyzer.analyze("蛇の目")) == ["janome"]
+
+    analyzer = Analyzer(token_filters=[TokenCountFilter(att="surface", sorted=True)])
+    assert list(analyzer.analyze("すもももももももものうち"))[0] == ("も", 2)
+
+
+def test_python_filters():
+    """Test filters implemented in Python"""
+    from runome.analyzer import Analyzer
+    from runome.charfilter import CharFilter
+    from runome.tokenfilter import TokenFilter, POSKeepFilter
+
+    class UpperCaseCharFilter(CharFilter):
+        def apply(self, text):
+            return text.upper()
+
+    class SuffixFilter(TokenFilter):
+        def __init__(self, suffix):
+            self.suffix = suffix
+
+        def apply(self, tokens):
+            for token in tokens:
+                yield token.surface + self.suffix
+
+    assert UpperCaseCharFilter()("janome") == "JANOME"
+    assert isinstance(SuffixFilter("!"), TokenFilter)
+
+    analyzer = Analyzer(
+        char_filters=[UpperCaseCharFilter()],
+        token_filters=[POSKeepFilter(["名詞"]), SuffixFilter("!")],
+    )
+    assert list(analyzer.analyze("janomeとすもも")) == ["JANOME!", "すもも!"]
+
+    # Plain callables work as filters too
+    analyzer = Analyzer(
+        char_filters=[lambda text: text.replace("もも", "モモ")],
+        token_filters=[lambda tokens: (token.reading for token in tokens)],
+    )
+    assert list(analyzer.analyze("もも")) == ["モモ"]
+
+    # The base classes do not implement apply
+    with pytest.raises(NotImplementedError):
+        CharFilter()("text")
+    with pytest.raises(NotImplementedError):
+        TokenFilter()([])
+
+
 def test_error_handling():
     """Test error handling in bindings"""
     from runome.charfilter import RegexReplaceCharFilter, UnicodeNormalizeCharFilter
@@ -9,9 +9,18 @@ This test suite includes:
 
 import pytest
 import os
+import multiprocessing
+import pickle
+from concurrent.futures import ThreadPoolExecutor
 from runome.tokenizer import Tokenizer, Token
 
 
+def _wakati(args):
+    """Tokenize in a worker process; module level so it can be pickled."""
+    tokenizer, text = args
+    return list(tokenizer.tokenize(text, wakati=True))
+
+
 class TestBasicPythonBinding:
     """Test basic Python binding functionality."""
 
@@ -67,6 +76,8 @@ class TestBasicPythonBinding:
         assert isinstance(token.reading, str)
         assert isinstance(token.phonetic, str)
         assert isinstance(token.node_type, str)
+        assert isinstance(token.word_id, int)
+        assert isinstance(token.word_cost, int)
 
         # Test surface is not empty
         assert len(token.surface) > 0
@@ -107,6 +118,110 @@ class TestBasicPythonBinding:
         assert "Token" in repr_str
         assert token.surface in repr_str
 
+    def test_token_equality_and_hash(self):
+        """Test tokens compare by attributes and can be deduplicated."""
+        tokenizer = Tokenizer()
+        tokens = list(tokenizer.tokenize("すもももももももものうち"))
+        momo = [t for t in tokens if t.surface == "もも"]
+        assert len(momo) == 2
+        assert momo[0] == momo[1]
+        assert hash(momo[0]) == hash(momo[1])
+        assert tokens[0] != tokens[1]
+        assert tokens[0] != "すもも"
+        assert len(set(tokens)) == len(tokens) - 2
+        counts = {}
+        for token in tokens:
+            counts[token] = counts.get(token, 0) + 1
+        assert counts[momo[0]] == 2
+
+    def test_token_len(self):
+        """Test len() is the surface length in characters."""
+        tokenizer = Tokenizer()
+        token = next(iter(tokenizer.tokenize("すもも")))
+        assert len(token) == 3
+
+    def test_token_as_dict(self):
+        """Test as_dict returns all attributes and dumps to JSON."""
+        import json
+
+        tokenizer = Tokenizer()
+        token = next(iter(tokenizer.tokenize("すもも")))
+        d = token.as_dict()
+        assert d == {
+            "surface": token.surface,
+            "part_of_speech": token.part_of_speech,
+            "infl_type": token.infl_type,
+            "infl_form": token.infl_form,
+            "base_form": token.base_form,
+            "reading": token.reading,
+            "phonetic": token.phonetic,
+            "node_type": token.node_type,
+            "word_id": token.word_id,
+            "word_cost": token.word_cost,
+        }
+        assert json.loads(json.dumps(d, ensure_ascii=False)) == d
+
+    def test_tokenize_batch(self):
+        """Test batch tokenization keeps input order and returns tokens."""
+        tokenizer = Tokenizer(wakati=True)
+        texts = ["すもももももももものうち", "", "東京都に住む"]
+        batch = tokenizer.tokenize_batch(texts)
+        assert len(batch) == len(texts)
+        for text, tokens in zip(texts, batch):
+            assert all(isinstance(t, Token) for t in tokens)
+            assert "".join(t.surface for t in tokens) == text
+
+    def test_wakati(self):
+        """Test wakati returns a list of surfaces."""
+        tokenizer = Tokenizer()
+        surfaces = tokenizer.wakati("すもももももももものうち")
+        assert surfaces == list(
+            tokenizer.tokenize("すもももももももものうち", wakati=True)
+        )
+        assert surfaces[0] == "すもも"
+        assert tokenizer.wakati("") == []
+
+    def test_nbest(self):
+        """Test nbest candidates and their probabilities."""
+        text = "すもももももももものうち"
+        candidates = Tokenizer().nbest(text, 5)
+        assert len(candidates) == 5
+        for tokens, cost, probability in candidates:
+            assert "".join(t.surface for t in tokens) == text
+            assert isinstance(cost, int)
+            assert 0.0 < probability <= 1.0
+        assert abs(sum(c[2] for c in candidates) - 1.0) < 1e-9
+
+        # Lower theta spreads the probability over the candidates
+        flat = Tokenizer(theta=0.1).nbest(text, 5)
+        assert flat[0][2] < candidates[0][2]
+        assert pickle.loads(pickle.dumps(Tokenizer(theta=0.1))).nbest(text, 5) == flat
+
+        # Costs are scaled by theta over the cost factor
+        doubled = Tokenizer(cost_factor=1600.0).nbest(text, 5)
+        halved = Tokenizer(theta=0.375).nbest(text, 5)
+        for a, b in zip(doubled, halved):
+            assert abs(a[2] - b[2]) < 1e-9
+        restored = pickle.loads(pickle.dumps(Tokenizer(cost_factor=1600.0)))
+        assert restored.nbest(text, 5) == doubled
+
+    def test_tokenize_bytes(self):
+        """Test bytes input is decoded with the given encoding."""
+        tokenizer = Tokenizer(wakati=True)
+        text = "すもももももももものうち"
+        expected = list(tokenizer.tokenize(text))
+        assert list(tokenizer.tokenize(text.encode("utf-8"))) == expected
+        for encoding in ["shift_jis", "cp932", "euc-jp", "euc_jp"]:
+            data = text.encode(encoding)
+            assert list(tokenizer.tokenize(data, encoding=encoding)) == expected
+
+        with pytest.raises(Exception, match="not valid"):
+            list(tokenizer.tokenize(b"\xff\xfe", encoding="shift_jis"))
+        with pytest.raises(Exception, match="Unsupported encoding"):
+            tokenizer.tokenize(b"abc", encoding="latin-1")
+        with pytest.raises(TypeError):
+            tokenizer.tokenize(text, encoding="shift_jis")
+
     def test_iterator_protocol(self):
         """Test that tokenize returns proper iterator."""
         tokenizer = Tokenizer()
@@ -123,6 +238,54 @@ class TestBasicPythonBinding:
         tokens = list(tokenizer.tokenize(""))
         assert len(tokens) == 0
 
+    def test_iterator_is_lazy(self):
+        """Test that tokens are produced on demand, like Janome's generator."""
+        tokenizer = Tokenizer()
+        text = "すもももももももものうち。" * 2000
+        iterator = tokenizer.tokenize(text)
+
+        # The input is tokenized chunk by chunk while iterating
+        assert next(iterator).surface == "すもも"
+        del text
+        assert sum(1 for _ in iterator) == 8 * 2000 - 1
+
+    def test_pickle(self):
+        """Test that a tokenizer survives pickling."""
+        tokenizer = Tokenizer(wakati=True)
+        restored = pickle.loads(pickle.dumps(tokenizer))
+        assert isinstance(restored, Tokenizer)
+        assert list(restored.tokenize("すもももももももものうち")) == list(
+            tokenizer.tokenize("すもももももももものうち")
+        )
+
+        restored = pickle.loads(pickle.dumps(Tokenizer(), protocol=2))
+        assert isinstance(next(restored.tokenize("すもも")), Token)
+
+    def test_multiprocessing(self):
+        """Test passing a tokenizer to worker processes."""
+        tokenizer = Tokenizer()
+        texts = ["すもももももももものうち", "東京駅で降りる"]
+        context = multiprocessing.get_context("spawn")
+        with context.Pool(2) as pool:
+            results = pool.map(_wakati, [(tokenizer, text) for text in texts])
+        assert results == [
+            list(tokenizer.tokenize(text, wakati=True)) for text in texts
+        ]
+
+    def test_tokenize_in_threads(self):
+        """Test that threads tokenize concurrently with the GIL released."""
+        tokenizer = Tokenizer()
+        text = "すもももももももものうち。" * 500
+        expected = list(tokenizer.tokenize(text, wakati=True))
+
+        with ThreadPoolExecutor(max_workers=4) as executor:
+            results = list(
+                executor.map(
+                    lambda t: list(tokenizer.tokenize(t, wakati=True)), [text] * 8
+                )
+            )
+        assert all(result == expected for result in results)
+
 
 class TestJanomeEquivalent:
     """Test cases equivalent to Janome's test_tokenizer.py."""
@@ -315,6 +478,18 @@ class TestJanomeEquivalent:
         assert tokens_true[0].base_form == "2009"  # baseform_unk=True
         assert tokens_false[0].base_form == "*"  # baseform_unk=False
 
+    def test_dotfile_parameter(self, tmp_path):
+        """Test dotfile parameter writes the lattice of short inputs."""
+        dotfile = tmp_path / "lattice.gv"
+        tokens = list(self.tokenizer.tokenize("すもももももももものうち", dotfile=str(dotfile)))
+        assert len(tokens) == 7
+        assert dotfile.read_text().startswith("digraph")
+
+        # Long inputs are tokenized without writing the file
+        dotfile.unlink()
+        list(self.tokenizer.tokenize("あ" * 1024, dotfile=str(dotfile)))
+        assert not dotfile.exists()
+
     def test_multiple_character_types(self):
         """Test various character types."""
         test_cases = [
@@ -503,6 +678,59 @@ class TestUserDictionary:
         tokens2 = list(tokenizer2.tokenize("東京スカイツリー"))
         assert len(tokens2) == 1
 
+    def test_user_dict_python_codec_names(self):
+        """Test encoding names as spelled by Python codecs."""
+        for udic_enc, path in [
+            ("euc_jp", self.user_ipadic_eucjp_path),
+            ("cp932", self.user_ipadic_sjis_path),
+            ("UTF8", self.user_ipadic_path),
+        ]:
+            tokenizer = Tokenizer(udic=path, udic_type="ipadic", udic_enc=udic_enc)
+            tokens = list(tokenizer.tokenize("東京スカイツリー"))
+            assert [t.surface for t in tokens] == ["東京スカイツリー"]
+
+    def test_user_dict_compiled_directory(self, tmp_path):
+        """Test loading a compiled user dictionary directory."""
+        from runome.tokenizer import compile_user_dictionary
+
+        compiled_dir = tmp_path / "userdic"
+        compile_user_dictionary(
+            self.user_ipadic_eucjp_path, compiled_dir, udic_enc="euc-jp"
+        )
+
+        text = "東武スカイツリーラインで東京スカイツリーへ"
+        from_csv = Tokenizer(udic=self.user_ipadic_path)
+        for udic in [compiled_dir, str(compiled_dir)]:
+            # udic_enc and udic_type only apply to CSV files
+            tokenizer = Tokenizer(udic=udic, udic_enc="shift_jis")
+            assert [str(t) for t in tokenizer.tokenize(text)] == [
+                str(t) for t in from_csv.tokenize(text)
+            ]
+
+        with pytest.raises(Exception, match="compiled user dictionary"):
+            Tokenizer(udic=str(tmp_path))
+
+    def test_pickle_with_user_dict(self, tmp_path):
+        """Test pickling a tokenizer with a user dictionary."""
+        tokenizer = Tokenizer(
+            udic=os.path.relpath(self.user_ipadic_eucjp_path),
+            udic_enc="euc-jp",
+            max_unknown_length=2,
+            wakati=True,
+        )
+        data = pickle.dumps(tokenizer)
+
+        # The relative path is resolved when pickling
+        cwd = os.getcwd()
+        os.chdir(tmp_path)
+        try:
+            restored = pickle.loads(data)
+        finally:
+            os.chdir(cwd)
+        text = "東京スカイツリーへのお越しはabcdefで"
+        assert list(restored.tokenize(text)) == list(tokenizer.tokenize(text))
+        assert "東京スカイツリー" in list(restored.tokenize(text))
+
     def test_user_dict_wakati_mode(self):
         """Test user dictionary with wakati mode."""
         tokenizer = Tokenizer(udic=self.user_ipadic_path, udic_type="ipadic")
@@ -729,6 +957,89 @@ class TestUserDictionary:
             )
 
 
+
+class TestFormatters:
+    """Test ChaSen and wakati output formatters."""
+
+    def test_chasen_formatter(self):
+        """Test ChaSen output matches mecab -Ochasen."""
+        from runome.formatters import ChasenFormatter
+
+        tokenizer = Tokenizer()
+        tokens = list(tokenizer.tokenize("すもももももももものうち"))
+        lines = ChasenFormatter().format(tokens).splitlines()
+
+        assert len(lines) == 8
+        assert lines[0] == "すもも\tスモモ\tすもも\t名詞-一般\t\t"
+        assert lines[6] == "うち\tウチ\tうち\t名詞-非自立-副詞可能\t\t"
+        assert lines[7] == "EOS"
+
+    def test_mecab_formatter(self):
+        """Test output in MeCab's default format."""
+        from runome.formatters import MecabFormatter
+
+        tokenizer = Tokenizer()
+        tokens = list(tokenizer.tokenize("すもももももももものうち"))
+        lines = MecabFormatter().format(tokens).splitlines()
+
+        assert len(lines) == 8
+        assert lines[0] == "すもも\t名詞,一般,*,*,*,*,すもも,スモモ,スモモ"
+        assert lines[7] == "EOS"
+
+    def test_conllu_formatter(self):
+        """Test CoNLL-U output."""
+        from runome.formatters import ConlluFormatter
+
+        tokenizer = Tokenizer()
+        tokens = list(tokenizer.tokenize("すもももももももものうち"))
+        lines = ConlluFormatter().format(tokens).split("\n")
+
+        assert lines[0] == "# text = すもももももももものうち"
+        assert lines[1].split("\t") == [
+            "1", "すもも", "すもも", "NOUN", "名詞-一般", "_", "_", "_", "_", "SpaceAfter=No"
+        ]
+        assert lines[-2:] == ["", ""]
+
+    def test_wakati_writer(self):
+        """Test wakati output from tokens and surface strings."""
+        from runome.formatters import WakatiWriter
+
+        tokenizer = Tokenizer()
+        tokens = list(tokenizer.tokenize("すもももももももものうち"))
+        surfaces = list(tokenizer.tokenize("すもももももももものうち", wakati=True))
+
+        expected = "すもも も もも も もも の うち\n"
+        assert WakatiWriter().format(tokens) == expected
+        assert WakatiWriter().format(surfaces) == expected
+        assert WakatiWriter("/").format(["a", "b"]) == "a/b\n"
+
+
+class TestPreloadDictionary:
+    """Test dictionary preloading for preforking servers."""
+
+    @pytest.mark.skipif(not hasattr(os, "fork"), reason="requires os.fork")
+    def test_preload_then_fork(self):
+        """Test forked workers can tokenize with the preloaded dictionary."""
+        from runome.tokenizer import preload_dictionary
+
+        preload_dictionary()
+        read_fd, write_fd = os.pipe()
+        pid = os.fork()
+        if pid == 0:
+            os.close(read_fd)
+            surfaces = [t.surface for t in Tokenizer().tokenize("すもももももももものうち")]
+            os.write(write_fd, " ".join(surfaces).encode("utf-8"))
+            os._exit(0)
+
+        os.close(write_fd)
+        with os.fdopen(read_fd, "rb") as pipe:
+            output = pipe.read().decode("utf-8")
+        _, status = os.waitpid(pid, 0)
+
+        assert os.WEXITSTATUS(status) == 0
+        assert output == "すもも も もも も もも の うち"
+
+
 if __name__ == "__main__":
     # Run tests
     pytest.main([__file__, "-v"])